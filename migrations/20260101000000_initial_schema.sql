@@ -0,0 +1,484 @@
+-- Full schema and starting data for the civic legislation platform, for new databases.
+-- Tables that already exist are left as they are, so this doesn't upgrade a database created
+-- from the old init.sql; see "Moving a Database Created From init.sql" in the README.
+
+-- Bills table
+CREATE TABLE IF NOT EXISTS bills (
+    id UUID PRIMARY KEY,
+    title TEXT NOT NULL,
+    bill_number TEXT UNIQUE NOT NULL,
+    year INTEGER NOT NULL,
+    session TEXT,
+    status TEXT,
+    introduction_date DATE,
+    pdf_url TEXT,
+    extracted_text TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS bill_chunks (
+    id UUID PRIMARY KEY,
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    chunk_index INTEGER NOT NULL,
+    chunk_type TEXT, -- 'clause', 'section', 'preamble', etc.
+    chunk_identifier TEXT, -- e.g., 'Clause 5', 'Section 2(a)'
+    content TEXT NOT NULL,
+    embedding_id TEXT, -- Reference to vector in Qdrant
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(bill_id, chunk_index)
+);
+
+-- Constituencies table (India parliamentary constituencies)
+CREATE TABLE IF NOT EXISTS constituencies (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    state TEXT NOT NULL,
+    code TEXT UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Pincodes to constituency mapping
+CREATE TABLE IF NOT EXISTS pincode_constituencies (
+    id SERIAL PRIMARY KEY,
+    pincode VARCHAR(6) NOT NULL,
+    constituency_id INTEGER NOT NULL REFERENCES constituencies(id) ON DELETE CASCADE,
+    UNIQUE(pincode)
+);
+
+-- Users table
+CREATE TABLE IF NOT EXISTS users (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    username VARCHAR(50) UNIQUE NOT NULL,
+    -- Lookups and uniqueness ignore case; `username` keeps the casing the user chose
+    username_canonical VARCHAR(50) GENERATED ALWAYS AS (LOWER(username)) STORED,
+    password_hash TEXT NOT NULL,
+    -- False for accounts created through Google sign-in until a password is set by reset
+    password_set BOOLEAN NOT NULL DEFAULT TRUE,
+    real_name TEXT,
+    age INTEGER,
+    gender VARCHAR(20),
+    pincode VARCHAR(6),
+    constituency_id INTEGER REFERENCES constituencies(id),
+    -- Whether other users can see each personal field on the profile page
+    real_name_public BOOLEAN NOT NULL DEFAULT FALSE,
+    age_public BOOLEAN NOT NULL DEFAULT TRUE,
+    gender_public BOOLEAN NOT NULL DEFAULT TRUE,
+    pincode_public BOOLEAN NOT NULL DEFAULT FALSE,
+    role VARCHAR(20) NOT NULL DEFAULT 'user' CHECK (role IN ('user', 'moderator', 'admin')),
+    -- Suspended users cannot log in or post
+    suspended_at TIMESTAMP WITH TIME ZONE,
+    -- Scales the per-user rate limits set by an admin; 0 exempts the user. NULL keeps the role's
+    -- default: moderators and admins are exempt, everyone else gets the usual limits
+    rate_limit_multiplier INTEGER CHECK (rate_limit_multiplier BETWEEN 0 AND 100),
+    -- Optional recovery address; only used once verified
+    email VARCHAR(254),
+    email_verified_at TIMESTAMP WITH TIME ZONE,
+    -- Weekly digest of followed bills, sent to the verified address unless turned off
+    digest_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    digest_sent_at TIMESTAMP WITH TIME ZONE,
+    -- Set when the account is deleted; the row is kept anonymized so posts can stay attributed
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Sessions table for login management
+CREATE TABLE IF NOT EXISTS sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_token TEXT UNIQUE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Pending email verifications; a token only confirms the address it was sent to
+CREATE TABLE IF NOT EXISTS email_verifications (
+    token TEXT PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(254) NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Pending password resets; only a SHA-256 hash of each emailed token is stored
+CREATE TABLE IF NOT EXISTS password_resets (
+    token_hash TEXT PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- External sign-in accounts linked to users, by provider and the provider's stable subject id
+CREATE TABLE IF NOT EXISTS identities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider VARCHAR(20) NOT NULL,
+    subject TEXT NOT NULL,
+    email VARCHAR(254),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, subject),
+    UNIQUE (user_id, provider)
+);
+
+-- Personal API tokens; only a SHA-256 hash of each token is stored, and read-only tokens
+-- can't reach routes that change anything
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT UNIQUE NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    scope VARCHAR(10) NOT NULL DEFAULT 'read' CHECK (scope IN ('read', 'write')),
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Posts/Reviews table
+CREATE TABLE IF NOT EXISTS posts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    -- Replies point at the top-level review they answer and carry no stance
+    parent_post_id UUID REFERENCES posts(id) ON DELETE CASCADE,
+    stance VARCHAR(20) CHECK (stance IN ('Support', 'Oppose', 'Critique')),
+    content TEXT NOT NULL,
+    -- Shown publicly as "Citizen from <constituency>"; the author is still known to moderators
+    is_anonymous BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Moderation status: 'approved', 'rejected', 'pending_review', or 'appealed' while the
+    -- author's appeal of a rejection waits for a moderator
+    moderation_status VARCHAR(20) NOT NULL DEFAULT 'pending_review',
+    moderation_reason TEXT,
+    -- Which moderation provider judged the post: 'ollama', 'openai', 'keyword', or 'language' for posts in unsupported languages
+    moderation_provider VARCHAR(30),
+    moderated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    moderated_at TIMESTAMP WITH TIME ZONE,
+    -- Sentence embedding of the content, compared against new posts to catch copy-pasted campaigns
+    embedding REAL[],
+    -- Earlier post this one closely matched when it was submitted
+    duplicate_of UUID REFERENCES posts(id) ON DELETE SET NULL,
+    duplicate_similarity REAL,
+    -- Approved on the author's trust without a moderator, and picked for one to spot-check;
+    -- cleared by any moderator decision on the post
+    audit_sampled BOOLEAN NOT NULL DEFAULT FALSE,
+    edited_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    upvotes INTEGER DEFAULT 0,
+    downvotes INTEGER DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CHECK (parent_post_id IS NOT NULL OR stance IS NOT NULL)
+);
+
+-- Post votes tracking (to prevent multiple votes from same user)
+CREATE TABLE IF NOT EXISTS post_votes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    vote_type VARCHAR(10) NOT NULL CHECK (vote_type IN ('upvote', 'downvote')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(post_id, user_id)
+);
+
+-- Community reports flagging posts for moderator attention
+CREATE TABLE IF NOT EXISTS post_reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason VARCHAR(30) NOT NULL CHECK (reason IN ('spam', 'harassment', 'hate_speech', 'misinformation', 'off_topic', 'other')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(post_id, user_id)
+);
+
+-- An author's appeal of their rejected post; one per post
+CREATE TABLE IF NOT EXISTS post_appeals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    post_id UUID NOT NULL UNIQUE REFERENCES posts(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    statement TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'upheld', 'overturned')),
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Users following a bill's discussion
+CREATE TABLE IF NOT EXISTS bill_subscriptions (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, bill_id)
+);
+
+-- Bills a user saved to their reading list
+CREATE TABLE IF NOT EXISTS bookmarks (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, bill_id)
+);
+
+-- Notifications for followed bills
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    post_id UUID REFERENCES posts(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('new_post', 'status_change', 'mention', 'appeal_decided')),
+    message TEXT NOT NULL,
+    read_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(user_id, post_id)
+);
+
+-- AI-generated synthesis of a bill's approved reviews, refreshed as reviews accumulate
+CREATE TABLE IF NOT EXISTS bill_summaries (
+    bill_id UUID PRIMARY KEY REFERENCES bills(id) ON DELETE CASCADE,
+    summary TEXT NOT NULL,
+    support_pct INTEGER NOT NULL,
+    oppose_pct INTEGER NOT NULL,
+    critique_pct INTEGER NOT NULL,
+    post_count INTEGER NOT NULL, -- approved reviews when the summary was generated
+    generated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- AI-generated overview for a constituency report, per period so regenerating a report doesn't re-prompt
+CREATE TABLE IF NOT EXISTS constituency_report_summaries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    constituency_id INTEGER NOT NULL REFERENCES constituencies(id) ON DELETE CASCADE,
+    period_from DATE, -- NULL when the report starts from the first post
+    period_to DATE, -- NULL when the report runs up to now
+    summary TEXT NOT NULL,
+    post_count INTEGER NOT NULL, -- posts in the period when the summary was generated
+    generated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE NULLS NOT DISTINCT (constituency_id, period_from, period_to)
+);
+
+-- What citizens search for; user_hash is a salted hash so searches can't be tied back to an account
+CREATE TABLE IF NOT EXISTS search_logs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    query TEXT NOT NULL, -- normalized: trimmed, lowercased, whitespace collapsed
+    result_count INTEGER NOT NULL,
+    top_score REAL, -- NULL for keyword fallback results, which aren't scored
+    user_hash VARCHAR(64),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Rate limiting table
+CREATE TABLE IF NOT EXISTS rate_limits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    ip_address VARCHAR(45), -- set instead of user_id for anonymous actions like login and register
+    action_type VARCHAR(50) NOT NULL, -- 'post_create', 'vote', etc.
+    timestamp TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CHECK (user_id IS NOT NULL OR ip_address IS NOT NULL)
+);
+
+-- Member actions a rate limit refused, for the admin rate limit view; cleared after a day
+CREATE TABLE IF NOT EXISTS rate_limit_rejections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    action_type VARCHAR(50) NOT NULL,
+    timestamp TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Failed logins, kept by submitted username and client address for brute-force lockout
+CREATE TABLE IF NOT EXISTS login_failures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    username VARCHAR(50) NOT NULL,
+    ip_address VARCHAR(45),
+    timestamp TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Security-relevant events (logins, password and role changes, moderation). Rows are never
+-- changed or removed; the trigger below rejects UPDATE and DELETE.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    actor_id UUID REFERENCES users(id),
+    action VARCHAR(50) NOT NULL,
+    target_id UUID, -- a user or post, depending on the action
+    ip_address VARCHAR(45),
+    user_agent TEXT,
+    metadata JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE FUNCTION reject_audit_log_change() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is insert-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_log_insert_only ON audit_log;
+CREATE TRIGGER audit_log_insert_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION reject_audit_log_change();
+
+-- Each automatic moderation attempt on a post, kept so disputed verdicts can be explained.
+-- raw_response is the provider's answer verbatim; rows past their retention are deleted.
+CREATE TABLE IF NOT EXISTS moderation_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    provider VARCHAR(30) NOT NULL,
+    prompt_hash VARCHAR(64),
+    raw_response TEXT,
+    -- Status the answer parsed to; NULL when the attempt failed
+    verdict VARCHAR(20),
+    error TEXT,
+    latency_ms INTEGER NOT NULL,
+    -- On the 'trust' row added when the author's trust level changed a borderline verdict
+    trust_bias VARCHAR(20) CHECK (trust_bias IN ('held', 'approved', 'approved_audit')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Patterns for the keyword moderation filter, managed from /admin/keywords. Plain patterns match
+-- whole words regardless of case; regexes are case-insensitive and checked when they're added.
+CREATE TABLE IF NOT EXISTS moderation_keywords (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    pattern TEXT NOT NULL,
+    is_regex BOOLEAN NOT NULL DEFAULT FALSE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('hate', 'threat', 'toxicity', 'spam')),
+    -- 'review' holds a matching post for a moderator; 'reject' rejects it outright
+    action VARCHAR(10) NOT NULL CHECK (action IN ('review', 'reject')),
+    -- A post is acted on for one 'high' match, or for two matches of any severity
+    severity VARCHAR(10) NOT NULL DEFAULT 'low' CHECK (severity IN ('low', 'high')),
+    -- Posts are matched against their detected language's patterns plus the English ones
+    language VARCHAR(10) NOT NULL DEFAULT 'en' CHECK (language IN ('en', 'hi', 'hinglish')),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(pattern, is_regex, language)
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_bills_bill_number ON bills(bill_number);
+CREATE INDEX IF NOT EXISTS idx_bills_year ON bills(year);
+CREATE INDEX IF NOT EXISTS idx_bill_chunks_bill_id ON bill_chunks(bill_id);
+CREATE INDEX IF NOT EXISTS idx_bill_chunks_embedding_id ON bill_chunks(embedding_id);
+CREATE INDEX IF NOT EXISTS idx_users_username ON users(username);
+CREATE INDEX IF NOT EXISTS idx_sessions_token ON sessions(session_token);
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user_id ON api_tokens(user_id);
+CREATE INDEX IF NOT EXISTS idx_posts_bill_id ON posts(bill_id);
+CREATE INDEX IF NOT EXISTS idx_posts_user_id ON posts(user_id);
+CREATE INDEX IF NOT EXISTS idx_posts_parent_post_id ON posts(parent_post_id);
+CREATE INDEX IF NOT EXISTS idx_posts_moderation_status ON posts(moderation_status);
+CREATE INDEX IF NOT EXISTS idx_post_reports_post_id ON post_reports(post_id);
+CREATE INDEX IF NOT EXISTS idx_post_appeals_pending ON post_appeals(created_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_bill_subscriptions_bill_id ON bill_subscriptions(bill_id);
+CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications(user_id) WHERE read_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_rate_limits_user_action ON rate_limits(user_id, action_type);
+CREATE INDEX IF NOT EXISTS idx_rate_limits_timestamp ON rate_limits(timestamp);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_username_canonical ON users(username_canonical);
+CREATE INDEX IF NOT EXISTS idx_audit_log_created_at ON audit_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor ON audit_log(actor_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_target ON audit_log(target_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_moderation_events_post ON moderation_events(post_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_moderation_events_created_at ON moderation_events(created_at);
+CREATE INDEX IF NOT EXISTS idx_rate_limits_ip_action ON rate_limits(ip_address, action_type, timestamp);
+CREATE INDEX IF NOT EXISTS idx_login_failures_username ON login_failures(username, timestamp);
+CREATE INDEX IF NOT EXISTS idx_login_failures_ip ON login_failures(ip_address, timestamp);
+CREATE INDEX IF NOT EXISTS idx_pincode_constituencies_pincode ON pincode_constituencies(pincode);
+CREATE INDEX IF NOT EXISTS idx_search_logs_created_at ON search_logs(created_at);
+CREATE INDEX IF NOT EXISTS idx_rate_limit_rejections_timestamp ON rate_limit_rejections(timestamp);
+CREATE INDEX IF NOT EXISTS idx_rate_limit_rejections_user ON rate_limit_rejections(user_id, timestamp);
+
+-- Starting keyword lists for fallback moderation
+INSERT INTO moderation_keywords (pattern, kind, action, severity) VALUES
+    ('kill', 'toxicity', 'review', 'low'),
+    ('murder', 'toxicity', 'review', 'high'),
+    ('hate', 'toxicity', 'review', 'low'),
+    ('terrorist', 'toxicity', 'review', 'high'),
+    ('bomb', 'toxicity', 'review', 'low'),
+    ('die', 'toxicity', 'review', 'low'),
+    ('stupid', 'toxicity', 'review', 'low'),
+    ('idiot', 'toxicity', 'review', 'low'),
+    ('moron', 'toxicity', 'review', 'low'),
+    ('racist', 'toxicity', 'review', 'low'),
+    ('sexist', 'toxicity', 'review', 'low'),
+    ('buy now', 'spam', 'reject', 'low'),
+    ('click here', 'spam', 'reject', 'low'),
+    ('free money', 'spam', 'reject', 'low'),
+    ('lottery', 'spam', 'reject', 'low'),
+    ('crypto', 'spam', 'reject', 'low'),
+    ('bitcoin', 'spam', 'reject', 'low'),
+    ('investment opportunity', 'spam', 'reject', 'low')
+ON CONFLICT (pattern, is_regex, language) DO NOTHING;
+
+INSERT INTO moderation_keywords (pattern, language, kind, action, severity) VALUES
+    ('मार डालो', 'hi', 'threat', 'review', 'high'),
+    ('हत्या', 'hi', 'toxicity', 'review', 'high'),
+    ('आतंकवादी', 'hi', 'toxicity', 'review', 'high'),
+    ('बम', 'hi', 'toxicity', 'review', 'low'),
+    ('नफरत', 'hi', 'toxicity', 'review', 'low'),
+    ('बेवकूफ', 'hi', 'toxicity', 'review', 'low'),
+    ('मूर्ख', 'hi', 'toxicity', 'review', 'low'),
+    ('गद्दार', 'hi', 'toxicity', 'review', 'low'),
+    ('लॉटरी', 'hi', 'spam', 'reject', 'low'),
+    ('मुफ्त पैसा', 'hi', 'spam', 'reject', 'low'),
+    ('अभी खरीदें', 'hi', 'spam', 'reject', 'low'),
+    ('यहां क्लिक करें', 'hi', 'spam', 'reject', 'low'),
+    ('maar dalo', 'hinglish', 'threat', 'review', 'high'),
+    ('jaan se maar', 'hinglish', 'threat', 'review', 'high'),
+    ('bewakoof', 'hinglish', 'toxicity', 'review', 'low'),
+    ('murkh', 'hinglish', 'toxicity', 'review', 'low'),
+    ('gaddar', 'hinglish', 'toxicity', 'review', 'low'),
+    ('kamina', 'hinglish', 'toxicity', 'review', 'low'),
+    ('paisa kamao', 'hinglish', 'spam', 'reject', 'low'),
+    ('free paisa', 'hinglish', 'spam', 'reject', 'low'),
+    ('abhi kharido', 'hinglish', 'spam', 'reject', 'low'),
+    ('yahan click karo', 'hinglish', 'spam', 'reject', 'low')
+ON CONFLICT (pattern, is_regex, language) DO NOTHING;
+
+-- Insert sample constituencies (major Indian cities/areas)
+INSERT INTO constituencies (name, state, code) VALUES 
+    ('Mumbai South', 'Maharashtra', 'MH-MS'),
+    ('Mumbai North', 'Maharashtra', 'MH-MN'),
+    ('Mumbai North Central', 'Maharashtra', 'MH-MNC'),
+    ('Delhi Central', 'Delhi', 'DL-C'),
+    ('Delhi East', 'Delhi', 'DL-E'),
+    ('Delhi North', 'Delhi', 'DL-N'),
+    ('Delhi South', 'Delhi', 'DL-S'),
+    ('Delhi West', 'Delhi', 'DL-W'),
+    ('Bangalore North', 'Karnataka', 'KA-BN'),
+    ('Bangalore South', 'Karnataka', 'KA-BS'),
+    ('Bangalore Central', 'Karnataka', 'KA-BC'),
+    ('Chennai North', 'Tamil Nadu', 'TN-CN'),
+    ('Chennai South', 'Tamil Nadu', 'TN-CS'),
+    ('Chennai Central', 'Tamil Nadu', 'TN-CC'),
+    ('Kolkata North', 'West Bengal', 'WB-KN'),
+    ('Kolkata South', 'West Bengal', 'WB-KS'),
+    ('Kolkata Dakshin', 'West Bengal', 'WB-KD'),
+    ('Hyderabad', 'Telangana', 'TS-HY'),
+    ('Secunderabad', 'Telangana', 'TS-SC'),
+    ('Ahmedabad East', 'Gujarat', 'GJ-AE'),
+    ('Ahmedabad West', 'Gujarat', 'GJ-AW'),
+    ('Pune', 'Maharashtra', 'MH-PU'),
+    ('Jaipur', 'Rajasthan', 'RJ-JP'),
+    ('Lucknow', 'Uttar Pradesh', 'UP-LK'),
+    ('Varanasi', 'Uttar Pradesh', 'UP-VN')
+ON CONFLICT (code) DO NOTHING;
+
+-- Insert sample pincode mappings
+INSERT INTO pincode_constituencies (pincode, constituency_id) VALUES 
+    ('400001', (SELECT id FROM constituencies WHERE code = 'MH-MS')),
+    ('400002', (SELECT id FROM constituencies WHERE code = 'MH-MS')),
+    ('400003', (SELECT id FROM constituencies WHERE code = 'MH-MS')),
+    ('400050', (SELECT id FROM constituencies WHERE code = 'MH-MN')),
+    ('400051', (SELECT id FROM constituencies WHERE code = 'MH-MN')),
+    ('110001', (SELECT id FROM constituencies WHERE code = 'DL-C')),
+    ('110002', (SELECT id FROM constituencies WHERE code = 'DL-C')),
+    ('110003', (SELECT id FROM constituencies WHERE code = 'DL-N')),
+    ('560001', (SELECT id FROM constituencies WHERE code = 'KA-BN')),
+    ('560002', (SELECT id FROM constituencies WHERE code = 'KA-BS')),
+    ('600001', (SELECT id FROM constituencies WHERE code = 'TN-CN')),
+    ('600002', (SELECT id FROM constituencies WHERE code = 'TN-CS')),
+    ('700001', (SELECT id FROM constituencies WHERE code = 'WB-KN')),
+    ('700002', (SELECT id FROM constituencies WHERE code = 'WB-KS')),
+    ('500001', (SELECT id FROM constituencies WHERE code = 'TS-HY')),
+    ('380001', (SELECT id FROM constituencies WHERE code = 'GJ-AE')),
+    ('411001', (SELECT id FROM constituencies WHERE code = 'MH-PU')),
+    ('302001', (SELECT id FROM constituencies WHERE code = 'RJ-JP')),
+    ('226001', (SELECT id FROM constituencies WHERE code = 'UP-LK')),
+    ('221001', (SELECT id FROM constituencies WHERE code = 'UP-VN'))
+ON CONFLICT (pincode) DO NOTHING;
@@ -0,0 +1,17 @@
+-- Bill text as chunked at ingest, kept alongside the vector index so it can be shown,
+-- re-embedded and joined with forum data. Each row's id is also its Qdrant point id.
+-- Replaces bill_chunks, which nothing ever wrote to.
+DROP TABLE IF EXISTS bill_chunks;
+
+CREATE TABLE IF NOT EXISTS chunks (
+    id UUID PRIMARY KEY,
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    chunk_index INTEGER NOT NULL,
+    chunk_type TEXT NOT NULL, -- 'Clause', 'Section', 'Preamble', etc.
+    identifier TEXT NOT NULL, -- e.g., 'Clause 5', 'Chapter II'
+    content TEXT NOT NULL,
+    page_start INTEGER, -- PDF pages the chunk was taken from, when known
+    page_end INTEGER,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(bill_id, chunk_index)
+);
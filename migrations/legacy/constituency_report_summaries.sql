@@ -0,0 +1,13 @@
+-- Cached AI-generated overviews for constituency reports, for databases created before
+-- `constituency_report_summaries` was in init.sql. Run once:
+--   docker exec -i civic_postgres psql -U civic_user -d civic_legislation -v ON_ERROR_STOP=1 < migrations/legacy/constituency_report_summaries.sql
+CREATE TABLE IF NOT EXISTS constituency_report_summaries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    constituency_id INTEGER NOT NULL REFERENCES constituencies(id) ON DELETE CASCADE,
+    period_from DATE,
+    period_to DATE,
+    summary TEXT NOT NULL,
+    post_count INTEGER NOT NULL,
+    generated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE NULLS NOT DISTINCT (constituency_id, period_from, period_to)
+);
@@ -0,0 +1,33 @@
+-- Case-insensitive usernames, for databases created before `username_canonical` was in init.sql.
+-- Run once:
+--   docker exec -i civic_postgres psql -U civic_user -d civic_legislation -v ON_ERROR_STOP=1 < migrations/legacy/username_canonical.sql
+--
+-- Accounts whose usernames differ only in case are never merged. If there are any, the migration
+-- stops without changing anything and lists them; rename all but one in each group, then run it again.
+BEGIN;
+
+DO $$
+DECLARE
+    collisions TEXT;
+BEGIN
+    SELECT string_agg(names, E'\n') INTO collisions
+    FROM (
+        SELECT LOWER(username) || ': '
+            || string_agg(username || ' (' || id || ')', ', ' ORDER BY created_at) AS names
+        FROM users
+        GROUP BY LOWER(username)
+        HAVING COUNT(*) > 1
+        ORDER BY LOWER(username)
+    ) groups;
+
+    IF collisions IS NOT NULL THEN
+        RAISE EXCEPTION E'Usernames differ only in case; rename all but one in each group:\n%', collisions;
+    END IF;
+END
+$$;
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS username_canonical VARCHAR(50) GENERATED ALWAYS AS (LOWER(username)) STORED;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_username_canonical ON users(username_canonical);
+
+COMMIT;
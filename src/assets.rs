@@ -0,0 +1,122 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+
+pub const STATIC_DIR: &str = "static";
+
+lazy_static::lazy_static! {
+    /// Hashed names for everything under static/, computed once at startup
+    pub static ref ASSETS: AssetManifest = AssetManifest::load(Path::new(STATIC_DIR));
+}
+
+/// Maps static files to content-hashed names, e.g. css/main.css -> css/main.1a2b3c4d5e6f.css.
+/// A hashed URL changes whenever the file does, so it can be cached indefinitely.
+#[derive(Debug, Default)]
+pub struct AssetManifest {
+    hashed: HashMap<String, String>,
+    logical: HashMap<String, String>,
+}
+
+impl AssetManifest {
+    pub fn load(dir: &Path) -> Self {
+        let mut manifest = Self::default();
+        manifest.add_dir(dir, "");
+        manifest
+    }
+
+    fn add_dir(&mut self, dir: &Path, prefix: &str) {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Failed to read static directory {}: {}", dir.display(), e);
+                return;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let logical = format!("{}{}", prefix, name);
+
+            if path.is_dir() {
+                self.add_dir(&path, &format!("{}/", logical));
+            } else {
+                match std::fs::read(&path) {
+                    Ok(contents) => self.insert(&logical, &contents),
+                    Err(e) => tracing::warn!("Failed to hash static file {}: {}", path.display(), e),
+                }
+            }
+        }
+    }
+
+    fn insert(&mut self, logical: &str, contents: &[u8]) {
+        let hashed = hashed_name(logical, contents);
+        self.logical.insert(hashed.clone(), logical.to_string());
+        self.hashed.insert(logical.to_string(), hashed);
+    }
+
+    /// URL for a static file; files missing from the manifest get their plain URL
+    pub fn url(&self, logical: &str) -> String {
+        format!("/static/{}", self.hashed.get(logical).map_or(logical, String::as_str))
+    }
+
+    /// The file a hashed name refers to, if it matches the current contents
+    pub fn resolve(&self, hashed: &str) -> Option<&str> {
+        self.logical.get(hashed).map(String::as_str)
+    }
+}
+
+/// URL for a static file, for use in templates: `{{ crate::assets::url("css/main.css") }}`
+pub fn url(logical: &str) -> String {
+    ASSETS.url(logical)
+}
+
+/// Short hex digest of some content, for hashed names and ETags
+pub fn content_hash(contents: &[u8]) -> String {
+    Sha256::digest(contents)
+        .iter()
+        .take(6)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Insert the content hash before the extension: css/main.css -> css/main.<hash>.css
+fn hashed_name(logical: &str, contents: &[u8]) -> String {
+    let hash = content_hash(contents);
+    let file_start = logical.rfind('/').map_or(0, |i| i + 1);
+
+    match logical[file_start..].rfind('.') {
+        Some(dot) if dot > 0 => {
+            let dot = file_start + dot;
+            format!("{}.{}{}", &logical[..dot], hash, &logical[dot..])
+        }
+        _ => format!("{}.{}", logical, hash),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashed_names_keep_extension() {
+        let hash = content_hash(b"body {}");
+        assert_eq!(hash.len(), 12);
+        assert_eq!(hashed_name("css/main.css", b"body {}"), format!("css/main.{}.css", hash));
+        assert_eq!(hashed_name("LICENSE", b"body {}"), format!("LICENSE.{}", hash));
+        assert_eq!(hashed_name("img/.hidden", b"body {}"), format!("img/.hidden.{}", hash));
+    }
+
+    #[test]
+    fn test_manifest_resolves_only_current_hash() {
+        let mut manifest = AssetManifest::default();
+        manifest.insert("css/main.css", b"body {}");
+
+        let url = manifest.url("css/main.css");
+        let hashed = url.trim_start_matches("/static/");
+        assert_ne!(hashed, "css/main.css");
+        assert_eq!(manifest.resolve(hashed), Some("css/main.css"));
+        assert_eq!(manifest.resolve(&hashed_name("css/main.css", b"old")), None);
+        assert_eq!(manifest.url("js/missing.js"), "/static/js/missing.js");
+    }
+}
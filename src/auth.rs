@@ -1,18 +1,67 @@
 use anyhow::{Context, Result};
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
+    Algorithm, Argon2, Params, Version,
 };
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
 use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
+use std::{convert::Infallible, marker::PhantomData, net::SocketAddr, sync::Arc};
 use uuid::Uuid;
 
-use crate::models::{Constituency, Session, User};
+use crate::models::{
+    ApiScope, ApiToken, AuditAction, Constituency, EmailVerification, Identity, ProfileVisibility, Role, Session,
+    User,
+};
+use crate::rate_limit;
+use crate::web::{is_htmx_request, AppError, AppState, SESSION_COOKIE_NAME};
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+pub const EMAIL_VERIFICATION_HOURS: i64 = 48;
+pub const PASSWORD_RESET_MINUTES: i64 = 30;
+pub const API_TOKEN_NAME_MAX_CHARS: usize = 100;
+// Constituencies offered when a pincode has no mapping
+const PINCODE_SUGGESTIONS: i64 = 5;
+// Prefix on every API token, so a leaked one is easy to recognise and grep for
+const API_TOKEN_PREFIX: &str = "rue_";
+// last_used_at is only refreshed this often, so busy scripts don't write on every request
+const API_TOKEN_TOUCH_SECS: i64 = 60;
+// Longer User-Agent headers are cut to this before they go in the audit log
+const USER_AGENT_MAX_CHARS: usize = 512;
+
+// Argon2id cost for new hashes from ARGON2_MEMORY_KIB, ARGON2_ITERATIONS and ARGON2_PARALLELISM,
+// each defaulting to the argon2 crate's own default (19 MiB, 2 passes, 1 lane)
+fn argon2_params() -> Params {
+    let var = |name: &str, default: u32| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(default)
+    };
+
+    let memory = var("ARGON2_MEMORY_KIB", Params::DEFAULT_M_COST);
+    let iterations = var("ARGON2_ITERATIONS", Params::DEFAULT_T_COST);
+    let parallelism = var("ARGON2_PARALLELISM", Params::DEFAULT_P_COST);
+    Params::new(memory, iterations, parallelism, None).unwrap_or_else(|e| {
+        tracing::error!("Invalid Argon2 parameters ({}), using the defaults", e);
+        Params::default()
+    })
+}
 
-// Hash a password using Argon2
+// Hash a password using Argon2id with the configured cost; the cost is recorded in the PHC string
 pub fn hash_password(password: &str) -> Result<String> {
+    hash_password_with(password, argon2_params())
+}
+
+fn hash_password_with(password: &str, params: Params) -> Result<String> {
     let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
     let password_hash = argon2
         .hash_password(password.as_bytes(), &salt)
         .map_err(|e| anyhow::anyhow!("Failed to hash password: {}", e))?
@@ -20,7 +69,25 @@ pub fn hash_password(password: &str) -> Result<String> {
     Ok(password_hash)
 }
 
-// Verify a password against a hash
+// Whether a stored hash is weaker than `params` or not Argon2id v1.3, and should be replaced
+fn needs_rehash(hash: &str, params: &Params) -> bool {
+    let Ok(parsed) = PasswordHash::new(hash) else {
+        return true;
+    };
+    if parsed.algorithm != Algorithm::Argon2id.ident() || parsed.version != Some(Version::V0x13.into()) {
+        return true;
+    }
+    match Params::try_from(&parsed) {
+        Ok(current) => {
+            current.m_cost() < params.m_cost()
+                || current.t_cost() < params.t_cost()
+                || current.p_cost() < params.p_cost()
+        }
+        Err(_) => true,
+    }
+}
+
+// Verify a password against a hash; the hash's own parameters are used, so older hashes still verify
 pub fn verify_password(password: &str, hash: &str) -> Result<bool> {
     let parsed_hash = PasswordHash::new(hash)
         .map_err(|e| anyhow::anyhow!("Failed to parse password hash: {}", e))?;
@@ -29,6 +96,193 @@ pub fn verify_password(password: &str, hash: &str) -> Result<bool> {
         .is_ok())
 }
 
+// Minimum role a RequireRole extractor demands
+pub trait RoleRequirement {
+    const ROLE: Role;
+}
+
+pub struct Moderator;
+pub struct Admin;
+
+impl RoleRequirement for Moderator {
+    const ROLE: Role = Role::Moderator;
+}
+
+impl RoleRequirement for Admin {
+    const ROLE: Role = Role::Admin;
+}
+
+// Extractor for the session user, rejecting anyone without at least R's role. Signed-out
+// visitors are sent to the login page, or get 401 from JSON routes; signed-in users get 403.
+pub struct RequireRole<R>(pub User, PhantomData<fn() -> R>);
+
+pub type RequireModerator = RequireRole<Moderator>;
+pub type RequireAdmin = RequireRole<Admin>;
+
+#[axum::async_trait]
+impl<R: RoleRequirement> FromRequestParts<Arc<AppState>> for RequireRole<R> {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let jar = CookieJar::from_headers(&parts.headers);
+        let user = match jar.get(SESSION_COOKIE_NAME) {
+            Some(cookie) => get_user_by_session(&state.db_pool, cookie.value()).await.ok().flatten(),
+            None => None,
+        };
+
+        authorize(user, R::ROLE, parts.uri.path(), &parts.headers).map(|u| RequireRole(u, PhantomData))
+    }
+}
+
+// The user if they hold `required` and aren't suspended, otherwise the response to send instead
+fn authorize(user: Option<User>, required: Role, path: &str, headers: &HeaderMap) -> std::result::Result<User, Response> {
+    match user {
+        Some(u) if u.role() >= required && !u.is_suspended() => Ok(u),
+        Some(_) => Err(AppError::forbidden(match required {
+            Role::Admin => "error.admin_required",
+            _ => "error.moderator_required",
+        })
+        .into_response()),
+        None if wants_json(path, headers) => {
+            Err(AppError::unauthorized("error.login_required").into_response())
+        }
+        // htmx swaps the body of a redirect into the page, so ask it to navigate instead
+        None if is_htmx_request(headers) => {
+            Err((StatusCode::UNAUTHORIZED, [("HX-Redirect", "/login")]).into_response())
+        }
+        None => Err(Redirect::to("/login").into_response()),
+    }
+}
+
+// JSON API routes, and clients that ask for JSON rather than a page
+fn wants_json(path: &str, headers: &HeaderMap) -> bool {
+    if path.starts_with("/api/") && !is_htmx_request(headers) {
+        return true;
+    }
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json") && !accept.contains("text/html"))
+}
+
+// Minimum scope an ApiUser extractor demands
+pub trait ScopeRequirement {
+    const SCOPE: ApiScope;
+}
+
+pub struct ReadScope;
+pub struct WriteScope;
+
+impl ScopeRequirement for ReadScope {
+    const SCOPE: ApiScope = ApiScope::Read;
+}
+
+impl ScopeRequirement for WriteScope {
+    const SCOPE: ApiScope = ApiScope::Write;
+}
+
+// Extractor for JSON API callers, authenticated by an `Authorization: Bearer` token or, failing
+// that, the session cookie. A session acts with full scope; a token must carry at least S's.
+pub struct ApiUser<S>(pub User, PhantomData<fn() -> S>);
+
+pub type ApiReader = ApiUser<ReadScope>;
+pub type ApiWriter = ApiUser<WriteScope>;
+
+#[axum::async_trait]
+impl<S: ScopeRequirement> FromRequestParts<Arc<AppState>> for ApiUser<S> {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let (user, scope) = match bearer_token(&parts.headers) {
+            Some(token) => get_user_by_api_token(&state.db_pool, token)
+                .await
+                .ok()
+                .flatten()
+                .ok_or_else(|| AppError::unauthorized("error.api_token_invalid"))?,
+            None => {
+                let jar = CookieJar::from_headers(&parts.headers);
+                let user = match jar.get(SESSION_COOKIE_NAME) {
+                    Some(cookie) => get_user_by_session(&state.db_pool, cookie.value()).await.ok().flatten(),
+                    None => None,
+                };
+                (user.ok_or_else(|| AppError::unauthorized("error.login_required"))?, ApiScope::Write)
+            }
+        };
+
+        if scope < S::SCOPE {
+            return Err(AppError::forbidden("error.api_scope_write"));
+        }
+        Ok(ApiUser(user, PhantomData))
+    }
+}
+
+// Who sent a request, by API token or session cookie, for routes that serve everyone but count
+// signed-in callers separately. An unknown token counts as nobody.
+pub async fn request_user(pool: &PgPool, headers: &HeaderMap) -> Option<User> {
+    match bearer_token(headers) {
+        Some(token) => get_user_by_api_token(pool, token).await.ok().flatten().map(|(user, _)| user),
+        None => {
+            let jar = CookieJar::from_headers(headers);
+            let cookie = jar.get(SESSION_COOKIE_NAME)?;
+            get_user_by_session(pool, cookie.value()).await.ok().flatten()
+        }
+    }
+}
+
+// The token from an `Authorization: Bearer <token>` header, if there is one
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+// Extractor for where a request came from, recorded with each audit log entry. The address is
+// resolved with `rate_limit::client_ip`, so it honours TRUSTED_PROXIES.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOrigin {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[axum::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestOrigin {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        Ok(RequestOrigin {
+            ip_address: rate_limit::client_ip(peer, &parts.headers).map(|ip| ip.to_string()),
+            user_agent: parts
+                .headers
+                .get(header::USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .map(|ua| ua.chars().take(USER_AGENT_MAX_CHARS).collect()),
+        })
+    }
+}
+
+// Check a new password against the strength rules, returning a user-facing reason on failure
+pub fn validate_password_strength(password: &str) -> std::result::Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!("Password must be at least {} characters", MIN_PASSWORD_LENGTH));
+    }
+    if !password.chars().any(|c| c.is_alphabetic()) || !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err("Password must contain both letters and numbers".to_string());
+    }
+    Ok(())
+}
+
 // Generate a session token
 pub fn generate_session_token() -> String {
     Uuid::new_v4().to_string()
@@ -49,14 +303,7 @@ pub async fn create_user(
     let user_id = Uuid::new_v4();
     let now = Utc::now();
 
-    // If pincode is provided but constituency_id is not, look up constituency
-    let final_constituency_id = if constituency_id.is_some() {
-        constituency_id
-    } else if let Some(pc) = pincode {
-        get_constituency_by_pincode(pool, pc).await?.map(|c| c.id)
-    } else {
-        None
-    };
+    let final_constituency_id = resolve_constituency(pool, pincode, constituency_id).await?;
 
     let user = sqlx::query_as::<_, User>(
         r#"
@@ -82,9 +329,126 @@ pub async fn create_user(
     Ok(user)
 }
 
-// Get user by username
+// The user an external account signs in as, if it has been linked or signed in before
+pub async fn get_user_by_identity(pool: &PgPool, provider: &str, subject: &str) -> Result<Option<User>> {
+    sqlx::query_as::<_, User>(
+        r#"
+        SELECT u.* FROM users u
+        INNER JOIN identities i ON u.id = i.user_id
+        WHERE i.provider = $1 AND i.subject = $2 AND u.deleted_at IS NULL
+        "#,
+    )
+    .bind(provider)
+    .bind(subject)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to fetch user by identity")
+}
+
+pub async fn get_identity(pool: &PgPool, user_id: Uuid, provider: &str) -> Result<Option<Identity>> {
+    sqlx::query_as::<_, Identity>("SELECT * FROM identities WHERE user_id = $1 AND provider = $2")
+        .bind(user_id)
+        .bind(provider)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to fetch identity")
+}
+
+// Link an external account to a user; returns false if that account is already linked to
+// someone, or the user already has one from this provider
+pub async fn link_identity(
+    pool: &PgPool,
+    user_id: Uuid,
+    provider: &str,
+    subject: &str,
+    email: Option<&str>,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO identities (user_id, provider, subject, email, created_at)
+        VALUES ($1, $2, $3, $4, NOW())
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(user_id)
+    .bind(provider)
+    .bind(subject)
+    .bind(email)
+    .execute(pool)
+    .await
+    .context("Failed to link identity")?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn unlink_identity(pool: &PgPool, user_id: Uuid, provider: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM identities WHERE user_id = $1 AND provider = $2")
+        .bind(user_id)
+        .bind(provider)
+        .execute(pool)
+        .await
+        .context("Failed to unlink identity")?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Create an account for a first sign-in with an external account, under `username_base` or,
+// if that's taken, the base with a numeric suffix. The account has no usable password until one
+// is set by reset, which works because a verified provider email becomes the recovery email.
+pub async fn create_user_from_identity(
+    pool: &PgPool,
+    provider: &str,
+    subject: &str,
+    email: Option<&str>,
+    username_base: &str,
+) -> Result<User> {
+    // Nobody knows this password; it only keeps password_hash a valid hash
+    let password_hash = hash_password(&generate_reset_token())?;
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+    let mut user = None;
+    for attempt in 0..10 {
+        let username = match attempt {
+            0 => username_base.to_string(),
+            _ => format!("{}{}", username_base, rand::random::<u16>() % 10_000),
+        };
+        // ON CONFLICT keeps a taken name from aborting the transaction
+        user = sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (id, username, password_hash, password_set, email, email_verified_at, created_at, updated_at)
+            VALUES ($1, $2, $3, FALSE, $4, CASE WHEN $4::TEXT IS NULL THEN NULL ELSE NOW() END, NOW(), NOW())
+            ON CONFLICT (username_canonical) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(&username)
+        .bind(&password_hash)
+        .bind(email)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to create user")?;
+
+        if user.is_some() {
+            break;
+        }
+    }
+    let user = user.context("No free username")?;
+
+    sqlx::query("INSERT INTO identities (user_id, provider, subject, email, created_at) VALUES ($1, $2, $3, $4, NOW())")
+        .bind(user.id)
+        .bind(provider)
+        .bind(subject)
+        .bind(email)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to link identity")?;
+
+    tx.commit().await.context("Failed to commit new user")?;
+    Ok(user)
+}
+
+// Get user by username, ignoring case
 pub async fn get_user_by_username(pool: &PgPool, username: &str) -> Result<Option<User>> {
-    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = $1")
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username_canonical = LOWER($1) AND deleted_at IS NULL")
         .bind(username)
         .fetch_optional(pool)
         .await
@@ -93,7 +457,6 @@ pub async fn get_user_by_username(pool: &PgPool, username: &str) -> Result<Optio
 }
 
 // Get user by ID
-#[allow(dead_code)]
 pub async fn get_user_by_id(pool: &PgPool, user_id: Uuid) -> Result<Option<User>> {
     let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
         .bind(user_id)
@@ -110,6 +473,7 @@ pub async fn authenticate_user(pool: &PgPool, username: &str, password: &str) ->
     match user {
         Some(u) => {
             if verify_password(password, &u.password_hash)? {
+                rehash_if_weak(pool, &u, password).await;
                 Ok(Some(u))
             } else {
                 Ok(None)
@@ -119,12 +483,58 @@ pub async fn authenticate_user(pool: &PgPool, username: &str, password: &str) ->
     }
 }
 
+// Upgrade a hash made with weaker parameters now that we have the plaintext. A failure is only
+// logged; the login itself has already succeeded.
+async fn rehash_if_weak(pool: &PgPool, user: &User, password: &str) {
+    if !needs_rehash(&user.password_hash, &argon2_params()) {
+        return;
+    }
+
+    let result = async {
+        let new_hash = hash_password(password)?;
+        // Only if the hash hasn't changed since it was checked, e.g. by a concurrent password change
+        sqlx::query("UPDATE users SET password_hash = $2 WHERE id = $1 AND password_hash = $3")
+            .bind(user.id)
+            .bind(&new_hash)
+            .bind(&user.password_hash)
+            .execute(pool)
+            .await
+            .context("Failed to store rehashed password")?;
+        anyhow::Ok(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => tracing::info!("Rehashed password for {} with the current Argon2 parameters", user.username),
+        Err(e) => tracing::warn!("Failed to rehash password for {}: {:#}", user.username, e),
+    }
+}
+
+fn days_from_env(name: &str, default: i64) -> i64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(default)
+}
+
+/// How long a session lasts without activity (SESSION_LIFETIME_DAYS, default 7)
+pub fn session_lifetime() -> Duration {
+    Duration::days(days_from_env("SESSION_LIFETIME_DAYS", 7))
+}
+
+/// How long activity can keep a session alive, counted from login (SESSION_MAX_AGE_DAYS,
+/// default 30); never shorter than the lifetime
+pub fn session_max_age() -> Duration {
+    Duration::days(days_from_env("SESSION_MAX_AGE_DAYS", 30)).max(session_lifetime())
+}
+
 // Create a session for a user
 pub async fn create_session(pool: &PgPool, user_id: Uuid) -> Result<Session> {
     let session_id = Uuid::new_v4();
     let session_token = generate_session_token();
-    let expires_at = Utc::now() + Duration::days(7); // Session valid for 7 days
     let now = Utc::now();
+    let expires_at = now + session_lifetime();
 
     let session = sqlx::query_as::<_, Session>(
         r#"
@@ -151,7 +561,7 @@ pub async fn get_user_by_session(pool: &PgPool, session_token: &str) -> Result<O
         r#"
         SELECT u.* FROM users u
         INNER JOIN sessions s ON u.id = s.user_id
-        WHERE s.session_token = $1 AND s.expires_at > NOW()
+        WHERE s.session_token = $1 AND s.expires_at > NOW() AND u.suspended_at IS NULL
         "#,
     )
     .bind(session_token)
@@ -162,6 +572,30 @@ pub async fn get_user_by_session(pool: &PgPool, session_token: &str) -> Result<O
     Ok(user)
 }
 
+// Once a session is past half its lifetime, push its expiry to a full lifetime from now, but never
+// past the max age from login. Returns the new expiry when it moved, so the cookie can follow.
+pub async fn extend_session(pool: &PgPool, session_token: &str) -> Result<Option<chrono::DateTime<Utc>>> {
+    let expires_at: Option<(chrono::DateTime<Utc>,)> = sqlx::query_as(
+        r#"
+        UPDATE sessions
+        SET expires_at = LEAST(NOW() + $2, created_at + $3)
+        WHERE session_token = $1
+            AND expires_at > NOW()
+            AND expires_at < NOW() + $2 / 2
+            AND expires_at < created_at + $3
+        RETURNING expires_at
+        "#,
+    )
+    .bind(session_token)
+    .bind(session_lifetime())
+    .bind(session_max_age())
+    .fetch_optional(pool)
+    .await
+    .context("Failed to extend session")?;
+
+    Ok(expires_at.map(|(e,)| e))
+}
+
 // Delete session (logout)
 pub async fn delete_session(pool: &PgPool, session_token: &str) -> Result<()> {
     sqlx::query("DELETE FROM sessions WHERE session_token = $1")
@@ -172,6 +606,490 @@ pub async fn delete_session(pool: &PgPool, session_token: &str) -> Result<()> {
     Ok(())
 }
 
+// List a user's unexpired sessions, newest first
+pub async fn list_sessions(pool: &PgPool, user_id: Uuid) -> Result<Vec<Session>> {
+    let sessions = sqlx::query_as::<_, Session>(
+        r#"
+        SELECT * FROM sessions
+        WHERE user_id = $1 AND expires_at > NOW()
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list sessions")?;
+
+    Ok(sessions)
+}
+
+// Delete one of a user's sessions by ID; returns false if it doesn't belong to them
+pub async fn delete_user_session(pool: &PgPool, user_id: Uuid, session_id: Uuid) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM sessions WHERE id = $1 AND user_id = $2")
+        .bind(session_id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .context("Failed to delete session")?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Delete sessions past their expiry; they no longer authenticate, so this only reclaims space
+pub async fn delete_expired_sessions(pool: &PgPool) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM sessions WHERE expires_at <= NOW()")
+        .execute(pool)
+        .await
+        .context("Failed to delete expired sessions")?;
+    Ok(result.rows_affected())
+}
+
+// Delete every session for a user, except the one identified by except_token if given.
+// Returns how many were deleted.
+pub async fn delete_sessions_for_user(pool: &PgPool, user_id: Uuid, except_token: Option<&str>) -> Result<u64> {
+    let result = sqlx::query(
+        "DELETE FROM sessions WHERE user_id = $1 AND ($2::text IS NULL OR session_token <> $2)",
+    )
+    .bind(user_id)
+    .bind(except_token)
+    .execute(pool)
+    .await
+    .context("Failed to delete sessions")?;
+    Ok(result.rows_affected())
+}
+
+// Replace a user's password hash and revoke their API tokens, which may have been made by
+// whoever knew the old password. Returns how many tokens were revoked.
+pub async fn update_password(pool: &PgPool, user_id: Uuid, new_password: &str) -> Result<u64> {
+    let password_hash = hash_password(new_password)?;
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+    sqlx::query("UPDATE users SET password_hash = $2, password_set = TRUE, updated_at = $3 WHERE id = $1")
+        .bind(user_id)
+        .bind(&password_hash)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to update password")?;
+
+    let tokens = sqlx::query("DELETE FROM api_tokens WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to revoke API tokens")?;
+
+    tx.commit().await.context("Failed to commit password change")?;
+    Ok(tokens.rows_affected())
+}
+
+// Delete an account: drop sessions and personal data, and anonymize the user row.
+// Posts stay attributed to the anonymized username unless delete_posts is set.
+pub async fn delete_account(pool: &PgPool, user_id: Uuid, delete_posts: bool) -> Result<()> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+    for table in [
+        "sessions",
+        "api_tokens",
+        "identities",
+        "rate_limits",
+        "bill_subscriptions",
+        "bookmarks",
+        "notifications",
+        "email_verifications",
+        "password_resets",
+    ] {
+        sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to delete {}", table))?;
+    }
+
+    if delete_posts {
+        sqlx::query(
+            r#"
+            UPDATE posts SET content = '[deleted]', deleted_at = $2, updated_at = $2
+            WHERE user_id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to delete posts")?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET username = 'deleted_user_' || LEFT(id::text, 8), password_hash = '',
+            real_name = NULL, age = NULL, gender = NULL, pincode = NULL, constituency_id = NULL,
+            email = NULL, email_verified_at = NULL, role = 'user', deleted_at = $2, updated_at = $2
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(now)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to anonymize user")?;
+
+    tx.commit().await.context("Failed to commit account deletion")?;
+    Ok(())
+}
+
+// Basic shape check for an email address; delivery of the verification link is the real test
+pub fn is_valid_email(email: &str) -> bool {
+    email.len() <= 254
+        && !email.chars().any(char::is_whitespace)
+        && match email.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain.contains('.')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+            }
+            None => false,
+        }
+}
+
+// Set or remove a user's email; a changed address starts out unverified
+pub async fn set_email(pool: &PgPool, user_id: Uuid, email: Option<&str>) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET email = $2,
+            email_verified_at = CASE WHEN email IS NOT DISTINCT FROM $2 THEN email_verified_at END,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(email)
+    .execute(pool)
+    .await
+    .context("Failed to update email")?;
+
+    Ok(())
+}
+
+// Turn the weekly digest email on or off
+pub async fn set_digest_enabled(pool: &PgPool, user_id: Uuid, enabled: bool) -> Result<()> {
+    sqlx::query("UPDATE users SET digest_enabled = $2, updated_at = NOW() WHERE id = $1")
+        .bind(user_id)
+        .bind(enabled)
+        .execute(pool)
+        .await
+        .context("Failed to update digest preference")?;
+
+    Ok(())
+}
+
+// Create a verification token for the user's current email, replacing any earlier ones
+pub async fn create_email_verification(pool: &PgPool, user_id: Uuid, email: &str) -> Result<EmailVerification> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+    sqlx::query("DELETE FROM email_verifications WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to clear old email verifications")?;
+
+    let verification = sqlx::query_as::<_, EmailVerification>(
+        r#"
+        INSERT INTO email_verifications (token, user_id, email, expires_at, created_at)
+        VALUES ($1, $2, $3, $4, NOW())
+        RETURNING *
+        "#,
+    )
+    .bind(generate_session_token())
+    .bind(user_id)
+    .bind(email)
+    .bind(Utc::now() + Duration::hours(EMAIL_VERIFICATION_HOURS))
+    .fetch_one(&mut *tx)
+    .await
+    .context("Failed to create email verification")?;
+
+    tx.commit().await.context("Failed to commit email verification")?;
+    Ok(verification)
+}
+
+// Confirm an email from its token. Returns the user, or None if the token is unknown, expired,
+// or was sent to an address the user has since changed.
+pub async fn verify_email(pool: &PgPool, token: &str) -> Result<Option<User>> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+    let verification = sqlx::query_as::<_, EmailVerification>(
+        "DELETE FROM email_verifications WHERE token = $1 RETURNING *",
+    )
+    .bind(token)
+    .fetch_optional(&mut *tx)
+    .await
+    .context("Failed to look up email verification")?;
+
+    let Some(verification) = verification.filter(|v| v.expires_at > Utc::now()) else {
+        tx.commit().await.context("Failed to commit email verification")?;
+        return Ok(None);
+    };
+
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users SET email_verified_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND email = $2 AND deleted_at IS NULL
+        RETURNING *
+        "#,
+    )
+    .bind(verification.user_id)
+    .bind(&verification.email)
+    .fetch_optional(&mut *tx)
+    .await
+    .context("Failed to mark email verified")?;
+
+    tx.commit().await.context("Failed to commit email verification")?;
+    Ok(user)
+}
+
+// Accounts whose confirmed email matches; unverified addresses are never looked up
+pub async fn get_users_by_verified_email(pool: &PgPool, email: &str) -> Result<Vec<User>> {
+    sqlx::query_as::<_, User>(
+        r#"
+        SELECT * FROM users
+        WHERE LOWER(email) = LOWER($1) AND email_verified_at IS NOT NULL
+          AND deleted_at IS NULL AND suspended_at IS NULL
+        "#,
+    )
+    .bind(email)
+    .fetch_all(pool)
+    .await
+    .context("Failed to look up users by email")
+}
+
+// Reset tokens are stored hashed, so a leaked table cannot be used to take over accounts
+fn hash_reset_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Create a password reset token, replacing any earlier ones; returns the token to email
+pub async fn create_password_reset(pool: &PgPool, user_id: Uuid) -> Result<String> {
+    let token = generate_reset_token();
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+    sqlx::query("DELETE FROM password_resets WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to clear old password resets")?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO password_resets (token_hash, user_id, expires_at, created_at)
+        VALUES ($1, $2, $3, NOW())
+        "#,
+    )
+    .bind(hash_reset_token(&token))
+    .bind(user_id)
+    .bind(Utc::now() + Duration::minutes(PASSWORD_RESET_MINUTES))
+    .execute(&mut *tx)
+    .await
+    .context("Failed to create password reset")?;
+
+    tx.commit().await.context("Failed to commit password reset")?;
+    Ok(token)
+}
+
+// Two UUIDs' worth of randomness, since a reset token is as good as the password
+fn generate_reset_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+// Whether a reset token exists and has not expired
+pub async fn password_reset_is_valid(pool: &PgPool, token: &str) -> Result<bool> {
+    let found: Option<(Uuid,)> =
+        sqlx::query_as("SELECT user_id FROM password_resets WHERE token_hash = $1 AND expires_at > NOW()")
+            .bind(hash_reset_token(token))
+            .fetch_optional(pool)
+            .await
+            .context("Failed to check password reset")?;
+
+    Ok(found.is_some())
+}
+
+// Use a reset token: set the new password, sign the user out everywhere, revoke their API tokens
+// and consume the token. Returns the account's id, or None if the token is unknown or expired.
+pub async fn reset_password(pool: &PgPool, token: &str, new_password: &str) -> Result<Option<Uuid>> {
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+    let reset: Option<(Uuid,)> = sqlx::query_as(
+        "DELETE FROM password_resets WHERE token_hash = $1 AND expires_at > NOW() RETURNING user_id",
+    )
+    .bind(hash_reset_token(token))
+    .fetch_optional(&mut *tx)
+    .await
+    .context("Failed to consume password reset")?;
+
+    let Some((user_id,)) = reset else {
+        return Ok(None);
+    };
+
+    // Hashed only once the token checks out, so junk tokens don't each cost an Argon2 run
+    let password_hash = hash_password(new_password)?;
+    sqlx::query("UPDATE users SET password_hash = $2, password_set = TRUE, updated_at = NOW() WHERE id = $1")
+        .bind(user_id)
+        .bind(&password_hash)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to update password")?;
+
+    for table in ["sessions", "api_tokens", "password_resets"] {
+        sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to delete {}", table))?;
+    }
+
+    tx.commit().await.context("Failed to commit password reset")?;
+    Ok(Some(user_id))
+}
+
+// Create an API token for a user; returns the token, which is only ever shown this once
+pub async fn create_api_token(pool: &PgPool, user_id: Uuid, name: &str, scope: ApiScope) -> Result<String> {
+    let token = format!("{}{}", API_TOKEN_PREFIX, generate_reset_token());
+
+    sqlx::query(
+        r#"
+        INSERT INTO api_tokens (user_id, token_hash, name, scope, created_at)
+        VALUES ($1, $2, $3, $4, NOW())
+        "#,
+    )
+    .bind(user_id)
+    .bind(hash_reset_token(&token))
+    .bind(name)
+    .bind(scope.as_str())
+    .execute(pool)
+    .await
+    .context("Failed to create API token")?;
+
+    Ok(token)
+}
+
+pub async fn list_api_tokens(pool: &PgPool, user_id: Uuid) -> Result<Vec<ApiToken>> {
+    sqlx::query_as::<_, ApiToken>("SELECT * FROM api_tokens WHERE user_id = $1 ORDER BY created_at DESC")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list API tokens")
+}
+
+// Revoke one of a user's API tokens; returns false if it doesn't belong to them
+pub async fn delete_api_token(pool: &PgPool, user_id: Uuid, token_id: Uuid) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM api_tokens WHERE id = $1 AND user_id = $2")
+        .bind(token_id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .context("Failed to delete API token")?;
+    Ok(result.rows_affected() > 0)
+}
+
+// The owner of an API token and the token's scope. Suspended and deleted accounts don't
+// authenticate, just as their sessions don't.
+pub async fn get_user_by_api_token(pool: &PgPool, token: &str) -> Result<Option<(User, ApiScope)>> {
+    let Some(api_token) = sqlx::query_as::<_, ApiToken>("SELECT * FROM api_tokens WHERE token_hash = $1")
+        .bind(hash_reset_token(token))
+        .fetch_optional(pool)
+        .await
+        .context("Failed to look up API token")?
+    else {
+        return Ok(None);
+    };
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT * FROM users WHERE id = $1 AND suspended_at IS NULL AND deleted_at IS NULL",
+    )
+    .bind(api_token.user_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to fetch API token owner")?;
+    let Some(user) = user else {
+        return Ok(None);
+    };
+
+    if api_token_needs_touch(api_token.last_used_at, Utc::now()) {
+        // The guard repeats the check so concurrent requests write at most once between them
+        if let Err(e) = sqlx::query(
+            r#"
+            UPDATE api_tokens SET last_used_at = NOW()
+            WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - $2)
+            "#,
+        )
+        .bind(api_token.id)
+        .bind(Duration::seconds(API_TOKEN_TOUCH_SECS))
+        .execute(pool)
+        .await
+        {
+            tracing::warn!("Failed to record API token use: {}", e);
+        }
+    }
+
+    Ok(Some((user, api_token.scope())))
+}
+
+fn api_token_needs_touch(last_used_at: Option<chrono::DateTime<Utc>>, now: chrono::DateTime<Utc>) -> bool {
+    last_used_at.is_none_or(|t| now - t >= Duration::seconds(API_TOKEN_TOUCH_SECS))
+}
+
+// Change a user's role
+pub async fn set_role(pool: &PgPool, user_id: Uuid, role: Role) -> Result<bool> {
+    let result = sqlx::query("UPDATE users SET role = $2, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL")
+        .bind(user_id)
+        .bind(role.as_str())
+        .execute(pool)
+        .await
+        .context("Failed to update user role")?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Scale a user's rate limits, 0 exempting them; None goes back to their role's default
+pub async fn set_rate_limit_multiplier(pool: &PgPool, user_id: Uuid, multiplier: Option<i32>) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE users SET rate_limit_multiplier = $2, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(user_id)
+    .bind(multiplier)
+    .execute(pool)
+    .await
+    .context("Failed to update rate limit multiplier")?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Suspend or reinstate a user; suspending also ends all of their sessions
+pub async fn set_user_suspended(pool: &PgPool, user_id: Uuid, suspended: bool) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE users
+        SET suspended_at = CASE WHEN $2 THEN NOW() ELSE NULL END, updated_at = NOW()
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(suspended)
+    .execute(pool)
+    .await
+    .context("Failed to update suspension")?;
+
+    if suspended {
+        sqlx::query("DELETE FROM sessions WHERE user_id = $1")
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .context("Failed to end sessions of suspended user")?;
+    }
+
+    Ok(result.rows_affected() > 0)
+}
+
 // Update user profile
 pub async fn update_user_profile(
     pool: &PgPool,
@@ -183,15 +1101,7 @@ pub async fn update_user_profile(
     constituency_id: Option<i32>,
 ) -> Result<User> {
     let now = Utc::now();
-
-    // If pincode is provided but constituency_id is not, look up constituency
-    let final_constituency_id = if constituency_id.is_some() {
-        constituency_id
-    } else if let Some(pc) = pincode {
-        get_constituency_by_pincode(pool, pc).await?.map(|c| c.id)
-    } else {
-        None
-    };
+    let final_constituency_id = resolve_constituency(pool, pincode, constituency_id).await?;
 
     let user = sqlx::query_as::<_, User>(
         r#"
@@ -215,6 +1125,27 @@ pub async fn update_user_profile(
     Ok(user)
 }
 
+// Update which personal fields other users can see
+pub async fn update_profile_visibility(pool: &PgPool, user_id: Uuid, visibility: &ProfileVisibility) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET real_name_public = $2, age_public = $3, gender_public = $4, pincode_public = $5, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(visibility.real_name_public)
+    .bind(visibility.age_public)
+    .bind(visibility.gender_public)
+    .bind(visibility.pincode_public)
+    .execute(pool)
+    .await
+    .context("Failed to update profile visibility")?;
+
+    Ok(())
+}
+
 // Get all constituencies
 pub async fn get_all_constituencies(pool: &PgPool) -> Result<Vec<Constituency>> {
     let constituencies = sqlx::query_as::<_, Constituency>(
@@ -241,6 +1172,68 @@ pub async fn get_constituency_by_id(pool: &PgPool, id: i32) -> Result<Option<Con
     Ok(constituency)
 }
 
+// Why a pincode given instead of a constituency can't be used. Returned inside the anyhow error
+// from create_user and update_user_profile, so handlers can downcast it into a form error.
+#[derive(Debug, thiserror::Error)]
+pub enum PincodeError {
+    #[error("pincode must be 6 digits")]
+    InvalidFormat,
+    #[error("no constituency is mapped to pincode {0}")]
+    Unmapped(String),
+}
+
+pub fn is_valid_pincode(pincode: &str) -> bool {
+    pincode.len() == 6 && pincode.bytes().all(|b| b.is_ascii_digit())
+}
+
+// The constituency to store: the one chosen, else the one the pincode maps to
+async fn resolve_constituency(pool: &PgPool, pincode: Option<&str>, constituency_id: Option<i32>) -> Result<Option<i32>> {
+    if constituency_id.is_some() {
+        return Ok(constituency_id);
+    }
+    let Some(pincode) = pincode else {
+        return Ok(None);
+    };
+    if !is_valid_pincode(pincode) {
+        return Err(PincodeError::InvalidFormat.into());
+    }
+
+    match get_constituency_by_pincode(pool, pincode).await? {
+        Some(constituency) => Ok(Some(constituency.id)),
+        None => Err(PincodeError::Unmapped(pincode.to_string()).into()),
+    }
+}
+
+// Constituencies of mapped pincodes sharing the longest possible prefix with an unmapped one,
+// down to the 3-digit sorting district. Empty for malformed pincodes.
+pub async fn suggest_constituencies_for_pincode(pool: &PgPool, pincode: &str) -> Result<Vec<Constituency>> {
+    if !is_valid_pincode(pincode) {
+        return Ok(vec![]);
+    }
+
+    for prefix_len in (3..6).rev() {
+        let constituencies = sqlx::query_as::<_, Constituency>(
+            r#"
+            SELECT c.* FROM constituencies c
+            WHERE c.id IN (SELECT constituency_id FROM pincode_constituencies WHERE pincode LIKE $1 || '%')
+            ORDER BY c.name
+            LIMIT $2
+            "#,
+        )
+        .bind(&pincode[..prefix_len])
+        .bind(PINCODE_SUGGESTIONS)
+        .fetch_all(pool)
+        .await
+        .context("Failed to suggest constituencies")?;
+
+        if !constituencies.is_empty() {
+            return Ok(constituencies);
+        }
+    }
+
+    Ok(vec![])
+}
+
 // Get constituency by pincode
 pub async fn get_constituency_by_pincode(pool: &PgPool, pincode: &str) -> Result<Option<Constituency>> {
     let constituency = sqlx::query_as::<_, Constituency>(
@@ -258,9 +1251,9 @@ pub async fn get_constituency_by_pincode(pool: &PgPool, pincode: &str) -> Result
     Ok(constituency)
 }
 
-// Check if username exists
+// Check if username exists, ignoring case, so "Asha" is taken once "asha" is
 pub async fn username_exists(pool: &PgPool, username: &str) -> Result<bool> {
-    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE username = $1")
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE username_canonical = LOWER($1)")
         .bind(username)
         .fetch_one(pool)
         .await
@@ -269,3 +1262,232 @@ pub async fn username_exists(pool: &PgPool, username: &str) -> Result<bool> {
     Ok(count.0 > 0)
 }
 
+// Append an entry to the audit log. `actor` is the signed-in user who acted, if any, and `target`
+// the user or post acted on. A failed write is logged rather than returned, so a problem with the
+// audit log never blocks the action it describes.
+pub async fn audit(
+    pool: &PgPool,
+    actor: Option<Uuid>,
+    origin: &RequestOrigin,
+    action: AuditAction,
+    target: Option<Uuid>,
+    metadata: serde_json::Value,
+) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO audit_log (actor_id, action, target_id, ip_address, user_agent, metadata, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, NOW())
+        "#,
+    )
+    .bind(actor)
+    .bind(action.as_str())
+    .bind(target)
+    .bind(origin.ip_address.as_deref())
+    .bind(origin.user_agent.as_deref())
+    .bind(metadata)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to write audit log entry {}: {}", action.as_str(), e);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_strength_rules() {
+        assert!(validate_password_strength("short1").is_err());
+        assert!(validate_password_strength("onlyletters").is_err());
+        assert!(validate_password_strength("12345678").is_err());
+        assert!(validate_password_strength("letters4you").is_ok());
+    }
+
+    fn user_with_role(role: Role) -> User {
+        User {
+            id: Uuid::new_v4(),
+            username: "meera".to_string(),
+            password_hash: String::new(),
+            password_set: true,
+            real_name: None,
+            age: None,
+            gender: None,
+            pincode: None,
+            constituency_id: None,
+            real_name_public: false,
+            age_public: false,
+            gender_public: false,
+            pincode_public: false,
+            role: role.as_str().to_string(),
+            suspended_at: None,
+            rate_limit_multiplier: None,
+            email: None,
+            email_verified_at: None,
+            digest_enabled: true,
+            digest_sent_at: None,
+            deleted_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_require_role_rejects_users_without_the_role() {
+        let page = HeaderMap::new();
+
+        let admin = authorize(Some(user_with_role(Role::Admin)), Admin::ROLE, "/admin/users", &page);
+        assert!(admin.is_ok());
+        assert!(authorize(Some(user_with_role(Role::Admin)), Moderator::ROLE, "/admin/moderation", &page).is_ok());
+
+        let user = authorize(Some(user_with_role(Role::User)), Admin::ROLE, "/admin/users", &page).unwrap_err();
+        assert_eq!(user.status(), StatusCode::FORBIDDEN);
+        let moderator = authorize(Some(user_with_role(Role::Moderator)), Admin::ROLE, "/admin/users", &page).unwrap_err();
+        assert_eq!(moderator.status(), StatusCode::FORBIDDEN);
+
+        let mut suspended = user_with_role(Role::Admin);
+        suspended.suspended_at = Some(Utc::now());
+        let suspended = authorize(Some(suspended), Admin::ROLE, "/admin/users", &page).unwrap_err();
+        assert_eq!(suspended.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_rate_limit_overrides() {
+        let limits = rate_limit::RateLimitConfig::default();
+        let posts = |user: &User| limits.limit_for(user, rate_limit::ActionType::PostCreate).map(|l| l.limit);
+
+        // Staff are exempt unless an admin says otherwise
+        assert_eq!(posts(&user_with_role(Role::User)), Some(5));
+        assert_eq!(posts(&user_with_role(Role::Moderator)), None);
+        assert_eq!(posts(&user_with_role(Role::Admin)), None);
+
+        let mut moderator = user_with_role(Role::Moderator);
+        moderator.rate_limit_multiplier = Some(1);
+        assert_eq!(posts(&moderator), Some(5));
+
+        let mut trusted = user_with_role(Role::User);
+        trusted.rate_limit_multiplier = Some(3);
+        assert_eq!(posts(&trusted), Some(15));
+        trusted.rate_limit_multiplier = Some(0);
+        assert_eq!(posts(&trusted), None);
+    }
+
+    #[test]
+    fn test_require_role_signed_out_response_depends_on_client() {
+        let page = authorize(None, Admin::ROLE, "/admin/users", &HeaderMap::new()).unwrap_err();
+        assert_eq!(page.status(), StatusCode::SEE_OTHER);
+        assert_eq!(page.headers()[header::LOCATION], "/login");
+
+        let api = authorize(None, Admin::ROLE, "/api/v1/admin/stats", &HeaderMap::new()).unwrap_err();
+        assert_eq!(api.status(), StatusCode::UNAUTHORIZED);
+
+        let mut json = HeaderMap::new();
+        json.insert(header::ACCEPT, "application/json".parse().unwrap());
+        let json = authorize(None, Admin::ROLE, "/admin/users", &json).unwrap_err();
+        assert_eq!(json.status(), StatusCode::UNAUTHORIZED);
+
+        let mut htmx = HeaderMap::new();
+        htmx.insert("HX-Request", "true".parse().unwrap());
+        let htmx = authorize(None, Moderator::ROLE, "/admin/posts/1/approve", &htmx).unwrap_err();
+        assert_eq!(htmx.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(htmx.headers()["HX-Redirect"], "/login");
+    }
+
+    #[test]
+    fn test_weaker_hashes_still_verify_and_are_rehashed() {
+        let weak = Params::new(1024, 1, 1, None).unwrap();
+        let current = Params::new(2048, 2, 1, None).unwrap();
+
+        let old_hash = hash_password_with("letters4you", weak.clone()).unwrap();
+        assert!(verify_password("letters4you", &old_hash).unwrap());
+        assert!(!verify_password("wrong4you", &old_hash).unwrap());
+        assert!(needs_rehash(&old_hash, &current));
+
+        let new_hash = hash_password_with("letters4you", current.clone()).unwrap();
+        assert!(new_hash.contains("m=2048,t=2,p=1"));
+        assert!(verify_password("letters4you", &new_hash).unwrap());
+        assert!(!needs_rehash(&new_hash, &current));
+        assert!(!needs_rehash(&new_hash, &weak), "stronger hashes are kept");
+
+        let argon2i = Argon2::new(Algorithm::Argon2i, Version::V0x13, current.clone())
+            .hash_password(b"letters4you", &SaltString::generate(&mut OsRng))
+            .unwrap()
+            .to_string();
+        assert!(verify_password("letters4you", &argon2i).unwrap());
+        assert!(needs_rehash(&argon2i, &current));
+    }
+
+    #[test]
+    fn test_admin_satisfies_moderator_requirement() {
+        assert!(Role::Admin >= Moderator::ROLE);
+        assert!(Role::Moderator >= Moderator::ROLE);
+        assert!(Role::User < Moderator::ROLE);
+        assert!(Role::Moderator < Admin::ROLE);
+        assert_eq!(Role::parse("moderator"), Some(Role::Moderator));
+        assert_eq!(Role::parse("owner"), None);
+    }
+
+    #[test]
+    fn test_reset_tokens_are_stored_hashed() {
+        let token = generate_reset_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_reset_token());
+
+        let hashed = hash_reset_token(&token);
+        assert_eq!(hashed.len(), 64);
+        assert_ne!(hashed, token);
+        assert_eq!(hashed, hash_reset_token(&token));
+    }
+
+    #[test]
+    fn test_bearer_token_and_scopes() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, "Bearer rue_abc123".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("rue_abc123"));
+        headers.insert(header::AUTHORIZATION, "bearer  rue_abc123 ".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("rue_abc123"));
+        headers.insert(header::AUTHORIZATION, "Basic dXNlcjpwYXNz".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+        headers.insert(header::AUTHORIZATION, "Bearer ".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+
+        assert!(ApiScope::Read < WriteScope::SCOPE);
+        assert!(ApiScope::Write >= ReadScope::SCOPE);
+        assert_eq!(ApiScope::parse("write"), Some(ApiScope::Write));
+        assert_eq!(ApiScope::parse("admin"), None);
+    }
+
+    #[test]
+    fn test_api_token_use_is_recorded_at_most_once_a_minute() {
+        let now = Utc::now();
+        assert!(api_token_needs_touch(None, now));
+        assert!(!api_token_needs_touch(Some(now - Duration::seconds(30)), now));
+        assert!(api_token_needs_touch(Some(now - Duration::seconds(API_TOKEN_TOUCH_SECS)), now));
+    }
+
+    #[test]
+    fn test_pincode_format() {
+        assert!(is_valid_pincode("110001"));
+        assert!(!is_valid_pincode("11001"));
+        assert!(!is_valid_pincode("1100011"));
+        assert!(!is_valid_pincode("11000a"));
+        assert!(!is_valid_pincode("११०००१"));
+        assert!(!is_valid_pincode(""));
+    }
+
+    #[test]
+    fn test_email_shape() {
+        assert!(is_valid_email("citizen@example.in"));
+        assert!(is_valid_email("first.last+forum@mail.example.org"));
+        assert!(!is_valid_email("citizen"));
+        assert!(!is_valid_email("@example.in"));
+        assert!(!is_valid_email("citizen@localhost"));
+        assert!(!is_valid_email("citizen@example.in."));
+        assert!(!is_valid_email("a@b@example.in"));
+        assert!(!is_valid_email("citizen @example.in"));
+    }
+}
@@ -0,0 +1,379 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use parking_lot::Mutex;
+use rand::RngCore;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// How long a proof-of-work challenge can be redeemed; long enough to fill in the form
+const POW_TTL_SECS: i64 = 30 * 60;
+
+/// What the registration form renders for the configured challenge
+#[derive(Default)]
+pub struct ChallengeWidget {
+    pub captcha: Option<CaptchaWidget>,
+    pub proof_of_work: Option<ProofOfWorkWidget>,
+}
+
+impl ChallengeWidget {
+    /// Whether the form has to wait for a challenge answer before it can be submitted
+    pub fn is_active(&self) -> bool {
+        self.captcha.is_some() || self.proof_of_work.is_some()
+    }
+}
+
+/// A third-party CAPTCHA: the provider's script, the element class it renders into, and the site key
+pub struct CaptchaWidget {
+    pub script: &'static str,
+    pub class: &'static str,
+    pub site_key: String,
+}
+
+/// A signed challenge the browser solves before submitting
+pub struct ProofOfWorkWidget {
+    pub challenge: String,
+    pub difficulty: u32,
+}
+
+/// Bot check on registration; the web server holds one behind an Arc
+#[async_trait]
+pub trait Challenge: Send + Sync {
+    /// A fresh challenge for the registration form
+    fn widget(&self) -> ChallengeWidget;
+
+    /// Whether the form's challenge_response is a valid answer
+    async fn verify(&self, response: &str, remote_ip: Option<&str>) -> Result<bool>;
+
+    /// Origins the widget loads scripts, frames and requests from, allowed by the CSP
+    fn origins(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Accepts every registration, for local development and tests
+pub struct NoChallenge;
+
+#[async_trait]
+impl Challenge for NoChallenge {
+    fn widget(&self) -> ChallengeWidget {
+        ChallengeWidget::default()
+    }
+
+    async fn verify(&self, _response: &str, _remote_ip: Option<&str>) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum CaptchaProvider {
+    HCaptcha,
+    Turnstile,
+}
+
+impl CaptchaProvider {
+    fn script(self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "https://js.hcaptcha.com/1/api.js",
+            CaptchaProvider::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/api.js",
+        }
+    }
+
+    fn origins(self) -> &'static [&'static str] {
+        match self {
+            CaptchaProvider::HCaptcha => &["https://hcaptcha.com", "https://*.hcaptcha.com"],
+            CaptchaProvider::Turnstile => &["https://challenges.cloudflare.com"],
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "h-captcha",
+            CaptchaProvider::Turnstile => "cf-turnstile",
+        }
+    }
+
+    fn verify_url(self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "https://api.hcaptcha.com/siteverify",
+            CaptchaProvider::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+        }
+    }
+
+    fn env_prefix(self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "HCAPTCHA",
+            CaptchaProvider::Turnstile => "TURNSTILE",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+}
+
+/// hCaptcha or Cloudflare Turnstile, checked against the provider's siteverify endpoint
+pub struct CaptchaChallenge {
+    provider: CaptchaProvider,
+    site_key: String,
+    secret_key: String,
+    http: reqwest::Client,
+}
+
+impl CaptchaChallenge {
+    /// Keys come from HCAPTCHA_SITE_KEY/HCAPTCHA_SECRET_KEY or TURNSTILE_SITE_KEY/TURNSTILE_SECRET_KEY
+    pub fn from_env(provider: CaptchaProvider) -> Result<Self> {
+        let var = |suffix: &str| {
+            let name = format!("{}_{}", provider.env_prefix(), suffix);
+            std::env::var(&name).with_context(|| format!("{} is not set", name))
+        };
+
+        Ok(Self {
+            provider,
+            site_key: var("SITE_KEY")?,
+            secret_key: var("SECRET_KEY")?,
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Challenge for CaptchaChallenge {
+    fn widget(&self) -> ChallengeWidget {
+        ChallengeWidget {
+            captcha: Some(CaptchaWidget {
+                script: self.provider.script(),
+                class: self.provider.class(),
+                site_key: self.site_key.clone(),
+            }),
+            proof_of_work: None,
+        }
+    }
+
+    fn origins(&self) -> &'static [&'static str] {
+        self.provider.origins()
+    }
+
+    async fn verify(&self, response: &str, remote_ip: Option<&str>) -> Result<bool> {
+        if response.is_empty() {
+            return Ok(false);
+        }
+
+        let mut params = vec![("secret", self.secret_key.as_str()), ("response", response)];
+        if let Some(ip) = remote_ip {
+            params.push(("remoteip", ip));
+        }
+
+        let result: SiteVerifyResponse = self
+            .http
+            .post(self.provider.verify_url())
+            .form(&params)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {:?} siteverify", self.provider))?
+            .json()
+            .await
+            .context("Invalid siteverify response")?;
+
+        Ok(result.success)
+    }
+}
+
+/// Built-in proof of work: the browser finds a nonce such that SHA-256("<challenge>:<nonce>")
+/// starts with `difficulty` zero bits. Challenges are signed instead of stored, so serving the
+/// form costs nothing; only redeemed ones are remembered, to stop an answer being reused.
+pub struct ProofOfWork {
+    key: [u8; 32],
+    difficulty: u32,
+    // Redeemed challenges and when they were issued, kept until they expire anyway
+    redeemed: Mutex<HashMap<String, i64>>,
+}
+
+impl ProofOfWork {
+    /// Signed with a key generated at startup, so challenges don't survive a restart
+    pub fn new(difficulty: u32) -> Self {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        Self {
+            key,
+            difficulty,
+            redeemed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn sign(&self, payload: &str) -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(payload.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn issue(&self, now: i64) -> String {
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let payload = format!("{}.{}", now, to_hex(&nonce));
+        format!("{}.{}", payload, to_hex(&self.sign(&payload)))
+    }
+
+    fn check(&self, response: &str, now: i64) -> bool {
+        let Some((challenge, nonce)) = response.rsplit_once(':') else {
+            return false;
+        };
+        if nonce.is_empty() || nonce.len() > 20 || !nonce.bytes().all(|b| b.is_ascii_digit()) {
+            return false;
+        }
+
+        let Some((payload, signature)) = challenge.rsplit_once('.') else {
+            return false;
+        };
+        let Some(issued) = payload.split_once('.').and_then(|(issued, _)| issued.parse::<i64>().ok()) else {
+            return false;
+        };
+        // `issued` is unverified here, so the subtraction mustn't overflow on a forged one
+        if !now.checked_sub(issued).is_some_and(|age| (0..=POW_TTL_SECS).contains(&age)) {
+            return false;
+        }
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(payload.as_bytes());
+        if !from_hex(signature).is_some_and(|sig| mac.verify_slice(&sig).is_ok()) {
+            return false;
+        }
+
+        if leading_zero_bits(&Sha256::digest(response.as_bytes())) < self.difficulty {
+            return false;
+        }
+
+        let mut redeemed = self.redeemed.lock();
+        redeemed.retain(|_, issued| now - *issued <= POW_TTL_SECS);
+        redeemed.insert(challenge.to_string(), issued).is_none()
+    }
+}
+
+#[async_trait]
+impl Challenge for ProofOfWork {
+    fn widget(&self) -> ChallengeWidget {
+        ChallengeWidget {
+            captcha: None,
+            proof_of_work: Some(ProofOfWorkWidget {
+                challenge: self.issue(chrono::Utc::now().timestamp()),
+                difficulty: self.difficulty,
+            }),
+        }
+    }
+
+    async fn verify(&self, response: &str, _remote_ip: Option<&str>) -> Result<bool> {
+        Ok(self.check(response, chrono::Utc::now().timestamp()))
+    }
+}
+
+fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        if *byte != 0 {
+            return bits + byte.leading_zeros();
+        }
+        bits += 8;
+    }
+    bits
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| s.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+        .collect()
+}
+
+/// The challenge named by REGISTRATION_CHALLENGE: "pow" (the default, with POW_DIFFICULTY
+/// leading zero bits, default 16), "turnstile", "hcaptcha", or "none" to turn it off
+pub fn from_env() -> Arc<dyn Challenge> {
+    let difficulty = std::env::var("POW_DIFFICULTY")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(16)
+        .clamp(1, 32);
+
+    let provider = match std::env::var("REGISTRATION_CHALLENGE").as_deref() {
+        Ok("none") => {
+            tracing::warn!("REGISTRATION_CHALLENGE=none; registration has no bot check");
+            return Arc::new(NoChallenge);
+        }
+        Ok("turnstile") => CaptchaProvider::Turnstile,
+        Ok("hcaptcha") => CaptchaProvider::HCaptcha,
+        Ok("pow") | Err(_) => return Arc::new(ProofOfWork::new(difficulty)),
+        Ok(other) => {
+            tracing::error!("Unknown REGISTRATION_CHALLENGE {:?}, using proof of work", other);
+            return Arc::new(ProofOfWork::new(difficulty));
+        }
+    };
+
+    match CaptchaChallenge::from_env(provider) {
+        Ok(captcha) => Arc::new(captcha),
+        Err(e) => {
+            tracing::error!("Failed to configure {:?}, falling back to proof of work: {:#}", provider, e);
+            Arc::new(ProofOfWork::new(difficulty))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(challenge: &str, difficulty: u32) -> String {
+        (0u64..)
+            .map(|nonce| format!("{}:{}", challenge, nonce))
+            .find(|answer| leading_zero_bits(&Sha256::digest(answer.as_bytes())) >= difficulty)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_proof_of_work_accepts_each_answer_once() {
+        let pow = ProofOfWork::new(8);
+        let answer = solve(&pow.issue(1_000), 8);
+
+        assert!(pow.check(&answer, 1_060));
+        assert!(!pow.check(&answer, 1_061), "a redeemed challenge can't be reused");
+        assert!(pow.check(&solve(&pow.issue(1_000), 8), 1_062));
+    }
+
+    #[test]
+    fn test_proof_of_work_rejects_bad_answers() {
+        let pow = ProofOfWork::new(8);
+        let challenge = pow.issue(1_000);
+        let answer = solve(&challenge, 8);
+
+        assert!(!pow.check(&answer, 1_000 + POW_TTL_SECS + 1), "expired");
+        assert!(!pow.check(&answer, 999), "issued in the future");
+        assert!(!ProofOfWork::new(8).check(&answer, 1_000), "signed by another key");
+        assert!(!pow.check(&answer.replacen("1000.", "1001.", 1), 1_001), "tampered");
+        assert!(!pow.check(&challenge, 1_000), "no nonce");
+        assert!(!pow.check("", 1_000));
+        assert!(!pow.check(&format!("{}.00.00:1", i64::MIN), 1_000), "forged issue time");
+
+        let weak = (0u64..)
+            .map(|nonce| format!("{}:{}", challenge, nonce))
+            .find(|answer| leading_zero_bits(&Sha256::digest(answer.as_bytes())) < 8)
+            .unwrap();
+        assert!(!pow.check(&weak, 1_000), "not enough work");
+    }
+
+    #[test]
+    fn test_leading_zero_bits() {
+        assert_eq!(leading_zero_bits(&[0, 0, 0x10, 0xff]), 19);
+        assert_eq!(leading_zero_bits(&[0x80]), 0);
+        assert_eq!(leading_zero_bits(&[0, 0]), 16);
+    }
+}
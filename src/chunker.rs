@@ -1,10 +1,9 @@
 use crate::models::{ChunkType, TextChunk};
 use uuid::Uuid;
 
-/// Chunks legislative text into semantic units (clauses, sections, etc.)
-pub fn chunk_text(text: &str, bill_number: &str) -> Vec<TextChunk> {
+/// Chunks legislative text into semantic units (clauses, sections, etc.) belonging to `bill_id`
+pub fn chunk_text(text: &str, bill_id: Uuid, bill_number: &str) -> Vec<TextChunk> {
     let mut chunks = Vec::new();
-    let bill_id = Uuid::new_v4();
     
     // Split by chapters and major sections
     let sections = split_into_sections(text);
@@ -15,12 +14,15 @@ pub fn chunk_text(text: &str, bill_number: &str) -> Vec<TextChunk> {
         // Only create chunks for non-empty content
         if section.trim().len() > 50 {
             chunks.push(TextChunk {
+                id: Uuid::new_v4(),
                 bill_id,
                 bill_number: bill_number.to_string(),
                 chunk_index: idx,
                 chunk_type,
                 chunk_identifier: identifier,
                 content: section.trim().to_string(),
+                page_start: None,
+                page_end: None,
             });
         }
     }
@@ -130,12 +132,15 @@ fn fallback_chunking(text: &str, bill_id: Uuid, bill_number: &str) -> Vec<TextCh
             if !current_chunk.is_empty() {
                 let identifier = extract_identifier(&current_chunk, chunk_index);
                 chunks.push(TextChunk {
+                    id: Uuid::new_v4(),
                     bill_id,
                     bill_number: bill_number.to_string(),
                     chunk_index,
                     chunk_type: ChunkType::Other,
                     chunk_identifier: identifier,
                     content: current_chunk.trim().to_string(),
+                    page_start: None,
+                    page_end: None,
                 });
                 chunk_index += 1;
             }
@@ -152,12 +157,15 @@ fn fallback_chunking(text: &str, bill_id: Uuid, bill_number: &str) -> Vec<TextCh
     if !current_chunk.is_empty() {
         let identifier = extract_identifier(&current_chunk, chunk_index);
         chunks.push(TextChunk {
+            id: Uuid::new_v4(),
             bill_id,
             bill_number: bill_number.to_string(),
             chunk_index,
             chunk_type: ChunkType::Other,
             chunk_identifier: identifier,
             content: current_chunk.trim().to_string(),
+            page_start: None,
+            page_end: None,
         });
     }
     
@@ -175,3 +183,51 @@ fn extract_identifier(chunk: &str, index: usize) -> String {
     }
 }
 
+/// Set each chunk's page range from where its content sits in `text`. `pages` holds the byte
+/// offset each page starts at with its page number, in order; chunks whose content isn't a
+/// slice of the text (merged paragraphs) are left without one.
+pub fn assign_pages(chunks: &mut [TextChunk], text: &str, pages: &[(usize, i32)]) {
+    let page_at = |offset: usize| {
+        pages
+            .iter()
+            .take_while(|(start, _)| *start <= offset)
+            .last()
+            .map(|(_, page)| *page)
+    };
+
+    let mut from = 0;
+    for chunk in chunks.iter_mut() {
+        let Some(found) = text.get(from..).and_then(|rest| rest.find(&chunk.content)) else {
+            continue;
+        };
+        let start = from + found;
+        let end = start + chunk.content.len();
+        chunk.page_start = page_at(start);
+        chunk.page_end = page_at(end.saturating_sub(1).max(start));
+        from = start;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign_pages() {
+        let text = "1. Short title and commencement of this Act, which extends to the whole of India.\n\
+                    2. Definitions for the purposes of this Act, unless the context otherwise requires.";
+        let second = text.find("2.").unwrap();
+        let mut chunks = chunk_text(text, Uuid::new_v4(), "12/2024");
+        assert_eq!(chunks.len(), 2);
+
+        // The page break falls inside the first clause
+        assign_pages(&mut chunks, text, &[(0, 1), (20, 2), (second + 10, 3)]);
+        assert_eq!((chunks[0].page_start, chunks[0].page_end), (Some(1), Some(2)));
+        assert_eq!((chunks[1].page_start, chunks[1].page_end), (Some(2), Some(3)));
+
+        // Text that didn't come from a PDF has no pages
+        let mut chunks = chunk_text(text, Uuid::new_v4(), "12/2024");
+        assign_pages(&mut chunks, text, &[]);
+        assert!(chunks.iter().all(|c| c.page_start.is_none() && c.page_end.is_none()));
+    }
+}
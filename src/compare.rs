@@ -0,0 +1,186 @@
+use anyhow::Result;
+use std::collections::{HashMap, VecDeque};
+
+use crate::embedder::Embedder;
+use crate::models::{SearchResult, StoredChunk};
+use crate::vector_store::{SearchFilter, VectorStore};
+
+/// One row of a side-by-side comparison; a missing side means the section has no counterpart
+#[derive(Debug)]
+pub struct AlignedRow<'a> {
+    pub left: Option<&'a StoredChunk>,
+    pub right: Option<&'a StoredChunk>,
+}
+
+/// Normalised key used to pair sections across bills, e.g. "clause:5" or "chapter:4".
+/// Free-text identifiers have no key and are never paired by identifier.
+pub fn section_key(identifier: &str) -> Option<String> {
+    let identifier = identifier.trim();
+    let (kind, rest) = identifier.split_once(' ').unwrap_or((identifier, ""));
+    let rest = rest.trim().trim_end_matches('.');
+
+    match kind.to_lowercase().as_str() {
+        "preamble" if rest.is_empty() => Some("preamble".to_string()),
+        "schedule" if rest.is_empty() => Some("schedule".to_string()),
+        "clause" | "section" => rest.parse::<u32>().ok().map(|n| format!("clause:{}", n)),
+        "chapter" => roman_to_number(rest)
+            .or_else(|| rest.parse().ok())
+            .map(|n| format!("chapter:{}", n)),
+        _ => None,
+    }
+}
+
+fn roman_to_number(numeral: &str) -> Option<u32> {
+    let mut total: u32 = 0;
+    let mut previous = 0;
+
+    for c in numeral.chars().rev() {
+        let value = match c.to_ascii_uppercase() {
+            'I' => 1,
+            'V' => 5,
+            'X' => 10,
+            'L' => 50,
+            'C' => 100,
+            'D' => 500,
+            'M' => 1000,
+            _ => return None,
+        };
+        // Malformed numerals such as "IIIIIIV" would go below zero
+        if value < previous {
+            total = total.checked_sub(value)?;
+        } else {
+            total = total.checked_add(value)?;
+            previous = value;
+        }
+    }
+
+    (total > 0).then_some(total)
+}
+
+/// Pair the sections of two bills by identifier, keeping the left bill's order.
+/// Sections only in the right bill are slotted in after the nearest earlier right-hand section.
+pub fn align_sections<'a>(left: &'a [StoredChunk], right: &'a [StoredChunk]) -> Vec<AlignedRow<'a>> {
+    // Repeated keys pair up in order: the first Clause 5 on the left takes the first on the right
+    let mut right_by_key: HashMap<String, VecDeque<usize>> = HashMap::new();
+    for (i, chunk) in right.iter().enumerate() {
+        if let Some(key) = section_key(&chunk.chunk_identifier) {
+            right_by_key.entry(key).or_default().push_back(i);
+        }
+    }
+
+    let mut matched = vec![false; right.len()];
+    let mut rows: Vec<(AlignedRow<'a>, Option<usize>)> = left
+        .iter()
+        .map(|chunk| {
+            let partner = section_key(&chunk.chunk_identifier)
+                .and_then(|key| right_by_key.get_mut(&key))
+                .and_then(|queue| queue.pop_front());
+            if let Some(i) = partner {
+                matched[i] = true;
+            }
+            (
+                AlignedRow {
+                    left: Some(chunk),
+                    right: partner.map(|i| &right[i]),
+                },
+                partner,
+            )
+        })
+        .collect();
+
+    for (i, chunk) in right.iter().enumerate() {
+        if matched[i] {
+            continue;
+        }
+        let position = rows
+            .iter()
+            .rposition(|(_, right_index)| right_index.is_some_and(|r| r < i))
+            .map_or(0, |p| p + 1);
+        rows.insert(position, (AlignedRow { left: None, right: Some(chunk) }, Some(i)));
+    }
+
+    rows.into_iter().map(|(row, _)| row).collect()
+}
+
+/// For each section, the most similar section of another bill by embedding similarity
+pub async fn closest_counterparts(
+    embedder: &dyn Embedder,
+    store: &dyn VectorStore,
+    sections: &[&StoredChunk],
+    other_bill_number: &str,
+) -> Result<Vec<Option<SearchResult>>> {
+    let mut vectors = Vec::with_capacity(sections.len());
+    for section in sections {
+        vectors.push(embedder.embed_query(&section.content).await?);
+    }
+
+    let filter = SearchFilter {
+        bill_number: Some(other_bill_number.to_string()),
+        ..SearchFilter::default()
+    };
+    let mut results = store.search_batch(&vectors, 1, &filter).await?;
+    results.resize_with(sections.len(), Vec::new);
+
+    Ok(results.into_iter().map(|hits| hits.into_iter().next()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(index: usize, identifier: &str) -> StoredChunk {
+        StoredChunk {
+            chunk_index: index,
+            chunk_type: "Clause".to_string(),
+            chunk_identifier: identifier.to_string(),
+            content: format!("{} text", identifier),
+        }
+    }
+
+    fn identifiers(rows: &[AlignedRow]) -> Vec<(Option<&str>, Option<&str>)> {
+        rows.iter()
+            .map(|r| {
+                (
+                    r.left.map(|c| c.chunk_identifier.as_str()),
+                    r.right.map(|c| c.chunk_identifier.as_str()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_section_keys() {
+        assert_eq!(section_key("Clause 12"), Some("clause:12".to_string()));
+        assert_eq!(section_key("Section 12"), Some("clause:12".to_string()));
+        assert_eq!(section_key("Chapter IV"), Some("chapter:4".to_string()));
+        assert_eq!(section_key("CHAPTER 4"), Some("chapter:4".to_string()));
+        assert_eq!(section_key("Chapter IIIIIIV"), None);
+        assert_eq!(section_key("Preamble"), Some("preamble".to_string()));
+        assert_eq!(section_key("Statement of Objects and Reasons"), None);
+    }
+
+    #[test]
+    fn test_align_pairs_by_identifier_and_keeps_unmatched() {
+        let old = vec![chunk(0, "Preamble"), chunk(1, "Clause 1"), chunk(2, "Clause 2"), chunk(3, "Clause 3")];
+        let new = vec![
+            chunk(0, "Preamble"),
+            chunk(1, "Clause 1"),
+            chunk(2, "Clause 1A"),
+            chunk(3, "Clause 3"),
+            chunk(4, "Chapter II"),
+        ];
+
+        let rows = align_sections(&old, &new);
+        assert_eq!(
+            identifiers(&rows),
+            vec![
+                (Some("Preamble"), Some("Preamble")),
+                (Some("Clause 1"), Some("Clause 1")),
+                (None, Some("Clause 1A")),
+                (Some("Clause 2"), None),
+                (Some("Clause 3"), Some("Clause 3")),
+                (None, Some("Chapter II")),
+            ]
+        );
+    }
+}
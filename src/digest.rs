@@ -0,0 +1,167 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::db;
+use crate::i18n::Locale;
+use crate::mailer::Mailer;
+use crate::models::{DigestRecipient, Notification};
+use crate::shutdown::ShutdownToken;
+
+/// How often the background job checks for users due a digest (DIGEST_INTERVAL_SECS, default 3600).
+/// Each user gets at most one digest per calendar week however often this runs.
+fn job_interval() -> std::time::Duration {
+    let secs = std::env::var("DIGEST_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&secs| secs > 0)
+        .unwrap_or(3600);
+    std::time::Duration::from_secs(secs)
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct DigestReport {
+    pub sent: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Subject and plain-text body of one user's digest; None when there is nothing to report
+fn build_digest(base_url: &str, username: &str, items: &[Notification]) -> Option<(String, String)> {
+    if items.is_empty() {
+        return None;
+    }
+
+    let lang = Locale::En;
+    let mut body = lang.tf("digest.intro", username);
+
+    // Items arrive ordered by bill, so each bill's updates are consecutive
+    let mut current_bill = None;
+    for item in items {
+        if current_bill != Some(item.bill_id) {
+            if current_bill.is_some() {
+                body.push('\n');
+            }
+            body.push_str(&format!("\n\n{}\n{}/f/{}\n", item.bill_title, base_url, item.bill_id));
+            current_bill = Some(item.bill_id);
+        }
+        body.push_str(&format!("  - {} ({})\n", item.message, item.created_at.format("%B %d")));
+    }
+
+    body.push_str("\n\n");
+    body.push_str(&lang.tf("digest.footer", format!("{}/u/{}#email", base_url, username)));
+
+    Some((lang.tf("digest.subject", items.len()), body))
+}
+
+/// Send one user's digest if this week's hasn't gone out yet; returns whether an email was sent
+async fn send_digest(pool: &PgPool, mailer: &dyn Mailer, base_url: &str, recipient: &DigestRecipient) -> Result<bool> {
+    let Some(previous) = db::claim_digest(pool, recipient.id).await? else {
+        return Ok(false);
+    };
+
+    let result = async {
+        let items = db::get_digest_notifications(pool, recipient.id, recipient.since).await?;
+        match build_digest(base_url, &recipient.username, &items) {
+            Some((subject, body)) => mailer.send(&recipient.email, &subject, &body).await.map(|_| true),
+            None => Ok(false),
+        }
+    }
+    .await;
+
+    if result.is_err() {
+        db::release_digest(pool, recipient.id, previous).await?;
+    }
+    result
+}
+
+/// Send this week's digest to every user due one. A failure for one user is logged
+/// and retried on the next run; it doesn't stop the others.
+pub async fn send_digests(pool: &PgPool, mailer: &dyn Mailer, base_url: &str) -> Result<DigestReport> {
+    let recipients = db::get_digest_recipients(pool).await?;
+    let mut report = DigestReport::default();
+
+    for recipient in &recipients {
+        match send_digest(pool, mailer, base_url, recipient).await {
+            Ok(true) => report.sent += 1,
+            Ok(false) => report.skipped += 1,
+            Err(e) => {
+                warn!("Failed to send digest to {}: {:#}", recipient.username, e);
+                report.failed += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Periodically send due digests until shutdown
+pub async fn run_job(pool: PgPool, mailer: Arc<dyn Mailer>, base_url: String, shutdown: ShutdownToken) {
+    let mut interval = tokio::time::interval(job_interval());
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let cancelled = shutdown.cancelled();
+    tokio::pin!(cancelled);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                match send_digests(&pool, mailer.as_ref(), &base_url).await {
+                    Ok(report) if report == DigestReport::default() => {}
+                    Ok(report) => info!("Digests: {} sent, {} with nothing new, {} failed", report.sent, report.skipped, report.failed),
+                    Err(e) => warn!("Digest run failed: {:#}", e),
+                }
+            }
+            _ = &mut cancelled => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    fn notification(bill_id: Uuid, bill_title: &str, message: &str) -> Notification {
+        Notification {
+            id: Uuid::new_v4(),
+            bill_id,
+            bill_title: bill_title.to_string(),
+            kind: "new_post".to_string(),
+            message: message.to_string(),
+            created_at: Utc.with_ymd_and_hms(2024, 3, 5, 10, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_digest_groups_updates_by_bill() {
+        let (data, tax) = (Uuid::new_v4(), Uuid::new_v4());
+        let items = vec![
+            notification(
+                data,
+                "The Data Bill, 2024",
+                "asha posted a review on The Data Bill, 2024",
+            ),
+            notification(data, "The Data Bill, 2024", "The Data Bill, 2024 is now Passed"),
+            notification(
+                tax,
+                "The Tax Bill, 2024",
+                "ravi replied to a review on The Tax Bill, 2024",
+            ),
+        ];
+
+        let (subject, body) = build_digest("https://example.org", "meera", &items).unwrap();
+        assert!(subject.contains('3'));
+        assert!(body.contains("meera"));
+        assert_eq!(body.matches("The Data Bill, 2024\nhttps://example.org/f/").count(), 1);
+        assert!(body.contains("  - The Data Bill, 2024 is now Passed (March 05)\n"));
+        assert!(body.contains(&format!("https://example.org/f/{}", tax)));
+        assert!(body.contains("https://example.org/u/meera#email"));
+    }
+
+    #[test]
+    fn test_empty_digest_is_not_sent() {
+        assert_eq!(build_digest("https://example.org", "meera", &[]), None);
+    }
+}
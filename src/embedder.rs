@@ -1,16 +1,18 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use crate::models::{EmbeddedChunk, TextChunk};
 use candle_core::{Device, IndexOp, Tensor};
 use candle_nn::VarBuilder;
 use candle_transformers::models::bert::{BertModel, Config, DTYPE};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokenizers::Tokenizer;
 use tokio::sync::Mutex;
 
 const MODEL_ID: &str = "sentence-transformers/all-MiniLM-L6-v2";
 
-fn download_file(cache_dir: &PathBuf, base_url: &str, filename: &str) -> Result<PathBuf> {
+pub(crate) fn download_file(cache_dir: &PathBuf, base_url: &str, filename: &str) -> Result<PathBuf> {
     let file_path = cache_dir.join(filename);
     
     // Check if file already exists
@@ -50,6 +52,56 @@ lazy_static::lazy_static! {
         Arc::new(Mutex::new(None));
 }
 
+// Set once the model has loaded, readable without waiting on the model lock
+static MODEL_LOADED: AtomicBool = AtomicBool::new(false);
+
+/// Turns text into query vectors; the web server holds one behind an Arc
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>>;
+    
+    /// Embed several texts at once, one vector per text in the same order
+    async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+    
+    /// Load the model ahead of the first request
+    async fn warm_up(&self) -> Result<()>;
+    
+    fn is_loaded(&self) -> bool;
+}
+
+/// The Candle BERT model (all-MiniLM-L6-v2)
+pub struct BertEmbedder;
+
+#[async_trait]
+impl Embedder for BertEmbedder {
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        embed_query(query).await
+    }
+    
+    async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        embed_texts(texts).await
+    }
+    
+    async fn warm_up(&self) -> Result<()> {
+        warm_up().await
+    }
+    
+    fn is_loaded(&self) -> bool {
+        is_model_loaded()
+    }
+}
+
+/// Whether the embedding model has finished loading
+pub fn is_model_loaded() -> bool {
+    MODEL_LOADED.load(Ordering::Acquire)
+}
+
+/// Load the embedding model ahead of the first request
+pub async fn warm_up() -> Result<()> {
+    get_or_init_model().await?;
+    Ok(())
+}
+
 /// Initialize the embedding model (call this once at startup)
 async fn get_or_init_model() -> Result<Arc<Mutex<Option<EmbeddingModel>>>> {
     let mut model_guard = EMBEDDING_MODEL.lock().await;
@@ -103,6 +155,7 @@ async fn get_or_init_model() -> Result<Arc<Mutex<Option<EmbeddingModel>>>> {
         .await??;
         
         *model_guard = Some(model_data);
+        MODEL_LOADED.store(true, Ordering::Release);
         tracing::info!("✓ Embedding model initialized successfully");
     }
     
@@ -112,8 +165,6 @@ async fn get_or_init_model() -> Result<Arc<Mutex<Option<EmbeddingModel>>>> {
 
 /// Embeds multiple text chunks using Candle BERT model
 pub async fn embed_chunks(chunks: &[TextChunk]) -> Result<Vec<EmbeddedChunk>> {
-    let model_arc = get_or_init_model().await?;
-    
     // Prepare texts for embedding
     let texts: Vec<String> = chunks
         .iter()
@@ -124,6 +175,29 @@ pub async fn embed_chunks(chunks: &[TextChunk]) -> Result<Vec<EmbeddedChunk>> {
         .collect();
     
     tracing::debug!("Generating embeddings for {} chunks...", texts.len());
+    let embeddings = embed_texts(&texts).await?;
+    
+    // Combine chunks with their embeddings
+    let embedded_chunks = chunks
+        .iter()
+        .zip(embeddings.iter())
+        .map(|(chunk, embedding)| EmbeddedChunk {
+            chunk: chunk.clone(),
+            embedding: embedding.clone(),
+        })
+        .collect();
+    
+    Ok(embedded_chunks)
+}
+
+/// Embeds arbitrary texts, returning one normalized vector per text in order
+pub async fn embed_texts(texts: &[String]) -> Result<Vec<Vec<f32>>> {
+    if texts.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let model_arc = get_or_init_model().await?;
+    let texts = texts.to_vec();
     
     // Generate embeddings (blocking operation, run in separate thread)
     let embeddings = {
@@ -146,17 +220,7 @@ pub async fn embed_chunks(chunks: &[TextChunk]) -> Result<Vec<EmbeddedChunk>> {
         .await??
     };
     
-    // Combine chunks with their embeddings
-    let embedded_chunks = chunks
-        .iter()
-        .zip(embeddings.iter())
-        .map(|(chunk, embedding)| EmbeddedChunk {
-            chunk: chunk.clone(),
-            embedding: embedding.clone(),
-        })
-        .collect();
-    
-    Ok(embedded_chunks)
+    Ok(embeddings)
 }
 
 /// Embeds a single query string
@@ -306,12 +370,15 @@ mod tests {
     async fn test_embed_chunks() {
         let chunks = vec![
             TextChunk {
+                id: Uuid::new_v4(),
                 bill_id: Uuid::new_v4(),
                 bill_number: "TEST/2024".to_string(),
                 chunk_index: 0,
                 chunk_type: ChunkType::Clause,
                 chunk_identifier: "Clause 1".to_string(),
                 content: "This is a test clause about data protection.".to_string(),
+                page_start: None,
+                page_end: None,
             },
         ];
         
@@ -329,20 +396,26 @@ mod tests {
     async fn test_semantic_similarity() {
         let chunks = vec![
             TextChunk {
+                id: Uuid::new_v4(),
                 bill_id: Uuid::new_v4(),
                 bill_number: "TEST/2024".to_string(),
                 chunk_index: 0,
                 chunk_type: ChunkType::Clause,
                 chunk_identifier: "Clause 1".to_string(),
                 content: "Data protection and privacy rights for citizens".to_string(),
+                page_start: None,
+                page_end: None,
             },
             TextChunk {
+                id: Uuid::new_v4(),
                 bill_id: Uuid::new_v4(),
                 bill_number: "TEST/2024".to_string(),
                 chunk_index: 1,
                 chunk_type: ChunkType::Clause,
                 chunk_identifier: "Clause 2".to_string(),
                 content: "Telecommunications infrastructure and network regulations".to_string(),
+                page_start: None,
+                page_end: None,
             },
         ];
         
@@ -1,8 +1,23 @@
 use anyhow::{Context, Result};
 use std::path::Path;
 
+/// A bill's text and where each PDF page begins in it
+#[derive(Debug, Clone)]
+pub struct ExtractedText {
+    pub text: String,
+    /// Byte offset of each page's first character in `text`, with its page number.
+    /// Empty when the text didn't come from a PDF.
+    pub pages: Vec<(usize, i32)>,
+}
+
+impl ExtractedText {
+    fn without_pages(text: String) -> Self {
+        Self { text, pages: Vec::new() }
+    }
+}
+
 /// Extracts text from a PDF URL or file path
-pub async fn extract_text_from_pdf(pdf_url: &str) -> Result<String> {
+pub async fn extract_text_from_pdf(pdf_url: &str) -> Result<ExtractedText> {
     // Download PDF if it's a URL
     let pdf_path = if pdf_url.starts_with("http") {
         download_pdf(pdf_url).await?
@@ -62,36 +77,38 @@ async fn try_download_pdf(url: &str) -> Result<String> {
     Ok(filepath)
 }
 
-fn extract_text_from_file(filepath: &str) -> Result<String> {
+fn extract_text_from_file(filepath: &str) -> Result<ExtractedText> {
     // If file doesn't exist or is the mock marker, return demo content
     if filepath == "mock_content" || !Path::new(filepath).exists() {
-        return Ok(create_demo_bill_content(filepath));
+        return Ok(ExtractedText::without_pages(create_demo_bill_content(filepath)));
     }
     
     tracing::debug!("Extracting text from: {}", filepath);
     
     match lopdf::Document::load(filepath) {
         Ok(doc) => {
-            let mut text = String::new();
-            
-            // Get the number of pages
-            let pages = doc.get_pages();
+            let mut extracted = ExtractedText::without_pages(String::new());
             
-            for page_num in pages.keys() {
+            // Clean each page on its own, remembering where it starts
+            for page_num in doc.get_pages().keys() {
                 if let Ok(page_text) = doc.extract_text(&[*page_num]) {
-                    text.push_str(&page_text);
-                    text.push('\n');
+                    let cleaned = clean_pdf_text(&page_text);
+                    if cleaned.is_empty() {
+                        continue;
+                    }
+                    if !extracted.text.is_empty() {
+                        extracted.text.push(' ');
+                    }
+                    extracted.pages.push((extracted.text.len(), *page_num as i32));
+                    extracted.text.push_str(&cleaned);
                 }
             }
             
-            // Clean up the text
-            let cleaned = clean_pdf_text(&text);
-            
-            Ok(cleaned)
+            Ok(extracted)
         }
         Err(e) => {
             tracing::warn!("Failed to parse PDF: {}. Using demo content.", e);
-            Ok(create_demo_bill_content(filepath))
+            Ok(ExtractedText::without_pages(create_demo_bill_content(filepath)))
         }
     }
 }
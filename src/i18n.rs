@@ -0,0 +1,768 @@
+use axum::{extract::FromRequestParts, http::{header, request::Parts}};
+use axum_extra::extract::cookie::CookieJar;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt::Display;
+
+pub const LOCALE_COOKIE_NAME: &str = "lang";
+
+/// Interface language. Only the site chrome is translated; bill text and
+/// user content are always shown as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Hi,
+}
+
+impl Locale {
+    pub fn parse(code: &str) -> Option<Self> {
+        // Accept region-qualified tags such as hi-IN or en-GB
+        let primary = code.trim().split(['-', '_']).next().unwrap_or("");
+        match primary.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::En),
+            "hi" => Some(Locale::Hi),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Hi => "hi",
+        }
+    }
+
+    /// Name of the language in that language, for the switcher
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Hi => "हिन्दी",
+        }
+    }
+
+    /// The language the switcher offers from this one
+    pub fn other(&self) -> Locale {
+        match self {
+            Locale::En => Locale::Hi,
+            Locale::Hi => Locale::En,
+        }
+    }
+
+    /// Pick the best supported language from an Accept-Language header
+    pub fn from_accept_language(value: &str) -> Option<Self> {
+        let mut best: Option<(Locale, f32)> = None;
+
+        for entry in value.split(',') {
+            let mut parts = entry.split(';');
+            let Some(locale) = parts.next().and_then(Locale::parse) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((locale, quality));
+            }
+        }
+
+        best.map(|(locale, _)| locale)
+    }
+
+    /// Locale for a request: the `lang` cookie wins, then Accept-Language, then English
+    pub fn from_headers(headers: &axum::http::HeaderMap) -> Self {
+        let jar = CookieJar::from_headers(headers);
+        if let Some(locale) = jar.get(LOCALE_COOKIE_NAME).and_then(|c| Locale::parse(c.value())) {
+            return locale;
+        }
+
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Locale::from_accept_language)
+            .unwrap_or_default()
+    }
+
+    /// Translate a message key, showing the key itself if it has no entry
+    pub fn t(&self, key: &'static str) -> &'static str {
+        match MESSAGES.get(key) {
+            Some(&(en, hi)) => match self {
+                Locale::En => en,
+                Locale::Hi => hi,
+            },
+            None => {
+                tracing::warn!("Missing translation key: {}", key);
+                key
+            }
+        }
+    }
+
+    /// Translate a message with one `{}` placeholder
+    pub fn tf(&self, key: &'static str, arg: impl Display) -> String {
+        self.t(key).replacen("{}", &arg.to_string(), 1)
+    }
+
+    /// Translate a message with two `{}` placeholders, filled in order
+    pub fn tf2(&self, key: &'static str, first: impl Display, second: impl Display) -> String {
+        self.t(key)
+            .replacen("{}", &first.to_string(), 1)
+            .replacen("{}", &second.to_string(), 1)
+    }
+
+    /// Translate a stored value such as a stance or moderation status under
+    /// `prefix`, leaving values without a translation unchanged
+    pub fn label<'a>(&self, prefix: &str, value: &'a str) -> &'a str {
+        match MESSAGES.get(format!("{}.{}", prefix, value).as_str()) {
+            Some(&(en, hi)) => match self {
+                Locale::En => en,
+                Locale::Hi => hi,
+            },
+            None => value,
+        }
+    }
+}
+
+#[axum::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Locale::from_headers(&parts.headers))
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref MESSAGES: HashMap<&'static str, (&'static str, &'static str)> =
+        STRINGS.iter().map(|&(key, en, hi)| (key, (en, hi))).collect();
+}
+
+// (key, English, Hindi)
+const STRINGS: &[(&str, &str, &str)] = &[
+    // Header and navigation
+    ("site.tagline", "A Civic Knowledge Base for Indian Legislation", "भारतीय विधान के लिए एक नागरिक ज्ञानकोश"),
+    ("nav.moderation", "Moderation", "मॉडरेशन"),
+    ("nav.users", "Users", "उपयोगकर्ता"),
+    ("nav.ingest", "Ingest", "विधेयक जोड़ें"),
+    ("nav.search_stats", "Searches", "खोजें"),
+    ("nav.rate_limits", "Rate limits", "दर सीमाएँ"),
+    ("nav.audit_log", "Audit log", "ऑडिट लॉग"),
+    ("nav.keywords", "Keywords", "कीवर्ड"),
+    ("nav.national_report", "National report", "राष्ट्रीय रिपोर्ट"),
+    ("nav.bookmarks", "Bookmarks", "बुकमार्क"),
+    ("nav.notifications", "Notifications", "सूचनाएँ"),
+    ("nav.profile", "Profile", "प्रोफ़ाइल"),
+    ("nav.logout", "Logout", "लॉग आउट"),
+    ("nav.login", "Login", "लॉग इन"),
+    ("nav.register", "Register", "पंजीकरण"),
+    ("nav.language", "Language", "भाषा"),
+    // Search and the bill list
+    ("search.placeholder", "Search legislative bills...", "विधेयक खोजें..."),
+    ("search.submit", "Search", "खोजें"),
+    ("search.rerank", "Rerank", "पुनः क्रमित करें"),
+    ("search.reranked_score", "reranked {}", "पुनः क्रमित {}"),
+    ("search.reranked_hint", "Relevance from a cross-encoder that reads the query and section together", "क्रॉस-एनकोडर से प्रासंगिकता, जो खोज और खंड को साथ पढ़ता है"),
+    ("search.keyword_fallback", "Keyword results (semantic search unavailable)", "कीवर्ड परिणाम (अर्थ-आधारित खोज उपलब्ध नहीं)"),
+    ("search.read_bill", "Read bill", "विधेयक पढ़ें"),
+    ("search.read_section", "Read section", "धारा पढ़ें"),
+    ("search.no_results", "No results found", "कोई परिणाम नहीं मिला"),
+    ("search.year_from", "From {}", "{} से"),
+    ("search.year_to", "Until {}", "{} तक"),
+    ("chunk_type.Preamble", "Preamble", "प्रस्तावना"),
+    ("chunk_type.Clause", "Clause", "खंड"),
+    ("chunk_type.Section", "Section", "धारा"),
+    ("chunk_type.Schedule", "Schedule", "अनुसूची"),
+    ("chunk_type.Other", "Other", "अन्य"),
+    ("home.empty_title", "Explore Legislative Bills", "विधेयकों को जानें"),
+    ("home.empty_query", "Enter a query to search through legislative bills", "विधेयकों में खोजने के लिए प्रश्न लिखें"),
+    ("home.empty_sidebar", "Or select a recent bill from the sidebar →", "या किनारे की सूची से हाल का कोई विधेयक चुनें →"),
+    ("home.recent_bills", "Recent Bills", "हाल के विधेयक"),
+    ("bills.filter_title", "Search titles...", "शीर्षक खोजें..."),
+    ("bills.filter_any_year", "Any year", "कोई भी वर्ष"),
+    ("bills.filter_any_status", "Any status", "कोई भी स्थिति"),
+    ("bills.filter_apply", "Filter", "छाँटें"),
+    ("bills.filter_clear", "Clear filters", "फ़िल्टर हटाएँ"),
+    ("bills.filter_no_matches", "No bills match these filters.", "इन फ़िल्टरों से कोई विधेयक मेल नहीं खाता।"),
+    ("home.trending", "Trending Discussions", "चर्चित विधेयक"),
+    ("home.trending_post_one", "{} post", "{} पोस्ट"),
+    ("home.trending_post_many", "{} posts", "{} पोस्ट"),
+    ("home.no_bills", "No bills ingested yet.", "अभी तक कोई विधेयक नहीं जोड़ा गया है।"),
+    ("home.no_bills_hint", "Run the ingestion command to populate the database.", "डेटाबेस भरने के लिए ingestion कमांड चलाएँ।"),
+    ("bill.number", "Bill No. {}", "विधेयक सं. {}"),
+    ("pagination.prev", "← Prev", "← पिछला"),
+    ("pagination.next", "Next →", "अगला →"),
+    ("pagination.page", "Page {} of {}", "पृष्ठ {} / {}"),
+    // MP report modal
+    ("mp.button", "For MPs", "सांसदों के लिए"),
+    ("mp.title", "MP Constituency Dashboard", "सांसद निर्वाचन क्षेत्र डैशबोर्ड"),
+    ("mp.subtitle", "Generate a comprehensive report of constituent sentiment", "अपने क्षेत्र के नागरिकों की राय की विस्तृत रिपोर्ट बनाएँ"),
+    ("mp.select", "Select Your Constituency", "अपना निर्वाचन क्षेत्र चुनें"),
+    ("mp.choose", "Choose constituency...", "निर्वाचन क्षेत्र चुनें..."),
+    ("mp.generate", "Generate PDF Report", "PDF रिपोर्ट बनाएँ"),
+    ("mp.from", "Posts from (optional)", "इस तारीख से पोस्ट (वैकल्पिक)"),
+    ("mp.to", "Posts up to (optional)", "इस तारीख तक पोस्ट (वैकल्पिक)"),
+    ("mp.period_hint", "Leave blank to cover every post, e.g. pick the session's dates for what constituents said this session.", "सभी पोस्ट शामिल करने के लिए खाली छोड़ें, या इस सत्र में नागरिकों ने क्या कहा यह जानने के लिए सत्र की तारीखें चुनें।"),
+    // Bill page
+    ("bill.introduced", "Introduced {}", "प्रस्तुत {}"),
+    ("bill.forum", "Discussion Forum", "चर्चा मंच"),
+    ("bill.original_pdf", "Original PDF", "मूल PDF"),
+    ("bill.report", "Report by constituency", "निर्वाचन क्षेत्रवार रिपोर्ट"),
+    ("bill.unavailable", "The text of this bill could not be loaded right now. Please try again later.", "इस विधेयक का पाठ अभी लोड नहीं हो सका। कृपया बाद में फिर प्रयास करें।"),
+    ("bill.not_ingested", "The text of this bill has not been ingested yet.", "इस विधेयक का पाठ अभी तक नहीं जोड़ा गया है।"),
+    ("bill.contents", "Contents", "विषय-सूची"),
+    // Bill comparison
+    ("compare.title", "Compare Bills", "विधेयकों की तुलना"),
+    ("compare.summary", "{} matched section(s) · {} unmatched", "{} मेल खाती धाराएँ · {} बिना मेल"),
+    ("compare.unavailable", "The text of these bills could not be loaded right now. Please try again later.", "इन विधेयकों का पाठ अभी लोड नहीं हो सका। कृपया बाद में फिर प्रयास करें।"),
+    ("compare.not_ingested", "The text of these bills has not been ingested yet.", "इन विधेयकों का पाठ अभी तक नहीं जोड़ा गया है।"),
+    ("compare.no_match", "No matching section", "कोई मेल खाती धारा नहीं"),
+    ("compare.closest", "Closest counterpart:", "सबसे मिलती-जुलती धारा:"),
+    // Forum
+    ("forum.read_bill", "Read the bill", "विधेयक पढ़ें"),
+    ("forum.review_one", "{} review", "{} समीक्षा"),
+    ("forum.review_many", "{} reviews", "{} समीक्षाएँ"),
+    ("forum.sort_by", "Sort by", "क्रम"),
+    ("forum.sort_new", "Newest", "नवीनतम"),
+    ("forum.sort_top", "Top", "शीर्ष"),
+    ("forum.sort_controversial", "Controversial", "विवादास्पद"),
+    ("forum.your_unpublished", "Your unpublished reviews", "आपकी अप्रकाशित समीक्षाएँ"),
+    ("forum.only_you_see", "Only you can see this until a moderator approves it.", "मॉडरेटर की स्वीकृति तक इसे केवल आप देख सकते हैं।"),
+    ("forum.no_reviews", "No reviews yet. Be the first to share your thoughts!", "अभी कोई समीक्षा नहीं है। अपनी राय साझा करने वाले पहले व्यक्ति बनें!"),
+    ("forum.login_prompt", "Want to share your thoughts on this bill?", "क्या आप इस विधेयक पर अपनी राय साझा करना चाहते हैं?"),
+    ("forum.login_to_participate", "Login to Participate", "भाग लेने के लिए लॉग इन करें"),
+    ("forum.or", "or", "या"),
+    ("forum.create_account", "Create an Account", "खाता बनाएँ"),
+    ("forum.follow", "Follow", "फ़ॉलो करें"),
+    ("forum.following", "Following", "फ़ॉलो कर रहे हैं"),
+    // Stances and moderation statuses, looked up with Locale::label
+    ("summary.title", "What reviewers are saying", "समीक्षक क्या कह रहे हैं"),
+    ("summary.disclaimer", "Generated by AI from {} approved reviews on {}. It may be inaccurate; read the reviews below.", "{} स्वीकृत समीक्षाओं से {} को AI द्वारा तैयार। यह गलत हो सकता है; नीचे दी गई समीक्षाएँ पढ़ें।"),
+    ("summary.regenerate", "Regenerate summary", "सारांश फिर से बनाएँ"),
+    ("summary.generate", "Generate AI summary", "AI सारांश बनाएँ"),
+    ("stance.Support", "Support", "समर्थन"),
+    ("stance.Oppose", "Oppose", "विरोध"),
+    ("stance.Critique", "Critique", "समालोचना"),
+    ("stance.Reply", "Reply", "जवाब"),
+    ("status.approved", "Published", "प्रकाशित"),
+    ("status.pending_review", "Pending Review", "समीक्षा लंबित"),
+    ("status.rejected", "Rejected", "अस्वीकृत"),
+    ("status.appealed", "Under Appeal", "अपील पर विचाराधीन"),
+    // Review form
+    ("review.share_view", "Share Your View", "अपनी राय दें"),
+    ("review.remaining", "You can post {} more review(s) for now.", "आप अभी {} और समीक्षाएँ पोस्ट कर सकते हैं।"),
+    ("review.your_stance", "Your Stance", "आपका पक्ष"),
+    ("review.your_review", "Your Review", "आपकी समीक्षा"),
+    ("review.placeholder", "Share your thoughts on this bill...", "इस विधेयक पर अपने विचार लिखें..."),
+    ("review.post_anonymously", "Post anonymously", "गुमनाम रूप से पोस्ट करें"),
+    ("review.anonymous_hint", "Your username is hidden from other readers; only your constituency is shown. Moderators can still see who wrote it.", "आपका उपयोगकर्ता नाम अन्य पाठकों से छिपा रहेगा; केवल आपका निर्वाचन क्षेत्र दिखेगा। मॉडरेटर फिर भी देख सकते हैं कि इसे किसने लिखा।"),
+    ("review.anonymous_from", "Citizen from {}", "{} के नागरिक"),
+    ("review.anonymous_badge", "Anonymous", "गुमनाम"),
+    ("review.submit", "Submit Review", "समीक्षा भेजें"),
+    ("review.limit_reached", "You've reached the maximum number of posts for now.", "आप अभी की अधिकतम पोस्ट सीमा तक पहुँच गए हैं।"),
+    ("review.daily_limit_reached", "You've reached the maximum number of posts for a day.", "आप एक दिन की अधिकतम पोस्ट सीमा तक पहुँच गए हैं।"),
+    ("review.wait", "Please wait before posting again.", "कृपया दोबारा पोस्ट करने से पहले प्रतीक्षा करें।"),
+    ("review.retry_moment", "Try again in a moment.", "कुछ ही देर में फिर प्रयास करें।"),
+    ("review.retry_minute", "Try again in 1 minute.", "1 मिनट में फिर प्रयास करें।"),
+    ("review.retry_minutes", "Try again in {} minutes.", "{} मिनट में फिर प्रयास करें।"),
+    ("review.retry_hours", "Try again in {} hours.", "{} घंटे में फिर प्रयास करें।"),
+    ("review.posted", "Your review has been posted.", "आपकी समीक्षा पोस्ट कर दी गई है।"),
+    ("review.rejected", "Your review was rejected by moderation.", "मॉडरेशन ने आपकी समीक्षा अस्वीकार कर दी।"),
+    ("review.checking", "Your review has been submitted and is being checked. It will appear once approved.", "आपकी समीक्षा भेज दी गई है और उसकी जाँच हो रही है। स्वीकृत होने पर यह दिखाई देगी।"),
+    ("review.awaiting_moderation", "Your review has been submitted and is awaiting moderation.", "आपकी समीक्षा भेज दी गई है और मॉडरेशन की प्रतीक्षा में है।"),
+    ("review.save_failed", "Your post could not be saved. Please try again.", "आपकी पोस्ट सहेजी नहीं जा सकी। कृपया फिर प्रयास करें।"),
+    // Review cards
+    ("review.edited", "edited {}", "संपादित {}"),
+    ("review.report", "Report", "रिपोर्ट करें"),
+    ("review.submit_report", "Submit Report", "रिपोर्ट भेजें"),
+    ("review.reported", "Reported. Thanks for helping keep the forum civil.", "रिपोर्ट हो गई। मंच को सभ्य बनाए रखने में मदद के लिए धन्यवाद।"),
+    ("review.report_rate_limited", "You've sent a lot of reports recently. Please try again in {} minute(s).", "आपने हाल में बहुत सारी रिपोर्ट भेजी हैं। कृपया {} मिनट बाद फिर प्रयास करें।"),
+    ("review.report_daily_limited", "You've sent the most reports allowed in a day. Please try again in {} hour(s).", "आपने एक दिन में अनुमत अधिकतम रिपोर्ट भेज दी हैं। कृपया {} घंटे बाद फिर प्रयास करें।"),
+    ("review.delete", "Delete", "हटाएँ"),
+    ("review.delete_confirm", "Delete this review? This cannot be undone.", "यह समीक्षा हटाएँ? इसे वापस नहीं किया जा सकता।"),
+    ("review.edit", "Edit", "संपादित करें"),
+    ("review.save", "Save Changes", "बदलाव सहेजें"),
+    ("review.view_reply", "View {} reply", "{} जवाब देखें"),
+    ("review.view_replies", "View {} replies", "{} जवाब देखें"),
+    ("review.reply", "Reply", "जवाब दें"),
+    ("review.reply_placeholder", "Write a reply...", "जवाब लिखें..."),
+    ("review.post_reply", "Post Reply", "जवाब पोस्ट करें"),
+    ("review.no_replies", "No replies yet.", "अभी कोई जवाब नहीं है।"),
+    ("review.load_more", "Load more reviews", "और समीक्षाएँ देखें"),
+    ("report.spam", "Spam or advertising", "स्पैम या विज्ञापन"),
+    ("report.harassment", "Harassment", "उत्पीड़न"),
+    ("report.hate_speech", "Hate speech", "घृणा फैलाने वाली भाषा"),
+    ("report.misinformation", "Misinformation", "भ्रामक जानकारी"),
+    ("report.off_topic", "Off topic", "विषय से हटकर"),
+    ("report.other", "Other", "अन्य"),
+    // Login and registration
+    ("login.page_title", "Login", "लॉग इन"),
+    ("login.title", "Welcome Back", "फिर से स्वागत है"),
+    ("login.subtitle", "Sign in to participate in legislative discussions", "विधायी चर्चाओं में भाग लेने के लिए साइन इन करें"),
+    ("login.submit", "Sign In", "साइन इन करें"),
+    ("login.no_account", "Don't have an account?", "खाता नहीं है?"),
+    ("login.register_here", "Register here", "यहाँ पंजीकरण करें"),
+    ("login.suspended", "This account has been suspended", "यह खाता निलंबित कर दिया गया है"),
+    ("login.invalid", "Invalid username or password", "उपयोगकर्ता नाम या पासवर्ड गलत है"),
+    ("login.too_many_attempts", "Too many login attempts.", "लॉगिन के बहुत अधिक प्रयास।"),
+    ("login.forgot_password", "Forgot your password?", "पासवर्ड भूल गए?"),
+    ("login.or", "or", "या"),
+    ("login.google", "Sign in with Google", "Google से साइन इन करें"),
+    ("login.google_failed", "Google sign-in did not complete. Please try again.", "Google साइन-इन पूरा नहीं हुआ। कृपया फिर से प्रयास करें।"),
+    ("login.session_failed", "Failed to create session", "सत्र शुरू नहीं हो सका"),
+    ("error.generic", "An error occurred", "एक त्रुटि हुई"),
+    ("error.not_found_title", "Page not found", "पृष्ठ नहीं मिला"),
+    ("error.not_found_text", "The page you are looking for does not exist or has been removed.", "आप जो पृष्ठ खोज रहे हैं वह मौजूद नहीं है या हटा दिया गया है।"),
+    ("error.server_title", "Something went wrong", "कुछ गड़बड़ हो गई"),
+    ("error.server_text", "We could not complete your request. Please try again later.", "हम आपका अनुरोध पूरा नहीं कर सके। कृपया बाद में फिर प्रयास करें।"),
+    ("error.bad_request_title", "Invalid request", "अमान्य अनुरोध"),
+    ("error.unauthorized_title", "Please log in", "कृपया लॉग इन करें"),
+    ("error.forbidden_title", "Not allowed", "अनुमति नहीं है"),
+    ("error.rate_limited_title", "Too many requests", "बहुत अधिक अनुरोध"),
+    ("error.search_rate_limited", "Too many searches in a short time. Please wait a moment and try again.", "कम समय में बहुत अधिक खोजें हुई हैं। कृपया थोड़ा रुककर फिर प्रयास करें।"),
+    ("error.ask_rate_limited", "Too many questions in a short time. Please wait a moment and try again.", "कम समय में बहुत अधिक प्रश्न पूछे गए हैं। कृपया थोड़ा रुककर फिर प्रयास करें।"),
+    ("error.report_job_rate_limited", "You've requested a lot of constituency reports recently. Please try again later.", "आपने हाल में बहुत सारी निर्वाचन क्षेत्र रिपोर्ट माँगी हैं। कृपया बाद में फिर प्रयास करें।"),
+    ("error.ip_rate_limited", "Too many attempts from your network. Please wait a few minutes and try again.", "आपके नेटवर्क से बहुत अधिक प्रयास हुए हैं। कृपया कुछ मिनट रुककर फिर प्रयास करें।"),
+    ("error.reference", "Reference: {}", "संदर्भ: {}"),
+    ("error.home", "Back to home", "मुखपृष्ठ पर लौटें"),
+    ("error.invalid_bill_id", "That is not a valid bill link.", "यह विधेयक का मान्य लिंक नहीं है।"),
+    ("error.invalid_review_id", "That is not a valid review link.", "यह समीक्षा का मान्य लिंक नहीं है।"),
+    ("error.invalid_user_id", "That is not a valid user link.", "यह उपयोगकर्ता का मान्य लिंक नहीं है।"),
+    ("error.bill_not_found", "We could not find that bill.", "हमें वह विधेयक नहीं मिला।"),
+    ("error.review_not_found", "We could not find that review. It may have been deleted.", "हमें वह समीक्षा नहीं मिली। हो सकता है उसे हटा दिया गया हो।"),
+    ("error.user_not_found", "We could not find that user.", "हमें वह उपयोगकर्ता नहीं मिला।"),
+    ("error.constituency_not_found", "We could not find that constituency.", "हमें वह निर्वाचन क्षेत्र नहीं मिला।"),
+    ("error.unsupported_language", "That language is not available.", "वह भाषा उपलब्ध नहीं है।"),
+    ("error.email_link_invalid", "This confirmation link is invalid or has expired. You can request a new one from your profile.", "यह पुष्टि लिंक अमान्य है या इसकी अवधि समाप्त हो गई है। आप अपनी प्रोफ़ाइल से नया लिंक मँगा सकते हैं।"),
+    ("error.ingest_job_not_found", "We could not find that ingest job. Jobs are kept only until the server restarts.", "हमें वह कार्य नहीं मिला। कार्य केवल सर्वर के पुनः आरंभ होने तक रखे जाते हैं।"),
+    ("error.not_your_account", "You can only do this for your own account.", "आप यह केवल अपने खाते के लिए कर सकते हैं।"),
+    ("error.not_your_review", "You can only edit your own reviews.", "आप केवल अपनी समीक्षाएँ संपादित कर सकते हैं।"),
+    ("error.unknown_role", "That role does not exist.", "वह भूमिका मौजूद नहीं है।"),
+    ("error.invalid_rate_limit", "That rate limit setting isn't available.", "वह दर सीमा विकल्प उपलब्ध नहीं है।"),
+    ("error.rate_limit_unverified", "Confirm you've checked this account isn't a bot before clearing its limits.", "सीमाएँ हटाने से पहले पुष्टि करें कि आपने जाँच लिया है कि यह खाता बॉट नहीं है।"),
+    ("error.invalid_keyword", "That keyword pattern request isn't valid.", "यह कीवर्ड पैटर्न अनुरोध मान्य नहीं है।"),
+    ("error.keyword_not_found", "That keyword pattern no longer exists.", "यह कीवर्ड पैटर्न अब मौजूद नहीं है।"),
+    ("error.report_job_not_found", "This report has expired or doesn't exist. Please request it again.", "यह रिपोर्ट समाप्त हो चुकी है या मौजूद नहीं है। कृपया इसे फिर से माँगें।"),
+    ("error.invalid_date", "Dates must be in YYYY-MM-DD format.", "तारीखें YYYY-MM-DD प्रारूप में होनी चाहिए।"),
+    ("error.invalid_report_table", "The CSV table must be \"sentiment\" or \"posts\".", "CSV तालिका \"sentiment\" या \"posts\" होनी चाहिए।"),
+    ("error.login_required", "You need to log in to do this.", "ऐसा करने के लिए आपको लॉग इन करना होगा।"),
+    ("error.api_token_invalid", "The API token is invalid or has been revoked.", "API टोकन अमान्य है या रद्द कर दिया गया है।"),
+    ("error.api_scope_write", "This API token is read-only.", "यह API टोकन केवल पढ़ने के लिए है।"),
+    ("error.moderator_required", "Only moderators and admins can do this.", "यह केवल मॉडरेटर और एडमिन कर सकते हैं।"),
+    ("error.admin_required", "Only admins can do this.", "यह केवल एडमिन कर सकते हैं।"),
+    ("error.own_role", "You cannot change your own role.", "आप अपनी भूमिका नहीं बदल सकते।"),
+    ("error.suspend_self", "You cannot suspend yourself.", "आप स्वयं को निलंबित नहीं कर सकते।"),
+    ("error.not_enough_reviews", "This bill does not have enough approved reviews to summarize yet.", "इस विधेयक पर सारांश के लिए अभी पर्याप्त स्वीकृत समीक्षाएँ नहीं हैं।"),
+    ("error.summary_unavailable", "Summary generation is unavailable right now.", "सारांश बनाने की सेवा अभी उपलब्ध नहीं है।"),
+    ("error.compare_ids", "Choose two bills to compare.", "तुलना के लिए दो विधेयक चुनें।"),
+    ("register.page_title", "Register", "पंजीकरण"),
+    ("register.title", "Join the Conversation", "चर्चा में शामिल हों"),
+    ("register.subtitle", "Create an account to share your views on legislation", "विधान पर अपनी राय साझा करने के लिए खाता बनाएँ"),
+    ("register.location", "Location Information", "स्थान की जानकारी"),
+    ("register.location_desc", "Choose how you'd like to identify your constituency", "चुनें कि आप अपना निर्वाचन क्षेत्र कैसे बताना चाहेंगे"),
+    ("register.submit", "Create Account", "खाता बनाएँ"),
+    ("register.have_account", "Already have an account?", "पहले से खाता है?"),
+    ("register.sign_in_here", "Sign in here", "यहाँ साइन इन करें"),
+    ("register.challenge_working", "Checking your browser before you can sign up…", "साइन अप से पहले आपके ब्राउज़र की जाँच हो रही है…"),
+    ("register.challenge_done", "Browser check complete.", "ब्राउज़र जाँच पूरी हुई।"),
+    ("register.challenge_failed", "We couldn't verify that you're not a bot. Please try again.", "हम पुष्टि नहीं कर सके कि आप बॉट नहीं हैं। कृपया फिर से प्रयास करें।"),
+    ("register.username_required", "Username is required", "उपयोगकर्ता नाम आवश्यक है"),
+    ("register.username_taken", "Username already taken", "यह उपयोगकर्ता नाम पहले से लिया जा चुका है"),
+    ("register.failed", "Failed to create account", "खाता नहीं बन सका"),
+    // Password reset
+    ("reset.forgot_page_title", "Forgot Password", "पासवर्ड भूल गए"),
+    ("reset.forgot_title", "Forgot Your Password?", "क्या आप अपना पासवर्ड भूल गए?"),
+    ("reset.forgot_subtitle", "Enter the confirmed recovery email of your account and we will send you a link to choose a new password.", "अपने खाते का पुष्टि किया गया पुनर्प्राप्ति ईमेल दर्ज करें, हम आपको नया पासवर्ड चुनने का लिंक भेजेंगे।"),
+    ("reset.send_link", "Send Reset Link", "रीसेट लिंक भेजें"),
+    ("reset.sent", "If that address is the confirmed email of an account, a reset link is on its way. It is valid for 30 minutes.", "यदि यह पता किसी खाते का पुष्टि किया गया ईमेल है, तो रीसेट लिंक भेजा जा रहा है। यह 30 मिनट तक मान्य है।"),
+    ("reset.back_to_login", "Back to sign in", "साइन इन पर वापस जाएँ"),
+    ("reset.page_title", "Reset Password", "पासवर्ड रीसेट करें"),
+    ("reset.title", "Choose a New Password", "नया पासवर्ड चुनें"),
+    ("reset.subtitle", "You will be signed out on all devices and your API tokens will be revoked once the password is changed.", "पासवर्ड बदलने के बाद आप सभी डिवाइस से साइन आउट हो जाएँगे और आपके API टोकन रद्द हो जाएँगे।"),
+    ("reset.submit", "Reset Password", "पासवर्ड रीसेट करें"),
+    ("reset.done", "Your password has been reset. Sign in with your new password.", "आपका पासवर्ड रीसेट कर दिया गया है। अपने नए पासवर्ड से साइन इन करें।"),
+    ("reset.invalid", "This reset link is invalid, has already been used or has expired.", "यह रीसेट लिंक अमान्य है, पहले ही उपयोग हो चुका है या इसकी अवधि समाप्त हो गई है।"),
+    ("reset.request_new", "Request a new link", "नया लिंक मँगाएँ"),
+    // Shared form fields
+    ("form.username", "Username", "उपयोगकर्ता नाम"),
+    ("form.password", "Password", "पासवर्ड"),
+    ("form.real_name", "Real Name", "वास्तविक नाम"),
+    ("form.email", "Email", "ईमेल"),
+    ("form.email_help", "Only used to recover your account, after you confirm it.", "पुष्टि करने के बाद, केवल आपके खाते को पुनः प्राप्त करने के लिए उपयोग होता है।"),
+    ("form.age", "Age", "आयु"),
+    ("form.gender", "Gender", "लिंग"),
+    ("form.optional", "(optional)", "(वैकल्पिक)"),
+    ("form.select", "Select...", "चुनें..."),
+    ("form.location", "Location", "स्थान"),
+    ("form.by_pincode", "By Pincode", "पिनकोड से"),
+    ("form.by_constituency", "By Constituency", "निर्वाचन क्षेत्र से"),
+    ("form.pincode", "Pincode", "पिनकोड"),
+    ("form.pincode_invalid", "A pincode must be 6 digits.", "पिनकोड 6 अंकों का होना चाहिए।"),
+    ("form.pincode_unmapped", "We don't know which constituency pincode {} belongs to. Please choose your constituency from the list instead.", "हमें नहीं पता कि पिनकोड {} किस निर्वाचन क्षेत्र में आता है। कृपया इसके बजाय सूची से अपना निर्वाचन क्षेत्र चुनें।"),
+    ("form.pincode_suggestions", "Nearby pincodes belong to: {}.", "आस-पास के पिनकोड इनमें आते हैं: {}।"),
+    ("form.constituency", "Constituency", "निर्वाचन क्षेत्र"),
+    ("form.select_constituency", "Select your constituency...", "अपना निर्वाचन क्षेत्र चुनें..."),
+    ("gender.Male", "Male", "पुरुष"),
+    ("gender.Female", "Female", "महिला"),
+    ("gender.Other", "Other", "अन्य"),
+    ("gender.Prefer not to say", "Prefer not to say", "बताना नहीं चाहते"),
+    // Profile
+    ("profile.age", "Age {}", "आयु {}"),
+    ("profile.member_since", "Member since {}", "{} से सदस्य"),
+    ("profile.post_count", "{} posts", "{} पोस्ट"),
+    ("profile.edit", "Edit Profile", "प्रोफ़ाइल संपादित करें"),
+    ("profile.visibility", "Visibility", "दृश्यता"),
+    ("profile.visibility_help", "Ticked fields are shown to everyone who visits your profile. Unticked fields are visible only to you and site admins.", "चिह्नित जानकारी आपकी प्रोफ़ाइल देखने वाले सभी को दिखती है। बिना चिह्न वाली जानकारी केवल आपको और साइट व्यवस्थापकों को दिखती है।"),
+    ("profile.save", "Save Changes", "बदलाव सहेजें"),
+    ("profile.security", "Security", "सुरक्षा"),
+    ("profile.manage_sessions", "Manage signed-in devices", "साइन-इन किए गए डिवाइस प्रबंधित करें"),
+    ("profile.no_password_help", "This account signs in with Google and has no password. To add one, request a reset link to your recovery email.", "यह खाता Google से साइन इन करता है और इसका कोई पासवर्ड नहीं है। पासवर्ड जोड़ने के लिए अपने पुनर्प्राप्ति ईमेल पर रीसेट लिंक मँगाएँ।"),
+    ("profile.google", "Google Account", "Google खाता"),
+    ("profile.google_help", "Link a Google account to sign in with it instead of your password.", "पासवर्ड के बजाय Google से साइन इन करने के लिए Google खाता लिंक करें।"),
+    ("profile.google_link", "Link Google account", "Google खाता लिंक करें"),
+    ("profile.google_unlink", "Unlink Google", "Google अनलिंक करें"),
+    ("profile.google_linked_as", "Linked to {}", "{} से लिंक है"),
+    ("profile.google_linked_plain", "Linked to a Google account", "एक Google खाते से लिंक है"),
+    ("profile.google_linked", "Your Google account has been linked.", "आपका Google खाता लिंक हो गया है।"),
+    ("profile.google_unlinked", "Your Google account has been unlinked.", "आपका Google खाता अनलिंक हो गया है।"),
+    ("profile.google_taken", "That Google account is already linked to another user.", "वह Google खाता पहले से किसी अन्य उपयोगकर्ता से लिंक है।"),
+    ("profile.google_needs_password", "Set a password before unlinking Google, or you will not be able to sign in.", "Google अनलिंक करने से पहले पासवर्ड सेट करें, नहीं तो आप साइन इन नहीं कर पाएँगे।"),
+    ("profile.manage_api_tokens", "Manage API tokens", "API टोकन प्रबंधित करें"),
+    ("profile.password_changed", "Your password has been changed.", "आपका पासवर्ड बदल दिया गया है।"),
+    ("profile.api_tokens_revoked", "Your API tokens ({}) were revoked; create new ones for any scripts that used them.", "आपके API टोकन ({}) रद्द कर दिए गए; उनका उपयोग करने वाली स्क्रिप्ट के लिए नए टोकन बनाएँ।"),
+    ("profile.current_password", "Current Password", "वर्तमान पासवर्ड"),
+    ("profile.new_password", "New Password", "नया पासवर्ड"),
+    ("profile.confirm_password", "Confirm New Password", "नए पासवर्ड की पुष्टि करें"),
+    ("profile.change_password", "Change Password", "पासवर्ड बदलें"),
+    ("profile.current_password_incorrect", "Current password is incorrect", "वर्तमान पासवर्ड गलत है"),
+    ("profile.passwords_mismatch", "New passwords do not match", "नए पासवर्ड मेल नहीं खाते"),
+    ("profile.email", "Recovery Email", "पुनर्प्राप्ति ईमेल"),
+    ("profile.email_help", "We send a confirmation link to this address. Until you open it, the address is used for nothing else. Leave the field empty to remove it.", "हम इस पते पर पुष्टि लिंक भेजते हैं। जब तक आप उसे नहीं खोलते, इस पते का कोई और उपयोग नहीं होता। हटाने के लिए फ़ील्ड खाली छोड़ें।"),
+    ("profile.email_verified", "Email verified", "ईमेल सत्यापित"),
+    ("profile.email_unverified", "Not verified", "सत्यापित नहीं"),
+    ("profile.email_save", "Save Email", "ईमेल सहेजें"),
+    ("profile.email_resend", "Resend confirmation link", "पुष्टि लिंक फिर से भेजें"),
+    ("profile.email_sent", "We sent a confirmation link to your email. It is valid for 48 hours.", "हमने आपके ईमेल पर पुष्टि लिंक भेजा है। यह 48 घंटे तक मान्य है।"),
+    ("profile.email_now_verified", "Your email address has been confirmed.", "आपके ईमेल पते की पुष्टि हो गई है।"),
+    ("profile.email_removed", "Your email address has been removed.", "आपका ईमेल पता हटा दिया गया है।"),
+    ("profile.digest_label", "Email me a weekly digest of bills I follow", "मेरे फ़ॉलो किए गए विधेयकों का साप्ताहिक सारांश मुझे ईमेल करें"),
+    ("profile.digest_help", "Sent once a week when there are new reviews or status changes on the bills you follow.", "जब आपके फ़ॉलो किए गए विधेयकों पर नई समीक्षाएँ या स्थिति में बदलाव हों, तब सप्ताह में एक बार भेजा जाता है।"),
+    ("profile.digest_save", "Save Digest Preference", "सारांश वरीयता सहेजें"),
+    ("profile.digest_saved", "Your digest preference has been saved.", "आपकी सारांश वरीयता सहेज ली गई है।"),
+    ("profile.email_invalid", "Please enter a valid email address", "कृपया एक मान्य ईमेल पता दर्ज करें"),
+    ("profile.export_data", "Your Data", "आपका डेटा"),
+    ("profile.export_help", "Download your profile, posts, votes and signed-in devices as a JSON file. You can do this once an hour.", "अपनी प्रोफ़ाइल, पोस्ट, वोट और साइन-इन किए गए डिवाइस JSON फ़ाइल के रूप में डाउनलोड करें। ऐसा घंटे में एक बार किया जा सकता है।"),
+    ("profile.export_download", "Download my data", "मेरा डेटा डाउनलोड करें"),
+    ("profile.delete_account", "Delete Account", "खाता हटाएँ"),
+    ("profile.delete_help", "Your sessions, followed bills and personal details (real name, age, gender and location) are removed permanently. This cannot be undone.", "आपके सत्र, फ़ॉलो किए गए विधेयक और निजी जानकारी (वास्तविक नाम, आयु, लिंग और स्थान) स्थायी रूप से हटा दिए जाएँगे। इसे वापस नहीं किया जा सकता।"),
+    ("profile.delete_confirm", "Delete your account permanently?", "अपना खाता स्थायी रूप से हटाएँ?"),
+    ("profile.delete_password", "Confirm with your password", "अपने पासवर्ड से पुष्टि करें"),
+    ("profile.password_incorrect", "Password is incorrect", "पासवर्ड गलत है"),
+    ("profile.your_posts", "Your Posts", "आपकी पोस्ट"),
+    ("profile.user_posts", "{}'s Posts", "{} की पोस्ट"),
+    ("profile.no_posts", "No posts yet.", "अभी कोई पोस्ट नहीं है।"),
+    ("profile.no_posts_hint", "Head to a bill's forum to share your thoughts!", "अपने विचार साझा करने के लिए किसी विधेयक के चर्चा मंच पर जाएँ!"),
+    ("moderation.reason", "Reason: {}", "कारण: {}"),
+    // Sessions
+    ("sessions.title", "Active Sessions", "सक्रिय सत्र"),
+    ("sessions.revoke_others", "Log out all other devices", "अन्य सभी डिवाइस से लॉग आउट करें"),
+    ("sessions.this_device", "This device", "यह डिवाइस"),
+    ("sessions.times", "Signed in {} · Expires {}", "{} को साइन इन · {} को समाप्त"),
+    ("sessions.revoke", "Revoke", "रद्द करें"),
+    ("sessions.revoked_none", "No other devices were signed in.", "कोई अन्य डिवाइस साइन इन नहीं था।"),
+    ("sessions.revoked_one", "{} other device was signed out.", "{} अन्य डिवाइस से साइन आउट किया गया।"),
+    ("sessions.revoked_many", "{} other devices were signed out.", "{} अन्य डिवाइस से साइन आउट किया गया।"),
+    // API tokens
+    ("api_tokens.title", "API Tokens", "API टोकन"),
+    ("api_tokens.help", "Tokens let scripts use the JSON API as you, without a browser. Send one in an Authorization: Bearer header.", "टोकन की मदद से स्क्रिप्ट बिना ब्राउज़र के आपकी ओर से JSON API का उपयोग कर सकती हैं। इसे Authorization: Bearer हेडर में भेजें।"),
+    ("api_tokens.docs", "API documentation", "API दस्तावेज़"),
+    ("api_tokens.created", "Copy your new token now. It will not be shown again.", "अपना नया टोकन अभी कॉपी करें। यह दोबारा नहीं दिखाया जाएगा।"),
+    ("api_tokens.name", "Token name", "टोकन का नाम"),
+    ("api_tokens.name_invalid", "Give the token a name of at most {} characters", "टोकन को अधिकतम {} अक्षरों का नाम दें"),
+    ("api_tokens.scope", "Access", "पहुँच"),
+    ("api_tokens.scope_read", "Read only", "केवल पढ़ना"),
+    ("api_tokens.scope_write", "Read and write", "पढ़ना और लिखना"),
+    ("api_tokens.create", "Create Token", "टोकन बनाएँ"),
+    ("api_tokens.times", "Created {} · Last used {}", "{} को बनाया · अंतिम उपयोग {}"),
+    ("api_tokens.never_used", "Created {} · Never used", "{} को बनाया · कभी उपयोग नहीं हुआ"),
+    ("api_tokens.revoke", "Revoke", "रद्द करें"),
+    ("api_tokens.revoke_confirm", "Revoke this token? Scripts using it will stop working.", "यह टोकन रद्द करें? इसका उपयोग करने वाली स्क्रिप्ट काम करना बंद कर देंगी।"),
+    ("api_tokens.none", "You have no API tokens.", "आपके पास कोई API टोकन नहीं है।"),
+    // Emails
+    ("email.verify_subject", "Confirm your email address", "अपने ईमेल पते की पुष्टि करें"),
+    ("email.verify_body", "Hello {},\n\nOpen this link within 48 hours to confirm this address as your account's recovery email:\n\n{}\n\nIf you did not add this address, you can ignore this email.", "नमस्ते {},\n\nइस पते को अपने खाते के पुनर्प्राप्ति ईमेल के रूप में पुष्टि करने के लिए 48 घंटे के भीतर यह लिंक खोलें:\n\n{}\n\nयदि आपने यह पता नहीं जोड़ा है, तो इस ईमेल को अनदेखा करें।"),
+    ("email.reset_subject", "Reset your password", "अपना पासवर्ड रीसेट करें"),
+    ("digest.subject", "Your weekly digest: {} update(s) on bills you follow", "आपका साप्ताहिक सारांश: फ़ॉलो किए गए विधेयकों पर {} अपडेट"),
+    ("digest.intro", "Hello {},\n\nHere is what happened this week on the bills you follow:", "नमस्ते {},\n\nइस सप्ताह आपके फ़ॉलो किए गए विधेयकों पर यह हुआ:"),
+    ("digest.footer", "You receive this because you follow these bills. To stop weekly digests, turn them off on your profile:\n{}", "आपको यह इसलिए मिला क्योंकि आप इन विधेयकों को फ़ॉलो करते हैं। साप्ताहिक सारांश बंद करने के लिए अपनी प्रोफ़ाइल पर जाएँ:\n{}"),
+    ("email.reset_body", "Hello {},\n\nSomeone asked to reset the password of your account. Open this link within 30 minutes to choose a new one:\n\n{}\n\nIf this was not you, ignore this email and your password will stay the same.", "नमस्ते {},\n\nकिसी ने आपके खाते का पासवर्ड रीसेट करने का अनुरोध किया है। नया पासवर्ड चुनने के लिए 30 मिनट के भीतर यह लिंक खोलें:\n\n{}\n\nयदि यह आप नहीं थे, तो इस ईमेल को अनदेखा करें, आपका पासवर्ड नहीं बदलेगा।"),
+    // Notifications
+    ("notifications.mark_all", "Mark all as read", "सभी को पढ़ा हुआ चिह्नित करें"),
+    ("notifications.mark", "Mark as read", "पढ़ा हुआ चिह्नित करें"),
+    ("bookmarks.subtitle", "Bills you saved to read later, with their latest status.", "बाद में पढ़ने के लिए सहेजे गए विधेयक, उनकी नवीनतम स्थिति के साथ।"),
+    ("bookmarks.empty", "No bookmarks yet. Use the star on a bill to save it here.", "अभी कोई बुकमार्क नहीं है। किसी विधेयक को यहाँ सहेजने के लिए उसके तारे का उपयोग करें।"),
+    ("bookmarks.saved", "saved {}", "{} को सहेजा गया"),
+    ("bookmarks.add", "Bookmark this bill", "इस विधेयक को बुकमार्क करें"),
+    ("bookmarks.remove", "Remove bookmark", "बुकमार्क हटाएँ"),
+    ("notifications.empty", "You're all caught up. Follow a bill from its forum page to hear about new reviews and status changes.", "कोई नई सूचना नहीं है। नई समीक्षाओं और स्थिति में बदलाव की जानकारी पाने के लिए किसी विधेयक को उसके चर्चा मंच से फ़ॉलो करें।"),
+    // Moderation queue
+    ("related.title", "Related legislation", "संबंधित विधान"),
+    ("related.similarity", "{}% similar", "{}% समान"),
+    ("moderation.title", "Moderation Queue", "मॉडरेशन कतार"),
+    ("moderation.awaiting", "{} post(s) awaiting review", "{} पोस्ट समीक्षा की प्रतीक्षा में"),
+    ("moderation.empty", "Nothing to review right now.", "अभी समीक्षा के लिए कुछ नहीं है।"),
+    ("moderation.flagged", "Flagged: {}", "चिह्नित: {}"),
+    ("moderation.remoderate", "Re-run automatic moderation", "स्वचालित मॉडरेशन फिर से चलाएँ"),
+    ("moderation.remoderate_hint", "Checks the oldest posts that moderation couldn't decide, e.g. while the model was unavailable. Reported posts, likely copies and posts a moderator has decided are left alone.", "उन सबसे पुरानी पोस्टों की जाँच करता है जिन पर मॉडरेशन निर्णय नहीं ले सका, जैसे जब मॉडल उपलब्ध नहीं था। रिपोर्ट की गई पोस्टें, संभावित प्रतियाँ और जिन पोस्टों पर मॉडरेटर निर्णय ले चुके हैं, वे नहीं बदलतीं।"),
+    ("moderation.remoderated", "Re-moderation finished: {} approved, {} rejected.", "पुनः मॉडरेशन पूरा हुआ: {} स्वीकृत, {} अस्वीकृत।"),
+    ("moderation.remoderated_pending", "{} still awaiting review.", "{} अभी भी समीक्षा की प्रतीक्षा में।"),
+    ("moderation.history", "Moderation history", "मॉडरेशन इतिहास"),
+    ("moderation.history_forum", "View forum", "मंच देखें"),
+    ("moderation.history_empty", "No automatic moderation has been recorded for this post.", "इस पोस्ट के लिए कोई स्वचालित मॉडरेशन दर्ज नहीं है।"),
+    ("moderation.history_provider", "Provider", "प्रदाता"),
+    ("moderation.history_verdict", "Verdict", "निर्णय"),
+    ("moderation.history_latency", "Time taken", "लगा समय"),
+    ("moderation.history_response", "Response", "उत्तर"),
+    ("moderation.history_ms", "{} ms", "{} ms"),
+    ("moderation.history_prompt", "Prompt SHA-256: {}", "प्रॉम्प्ट SHA-256: {}"),
+    ("moderation.checked_by", "(checked by {})", "({} द्वारा जाँचा गया)"),
+    ("moderation.trust_bias.held", "Held: low-trust author", "रोका गया: कम भरोसे वाला लेखक"),
+    ("moderation.trust_bias.approved", "Approved: high-trust author", "स्वीकृत: उच्च भरोसे वाला लेखक"),
+    ("moderation.trust_bias.approved_audit", "Approved: high-trust author, sampled for a spot-check", "स्वीकृत: उच्च भरोसे वाला लेखक, जाँच के लिए चुना गया"),
+    ("appeal.action", "Appeal this decision", "इस निर्णय के विरुद्ध अपील करें"),
+    ("appeal.statement_placeholder", "Why should a moderator take another look? (optional)", "मॉडरेटर को दोबारा क्यों देखना चाहिए? (वैकल्पिक)"),
+    ("appeal.hint", "A moderator will review the post and the reason it was rejected. Each post can be appealed once.", "एक मॉडरेटर पोस्ट और उसकी अस्वीकृति के कारण की समीक्षा करेगा। हर पोस्ट पर एक ही बार अपील की जा सकती है।"),
+    ("appeal.submit", "Submit appeal", "अपील भेजें"),
+    ("appeal.filed", "Appeal submitted. You'll get a notification when a moderator has decided.", "अपील भेज दी गई। मॉडरेटर के निर्णय लेने पर आपको सूचना मिलेगी।"),
+    ("appeal.too_long", "Appeal statements can be at most {} characters.", "अपील का विवरण अधिकतम {} अक्षरों का हो सकता है।"),
+    ("appeal.rate_limited", "You've made too many appeals today. Please try again tomorrow.", "आपने आज बहुत अधिक अपीलें की हैं। कृपया कल फिर प्रयास करें।"),
+    ("appeal.unavailable", "This post can't be appealed. It may already have been appealed or is no longer rejected.", "इस पोस्ट पर अपील नहीं की जा सकती। हो सकता है इस पर पहले ही अपील हो चुकी हो या यह अब अस्वीकृत न हो।"),
+    ("appeal.queue_title", "Appeals", "अपीलें"),
+    ("appeal.awaiting", "{} appeal(s) awaiting a decision", "{} अपीलें निर्णय की प्रतीक्षा में"),
+    ("appeal.queue_empty", "No appeals to review.", "समीक्षा के लिए कोई अपील नहीं।"),
+    ("appeal.appealed_on", "The author appealed on {}:", "लेखक ने {} को अपील की:"),
+    ("appeal.no_statement", "The author gave no statement.", "लेखक ने कोई विवरण नहीं दिया।"),
+    ("appeal.no_reason", "No rejection reason was recorded.", "अस्वीकृति का कोई कारण दर्ज नहीं है।"),
+    ("appeal.overturn", "Overturn and publish", "पलटें और प्रकाशित करें"),
+    ("appeal.uphold", "Uphold rejection", "अस्वीकृति बरकरार रखें"),
+    ("moderation.reports", "{} report(s)", "{} रिपोर्ट"),
+    ("moderation.duplicate_of", "{}% similar to an earlier post by", "इनके पिछले पोस्ट से {}% मिलता-जुलता:"),
+    ("moderation.anonymous", "Posted anonymously", "गुमनाम रूप से पोस्ट किया गया"),
+    ("moderation.approve", "Approve", "स्वीकृत करें"),
+    ("moderation.reject", "Reject", "अस्वीकार करें"),
+    ("moderation.reject_placeholder", "Reason shown to the author", "लेखक को दिखाया जाने वाला कारण"),
+    ("moderation.spot_check_title", "Trusted approvals to spot-check", "जाँच के लिए भरोसेमंद स्वीकृतियाँ"),
+    ("moderation.spot_check_awaiting", "{} post(s) approved on their authors' trust", "{} पोस्ट लेखकों के भरोसे पर स्वीकृत"),
+    ("moderation.spot_check_reason", "Moderation was unsure; approved because the author is trusted.", "मॉडरेशन निश्चित नहीं था; लेखक भरोसेमंद होने के कारण स्वीकृत।"),
+    ("moderation.spot_check_ok", "Looks fine", "ठीक है"),
+    // Admin user console
+    ("admin.users_subtitle", "Change roles, suspend accounts and review each user's moderation history", "भूमिकाएँ बदलें, खाते निलंबित करें और हर उपयोगकर्ता का मॉडरेशन इतिहास देखें"),
+    ("admin.role", "Role", "भूमिका"),
+    ("admin.status", "Status", "स्थिति"),
+    ("admin.posts", "Posts", "पोस्ट"),
+    ("admin.rejected", "Rejected", "अस्वीकृत"),
+    ("admin.joined", "Joined", "शामिल हुए"),
+    ("admin.joined_on", "Joined {}", "{} को शामिल हुए"),
+    ("admin.active", "Active", "सक्रिय"),
+    ("admin.suspended", "Suspended", "निलंबित"),
+    ("search_stats.subtitle", "What citizens searched for in the last {} days", "पिछले {} दिनों में नागरिकों ने क्या खोजा"),
+    ("search_stats.empty", "No searches recorded yet.", "अभी तक कोई खोज दर्ज नहीं हुई।"),
+    ("search_stats.totals", "{} searches, {} with no results", "{} खोजें, जिनमें से {} का कोई परिणाम नहीं"),
+    ("search_stats.daily", "Daily volume", "दैनिक खोजें"),
+    ("search_stats.day", "Day", "दिन"),
+    ("search_stats.searches", "Searches", "खोजें"),
+    ("search_stats.zero_results", "No results", "कोई परिणाम नहीं"),
+    ("search_stats.searchers", "Signed-in searchers", "लॉग-इन खोजकर्ता"),
+    ("search_stats.top", "Top searches", "सबसे अधिक खोजे गए"),
+    ("search_stats.zero", "Searches with no results", "बिना परिणाम वाली खोजें"),
+    ("search_stats.zero_hint", "Topics citizens are asking about that no ingested bill covers", "ऐसे विषय जिनके बारे में नागरिक पूछ रहे हैं पर कोई जोड़ा गया विधेयक उन्हें शामिल नहीं करता"),
+    ("search_stats.no_zero", "Every search found something.", "हर खोज का कोई परिणाम मिला।"),
+    ("search_stats.query", "Query", "खोज"),
+    ("search_stats.avg_results", "Avg. results", "औसत परिणाम"),
+    ("search_stats.last_searched", "Last searched", "अंतिम खोज"),
+    ("rate_limits.subtitle", "Actions allowed and refused by rate limits over the last 24 hours", "पिछले 24 घंटों में दर सीमाओं द्वारा अनुमत और अस्वीकृत कार्रवाइयाँ"),
+    ("rate_limits.empty", "No rate-limited actions in the last 24 hours.", "पिछले 24 घंटों में कोई दर-सीमित कार्रवाई नहीं हुई।"),
+    ("rate_limits.action", "Action", "कार्रवाई"),
+    ("rate_limits.allowed", "Allowed", "अनुमत"),
+    ("rate_limits.rejected", "Refused", "अस्वीकृत"),
+    ("rate_limits.throttled", "Most throttled members", "सबसे अधिक सीमित सदस्य"),
+    ("rate_limits.throttled_hint", "Remaining shows what each member has left in the current window, out of their limit.", "शेष दिखाता है कि वर्तमान अवधि में प्रत्येक सदस्य की सीमा में से कितना बचा है।"),
+    ("rate_limits.no_throttled", "Nobody has hit a rate limit in the last 24 hours.", "पिछले 24 घंटों में किसी ने दर सीमा नहीं छुई।"),
+    ("rate_limits.member", "Member", "सदस्य"),
+    ("rate_limits.last_rejected", "Last refused", "अंतिम अस्वीकृति"),
+    ("rate_limits.exempt", "Exempt", "छूट"),
+    ("rate_limits.not_a_bot", "Checked: not a bot", "जाँचा: बॉट नहीं"),
+    ("rate_limits.clear", "Clear window", "अवधि साफ़ करें"),
+    ("rate_limits.clear_confirm", "Clear this member's rate limit window? Their recent actions stop counting.", "इस सदस्य की दर सीमा अवधि साफ़ करें? उनकी हाल की कार्रवाइयाँ गिनी नहीं जाएँगी।"),
+    ("rate_limits.action.post_create", "Posts", "पोस्ट"),
+    ("rate_limits.action.vote", "Votes", "वोट"),
+    ("rate_limits.action.report", "Reports", "रिपोर्ट"),
+    ("rate_limits.action.search", "Searches", "खोजें"),
+    ("rate_limits.action.report_job", "Constituency PDFs", "निर्वाचन क्षेत्र PDF"),
+    ("rate_limits.action.ask", "Questions", "प्रश्न"),
+    ("audit.subtitle", "Sign-ins, password and role changes, profile updates and moderation, newest first", "लॉगिन, पासवर्ड और भूमिका में बदलाव, प्रोफ़ाइल अपडेट और मॉडरेशन, नवीनतम पहले"),
+    ("audit.user", "Username", "उपयोगकर्ता नाम"),
+    ("audit.from", "From", "से"),
+    ("audit.to", "To", "तक"),
+    ("audit.any_action", "Any action", "कोई भी कार्रवाई"),
+    ("audit.filter", "Filter", "छाँटें"),
+    ("audit.clear", "Clear", "हटाएँ"),
+    ("audit.empty", "No entries match these filters.", "इन फ़िल्टरों से मेल खाती कोई प्रविष्टि नहीं है।"),
+    ("audit.when", "When", "कब"),
+    ("audit.action", "Action", "कार्रवाई"),
+    ("audit.actor", "By", "द्वारा"),
+    ("audit.target", "Target", "लक्ष्य"),
+    ("audit.origin", "From address", "पता"),
+    ("audit.details", "Details", "विवरण"),
+    ("audit.action.login_succeeded", "Signed in", "लॉगिन किया"),
+    ("audit.action.login_failed", "Failed sign-in", "असफल लॉगिन"),
+    ("audit.action.logout", "Signed out", "लॉगआउट किया"),
+    ("audit.action.password_changed", "Password changed", "पासवर्ड बदला"),
+    ("audit.action.password_reset", "Password reset", "पासवर्ड रीसेट"),
+    ("audit.action.profile_updated", "Profile updated", "प्रोफ़ाइल अपडेट"),
+    ("audit.action.role_changed", "Role changed", "भूमिका बदली"),
+    ("audit.action.rate_limit_changed", "Rate limit changed", "दर सीमा बदली"),
+    ("audit.action.rate_limit_cleared", "Rate limit window cleared", "दर सीमा अवधि साफ़ की"),
+    ("audit.action.user_suspended", "User suspended", "उपयोगकर्ता निलंबित"),
+    ("audit.action.user_reinstated", "User reinstated", "उपयोगकर्ता बहाल"),
+    ("audit.action.post_approved", "Post approved", "पोस्ट स्वीकृत"),
+    ("audit.action.post_rejected", "Post rejected", "पोस्ट अस्वीकृत"),
+    ("audit.action.appeal_upheld", "Appeal upheld", "अपील पर अस्वीकृति बरकरार"),
+    ("audit.action.appeal_overturned", "Appeal overturned rejection", "अपील पर अस्वीकृति पलटी"),
+    ("audit.action.keywords_changed", "Moderation keywords changed", "मॉडरेशन कीवर्ड बदले"),
+    ("keywords.title", "Moderation keywords", "मॉडरेशन कीवर्ड"),
+    ("keywords.subtitle", "Patterns the keyword filter checks when the moderation model can't decide, or on its own with MODERATION_BACKEND=keyword.", "जब मॉडरेशन मॉडल निर्णय नहीं ले पाता, या MODERATION_BACKEND=keyword होने पर, कीवर्ड फ़िल्टर इन पैटर्नों की जाँच करता है।"),
+    ("keywords.help", "Plain patterns match whole words, ignoring case. Regexes are case-insensitive. A post is acted on for one high-severity match or two matches of any severity. Posts are checked against their detected language's patterns and the English ones. Changes apply within a minute.", "सामान्य पैटर्न पूरे शब्दों से मेल खाते हैं, अक्षरों के छोटे-बड़े होने से फ़र्क नहीं पड़ता। रेगेक्स भी केस-असंवेदी हैं। किसी पोस्ट पर कार्रवाई एक उच्च गंभीरता वाले मेल या किसी भी गंभीरता के दो मेलों पर होती है। पोस्ट की जाँच उसकी पहचानी गई भाषा के पैटर्नों और अंग्रेज़ी पैटर्नों से होती है। बदलाव एक मिनट के भीतर लागू होते हैं।"),
+    ("keywords.pattern", "Pattern", "पैटर्न"),
+    ("keywords.pattern_placeholder", "e.g. free money", "जैसे free money"),
+    ("keywords.regex", "Regex", "रेगेक्स"),
+    ("keywords.kind", "Category", "श्रेणी"),
+    ("keywords.kind.hate", "Hate", "घृणा"),
+    ("keywords.kind.threat", "Threat", "धमकी"),
+    ("keywords.kind.toxicity", "Toxicity", "विषाक्तता"),
+    ("keywords.kind.spam", "Spam", "स्पैम"),
+    ("keywords.action", "On match", "मेल होने पर"),
+    ("keywords.action.review", "Hold for review", "समीक्षा के लिए रोकें"),
+    ("keywords.action.reject", "Reject", "अस्वीकार करें"),
+    ("keywords.severity", "Severity", "गंभीरता"),
+    ("keywords.severity.low", "Low", "कम"),
+    ("keywords.severity.high", "High", "उच्च"),
+    ("keywords.language", "Language", "भाषा"),
+    ("keywords.language.en", "English (all posts)", "अंग्रेज़ी (सभी पोस्ट)"),
+    ("keywords.language.hi", "Hindi", "हिन्दी"),
+    ("keywords.language.hinglish", "Hinglish", "हिंग्लिश"),
+    ("keywords.enabled", "Enabled", "सक्रिय"),
+    ("keywords.updated", "Last changed", "अंतिम बदलाव"),
+    ("keywords.add", "Add pattern", "पैटर्न जोड़ें"),
+    ("keywords.save", "Save", "सहेजें"),
+    ("keywords.delete", "Delete", "हटाएँ"),
+    ("keywords.delete_confirm", "Delete this pattern?", "यह पैटर्न हटाएँ?"),
+    ("keywords.empty", "No patterns yet. Every post the keyword filter checks will be approved.", "अभी कोई पैटर्न नहीं। कीवर्ड फ़िल्टर द्वारा जाँची गई हर पोस्ट स्वीकृत होगी।"),
+    ("keywords.invalid", "That pattern can't be used: {}", "यह पैटर्न उपयोग नहीं किया जा सकता: {}"),
+    ("keywords.duplicate", "That pattern is already in the list.", "यह पैटर्न पहले से सूची में है।"),
+    ("admin.post_summary", "{} post(s), {} rejected", "{} पोस्ट, {} अस्वीकृत"),
+    ("admin.all_users", "← All users", "← सभी उपयोगकर्ता"),
+    ("admin.change_role", "Change role", "भूमिका बदलें"),
+    ("admin.rate_limit_default", "Usual rate limits (role default)", "सामान्य दर सीमाएँ (भूमिका के अनुसार)"),
+    ("admin.rate_limit_default_exempt", "Exempt from rate limits (role default)", "दर सीमाओं से छूट (भूमिका के अनुसार)"),
+    ("admin.rate_limit_exempt", "Exempt from rate limits", "दर सीमाओं से छूट"),
+    ("admin.rate_limit_times", "{}× the usual rate limits", "सामान्य दर सीमाओं का {} गुना"),
+    ("admin.change_rate_limit", "Set rate limit", "दर सीमा तय करें"),
+    ("admin.reinstate", "Reinstate", "बहाल करें"),
+    ("admin.suspend", "Suspend", "निलंबित करें"),
+    ("admin.suspend_confirm", "Suspend this user? They will be logged out and unable to post.", "इस उपयोगकर्ता को निलंबित करें? वे लॉग आउट हो जाएँगे और पोस्ट नहीं कर सकेंगे।"),
+    ("admin.history", "Moderation History", "मॉडरेशन इतिहास"),
+    ("admin.history_empty", "No moderated or reported posts.", "कोई मॉडरेट या रिपोर्ट की गई पोस्ट नहीं है।"),
+    ("admin.moderated_by", "By {}", "{} द्वारा"),
+    ("admin.moderated_on", "on {}", "{} को"),
+    // Bill ingestion
+    ("ingest.title", "Ingest a Bill", "विधेयक जोड़ें"),
+    ("ingest.subtitle", "Fetch a bill from its PRS page or PDF link, then extract, chunk and embed it for search. One bill is ingested at a time.", "PRS पृष्ठ या PDF लिंक से विधेयक लाएँ, फिर खोज के लिए उसका पाठ निकालें, खंडों में बाँटें और एम्बेड करें। एक समय में एक ही विधेयक जोड़ा जाता है।"),
+    ("ingest.url", "PRS page or PDF URL", "PRS पृष्ठ या PDF URL"),
+    ("ingest.bill_title", "Bill title", "विधेयक का शीर्षक"),
+    ("ingest.bill_title_help", "Taken from the PRS page when left empty; for a PDF link, the file name is used.", "खाली छोड़ने पर PRS पृष्ठ से लिया जाता है; PDF लिंक के लिए फ़ाइल का नाम उपयोग होता है।"),
+    ("ingest.start", "Start Ingest", "जोड़ना शुरू करें"),
+    ("ingest.recent", "Recent Jobs", "हाल के कार्य"),
+    ("ingest.none", "No bills have been ingested since the server started.", "सर्वर शुरू होने के बाद से कोई विधेयक नहीं जोड़ा गया है।"),
+    ("ingest.started", "Started", "शुरू"),
+    ("ingest.started_at", "Started {}", "{} को शुरू"),
+    ("ingest.finished_at", "Finished {}", "{} को समाप्त"),
+    ("ingest.all_jobs", "← All ingest jobs", "← सभी कार्य"),
+    ("ingest.job_title", "Ingest Job", "विधेयक जोड़ने का कार्य"),
+    ("ingest.status_running", "Running", "चल रहा है"),
+    ("ingest.status_succeeded", "Succeeded", "सफल"),
+    ("ingest.status_failed", "Failed", "विफल"),
+    ("ingest.invalid_url", "Enter an http:// or https:// URL.", "http:// या https:// URL दर्ज करें।"),
+    ("ingest.already_running", "Another bill is already being ingested. Wait for it to finish before starting a new one.", "एक अन्य विधेयक पहले से जोड़ा जा रहा है। नया शुरू करने से पहले उसके पूरा होने की प्रतीक्षा करें।"),
+    ("ingest.view_running", "View running job", "चल रहा कार्य देखें"),
+    // Constituency dashboard
+    ("constituency.page_title", "Constituency Dashboard", "निर्वाचन क्षेत्र डैशबोर्ड"),
+    ("constituency.summary", "{} participating user(s) · {} bill(s) discussed", "{} सहभागी उपयोगकर्ता · {} विधेयकों पर चर्चा"),
+    ("constituency.download", "Download PDF", "PDF डाउनलोड करें"),
+    ("report_job.title", "Constituency Report", "निर्वाचन क्षेत्र रिपोर्ट"),
+    ("report_job.back", "Back to the constituency", "निर्वाचन क्षेत्र पर वापस जाएँ"),
+    ("report_job.preparing", "The report is being prepared. This page updates when it is ready; you can leave and come back to it.", "रिपोर्ट तैयार की जा रही है। तैयार होने पर यह पेज अपडेट हो जाएगा; आप जाकर बाद में लौट सकते हैं।"),
+    ("report_job.failed", "The report couldn't be made. Please try again later.", "रिपोर्ट नहीं बन सकी। कृपया बाद में फिर से प्रयास करें।"),
+    ("report_job.status_running", "Preparing", "तैयार हो रही है"),
+    ("report_job.status_succeeded", "Ready", "तैयार"),
+    ("report_job.status_failed", "Failed", "विफल"),
+    ("constituency.download_csv", "Stances CSV", "रुख CSV"),
+    ("constituency.download_posts_csv", "Posts CSV", "पोस्ट CSV"),
+    ("constituency.empty", "No one from this constituency has reviewed a bill yet.", "इस निर्वाचन क्षेत्र से अभी तक किसी ने किसी विधेयक की समीक्षा नहीं की है।"),
+    ("constituency.leaderboard", "Constituency Participation", "निर्वाचन क्षेत्रों की भागीदारी"),
+    ("constituency.leaderboard_subtitle", "Constituencies ranked by approved reviews and replies from their residents.", "निवासियों की स्वीकृत समीक्षाओं और जवाबों के आधार पर निर्वाचन क्षेत्रों की रैंकिंग।"),
+    ("constituency.window_30", "Last 30 days", "पिछले 30 दिन"),
+    ("constituency.window_90", "Last 90 days", "पिछले 90 दिन"),
+    ("constituency.window_all", "All time", "अब तक"),
+    ("constituency.rank", "Rank", "क्रम"),
+    ("constituency.name", "Constituency", "निर्वाचन क्षेत्र"),
+    ("constituency.state", "State", "राज्य"),
+    ("constituency.posts", "Posts", "पोस्ट"),
+    ("constituency.participants", "Participants", "सहभागी"),
+    ("constituency.all", "All constituencies", "सभी निर्वाचन क्षेत्र"),
+    ("constituency.bill_summary", "Bill No. {} · {} review(s)", "विधेयक सं. {} · {} समीक्षाएँ"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_language_prefers_highest_quality() {
+        assert_eq!(Locale::from_accept_language("hi-IN,hi;q=0.9,en;q=0.8"), Some(Locale::Hi));
+        assert_eq!(Locale::from_accept_language("en-GB;q=0.5, hi;q=0.7"), Some(Locale::Hi));
+        assert_eq!(Locale::from_accept_language("fr, en;q=0.3"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("hi;q=0, ta"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+    }
+
+    #[test]
+    fn test_cookie_overrides_accept_language() {
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert(header::ACCEPT_LANGUAGE, "hi-IN".parse().unwrap());
+        assert_eq!(Locale::from_headers(&headers), Locale::Hi);
+
+        headers.insert(header::COOKIE, "lang=en".parse().unwrap());
+        assert_eq!(Locale::from_headers(&headers), Locale::En);
+
+        assert_eq!(Locale::from_headers(&axum::http::HeaderMap::new()), Locale::En);
+    }
+
+    #[test]
+    fn test_every_key_is_unique_and_translated() {
+        let mut seen = std::collections::HashSet::new();
+        for (key, en, hi) in STRINGS {
+            assert!(seen.insert(key), "duplicate key {}", key);
+            assert!(!en.is_empty() && !hi.is_empty(), "empty translation for {}", key);
+            assert_eq!(en.matches("{}").count(), hi.matches("{}").count(), "placeholder mismatch in {}", key);
+        }
+    }
+
+    #[test]
+    fn test_label_falls_back_to_stored_value() {
+        assert_eq!(Locale::Hi.label("stance", "Support"), "समर्थन");
+        assert_eq!(Locale::En.label("status", "pending_review"), "Pending Review");
+        assert_eq!(Locale::Hi.label("stance", "Neutral"), "Neutral");
+        assert_eq!(Locale::Hi.tf2("pagination.page", 2, 5), "पृष्ठ 2 / 5");
+    }
+}
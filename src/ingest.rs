@@ -0,0 +1,255 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::extractor::ExtractedText;
+use crate::models::{Bill, DbBill, TextChunk};
+use crate::{chunker, db, embedder, extractor, scraper, vector_store};
+
+/// Finished jobs kept for the admin pages; older ones are dropped
+const MAX_FINISHED_JOBS: usize = 20;
+
+/// Run one bill through extract -> chunk -> embed -> store, reporting each step to `log`.
+/// With a pool the bill, its text and its chunks are stored in one transaction and the chunks
+/// carry the database ids, so search results link to its forum; without one only the vector
+/// index is written.
+pub async fn ingest_bill(pool: Option<&PgPool>, bill: &Bill, log: impl Fn(String)) -> Result<()> {
+    log(format!("Processing: {}", bill.title));
+
+    log(format!("  → Extracting text from PDF {}...", bill.pdf_url));
+    let extracted = extractor::extract_text_from_pdf(&bill.pdf_url).await?;
+
+    let mut bill = bill.clone();
+    let chunks = match pool {
+        Some(pool) => {
+            let mut tx = pool.begin().await.context("Failed to start transaction")?;
+            // A re-ingested bill keeps the id it was first stored with
+            let db_bill = db::insert_bill(&mut *tx, &bill).await?;
+            bill.id = db_bill.id;
+            db::set_bill_text(&mut *tx, &bill.bill_number, &extracted.text).await?;
+
+            let mut chunks = chunk_bill(&bill, &extracted, &log);
+            let ids = db::replace_bill_chunks(&mut *tx, bill.id, &chunks).await?;
+            for (chunk, id) in chunks.iter_mut().zip(ids) {
+                chunk.id = id;
+            }
+            tx.commit().await.context("Failed to commit bill")?;
+            log("  → Stored bill and chunks in database".to_string());
+            chunks
+        }
+        None => chunk_bill(&bill, &extracted, &log),
+    };
+
+    log("  → Generating embeddings...".to_string());
+    let embedded_chunks = embedder::embed_chunks(&chunks).await?;
+
+    log("  → Storing in vector database...".to_string());
+    vector_store::store_chunks(&bill.title, bill.year, &embedded_chunks).await?;
+
+    log(format!("✓ Completed: {}", bill.title));
+    Ok(())
+}
+
+fn chunk_bill(bill: &Bill, extracted: &ExtractedText, log: &impl Fn(String)) -> Vec<TextChunk> {
+    log("  → Chunking text semantically...".to_string());
+    let mut chunks = chunker::chunk_text(&extracted.text, bill.id, &bill.bill_number);
+    chunker::assign_pages(&mut chunks, &extracted.text, &extracted.pages);
+    log(format!("  → Created {} chunks", chunks.len()));
+    chunks
+}
+
+/// Embed a stored bill's chunks again and overwrite their points, e.g. after the embedding
+/// model changes. Returns how many chunks were indexed.
+pub async fn reindex_bill(pool: &PgPool, bill: &DbBill) -> Result<usize> {
+    let chunks: Vec<TextChunk> = db::get_chunks_for_bill(pool, bill.id)
+        .await?
+        .iter()
+        .map(|c| c.to_text_chunk(&bill.bill_number))
+        .collect();
+    if chunks.is_empty() {
+        return Ok(0);
+    }
+
+    let embedded_chunks = embedder::embed_chunks(&chunks).await?;
+    vector_store::store_chunks(&bill.title, bill.year, &embedded_chunks).await?;
+    Ok(chunks.len())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+        }
+    }
+}
+
+/// An admin-triggered ingest of one bill URL
+#[derive(Debug, Clone)]
+pub struct IngestJob {
+    pub id: Uuid,
+    pub url: String,
+    pub status: JobStatus,
+    pub log: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+struct JobsInner {
+    jobs: HashMap<Uuid, IngestJob>,
+    running: Option<Uuid>,
+}
+
+/// Ingest jobs started from the admin UI. Only one runs at a time, since embedding
+/// holds the model lock and a second job would just queue behind it.
+#[derive(Default)]
+pub struct IngestJobs {
+    inner: Mutex<JobsInner>,
+}
+
+impl IngestJobs {
+    /// Register a new running job, or return the id of the one already running
+    fn start(&self, url: &str) -> Result<Uuid, Uuid> {
+        let mut inner = self.inner.lock();
+        if let Some(running) = inner.running {
+            return Err(running);
+        }
+
+        let id = Uuid::new_v4();
+        inner.jobs.insert(
+            id,
+            IngestJob {
+                id,
+                url: url.to_string(),
+                status: JobStatus::Running,
+                log: Vec::new(),
+                started_at: Utc::now(),
+                finished_at: None,
+            },
+        );
+        inner.running = Some(id);
+        Ok(id)
+    }
+
+    fn append(&self, id: Uuid, line: String) {
+        tracing::info!("[ingest {}] {}", id, line);
+        if let Some(job) = self.inner.lock().jobs.get_mut(&id) {
+            job.log.push(line);
+        }
+    }
+
+    fn finish(&self, id: Uuid, result: Result<()>) {
+        let mut inner = self.inner.lock();
+        if let Some(job) = inner.jobs.get_mut(&id) {
+            match result {
+                Ok(()) => job.status = JobStatus::Succeeded,
+                Err(e) => {
+                    tracing::warn!("[ingest {}] Failed: {:#}", id, e);
+                    job.log.push(format!("✗ Failed: {:#}", e));
+                    job.status = JobStatus::Failed;
+                }
+            }
+            job.finished_at = Some(Utc::now());
+        }
+        if inner.running == Some(id) {
+            inner.running = None;
+        }
+
+        let mut finished: Vec<(DateTime<Utc>, Uuid)> = inner
+            .jobs
+            .values()
+            .filter(|j| j.status != JobStatus::Running)
+            .map(|j| (j.started_at, j.id))
+            .collect();
+        if finished.len() > MAX_FINISHED_JOBS {
+            finished.sort();
+            for (_, old) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+                inner.jobs.remove(old);
+            }
+        }
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<IngestJob> {
+        self.inner.lock().jobs.get(&id).cloned()
+    }
+
+    /// All known jobs, newest first
+    pub fn recent(&self) -> Vec<IngestJob> {
+        let mut jobs: Vec<IngestJob> = self.inner.lock().jobs.values().cloned().collect();
+        jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        jobs
+    }
+}
+
+/// Start ingesting a PRS bill page or PDF URL in the background.
+/// Returns the new job's id, or Err with the id of the job that is already running.
+pub fn spawn(jobs: Arc<IngestJobs>, pool: PgPool, url: String, title: Option<String>) -> Result<Uuid, Uuid> {
+    let id = jobs.start(&url)?;
+
+    tokio::spawn(async move {
+        // The work runs in a task of its own so that a panic while extracting, chunking or
+        // embedding fails the job instead of leaving it running and blocking every later ingest
+        let log = jobs.clone();
+        let work = tokio::spawn(async move {
+            log.append(id, format!("Fetching {}...", url));
+            let bill = scraper::fetch_bill_from_url(&url, title.as_deref()).await?;
+            log.append(id, format!("✓ Found {} ({})", bill.title, bill.bill_number));
+            ingest_bill(Some(&pool), &bill, |line| log.append(id, line)).await
+        });
+
+        let result = match work.await {
+            Ok(result) => result,
+            Err(e) => Err(anyhow::anyhow!("Ingest task stopped unexpectedly: {}", e)),
+        };
+        jobs.finish(id, result);
+    });
+
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_one_job_runs_at_a_time() {
+        let jobs = IngestJobs::default();
+        let first = jobs.start("https://prsindia.org/billtrack/a").unwrap();
+        assert_eq!(jobs.start("https://prsindia.org/billtrack/b"), Err(first));
+
+        jobs.append(first, "Processing".to_string());
+        jobs.finish(first, Err(anyhow::anyhow!("PDF not found")));
+
+        let job = jobs.get(first).unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.log, vec!["Processing".to_string(), "✗ Failed: PDF not found".to_string()]);
+        assert!(job.finished_at.is_some());
+
+        let second = jobs.start("https://prsindia.org/billtrack/b").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(jobs.recent().len(), 2);
+    }
+
+    #[test]
+    fn test_old_finished_jobs_are_dropped() {
+        let jobs = IngestJobs::default();
+        for i in 0..MAX_FINISHED_JOBS + 3 {
+            let id = jobs.start(&format!("https://example.org/{}.pdf", i)).unwrap();
+            jobs.finish(id, Ok(()));
+        }
+        assert_eq!(jobs.recent().len(), MAX_FINISHED_JOBS);
+    }
+}
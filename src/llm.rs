@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+struct OllamaRequest<'a> {
+    model: String,
+    prompt: &'a str,
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaResponse {
+    response: String,
+}
+
+fn ollama_url() -> String {
+    std::env::var("OLLAMA_URL").unwrap_or_else(|_| "http://localhost:11434".to_string())
+}
+
+fn ollama_model() -> String {
+    std::env::var("OLLAMA_MODEL").unwrap_or_else(|_| "llama3.2".to_string())
+}
+
+/// Ollama client configured from OLLAMA_URL and OLLAMA_MODEL; cheap to clone
+#[derive(Clone)]
+pub struct OllamaClient {
+    http: reqwest::Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaClient {
+    pub fn new(http: reqwest::Client) -> Self {
+        Self {
+            http,
+            base_url: ollama_url(),
+            model: ollama_model(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    #[cfg(test)]
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Send a prompt to Ollama and return the generated text
+    #[tracing::instrument(name = "ollama.generate", skip_all, fields(model = %self.model))]
+    pub async fn generate(&self, prompt: &str, timeout_secs: u64) -> Result<String> {
+        let request = OllamaRequest {
+            model: self.model.clone(),
+            prompt,
+            stream: false,
+        };
+
+        let response = self
+            .http
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&request)
+            .timeout(std::time::Duration::from_secs(timeout_secs))
+            .send()
+            .await
+            .context("Failed to connect to Ollama")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Ollama returned error status: {}", response.status());
+        }
+
+        let ollama_response = response
+            .json::<OllamaResponse>()
+            .await
+            .context("Failed to parse Ollama response")?;
+
+        Ok(ollama_response.response)
+    }
+}
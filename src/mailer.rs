@@ -0,0 +1,84 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::sync::Arc;
+
+/// Sends plain-text email; the web server holds one behind an Arc
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()>;
+}
+
+/// SMTP delivery, configured from SMTP_HOST, SMTP_PORT, SMTP_USERNAME, SMTP_PASSWORD and MAIL_FROM
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn from_env(host: &str) -> Result<Self> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .with_context(|| format!("Invalid SMTP host {}", host))?;
+
+        if let Some(port) = std::env::var("SMTP_PORT").ok().and_then(|p| p.parse().ok()) {
+            builder = builder.port(port);
+        }
+        if let (Ok(username), Ok(password)) = (std::env::var("SMTP_USERNAME"), std::env::var("SMTP_PASSWORD")) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let from = std::env::var("MAIL_FROM")
+            .unwrap_or_else(|_| "Representation Upon Enigma <noreply@localhost>".to_string())
+            .parse()
+            .context("Invalid MAIL_FROM address")?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse().with_context(|| format!("Invalid recipient {}", to))?)
+            .subject(subject)
+            .body(body.to_string())
+            .context("Failed to build email")?;
+
+        self.transport.send(message).await.context("Failed to send email")?;
+        Ok(())
+    }
+}
+
+/// Writes mail to the log instead of sending it, for development without an SMTP server
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()> {
+        tracing::info!("Email to {} ({}):\n{}", to, subject, body);
+        Ok(())
+    }
+}
+
+/// SMTP when SMTP_HOST is set, otherwise the log-only mailer
+pub fn from_env() -> Arc<dyn Mailer> {
+    let Ok(host) = std::env::var("SMTP_HOST") else {
+        tracing::info!("SMTP_HOST not set; emails will be written to the log");
+        return Arc::new(LogMailer);
+    };
+
+    match SmtpMailer::from_env(&host) {
+        Ok(mailer) => Arc::new(mailer),
+        Err(e) => {
+            tracing::error!("Failed to configure SMTP, falling back to logging emails: {:#}", e);
+            Arc::new(LogMailer)
+        }
+    }
+}
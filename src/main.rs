@@ -10,10 +10,26 @@ mod db;
 mod moderation;
 mod rate_limit;
 mod pdf_generator;
+mod llm;
+mod rag;
+mod shutdown;
+mod i18n;
+mod compare;
+mod summary;
+mod assets;
+mod mailer;
+mod ingest;
+mod digest;
+mod challenge;
+mod maintenance;
+mod reranker;
+mod oauth;
+mod report_jobs;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use vector_store::VectorStore;
 
 #[derive(Parser)]
 #[command(name = "civic-legislation")]
@@ -23,6 +39,13 @@ struct Cli {
     command: Commands,
 }
 
+/// What the report command writes
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ReportFormat {
+    Pdf,
+    Csv,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Fetch recent bills from PRS and ingest them
@@ -30,6 +53,15 @@ enum Commands {
         /// Number of bills to fetch
         #[arg(short, long, default_value_t = 5)]
         count: usize,
+        /// Only write the vector index, without storing bills in PostgreSQL
+        #[arg(long)]
+        no_db: bool,
+    },
+    /// Embed the chunks stored in PostgreSQL again and overwrite their vectors, e.g. after changing the embedding model
+    Reindex {
+        /// Only this bill (its ID)
+        #[arg(long)]
+        bill: Option<uuid::Uuid>,
     },
     /// Query the knowledge base
     Query {
@@ -38,9 +70,71 @@ enum Commands {
         /// Number of results to return
         #[arg(short, long, default_value_t = 3)]
         limit: usize,
+        /// Rescore the top 20 matches with a cross-encoder before taking the best
+        #[arg(long)]
+        rerank: bool,
+    },
+    /// Ask a question and get an answer grounded in bill text
+    Ask {
+        /// The question to answer
+        question: String,
+        /// Number of bill sections to ground the answer in
+        #[arg(short, long, default_value_t = 5)]
+        limit: usize,
     },
     /// Initialize the vector database
     Init,
+    /// Apply pending database migrations
+    Migrate,
+    /// Set a user's role, e.g. to bootstrap the first admin
+    PromoteUser {
+        /// Username of the account to change
+        username: String,
+        /// Role to grant: user, moderator or admin
+        #[arg(short, long, default_value = "admin")]
+        role: String,
+    },
+    /// Email this week's digest to every user who hasn't had one yet, e.g. from cron
+    SendDigests,
+    /// Delete expired sessions and old rate limit records, search logs and moderation events once, e.g. from cron
+    Maintenance,
+    /// Run moderation again on posts held for review because it couldn't decide, e.g. while Ollama was down
+    Remoderate {
+        /// Only posts created at least this many hours ago
+        #[arg(long)]
+        older_than_hours: Option<i64>,
+        /// Only posts on this bill (its ID)
+        #[arg(long)]
+        bill: Option<uuid::Uuid>,
+        /// Check at most this many posts, oldest first
+        #[arg(short, long)]
+        limit: Option<i64>,
+    },
+    /// Write a constituency's sentiment report as a PDF or CSV, a bill's report by constituency, or the national report
+    Report {
+        /// The constituency's ID
+        #[arg(required_unless_present_any = ["bill", "national"])]
+        constituency: Option<i32>,
+        /// Report on this bill (its ID) across all constituencies instead, as a PDF
+        #[arg(long, conflicts_with_all = ["constituency", "from", "to", "format"])]
+        bill: Option<uuid::Uuid>,
+        /// Report on every bill with posts across the country instead, as a PDF
+        #[arg(long, conflicts_with_all = ["constituency", "bill", "from", "to", "format"])]
+        national: bool,
+        /// Only posts from this day on (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<chrono::NaiveDate>,
+        /// Only posts up to and including this day (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<chrono::NaiveDate>,
+        /// Write a PDF, or two CSVs (stance counts per bill and the posts themselves)
+        #[arg(long, value_enum, default_value_t = ReportFormat::Pdf)]
+        format: ReportFormat,
+        /// Where to write the PDF; defaults to the report's usual filename in the current directory.
+        /// For CSV, the two files are named from it with _sentiment.csv and _posts.csv
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
     /// Start the web server
     Serve {
         /// Port to listen on
@@ -68,57 +162,181 @@ async fn main() -> Result<()> {
             vector_store::initialize_collection().await?;
             tracing::info!("✓ Vector database initialized successfully");
         }
-        Commands::Ingest { count } => {
-            tracing::info!("Starting ingestion of {} bills...", count);
+        Commands::Migrate => {
+            let db_pool = db::connect().await?;
+            let applied = db::run_migrations(&db_pool).await?;
+            if applied.is_empty() {
+                tracing::info!("✓ Database is already up to date");
+            }
+            for (version, description) in &applied {
+                tracing::info!("✓ Applied migration {} ({})", version, description);
+            }
+        }
+        Commands::PromoteUser { username, role } => {
+            let role = models::Role::parse(&role).ok_or_else(|| {
+                anyhow::anyhow!("Unknown role '{}' (expected user, moderator or admin)", role)
+            })?;
+            
+            let db_pool = db::create_pool().await?;
+            let user = auth::get_user_by_username(&db_pool, &username)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No user named '{}'", username))?;
+            
+            auth::set_role(&db_pool, user.id, role).await?;
+            tracing::info!("✓ {} is now {}", user.username, role.as_str());
+        }
+        Commands::SendDigests => {
+            let db_pool = db::create_pool().await?;
+            let report = digest::send_digests(&db_pool, mailer::from_env().as_ref(), &web::public_base_url()).await?;
+            tracing::info!(
+                "✓ Digests: {} sent, {} with nothing new, {} failed",
+                report.sent,
+                report.skipped,
+                report.failed
+            );
+        }
+        Commands::Maintenance => {
+            let db_pool = db::create_pool().await?;
+            let report = maintenance::run_maintenance(&db_pool).await?;
+            tracing::info!(
+                "✓ Maintenance: removed {} expired sessions, {} rate limit records, {} search logs and {} moderation events",
+                report.sessions,
+                report.rate_limit_records,
+                report.search_logs,
+                report.moderation_events
+            );
+        }
+        Commands::Remoderate { older_than_hours, bill, limit } => {
+            let db_pool = db::create_pool().await?;
+            let http = reqwest::Client::new();
+            let keywords = std::sync::Arc::new(moderation::KeywordCache::new(db_pool.clone()));
+            let providers = moderation::from_env(llm::OllamaClient::new(http.clone()), http, keywords);
+            let filter = moderation::RemoderateFilter {
+                older_than: older_than_hours.map(chrono::Duration::hours),
+                bill_id: bill,
+                limit,
+            };
+            
+            let report = moderation::remoderate(&db_pool, &providers, &filter).await?;
+            for post in &report.approved {
+                web::notify_approved_post(&db_pool, post).await;
+            }
+            tracing::info!(
+                "✓ Re-moderation: {} approved, {} rejected, {} still pending, {} skipped",
+                report.approved.len(),
+                report.rejected,
+                report.still_pending,
+                report.skipped
+            );
+        }
+        Commands::Report { national: true, output, .. } => {
+            let db_pool = db::create_pool().await?;
+            let (filename, pdf) = web::national_report(&db_pool).await?;
             
-            // Create database pool for storing bills
+            let path = output.unwrap_or_else(|| filename.into());
+            std::fs::write(&path, pdf)?;
+            tracing::info!("✓ National report written to {}", path.display());
+        }
+        Commands::Report { bill: Some(bill), output, .. } => {
             let db_pool = db::create_pool().await?;
+            let (filename, pdf) = web::bill_report(&db_pool, bill)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No bill with ID {}", bill))?;
+            
+            let path = output.unwrap_or_else(|| filename.into());
+            std::fs::write(&path, pdf)?;
+            tracing::info!("✓ Report for bill {} written to {}", bill, path.display());
+        }
+        Commands::Report { constituency, from, to, format, output, .. } => {
+            // clap requires the constituency unless --bill or --national is given
+            let constituency = constituency.expect("constituency, --bill or --national is required");
+            let db_pool = db::create_pool().await?;
+            let period = models::ReportPeriod { from, to };
+            let missing = || anyhow::anyhow!("No constituency with ID {}", constituency);
+            match format {
+                ReportFormat::Pdf => {
+                    let llm = llm::OllamaClient::new(reqwest::Client::new());
+                    let (filename, pdf) = web::constituency_report(&db_pool, &llm, constituency, &period)
+                        .await?
+                        .ok_or_else(missing)?;
+                    
+                    let path = output.unwrap_or_else(|| filename.into());
+                    std::fs::write(&path, pdf)?;
+                    tracing::info!("✓ Report for {} written to {}", period.describe(), path.display());
+                }
+                ReportFormat::Csv => {
+                    let (stem, csv) = web::constituency_report_csv(&db_pool, constituency, &period)
+                        .await?
+                        .ok_or_else(missing)?;
+                    
+                    let stem = output
+                        .map(|p| p.with_extension("").into_os_string())
+                        .unwrap_or_else(|| stem.into());
+                    for (suffix, body) in [("_sentiment.csv", csv.sentiment), ("_posts.csv", csv.posts)] {
+                        let mut path = stem.clone();
+                        path.push(suffix);
+                        let path = std::path::PathBuf::from(path);
+                        std::fs::write(&path, body)?;
+                        tracing::info!("✓ Report for {} written to {}", period.describe(), path.display());
+                    }
+                }
+            }
+        }
+        Commands::Ingest { count, no_db } => {
+            tracing::info!("Starting ingestion of {} bills...", count);
+            
+            // Create database pool for storing bills, before any scraping
+            let db_pool = if no_db { None } else { Some(db::create_pool().await?) };
             
             // Step 1: Scrape bills
             tracing::info!("Fetching bills from PRS...");
             let bills = scraper::fetch_recent_bills(count).await?;
             tracing::info!("✓ Found {} bills", bills.len());
             
-            // Step 2: Process each bill
+            // Step 2: Extract, chunk, embed and store each bill
             for bill in bills {
-                tracing::info!("Processing: {}", bill.title);
-                
-                // Store bill in database
-                match db::insert_bill(&db_pool, &bill).await {
-                    Ok(_) => tracing::info!("  → Stored bill in database"),
-                    Err(e) => tracing::warn!("  → Failed to store bill in database: {}", e),
-                }
-                
-                // Extract text from PDF
-                tracing::info!("  → Extracting text from PDF...");
-                let text = extractor::extract_text_from_pdf(&bill.pdf_url).await?;
-                
-                // Chunk the text
-                tracing::info!("  → Chunking text semantically...");
-                let chunks = chunker::chunk_text(&text, &bill.bill_number);
-                tracing::info!("  → Created {} chunks", chunks.len());
-                
-                // Generate embeddings
-                tracing::info!("  → Generating embeddings...");
-                let embedded_chunks = embedder::embed_chunks(&chunks).await?;
-                
-                // Store in vector database
-                tracing::info!("  → Storing in vector database...");
-                vector_store::store_chunks(&bill, &embedded_chunks).await?;
-                
-                tracing::info!("✓ Completed: {}", bill.title);
+                ingest::ingest_bill(db_pool.as_ref(), &bill, |line| tracing::info!("{}", line)).await?;
             }
             
             tracing::info!("✓ Ingestion completed successfully ({} bills processed)", count);
         }
-        Commands::Query { query, limit } => {
+        Commands::Reindex { bill } => {
+            let db_pool = db::create_pool().await?;
+            let bills = match bill {
+                Some(id) => vec![db::get_bill_by_id(&db_pool, id)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("No bill with ID {}", id))?],
+                None => db::get_chunked_bills(&db_pool).await?,
+            };
+            
+            let mut total = 0;
+            for bill in &bills {
+                let count = ingest::reindex_bill(&db_pool, bill).await?;
+                tracing::info!("Reindexed {} chunks of {}", count, bill.title);
+                total += count;
+            }
+            
+            tracing::info!("✓ Reindexed {} chunks of {} bills", total, bills.len());
+        }
+        Commands::Query { query, limit, rerank } => {
             tracing::info!("Searching for: \"{}\"", query);
             
             // Generate query embedding
             let query_vector = embedder::embed_query(&query).await?;
             
             // Search vector database
-            let results = vector_store::search(&query_vector, limit).await?;
+            let http = reqwest::Client::new();
+            let store = vector_store::QdrantStore::new(http.clone());
+            let results = if rerank {
+                let candidates = store
+                    .search(&query_vector, reranker::RERANK_CANDIDATES.max(limit), &vector_store::SearchFilter::default())
+                    .await?;
+                let reranker = reranker::from_env(llm::OllamaClient::new(http));
+                reranker::rerank(reranker.as_ref(), &query, candidates, limit).await?
+            } else {
+                store.search(&query_vector, limit, &vector_store::SearchFilter::default()).await?
+            };
+            let score_label = if rerank { "Rerank score" } else { "Score" };
             
             // Display results
             println!("\n{}", "=".repeat(80));
@@ -130,7 +348,7 @@ async fn main() -> Result<()> {
                 println!("  cargo run -- ingest");
             } else {
                 for (idx, result) in results.iter().enumerate() {
-                    println!("\n[Result {}] Score: {:.4}", idx + 1, result.score);
+                    println!("\n[Result {}] {}: {:.4}", idx + 1, score_label, result.score);
                     println!("Bill: {}", result.bill_title);
                     println!("Section: {}", result.chunk_identifier);
                     println!("\nContent:\n{}", result.content);
@@ -138,10 +356,47 @@ async fn main() -> Result<()> {
                 }
             }
         }
+        Commands::Ask { question, limit } => {
+            tracing::info!("Answering: \"{}\"", question);
+            
+            let http = reqwest::Client::new();
+            let answer = rag::answer_question(
+                &embedder::BertEmbedder,
+                &vector_store::QdrantStore::new(http.clone()),
+                &llm::OllamaClient::new(http),
+                &question,
+                limit,
+            )
+            .await?;
+            
+            println!("\n{}", "=".repeat(80));
+            println!("Question: \"{}\"", answer.question);
+            println!("{}", "=".repeat(80));
+            
+            if let Some(text) = &answer.answer {
+                println!("\n{}", text);
+            }
+            if let Some(message) = &answer.message {
+                println!("\n{}", message);
+            }
+            
+            if !answer.sources.is_empty() {
+                println!("\nSources:");
+                for source in &answer.sources {
+                    println!("\n[{}, {}] {} (score {:.4})", source.bill_number, source.chunk_identifier, source.bill_title, source.score);
+                    if answer.answer.is_none() {
+                        println!("{}", source.content);
+                    }
+                }
+            }
+        }
         Commands::Serve { port } => {
             tracing::info!("Starting web server on port {}...", port);
             
-            let app = web::create_router().await;
+            let db_pool = db::create_pool().await?;
+            let (shutdown_trigger, shutdown_token) = shutdown::channel();
+            
+            let app = web::create_router(db_pool.clone(), shutdown_token.clone()).await;
             
             let addr = format!("0.0.0.0:{}", port);
             let listener = tokio::net::TcpListener::bind(&addr).await?;
@@ -149,9 +404,51 @@ async fn main() -> Result<()> {
             tracing::info!("🌐 Server running at http://localhost:{}", port);
             tracing::info!("📚 Access the civic knowledge base at http://localhost:{}/", port);
             
-            axum::serve(listener, app).await?;
+            let mut server = tokio::spawn(async move {
+                axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+                    .with_graceful_shutdown(shutdown_token.cancelled())
+                    .await
+            });
+            
+            tokio::select! {
+                result = &mut server => {
+                    result??;
+                }
+                _ = shutdown::wait_for_signal() => {
+                    let drain_timeout = shutdown_timeout();
+                    tracing::info!(
+                        "Shutdown signal received, draining {} in-flight request(s) (timeout {}s)...",
+                        web::in_flight_requests(),
+                        drain_timeout.as_secs()
+                    );
+                    shutdown_trigger.trigger();
+                    
+                    match tokio::time::timeout(drain_timeout, &mut server).await {
+                        Ok(result) => result??,
+                        Err(_) => {
+                            tracing::warn!(
+                                "Drain timed out with {} request(s) still in flight",
+                                web::in_flight_requests()
+                            );
+                            server.abort();
+                        }
+                    }
+                }
+            }
+            
+            db_pool.close().await;
+            tracing::info!("✓ Server stopped");
         }
     }
 
     Ok(())
 }
+
+/// How long to wait for in-flight requests on shutdown (SHUTDOWN_TIMEOUT_SECS, default 30)
+fn shutdown_timeout() -> std::time::Duration {
+    let secs = std::env::var("SHUTDOWN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30);
+    std::time::Duration::from_secs(secs)
+}
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::auth;
+use crate::db;
+use crate::rate_limit;
+use crate::shutdown::ShutdownToken;
+
+/// Search logs are kept this long for the admin search analytics
+pub const SEARCH_LOG_RETENTION_DAYS: i64 = 90;
+
+/// Moderation transcripts are kept this long (MODERATION_EVENT_RETENTION_DAYS, default 365)
+fn moderation_event_retention_days() -> i64 {
+    std::env::var("MODERATION_EVENT_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&days| days > 0)
+        .unwrap_or(365)
+}
+
+/// How often the background job cleans up (MAINTENANCE_INTERVAL_SECS, default 3600). It only has
+/// to keep tables from growing without bound, so the exact interval hardly matters.
+fn job_interval() -> std::time::Duration {
+    let secs = std::env::var("MAINTENANCE_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&secs| secs > 0)
+        .unwrap_or(3600);
+    std::time::Duration::from_secs(secs)
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct MaintenanceReport {
+    pub sessions: u64,
+    pub rate_limit_records: u64,
+    pub search_logs: u64,
+    pub moderation_events: u64,
+}
+
+/// Delete expired sessions, rate limit records older than a day, and search logs and moderation
+/// events past their retention
+pub async fn run_maintenance(pool: &PgPool) -> Result<MaintenanceReport> {
+    let sessions = auth::delete_expired_sessions(pool).await?;
+    let rate_limit_records = rate_limit::cleanup_old_records(pool).await?;
+    let search_logs = db::delete_search_logs_before(pool, Utc::now() - Duration::days(SEARCH_LOG_RETENTION_DAYS)).await?;
+    let moderation_events =
+        db::delete_moderation_events_before(pool, Utc::now() - Duration::days(moderation_event_retention_days()))
+            .await?;
+
+    Ok(MaintenanceReport {
+        sessions,
+        rate_limit_records,
+        search_logs,
+        moderation_events,
+    })
+}
+
+/// Run maintenance at startup and then every interval until shutdown, also dropping stale entries
+/// from the server's rate limit cache. A run in progress is finished before the job stops.
+pub async fn run_job(pool: PgPool, rate_limit_cache: Arc<rate_limit::RateLimitCache>, shutdown: ShutdownToken) {
+    let mut interval = tokio::time::interval(job_interval());
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let cancelled = shutdown.cancelled();
+    tokio::pin!(cancelled);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let purged = rate_limit_cache.purge_stale(Utc::now());
+                if purged > 0 {
+                    debug!("Maintenance: dropped {} stale rate limit cache entries", purged);
+                }
+                match run_maintenance(&pool).await {
+                    Ok(report) if report == MaintenanceReport::default() => {}
+                    Ok(report) => info!(
+                        "Maintenance: removed {} expired sessions, {} rate limit records, {} search logs and {} moderation events",
+                        report.sessions, report.rate_limit_records, report.search_logs, report.moderation_events
+                    ),
+                    Err(e) => warn!("Maintenance run failed: {:#}", e),
+                }
+            }
+            _ = &mut cancelled => break,
+        }
+    }
+}
@@ -10,7 +10,7 @@ pub struct Bill {
     pub year: i32,
     pub session: Option<String>,
     pub status: Option<String>,
-    pub introduction_date: Option<String>,
+    pub introduction_date: Option<chrono::NaiveDate>,
     pub pdf_url: String,
 }
 
@@ -36,17 +36,20 @@ impl Bill {
 
 #[derive(Debug, Clone)]
 pub struct TextChunk {
-    #[allow(dead_code)]
+    /// Row id in `chunks`, and the chunk's Qdrant point id
+    pub id: Uuid,
     pub bill_id: Uuid,
-    #[allow(dead_code)]
     pub bill_number: String,
     pub chunk_index: usize,
     pub chunk_type: ChunkType,
     pub chunk_identifier: String,
     pub content: String,
+    /// First and last PDF pages the chunk was taken from, when known
+    pub page_start: Option<i32>,
+    pub page_end: Option<i32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChunkType {
     Preamble,
     Clause,
@@ -55,6 +58,24 @@ pub enum ChunkType {
     Other,
 }
 
+impl ChunkType {
+    pub const ALL: [ChunkType; 5] = [
+        ChunkType::Preamble,
+        ChunkType::Clause,
+        ChunkType::Section,
+        ChunkType::Schedule,
+        ChunkType::Other,
+    ];
+
+    /// Parse a chunk type name case-insensitively
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|t| t.to_string().eq_ignore_ascii_case(value))
+    }
+}
+
 impl std::fmt::Display for ChunkType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -73,16 +94,36 @@ pub struct EmbeddedChunk {
     pub embedding: Vec<f32>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
 pub struct SearchResult {
     pub bill_title: String,
     #[allow(dead_code)]
     pub bill_number: String,
+    pub chunk_index: usize,
     pub chunk_identifier: String,
     pub content: String,
     pub score: f32,
 }
 
+// Another bill whose text is close to a given bill's, for "related legislation"
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarBill {
+    pub bill_id: Uuid,
+    pub title: String,
+    pub bill_number: String,
+    pub year: i32,
+    pub score: f32,
+}
+
+// A chunk as stored in the database or vector index, used to render bill text
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredChunk {
+    pub chunk_index: usize,
+    pub chunk_type: String,
+    pub chunk_identifier: String,
+    pub content: String,
+}
+
 // User model
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct User {
@@ -90,15 +131,175 @@ pub struct User {
     pub username: String,
     #[serde(skip_serializing)]
     pub password_hash: String,
+    pub password_set: bool,
     pub real_name: Option<String>,
     pub age: Option<i32>,
     pub gender: Option<String>,
     pub pincode: Option<String>,
     pub constituency_id: Option<i32>,
+    pub real_name_public: bool,
+    pub age_public: bool,
+    pub gender_public: bool,
+    pub pincode_public: bool,
+    pub role: String,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub rate_limit_multiplier: Option<i32>,
+    #[serde(skip_serializing)]
+    pub email: Option<String>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub digest_enabled: bool,
+    pub digest_sent_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl User {
+    pub fn role(&self) -> Role {
+        Role::parse(&self.role).unwrap_or(Role::User)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role() == Role::Admin
+    }
+
+    /// Moderators and admins can work the moderation queue
+    pub fn can_moderate(&self) -> bool {
+        self.role() >= Role::Moderator
+    }
+
+    /// How many times the usual rate limits this user gets, or None when they're exempt
+    pub fn rate_limit_scale(&self) -> Option<i64> {
+        rate_limit_scale(self.role(), self.rate_limit_multiplier)
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended_at.is_some()
+    }
+}
+
+// User roles, ordered by privilege
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    User,
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::User, Role::Moderator, Role::Admin];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user" => Some(Role::User),
+            "moderator" => Some(Role::Moderator),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Moderator => "moderator",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+/// The rate limit scale for a role and an admin's override. Without an override moderators and
+/// admins are exempt (None) and everyone else gets the usual limits; an override of 0 exempts anyone.
+pub fn rate_limit_scale(role: Role, multiplier: Option<i32>) -> Option<i64> {
+    match multiplier {
+        Some(0) => None,
+        Some(multiplier) => Some(multiplier as i64),
+        None if role >= Role::Moderator => None,
+        None => Some(1),
+    }
+}
+
+// Kinds of audit_log entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    LoginSucceeded,
+    LoginFailed,
+    Logout,
+    PasswordChanged,
+    PasswordReset,
+    ProfileUpdated,
+    RoleChanged,
+    RateLimitChanged,
+    RateLimitCleared,
+    UserSuspended,
+    UserReinstated,
+    PostApproved,
+    PostRejected,
+    AppealUpheld,
+    AppealOverturned,
+    KeywordsChanged,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 16] = [
+        AuditAction::LoginSucceeded,
+        AuditAction::LoginFailed,
+        AuditAction::Logout,
+        AuditAction::PasswordChanged,
+        AuditAction::PasswordReset,
+        AuditAction::ProfileUpdated,
+        AuditAction::RoleChanged,
+        AuditAction::RateLimitChanged,
+        AuditAction::RateLimitCleared,
+        AuditAction::UserSuspended,
+        AuditAction::UserReinstated,
+        AuditAction::PostApproved,
+        AuditAction::PostRejected,
+        AuditAction::AppealUpheld,
+        AuditAction::AppealOverturned,
+        AuditAction::KeywordsChanged,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::LoginSucceeded => "login_succeeded",
+            AuditAction::LoginFailed => "login_failed",
+            AuditAction::Logout => "logout",
+            AuditAction::PasswordChanged => "password_changed",
+            AuditAction::PasswordReset => "password_reset",
+            AuditAction::ProfileUpdated => "profile_updated",
+            AuditAction::RoleChanged => "role_changed",
+            AuditAction::RateLimitChanged => "rate_limit_changed",
+            AuditAction::RateLimitCleared => "rate_limit_cleared",
+            AuditAction::UserSuspended => "user_suspended",
+            AuditAction::UserReinstated => "user_reinstated",
+            AuditAction::PostApproved => "post_approved",
+            AuditAction::PostRejected => "post_rejected",
+            AuditAction::AppealUpheld => "appeal_upheld",
+            AuditAction::AppealOverturned => "appeal_overturned",
+            AuditAction::KeywordsChanged => "keywords_changed",
+        }
+    }
+}
+
+// An audit_log row, with the usernames of the actor and of the target when it is a user
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub action: String,
+    pub target_id: Option<Uuid>,
+    pub target_username: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub metadata: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
 // Constituency model
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Constituency {
@@ -119,18 +320,89 @@ pub struct Session {
     pub created_at: DateTime<Utc>,
 }
 
+// An external sign-in account linked to a user
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Identity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// What an API token may do; write implies read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiScope {
+    Read,
+    Write,
+}
+
+impl ApiScope {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(ApiScope::Read),
+            "write" => Some(ApiScope::Write),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::Read => "read",
+            ApiScope::Write => "write",
+        }
+    }
+}
+
+// Personal API token; the token itself is only shown once, at creation
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub name: String,
+    pub scope: String,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    pub fn scope(&self) -> ApiScope {
+        ApiScope::parse(&self.scope).unwrap_or(ApiScope::Read)
+    }
+}
+
+// Pending email verification
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct EmailVerification {
+    pub token: String,
+    pub user_id: Uuid,
+    pub email: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
 // Post/Review model
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Post {
     pub id: Uuid,
     pub user_id: Uuid,
     pub bill_id: Uuid,
-    pub stance: String,
+    pub parent_post_id: Option<Uuid>,
+    pub stance: Option<String>,
     pub content: String,
+    pub is_anonymous: bool,
     pub moderation_status: String,
     pub moderation_reason: Option<String>,
+    pub moderation_provider: Option<String>,
     pub upvotes: i32,
     pub downvotes: i32,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -153,20 +425,359 @@ impl ModerationResult {
     }
 }
 
+// What a new review or reply says and where it goes, for `db::create_post`
+#[derive(Debug, Clone, Copy)]
+pub struct NewPost<'a> {
+    pub bill_id: Uuid,
+    // The review a reply answers; None for a review
+    pub parent_post_id: Option<Uuid>,
+    // None for a reply
+    pub stance: Option<&'a str>,
+    pub content: &'a str,
+    pub is_anonymous: bool,
+}
+
+// The moderation columns a post is created or edited with
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostModeration {
+    pub status: &'static str,
+    pub reason: Option<String>,
+    // None while the post waits in the moderation queue
+    pub provider: Option<&'static str>,
+    // Recorded in moderation_events once the post has an id
+    pub attempts: Vec<ModerationAttempt>,
+    // Stored as posts.audit_sampled
+    pub audit_sample: bool,
+}
+
+impl PostModeration {
+    // Held with no verdict yet, for the moderation queue to decide
+    pub fn queued() -> Self {
+        Self {
+            status: "pending_review",
+            reason: None,
+            provider: None,
+            attempts: Vec::new(),
+            audit_sample: false,
+        }
+    }
+}
+
+// One provider's try at judging a post, kept so a disputed verdict can be explained
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModerationAttempt {
+    pub provider: &'static str,
+    // SHA-256 of the request sent to the provider; None for the keyword lists
+    pub prompt_hash: Option<String>,
+    // The provider's answer exactly as received
+    pub raw_response: Option<String>,
+    // Status the answer parsed to, before the rejection confidence threshold; None if it failed
+    pub verdict: Option<&'static str>,
+    pub error: Option<String>,
+    pub latency_ms: i32,
+    // How the author's trust level changed the verdict; only on the "trust" attempt
+    pub trust_bias: Option<&'static str>,
+}
+
+// A stored moderation attempt, for the admin view of a post's moderation history
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ModerationEvent {
+    pub id: Uuid,
+    pub post_id: Uuid,
+    pub provider: String,
+    pub prompt_hash: Option<String>,
+    pub raw_response: Option<String>,
+    pub verdict: Option<String>,
+    pub error: Option<String>,
+    pub latency_ms: i32,
+    pub trust_bias: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// A moderation check's outcome, with what was flagged and why when the checker says
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModerationVerdict {
+    // Which provider judged the post, e.g. "ollama"; stored in posts.moderation_provider
+    pub provider: &'static str,
+    pub result: ModerationResult,
+    // Any of hate, threat, toxicity, spam
+    pub categories: Vec<String>,
+    // 0.0-1.0; None when the checker gave no confidence
+    pub confidence: Option<f32>,
+    pub rationale: Option<String>,
+    // Every provider tried on the way to this verdict, failures included, for moderation_events
+    pub attempts: Vec<ModerationAttempt>,
+    // Approved on the author's trust and picked for a moderator to spot-check
+    pub audit_sample: bool,
+}
+
+impl ModerationVerdict {
+    pub fn new(provider: &'static str, result: ModerationResult) -> Self {
+        Self {
+            provider,
+            result,
+            categories: Vec::new(),
+            confidence: None,
+            rationale: None,
+            attempts: Vec::new(),
+            audit_sample: false,
+        }
+    }
+
+    // What a new or edited post is saved with
+    pub fn to_post_moderation(&self) -> PostModeration {
+        PostModeration {
+            status: self.result.to_status(),
+            reason: self.reason(),
+            provider: Some(self.provider),
+            attempts: self.attempts.clone(),
+            audit_sample: self.audit_sample,
+        }
+    }
+
+    // Text for posts.moderation_reason: the categories, confidence and rationale when there are
+    // any, else a generic note. Approved posts have no reason.
+    pub fn reason(&self) -> Option<String> {
+        let generic = match self.result {
+            ModerationResult::Falafel => return None,
+            ModerationResult::Popcorn => "Content rejected by moderation",
+            ModerationResult::AdminReview => "Pending admin review",
+        };
+
+        let mut parts = Vec::new();
+        if !self.categories.is_empty() {
+            parts.push(format!("Categories: {}.", self.categories.join(", ")));
+        }
+        if let Some(confidence) = self.confidence {
+            parts.push(format!("Confidence: {:.0}%.", confidence * 100.0));
+        }
+        if let Some(rationale) = self.rationale.as_deref().filter(|r| !r.is_empty()) {
+            parts.push(rationale.to_string());
+        }
+
+        if parts.is_empty() {
+            Some(generic.to_string())
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+}
+
+// Sort order for forum reviews
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ReviewSort {
+    Top,
+    #[default]
+    New,
+    Controversial,
+}
+
+impl ReviewSort {
+    /// Parse the `sort` query parameter, falling back to newest first
+    pub fn from_param(param: Option<&str>) -> Self {
+        match param {
+            Some("top") => ReviewSort::Top,
+            Some("controversial") => ReviewSort::Controversial,
+            _ => ReviewSort::New,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewSort::Top => "top",
+            ReviewSort::New => "new",
+            ReviewSort::Controversial => "controversial",
+        }
+    }
+}
+
+// A bill on a user's reading list
+#[derive(Debug, sqlx::FromRow)]
+pub struct BookmarkedBill {
+    pub id: Uuid,
+    pub title: String,
+    pub bill_number: String,
+    pub year: i32,
+    pub status: Option<String>,
+    pub bookmarked_at: DateTime<Utc>,
+}
+
+// Filters for the bills list; unset fields match every bill
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BillFilter {
+    pub year: Option<i32>,
+    pub status: Option<String>,
+    // Matched against titles as a substring or with full-text search
+    pub query: Option<String>,
+}
+
+/// The days a constituency report covers, both ends included; unset ends are open
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReportPeriod {
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+}
+
+impl ReportPeriod {
+    /// Start of `from` and the end of `to` as whole UTC days, for filtering on created_at
+    pub fn bounds(&self) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let day_start = |d: chrono::NaiveDate| d.and_time(chrono::NaiveTime::MIN).and_utc();
+        (
+            self.from.map(day_start),
+            self.to.map(|d| day_start(d) + chrono::Duration::days(1)),
+        )
+    }
+
+    /// How the report header names the period
+    pub fn describe(&self) -> String {
+        let day = |d: chrono::NaiveDate| d.format("%d %B %Y").to_string();
+        match (self.from, self.to) {
+            (None, None) => "All posts to date".to_string(),
+            (Some(from), None) => format!("Posts since {}", day(from)),
+            (None, Some(to)) => format!("Posts up to {}", day(to)),
+            (Some(from), Some(to)) => format!("Posts from {} to {}", day(from), day(to)),
+        }
+    }
+}
+
+// Filters for the admin audit log; unset fields match every entry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditFilter {
+    // Matches the actor, the target, or the username tried in a failed login
+    pub username: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub action: Option<AuditAction>,
+}
+
 // For displaying posts with user info
 #[derive(Debug, Clone, Serialize)]
 pub struct PostWithUser {
     pub id: Uuid,
+    pub user_id: Uuid,
+    // A placeholder for anonymous posts, never the real username
     pub username: String,
+    pub is_anonymous: bool,
     pub constituency_name: Option<String>,
-    pub stance: String,
+    pub stance: Option<String>,
     pub content: String,
     pub upvotes: i32,
     pub downvotes: i32,
+    pub reply_count: i64,
+    pub edited_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub formatted_date: String,
 }
 
+// For the admin moderation queue
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PostForReview {
+    pub id: Uuid,
+    pub username: String,
+    pub bill_id: Uuid,
+    pub bill_title: String,
+    pub bill_number: String,
+    pub stance: Option<String>,
+    pub content: String,
+    pub is_anonymous: bool,
+    pub moderation_reason: Option<String>,
+    pub moderation_provider: Option<String>,
+    pub report_count: i64,
+    pub report_reasons: Option<String>,
+    pub created_at: DateTime<Utc>,
+    // The earlier post this one was held as a likely copy of
+    pub duplicate_of: Option<Uuid>,
+    pub duplicate_similarity: Option<f32>,
+    pub duplicate_username: Option<String>,
+    pub duplicate_content: Option<String>,
+    pub duplicate_status: Option<String>,
+    pub duplicate_created_at: Option<DateTime<Utc>>,
+}
+
+// What a user's moderation trust level is worked out from
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TrustStats {
+    pub created_at: DateTime<Utc>,
+    pub approved: i64,
+    // Rejected posts, including those under appeal
+    pub rejected: i64,
+}
+
+// A pattern an admin is adding to the keyword filter, with the fields of `ModerationKeyword`
+#[derive(Debug, Clone, Copy)]
+pub struct NewModerationKeyword<'a> {
+    pub pattern: &'a str,
+    pub is_regex: bool,
+    pub kind: &'a str,
+    pub action: &'a str,
+    pub severity: &'a str,
+    pub language: &'a str,
+}
+
+// A pattern for the keyword moderation filter
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ModerationKeyword {
+    pub id: Uuid,
+    pub pattern: String,
+    pub is_regex: bool,
+    // One of the moderation categories: hate, threat, toxicity, spam
+    pub kind: String,
+    // "review" or "reject"
+    pub action: String,
+    // "high" patterns are acted on alone, "low" ones only alongside another match
+    pub severity: String,
+    // Detected post language the pattern applies to: "en" (every post), "hi" or "hinglish"
+    pub language: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// A rejected post whose author appealed, with the appeal, for the moderation queue
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AppealForReview {
+    pub post_id: Uuid,
+    pub username: String,
+    pub bill_id: Uuid,
+    pub bill_title: String,
+    pub bill_number: String,
+    pub stance: Option<String>,
+    pub content: String,
+    pub is_anonymous: bool,
+    pub moderation_reason: Option<String>,
+    pub moderation_provider: Option<String>,
+    pub statement: Option<String>,
+    pub posted_at: DateTime<Utc>,
+    pub appealed_at: DateTime<Utc>,
+}
+
+// A row in the admin user list
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserSummary {
+    pub id: Uuid,
+    pub username: String,
+    pub role: String,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub post_count: i64,
+    pub rejected_count: i64,
+}
+
+// A moderation decision or pending review on one of a user's posts
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ModerationRecord {
+    pub post_id: Uuid,
+    pub bill_id: Uuid,
+    pub bill_title: String,
+    pub content: String,
+    pub moderation_status: String,
+    pub moderation_reason: Option<String>,
+    pub moderator_username: Option<String>,
+    pub moderated_at: Option<DateTime<Utc>>,
+    pub report_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
 // Database bill model (with timestamps)
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct DbBill {
@@ -183,8 +794,50 @@ pub struct DbBill {
     pub updated_at: DateTime<Utc>,
 }
 
+// A bill's chunk as stored in the database; its id is also its Qdrant point id
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DbChunk {
+    pub id: Uuid,
+    pub bill_id: Uuid,
+    pub chunk_index: i32,
+    pub chunk_type: String,
+    pub identifier: String,
+    pub content: String,
+    pub page_start: Option<i32>,
+    pub page_end: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DbChunk {
+    /// The chunk ready to be embedded again
+    pub fn to_text_chunk(&self, bill_number: &str) -> TextChunk {
+        TextChunk {
+            id: self.id,
+            bill_id: self.bill_id,
+            bill_number: bill_number.to_string(),
+            chunk_index: self.chunk_index as usize,
+            chunk_type: ChunkType::parse(&self.chunk_type).unwrap_or(ChunkType::Other),
+            chunk_identifier: self.identifier.clone(),
+            content: self.content.clone(),
+            page_start: self.page_start,
+            page_end: self.page_end,
+        }
+    }
+}
+
+impl From<DbChunk> for StoredChunk {
+    fn from(chunk: DbChunk) -> Self {
+        StoredChunk {
+            chunk_index: chunk.chunk_index as usize,
+            chunk_type: chunk.chunk_type,
+            chunk_identifier: chunk.identifier,
+            content: chunk.content,
+        }
+    }
+}
+
 // User profile view
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct UserProfile {
     pub id: Uuid,
     pub username: String,
@@ -193,6 +846,230 @@ pub struct UserProfile {
     pub gender: Option<String>,
     pub pincode: Option<String>,
     pub constituency_name: Option<String>,
+    #[sqlx(flatten)]
+    pub visibility: ProfileVisibility,
+    pub email_verified: bool,
+    pub created_at: DateTime<Utc>,
+    pub post_count: i64,
+}
+
+// Which personal fields are shown to viewers other than the owner and admins
+#[derive(Debug, Clone, Copy, Serialize, sqlx::FromRow)]
+pub struct ProfileVisibility {
+    pub real_name_public: bool,
+    pub age_public: bool,
+    pub gender_public: bool,
+    pub pincode_public: bool,
+}
+
+// A bill ranked by recent forum activity
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TrendingBill {
+    pub id: Uuid,
+    pub title: String,
+    pub bill_number: String,
+    pub year: i32,
+    pub post_count: i64,
+    pub score: f64,
+}
+
+// One of a user's posts in their data export, including removed and deleted ones
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExportPost {
+    pub id: Uuid,
+    pub bill_id: Uuid,
+    pub bill_number: String,
+    pub bill_title: String,
+    pub parent_post_id: Option<Uuid>,
+    pub stance: Option<String>,
+    pub content: String,
+    pub is_anonymous: bool,
+    pub moderation_status: String,
+    pub moderation_reason: Option<String>,
+    pub upvotes: i32,
+    pub downvotes: i32,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// A vote the user cast, in their data export
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExportVote {
+    pub post_id: Uuid,
+    pub bill_id: Uuid,
+    pub vote_type: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// AI-generated summary of a bill's approved reviews
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BillSummary {
+    pub bill_id: Uuid,
+    pub summary: String,
+    pub support_pct: i32,
+    pub oppose_pct: i32,
+    pub critique_pct: i32,
+    pub post_count: i32,
+    pub generated_at: DateTime<Utc>,
+}
+
+// Stance counts for one bill among a constituency's users
+#[derive(Debug, sqlx::FromRow)]
+pub struct BillSentiment {
+    pub bill_id: Uuid,
+    pub bill_number: String,
+    pub bill_title: String,
+    pub introduction_date: Option<chrono::NaiveDate>,
+    pub support_count: i64,
+    pub oppose_count: i64,
+    pub critique_count: i64,
+}
+
+// Stance counts for one bill among one constituency's users
+#[derive(Debug, sqlx::FromRow)]
+pub struct ConstituencySentiment {
+    pub constituency_id: i32,
+    pub constituency_name: String,
+    pub state: String,
+    pub support_count: i64,
+    pub oppose_count: i64,
+    pub critique_count: i64,
+}
+
+// Stance counts for one bill among one constituency's users, for the national report
+#[derive(Debug, sqlx::FromRow)]
+pub struct BillConstituencySentiment {
+    pub bill_id: Uuid,
+    pub constituency_name: String,
+    pub state: String,
+    pub support_count: i64,
+    pub oppose_count: i64,
+    pub critique_count: i64,
+}
+
+// Stance counts and participation for one bill across every constituency
+#[derive(Debug, sqlx::FromRow)]
+pub struct NationalBillSentiment {
+    pub bill_id: Uuid,
+    pub bill_number: String,
+    pub bill_title: String,
+    pub introduction_date: Option<chrono::NaiveDate>,
+    pub support_count: i64,
+    pub oppose_count: i64,
+    pub critique_count: i64,
+    pub participant_count: i64,
+    pub constituency_count: i64,
+}
+
+// Stance counts and participation across all bills among one state's users
+#[derive(Debug, sqlx::FromRow)]
+pub struct StateSentiment {
+    pub state: String,
+    pub support_count: i64,
+    pub oppose_count: i64,
+    pub critique_count: i64,
+    pub participant_count: i64,
+}
+
+// Posts, distinct posting users and constituencies they come from, across every bill
+#[derive(Debug, Default, sqlx::FromRow)]
+pub struct NationalTotals {
+    pub post_count: i64,
+    pub participant_count: i64,
+    pub constituency_count: i64,
+}
+
+// One of a bill's most upvoted posts for its stance, with where the author is from
+#[derive(Debug, sqlx::FromRow)]
+pub struct BillReportPost {
+    pub constituency_name: Option<String>,
+    pub username: String,
+    pub content: String,
+    pub stance: String,
+    pub upvotes: i32,
+    pub downvotes: i32,
     pub created_at: DateTime<Utc>,
+}
+
+// A constituency's approved posts and distinct posting users within a time window
+#[derive(Debug, sqlx::FromRow)]
+pub struct ConstituencyActivity {
+    pub id: i32,
+    pub name: String,
+    pub state: String,
     pub post_count: i64,
+    pub participant_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ConstituencyPost {
+    pub bill_id: Uuid,
+    pub bill_number: String,
+    pub bill_title: String,
+    pub username: String,
+    pub content: String,
+    pub stance: String,
+    pub created_at: DateTime<Utc>,
+    pub upvotes: i32,
+    pub downvotes: i32,
+    pub introduction_date: Option<chrono::NaiveDate>,
+}
+
+// Notification with the bill it refers to
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    pub bill_id: Uuid,
+    pub bill_title: String,
+    pub kind: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// A user due a weekly digest; `since` is where the last digest left off
+#[derive(Debug, sqlx::FromRow)]
+pub struct DigestRecipient {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub since: DateTime<Utc>,
+}
+
+// A normalized search query and how often it was run, for the admin search analytics
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SearchQueryStat {
+    pub query: String,
+    pub searches: i64,
+    pub avg_results: f64,
+    pub last_searched: DateTime<Utc>,
+}
+
+// Searches on one day, for the admin search analytics
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SearchVolume {
+    pub day: chrono::NaiveDate,
+    pub searches: i64,
+    pub zero_results: i64,
+    // Distinct signed-in searchers
+    pub searchers: i64,
+}
+
+// Allowed and refused actions of one kind over the last day, for the admin rate limit view
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RateLimitTotals {
+    pub action_type: String,
+    pub allowed: i64,
+    pub rejected: i64,
+}
+
+// A member whose actions rate limits refused recently, for the admin rate limit view
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ThrottledUser {
+    pub user_id: Uuid,
+    pub username: String,
+    pub role: String,
+    pub rate_limit_multiplier: Option<i32>,
+    pub rejected: i64,
+    pub last_rejected: DateTime<Utc>,
 }
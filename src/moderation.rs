@@ -1,162 +1,1783 @@
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 use tracing::{info, warn};
+use uuid::Uuid;
 
-use crate::models::ModerationResult;
+use crate::db;
+use crate::embedder::Embedder;
+use crate::llm::OllamaClient;
+use crate::models::{ModerationAttempt, ModerationKeyword, ModerationResult, ModerationVerdict, Post, TrustStats};
 
-#[derive(Debug, Serialize)]
-struct OllamaRequest {
-    model: String,
-    prompt: String,
-    stream: bool,
+/// Reason recorded on reviews held because they closely match an earlier post
+pub const DUPLICATE_REASON: &str = "Possible duplicate/campaign text";
+
+/// Cosine similarity at or above which a new review is held as a likely copy (DUPLICATE_SIMILARITY_THRESHOLD, default 0.92)
+fn duplicate_threshold() -> f32 {
+    std::env::var("DUPLICATE_SIMILARITY_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0.92)
+}
+
+const OLLAMA_TIMEOUT_SECS: u64 = 30;
+// Reviews that can wait for a verdict at once; beyond this they stay held for `remoderate`
+const QUEUE_CAPACITY: usize = 1_000;
+const OPENAI_TIMEOUT_SECS: u64 = 10;
+const RETRY_DELAY_MS: u64 = 200;
+// How long keyword lists loaded from the database are used before being read again
+const KEYWORD_CACHE_TTL_SECS: u64 = 30;
+// Covers reading the keyword lists from the database when the cache has expired
+const KEYWORD_TIMEOUT_SECS: u64 = 5;
+/// Longest keyword pattern accepted from the admin page
+pub const KEYWORD_PATTERN_MAX_CHARS: usize = 200;
+// Compiled size allowed for an admin-supplied regex
+const KEYWORD_REGEX_SIZE_LIMIT: usize = 1 << 20;
+/// What a keyword match does: hold the post for a moderator, or reject it outright
+pub const KEYWORD_ACTIONS: [&str; 2] = ["review", "reject"];
+/// A single "high" match is enough to act on; "low" matches need company
+pub const KEYWORD_SEVERITIES: [&str; 2] = ["low", "high"];
+// Distinct low-severity matches needed before the keyword filter acts on a post
+const KEYWORD_MIN_HITS: usize = 2;
+/// Languages keyword patterns are kept for; "en" patterns apply to every supported post
+pub const KEYWORD_LANGUAGES: [&str; 3] = ["en", "hi", "hinglish"];
+// Function words from one list a Latin-script post needs before it's read as anything but English
+const LANGUAGE_MIN_HITS: usize = 2;
+
+// The keyword lists used until the database lists load, or if they can't be read:
+// (pattern, language, category, action, severity). The initial migration seeds moderation_keywords with the same.
+const BUILTIN_KEYWORDS: [(&str, &str, &str, &str, &str); 40] = [
+    ("kill", "en", "toxicity", "review", "low"),
+    ("murder", "en", "toxicity", "review", "high"),
+    ("hate", "en", "toxicity", "review", "low"),
+    ("terrorist", "en", "toxicity", "review", "high"),
+    ("bomb", "en", "toxicity", "review", "low"),
+    ("die", "en", "toxicity", "review", "low"),
+    ("stupid", "en", "toxicity", "review", "low"),
+    ("idiot", "en", "toxicity", "review", "low"),
+    ("moron", "en", "toxicity", "review", "low"),
+    ("racist", "en", "toxicity", "review", "low"),
+    ("sexist", "en", "toxicity", "review", "low"),
+    ("buy now", "en", "spam", "reject", "low"),
+    ("click here", "en", "spam", "reject", "low"),
+    ("free money", "en", "spam", "reject", "low"),
+    ("lottery", "en", "spam", "reject", "low"),
+    ("crypto", "en", "spam", "reject", "low"),
+    ("bitcoin", "en", "spam", "reject", "low"),
+    ("investment opportunity", "en", "spam", "reject", "low"),
+    ("मार डालो", "hi", "threat", "review", "high"),
+    ("हत्या", "hi", "toxicity", "review", "high"),
+    ("आतंकवादी", "hi", "toxicity", "review", "high"),
+    ("बम", "hi", "toxicity", "review", "low"),
+    ("नफरत", "hi", "toxicity", "review", "low"),
+    ("बेवकूफ", "hi", "toxicity", "review", "low"),
+    ("मूर्ख", "hi", "toxicity", "review", "low"),
+    ("गद्दार", "hi", "toxicity", "review", "low"),
+    ("लॉटरी", "hi", "spam", "reject", "low"),
+    ("मुफ्त पैसा", "hi", "spam", "reject", "low"),
+    ("अभी खरीदें", "hi", "spam", "reject", "low"),
+    ("यहां क्लिक करें", "hi", "spam", "reject", "low"),
+    ("maar dalo", "hinglish", "threat", "review", "high"),
+    ("jaan se maar", "hinglish", "threat", "review", "high"),
+    ("bewakoof", "hinglish", "toxicity", "review", "low"),
+    ("murkh", "hinglish", "toxicity", "review", "low"),
+    ("gaddar", "hinglish", "toxicity", "review", "low"),
+    ("kamina", "hinglish", "toxicity", "review", "low"),
+    ("paisa kamao", "hinglish", "spam", "reject", "low"),
+    ("free paisa", "hinglish", "spam", "reject", "low"),
+    ("abhi kharido", "hinglish", "spam", "reject", "low"),
+    ("yahan click karo", "hinglish", "spam", "reject", "low"),
+];
+
+lazy_static::lazy_static! {
+    /// Figures of speech from legislative debate that use violent words about bills rather than
+    /// people, e.g. "kill the bill" or "died in committee". They're blanked out before keywords
+    /// are matched.
+    static ref ALLOWED_IDIOMS: Vec<Regex> = [
+        r"\bkill(?:s|ed|ing)?\s+(?:the|this|that|a|an|their|our)\s+(?:\w+\s+)?(?:bill|amendment|clause|motion|proposal|provision|law|ordinance|section)s?\b",
+        r"\b(?:die|dies|died|dying)\s+in\s+(?:committee|the\s+(?:house|senate|assembly|lok\s+sabha|rajya\s+sabha))\b",
+        r"\b(?:bill|amendment|clause|motion|proposal)s?\s+(?:is|was|will|would)\s+(?:be\s+)?(?:dead|killed)\b",
+    ]
+    .iter()
+    .map(|idiom| compile_regex(idiom).expect("built-in idiom regex"))
+    .collect();
+}
+
+/// Categories a moderation verdict may flag; anything else the model returns is dropped
+pub const CATEGORIES: [&str; 4] = ["hate", "threat", "toxicity", "spam"];
+
+/// Confidence a "Popcorn" verdict needs to reject a post outright (MODERATION_REJECT_CONFIDENCE,
+/// default 0.8); less confident rejections go to admin review instead
+fn reject_confidence() -> f32 {
+    std::env::var("MODERATION_REJECT_CONFIDENCE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0.8)
+}
+
+/// How many of the bill's most recent posts a new review is compared against (DUPLICATE_WINDOW_POSTS, default 200)
+fn duplicate_window() -> i64 {
+    std::env::var("DUPLICATE_WINDOW_POSTS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(200)
+}
+
+/// Outcome of comparing a new review with the bill's recent posts
+pub struct DuplicateCheck {
+    /// The new review's embedding, stored with the post once it's created
+    pub embedding: Vec<f32>,
+    /// The closest earlier post and its similarity, when it reached the threshold
+    pub original: Option<(Uuid, f32)>,
+}
+
+/// Cosine similarity of two vectors; 0 when either is empty, zero or the lengths differ
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    dot / (norm_a * norm_b)
+}
+
+/// The candidate most similar to `embedding`, if it reaches the threshold
+fn most_similar(embedding: &[f32], candidates: &[(Uuid, Vec<f32>)], threshold: f32) -> Option<(Uuid, f32)> {
+    candidates
+        .iter()
+        .map(|(id, candidate)| (*id, cosine_similarity(embedding, candidate)))
+        .filter(|(_, similarity)| *similarity >= threshold)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Embed a new review and compare it with the bill's recent posts. Posts from before
+/// embeddings were stored are embedded in the same batch and saved for next time.
+pub async fn check_duplicate(
+    pool: &PgPool,
+    embedder: &dyn Embedder,
+    bill_id: Uuid,
+    content: &str,
+) -> Result<DuplicateCheck> {
+    let recent = db::get_recent_post_embeddings(pool, bill_id, duplicate_window()).await?;
+
+    let mut candidates = Vec::with_capacity(recent.len());
+    let mut missing = Vec::new();
+    let mut texts = vec![content.to_string()];
+    for (id, text, embedding) in recent {
+        match embedding {
+            Some(embedding) => candidates.push((id, embedding)),
+            None => {
+                missing.push(id);
+                texts.push(text);
+            }
+        }
+    }
+
+    let mut embeddings = embedder.embed_texts(&texts).await?.into_iter();
+    let embedding = embeddings
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Embedder returned no vector for the review"))?;
+
+    for (id, backfilled) in missing.into_iter().zip(embeddings) {
+        if let Err(e) = db::set_post_embedding(pool, id, &backfilled).await {
+            warn!("Failed to store embedding for post {}: {:#}", id, e);
+        }
+        candidates.push((id, backfilled));
+    }
+
+    let original = most_similar(&embedding, &candidates, duplicate_threshold());
+    if let Some((id, similarity)) = original {
+        info!("Review on bill {} matches post {} (similarity {:.3})", bill_id, id, similarity);
+    }
+
+    Ok(DuplicateCheck { embedding, original })
+}
+
+/// The language a post is written in, as far as `detect_language` can tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    /// Devanagari script; Marathi and Nepali posts are read as Hindi too
+    Hindi,
+    /// Hindi written in Latin script, usually mixed with English
+    Hinglish,
+    /// Anything moderation can't judge, named for the moderator, e.g. "Tamil"
+    Unsupported(&'static str),
+}
+
+impl Language {
+    /// The code keyword patterns are stored under; None for unsupported languages
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            Language::English => Some("en"),
+            Language::Hindi => Some("hi"),
+            Language::Hinglish => Some("hinglish"),
+            Language::Unsupported(_) => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Hindi => "Hindi",
+            Language::Hinglish => "Hinglish",
+            Language::Unsupported(name) => name,
+        }
+    }
+}
+
+// Common short words that tell Latin-script languages apart
+const LATIN_FUNCTION_WORDS: [(Language, &[&str]); 5] = [
+    (
+        Language::English,
+        &[
+            "the", "and", "is", "are", "was", "of", "to", "in", "for", "that", "this", "it", "not", "with", "be", "we",
+            "they", "should", "will", "have", "on", "by", "a", "an", "i", "you",
+        ],
+    ),
+    (
+        Language::Hinglish,
+        &[
+            "hai", "hain", "nahi", "nahin", "kya", "kyun", "kyon", "aur", "mein", "hum", "hamein", "yeh", "ye", "woh",
+            "bhi", "lekin", "bahut", "accha", "acha", "sarkar", "kaise", "karo", "karna", "chahiye", "tha", "thi",
+            "raha", "rahe", "gaya", "hoga", "apna", "apne", "sab", "kuch", "ki", "ka", "ke", "ko", "se", "liye",
+        ],
+    ),
+    (
+        Language::Unsupported("Spanish"),
+        &["el", "los", "las", "que", "es", "por", "para", "con", "una", "del", "pero", "muy"],
+    ),
+    (
+        Language::Unsupported("French"),
+        &["le", "les", "des", "est", "une", "pour", "avec", "dans", "qui", "pas", "du", "sont"],
+    ),
+    (
+        Language::Unsupported("German"),
+        &["der", "die", "das", "und", "ist", "nicht", "mit", "ein", "eine", "zu", "den", "sind"],
+    ),
+];
+
+// The script a letter is written in: Latin, Devanagari, or the language another script is read as
+fn script_of(c: char) -> &'static str {
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => "Latin",
+        '\u{0900}'..='\u{097F}' => "Devanagari",
+        '\u{0980}'..='\u{09FF}' => "Bengali",
+        '\u{0A00}'..='\u{0A7F}' => "Punjabi",
+        '\u{0A80}'..='\u{0AFF}' => "Gujarati",
+        '\u{0B00}'..='\u{0B7F}' => "Odia",
+        '\u{0B80}'..='\u{0BFF}' => "Tamil",
+        '\u{0C00}'..='\u{0C7F}' => "Telugu",
+        '\u{0C80}'..='\u{0CFF}' => "Kannada",
+        '\u{0D00}'..='\u{0D7F}' => "Malayalam",
+        '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' => "Urdu",
+        '\u{0400}'..='\u{04FF}' => "a Cyrillic-script language",
+        '\u{3040}'..='\u{30FF}' => "Japanese",
+        '\u{AC00}'..='\u{D7AF}' => "Korean",
+        '\u{4E00}'..='\u{9FFF}' => "Chinese",
+        _ => "an unrecognised language",
+    }
+}
+
+/// Guess a post's language from the script most of its letters are in, then for Latin script
+/// from common function words. Posts with too little to go on are taken as English.
+pub fn detect_language(content: &str) -> Language {
+    let mut scripts: Vec<(&'static str, usize)> = Vec::new();
+    for script in content.chars().filter(|c| c.is_alphabetic()).map(script_of) {
+        match scripts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += 1,
+            None => scripts.push((script, 1)),
+        }
+    }
+
+    match scripts.iter().max_by_key(|(_, count)| *count).map(|(script, _)| *script) {
+        None | Some("Latin") => latin_language(content),
+        Some("Devanagari") => Language::Hindi,
+        Some(other) => Language::Unsupported(other),
+    }
+}
+
+fn latin_language(content: &str) -> Language {
+    let lowered = content.to_lowercase();
+    let words: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    // Ties go to the earlier list, so English wins them
+    let mut best = (Language::English, 0);
+    for (language, markers) in LATIN_FUNCTION_WORDS {
+        let hits = words.iter().filter(|w| markers.contains(w)).count();
+        if hits > best.1 {
+            best = (language, hits);
+        }
+    }
+
+    if best.1 >= LANGUAGE_MIN_HITS {
+        best.0
+    } else {
+        Language::English
+    }
+}
+
+/// Something that can judge a post: Ollama, an OpenAI-compatible moderation API, or keywords
+#[async_trait]
+pub trait ModerationProvider: Send + Sync {
+    /// Short name recorded with each verdict, e.g. "ollama"
+    fn name(&self) -> &'static str;
+
+    /// How long `check_content` waits before moving on to the next provider
+    fn timeout(&self) -> Duration;
+
+    /// Judge a post in a supported language, as detected by `check_content`
+    async fn check(&self, content: &str, language: Language) -> Result<ProviderResponse>;
+
+    /// Providers with a breaker are skipped while it's open
+    fn breaker(&self) -> Option<&CircuitBreaker> {
+        None
+    }
+}
+
+/// A provider's verdict with the exchange behind it, for moderation_events
+pub struct ProviderResponse {
+    pub verdict: ModerationVerdict,
+    pub prompt_hash: Option<String>,
+    pub raw_response: Option<String>,
+}
+
+/// Hex SHA-256 of what was sent to a provider, so attempts on the same text can be matched
+fn prompt_hash(prompt: &str) -> String {
+    Sha256::digest(prompt.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Whether a provider's circuit breaker lets calls through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    Closed,
+    /// Failing; calls are skipped until the cool-down ends
+    Open,
+    /// Cooled down; one probe call decides whether to close or reopen
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Opens after `threshold` consecutive failures so posts skip a provider that's down instead of
+/// each waiting out its timeout, then lets one probe through after `cooldown`
+pub struct CircuitBreaker {
+    name: &'static str,
+    threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<BreakerInner>,
+}
+
+enum BreakerInner {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    // A probe that never reports back (its caller went away) is replaced after another cool-down
+    HalfOpen { probe_started: Instant },
+}
+
+impl CircuitBreaker {
+    pub fn new(name: &'static str, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            name,
+            threshold: threshold.max(1),
+            cooldown,
+            inner: Mutex::new(BreakerInner::Closed { failures: 0 }),
+        }
+    }
+
+    /// Configured by MODERATION_BREAKER_FAILURES (default 3) and MODERATION_BREAKER_COOLDOWN_SECS (default 60)
+    pub fn from_env(name: &'static str) -> Self {
+        let threshold = std::env::var("MODERATION_BREAKER_FAILURES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(3);
+        let cooldown = std::env::var("MODERATION_BREAKER_COOLDOWN_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(60);
+        Self::new(name, threshold, Duration::from_secs(cooldown))
+    }
+
+    pub fn state(&self) -> BreakerState {
+        match *self.inner.lock() {
+            BreakerInner::Closed { .. } => BreakerState::Closed,
+            BreakerInner::Open { until } if Instant::now() < until => BreakerState::Open,
+            BreakerInner::Open { .. } | BreakerInner::HalfOpen { .. } => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Whether a call may go ahead; after the cool-down only the first caller gets through, as the probe
+    fn allow(&self) -> bool {
+        let mut inner = self.inner.lock();
+        let now = Instant::now();
+        match *inner {
+            BreakerInner::Closed { .. } => true,
+            BreakerInner::Open { until } if now < until => false,
+            BreakerInner::HalfOpen { probe_started } if now < probe_started + self.cooldown => false,
+            BreakerInner::Open { .. } | BreakerInner::HalfOpen { .. } => {
+                info!("{} moderation circuit breaker half-open; probing", self.name);
+                *inner = BreakerInner::HalfOpen { probe_started: now };
+                true
+            }
+        }
+    }
+
+    fn record_success(&self) {
+        let mut inner = self.inner.lock();
+        if !matches!(*inner, BreakerInner::Closed { .. }) {
+            info!("{} moderation recovered; circuit breaker closed", self.name);
+        }
+        *inner = BreakerInner::Closed { failures: 0 };
+    }
+
+    fn record_failure(&self) {
+        let mut inner = self.inner.lock();
+        let failures = match *inner {
+            BreakerInner::Closed { failures } => failures + 1,
+            // The probe failed, or a call from before the breaker opened finished late
+            BreakerInner::Open { .. } | BreakerInner::HalfOpen { .. } => self.threshold,
+        };
+
+        if failures >= self.threshold {
+            warn!(
+                "{} moderation circuit breaker open after {} failure(s); using the next provider for {:?}",
+                self.name, failures, self.cooldown
+            );
+            *inner = BreakerInner::Open {
+                until: Instant::now() + self.cooldown,
+            };
+        } else {
+            *inner = BreakerInner::Closed { failures };
+        }
+    }
+}
+
+/// Judge content with each provider in turn, moving on when one fails or times out, and with
+/// the built-in keyword lists if none answers. Low-confidence rejections are sent to admin review,
+/// as are posts in languages moderation doesn't support.
+pub async fn check_content(providers: &[Arc<dyn ModerationProvider>], content: &str) -> ModerationVerdict {
+    let language = detect_language(content);
+    if let Language::Unsupported(name) = language {
+        info!("Post is in {}, which moderation doesn't support; marking for admin review", name);
+        let mut verdict = ModerationVerdict::new("language", ModerationResult::AdminReview);
+        verdict.rationale = Some(format!("Written in {}, which automatic moderation doesn't support", name));
+        verdict.attempts.push(ModerationAttempt {
+            provider: verdict.provider,
+            prompt_hash: None,
+            raw_response: None,
+            verdict: Some(verdict.result.to_status()),
+            error: None,
+            latency_ms: 0,
+            trust_bias: None,
+        });
+        return verdict;
+    }
+
+    let mut attempts = Vec::new();
+    let failed = |provider: &dyn ModerationProvider, error: String, latency_ms| ModerationAttempt {
+        provider: provider.name(),
+        prompt_hash: None,
+        raw_response: None,
+        verdict: None,
+        error: Some(error),
+        latency_ms,
+        trust_bias: None,
+    };
+
+    for provider in providers {
+        let breaker = provider.breaker();
+        if breaker.is_some_and(|b| !b.allow()) {
+            info!("Skipping {} moderation while its circuit breaker is open", provider.name());
+            continue;
+        }
+
+        let started = Instant::now();
+        let outcome = tokio::time::timeout(provider.timeout(), provider.check(content, language)).await;
+        let latency_ms = started.elapsed().as_millis() as i32;
+        if let Some(breaker) = breaker {
+            match outcome {
+                Ok(Ok(_)) => breaker.record_success(),
+                _ => breaker.record_failure(),
+            }
+        }
+
+        match outcome {
+            Ok(Ok(response)) => {
+                attempts.push(ModerationAttempt {
+                    provider: provider.name(),
+                    prompt_hash: response.prompt_hash,
+                    raw_response: response.raw_response,
+                    verdict: Some(response.verdict.result.to_status()),
+                    error: None,
+                    latency_ms,
+                    trust_bias: None,
+                });
+                let mut verdict = apply_reject_confidence(response.verdict, reject_confidence());
+                verdict.attempts = attempts;
+                match verdict.result {
+                    ModerationResult::Falafel => info!("Falafel ({}) - Post is approved", verdict.provider),
+                    ModerationResult::Popcorn => info!("Popcorn ({}) - Post is rejected", verdict.provider),
+                    ModerationResult::AdminReview => {
+                        info!("Default ({}) - Post marked for admin review", verdict.provider)
+                    }
+                }
+                return verdict;
+            }
+            Ok(Err(e)) => {
+                warn!("{} moderation failed: {:#}", provider.name(), e);
+                attempts.push(failed(provider.as_ref(), format!("{:#}", e), latency_ms));
+            }
+            Err(_) => {
+                warn!("{} moderation timed out after {:?}", provider.name(), provider.timeout());
+                let error = format!("Timed out after {:?}", provider.timeout());
+                attempts.push(failed(provider.as_ref(), error, latency_ms));
+            }
+        }
+    }
+
+    info!("Using built-in keyword lists");
+    let mut verdict = KeywordList::builtin().verdict(content, language);
+    attempts.push(ModerationAttempt {
+        provider: verdict.provider,
+        prompt_hash: None,
+        raw_response: None,
+        verdict: Some(verdict.result.to_status()),
+        error: None,
+        latency_ms: 0,
+        trust_bias: None,
+    });
+    verdict.attempts = attempts;
+    verdict
+}
+
+/// Keep the attempts behind a post's verdict in moderation_events. Failures are logged, not
+/// returned, so a post is never lost over its transcript.
+pub async fn record_attempts(pool: &PgPool, post_id: Uuid, attempts: &[ModerationAttempt]) {
+    if let Err(e) = db::insert_moderation_events(pool, post_id, attempts).await {
+        tracing::error!("Failed to record moderation events for post {}: {:#}", post_id, e);
+    }
+}
+
+/// How far moderation trusts a post's author, from `trust_level`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustLevel {
+    /// New accounts and those with many rejections: unsure approvals are held for review
+    Low,
+    Normal,
+    /// Long-standing accounts with a clean record: posts a model was unsure about are approved
+    High,
+}
+
+impl TrustLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrustLevel::Low => "low",
+            TrustLevel::Normal => "normal",
+            TrustLevel::High => "high",
+        }
+    }
+}
+
+// Decided posts needed before a rejection ratio can make an account low-trust
+const TRUST_MIN_DECIDED_POSTS: i64 = 3;
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+/// Where trust levels start and how they're applied
+#[derive(Debug, Clone)]
+pub struct TrustThresholds {
+    /// Accounts younger than this are low-trust (TRUST_NEW_ACCOUNT_DAYS, default 7)
+    pub new_account_days: i64,
+    /// A rejected share of decided posts at or above this makes an account low-trust
+    /// (TRUST_LOW_REJECTION_RATIO, default 0.3)
+    pub low_rejection_ratio: f64,
+    /// High trust needs an account at least this old (TRUST_HIGH_MIN_AGE_DAYS, default 90)...
+    pub high_min_age_days: i64,
+    /// ...with this many approved posts (TRUST_HIGH_MIN_APPROVED, default 20)...
+    pub high_min_approved: i64,
+    /// ...and a rejected share no higher than this (TRUST_HIGH_MAX_REJECTION_RATIO, default 0.05)
+    pub high_max_rejection_ratio: f64,
+    /// Approvals less confident than this are borderline (TRUST_BORDERLINE_CONFIDENCE, default 0.7)
+    pub borderline_confidence: f32,
+    /// Share of trusted approvals picked for a moderator to spot-check (TRUST_AUDIT_SAMPLE_RATE, default 0.1)
+    pub audit_sample_rate: f64,
+}
+
+impl Default for TrustThresholds {
+    fn default() -> Self {
+        Self {
+            new_account_days: 7,
+            low_rejection_ratio: 0.3,
+            high_min_age_days: 90,
+            high_min_approved: 20,
+            high_max_rejection_ratio: 0.05,
+            borderline_confidence: 0.7,
+            audit_sample_rate: 0.1,
+        }
+    }
+}
+
+impl TrustThresholds {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            new_account_days: env_or("TRUST_NEW_ACCOUNT_DAYS", defaults.new_account_days),
+            low_rejection_ratio: env_or("TRUST_LOW_REJECTION_RATIO", defaults.low_rejection_ratio),
+            high_min_age_days: env_or("TRUST_HIGH_MIN_AGE_DAYS", defaults.high_min_age_days),
+            high_min_approved: env_or("TRUST_HIGH_MIN_APPROVED", defaults.high_min_approved),
+            high_max_rejection_ratio: env_or("TRUST_HIGH_MAX_REJECTION_RATIO", defaults.high_max_rejection_ratio),
+            borderline_confidence: env_or("TRUST_BORDERLINE_CONFIDENCE", defaults.borderline_confidence),
+            audit_sample_rate: env_or("TRUST_AUDIT_SAMPLE_RATE", defaults.audit_sample_rate).clamp(0.0, 1.0),
+        }
+    }
+
+    fn classify(&self, stats: &TrustStats, now: chrono::DateTime<chrono::Utc>) -> TrustLevel {
+        let age_days = (now - stats.created_at).num_days();
+        let decided = stats.approved + stats.rejected;
+        let rejection_ratio = if decided > 0 {
+            stats.rejected as f64 / decided as f64
+        } else {
+            0.0
+        };
+
+        if age_days < self.new_account_days
+            || (decided >= TRUST_MIN_DECIDED_POSTS && rejection_ratio >= self.low_rejection_ratio)
+        {
+            TrustLevel::Low
+        } else if age_days >= self.high_min_age_days
+            && stats.approved >= self.high_min_approved
+            && rejection_ratio <= self.high_max_rejection_ratio
+        {
+            TrustLevel::High
+        } else {
+            TrustLevel::Normal
+        }
+    }
+}
+
+/// A user's trust level from their account age, approved posts and rejected share, with the
+/// thresholds from `TrustThresholds::from_env`
+pub async fn trust_level(pool: &PgPool, user_id: Uuid) -> Result<TrustLevel> {
+    let stats = db::get_trust_stats(pool, user_id).await?;
+    Ok(stats.map_or(TrustLevel::Normal, |stats| {
+        TrustThresholds::from_env().classify(&stats, chrono::Utc::now())
+    }))
 }
 
-#[derive(Debug, Deserialize)]
-struct OllamaResponse {
-    response: String,
+// A hold from a model rather than a deterministic check, over nothing as serious as hate or
+// threats, which a high trust level may lift
+fn unsure_hold(verdict: &ModerationVerdict) -> bool {
+    verdict.result == ModerationResult::AdminReview
+        && matches!(verdict.provider, "ollama" | "openai")
+        && !verdict.categories.iter().any(|c| c == "hate" || c == "threat")
 }
 
-/// Check content for toxicity, hate speech, threats, or spam using Ollama
-/// Returns ModerationResult based on the analysis
-pub async fn check_content(content: &str) -> Result<ModerationResult> {
-    let prompt = format!(
+/// Bias a borderline verdict by the author's trust level. A low-trust author's approval the
+/// model wasn't sure of is held for review; a high-trust author's post a model held because it
+/// was unsure is approved, and picked for a spot-check when `sampled`. Keyword matches,
+/// unsupported languages, suspected hate or threats and confident verdicts stand. Any change is
+/// added to the attempts.
+fn apply_trust(
+    mut verdict: ModerationVerdict,
+    trust: TrustLevel,
+    thresholds: &TrustThresholds,
+    sampled: bool,
+) -> ModerationVerdict {
+    let bias = match (trust, verdict.result) {
+        (TrustLevel::Low, ModerationResult::Falafel)
+            if verdict.confidence.is_some_and(|c| c < thresholds.borderline_confidence) =>
+        {
+            verdict.result = ModerationResult::AdminReview;
+            let note = "Held because the author's account is new or often rejected";
+            verdict.rationale = Some(match verdict.rationale.take() {
+                Some(rationale) => format!("{} {}", rationale, note),
+                None => note.to_string(),
+            });
+            "held"
+        }
+        (TrustLevel::High, ModerationResult::AdminReview) if unsure_hold(&verdict) => {
+            verdict.result = ModerationResult::Falafel;
+            verdict.audit_sample = sampled;
+            if sampled {
+                "approved_audit"
+            } else {
+                "approved"
+            }
+        }
+        _ => return verdict,
+    };
+
+    info!("{} trust: borderline verdict {}", trust.as_str(), bias);
+    verdict.attempts.push(ModerationAttempt {
+        provider: "trust",
+        prompt_hash: None,
+        raw_response: None,
+        verdict: Some(verdict.result.to_status()),
+        error: None,
+        latency_ms: 0,
+        trust_bias: Some(bias),
+    });
+    verdict
+}
+
+/// `check_content` for a post by `author`, with a borderline verdict biased by the author's
+/// trust level. If the level can't be read the verdict stands as given.
+pub async fn check_post(
+    pool: &PgPool,
+    providers: &[Arc<dyn ModerationProvider>],
+    author: Uuid,
+    content: &str,
+) -> ModerationVerdict {
+    let verdict = check_content(providers, content).await;
+    // Only a verdict some trust level could change is worth the lookup
+    let borderline = match verdict.result {
+        ModerationResult::Falafel => verdict.confidence.is_some(),
+        ModerationResult::AdminReview => unsure_hold(&verdict),
+        ModerationResult::Popcorn => false,
+    };
+    if !borderline {
+        return verdict;
+    }
+
+    match trust_level(pool, author).await {
+        Ok(trust) => {
+            let thresholds = TrustThresholds::from_env();
+            let sampled = rand::random::<f64>() < thresholds.audit_sample_rate;
+            apply_trust(verdict, trust, &thresholds, sampled)
+        }
+        Err(e) => {
+            warn!("Couldn't work out the trust level of user {}: {:#}", author, e);
+            verdict
+        }
+    }
+}
+
+/// Whether reviews are moderated in the background after they're saved (MODERATION_MODE=async,
+/// the default) or before the submission returns (sync)
+pub fn async_mode() -> bool {
+    match std::env::var("MODERATION_MODE").as_deref() {
+        Ok("async") | Err(_) => true,
+        Ok("sync") => false,
+        Ok(other) => {
+            tracing::error!("Unknown MODERATION_MODE {:?}, using async", other);
+            true
+        }
+    }
+}
+
+/// Held reviews waiting for a verdict in async mode, drained by the web server's worker
+#[derive(Clone)]
+pub struct ModerationQueue {
+    sender: mpsc::Sender<Uuid>,
+}
+
+impl ModerationQueue {
+    pub fn new() -> (Self, mpsc::Receiver<Uuid>) {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        (Self { sender }, receiver)
+    }
+
+    /// Queue a held post for a verdict. If the queue is full or the worker has stopped, the
+    /// post stays held until the worker's next sweep for unjudged posts picks it up.
+    pub fn enqueue(&self, post_id: Uuid) {
+        if let Err(e) = self.sender.try_send(post_id) {
+            warn!("Couldn't queue post {} for moderation: {}", post_id, e);
+        }
+    }
+}
+
+/// Which held posts `remoderate` looks at
+#[derive(Debug, Default)]
+pub struct RemoderateFilter {
+    /// Only posts created at least this long ago
+    pub older_than: Option<chrono::Duration>,
+    pub bill_id: Option<Uuid>,
+    /// At most this many posts, oldest first
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+pub struct RemoderateReport {
+    /// Newly approved posts, for the caller to notify followers and mentioned users about
+    pub approved: Vec<Post>,
+    pub rejected: usize,
+    pub still_pending: usize,
+    /// Posts a moderator decided, or that were edited, reported or deleted, while being checked
+    pub skipped: usize,
+}
+
+/// Run moderation again on posts held for review only because moderation couldn't decide,
+/// e.g. while Ollama was down. Each verdict is only saved if the post is still held and
+/// undecided, so this is safe alongside the web server and other runs.
+pub async fn remoderate(
+    pool: &PgPool,
+    providers: &[Arc<dyn ModerationProvider>],
+    filter: &RemoderateFilter,
+) -> Result<RemoderateReport> {
+    let created_before = filter.older_than.map(|age| chrono::Utc::now() - age);
+    let posts = db::get_posts_to_remoderate(pool, created_before, filter.bill_id, filter.limit).await?;
+    info!("Re-moderating {} held post(s)", posts.len());
+
+    let mut report = RemoderateReport::default();
+    for post in posts {
+        let verdict = check_post(pool, providers, post.user_id, &post.content).await;
+        record_attempts(pool, post.id, &verdict.attempts).await;
+        match db::set_remoderated_status(pool, &post, &verdict.to_post_moderation()).await? {
+            Some(updated) => match verdict.result {
+                ModerationResult::Falafel => report.approved.push(updated),
+                ModerationResult::Popcorn => report.rejected += 1,
+                ModerationResult::AdminReview => report.still_pending += 1,
+            },
+            None => {
+                info!("Post {} changed while it was being re-moderated; leaving it alone", post.id);
+                report.skipped += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// The provider named by MODERATION_BACKEND: "ollama" (the default), "openai" or "keyword",
+/// followed by the keyword filter with the database lists from `keywords`, so "keyword" means
+/// that filter alone.
+pub fn from_env(
+    llm: OllamaClient,
+    http: reqwest::Client,
+    keywords: Arc<KeywordCache>,
+) -> Vec<Arc<dyn ModerationProvider>> {
+    let mut providers: Vec<Arc<dyn ModerationProvider>> = match std::env::var("MODERATION_BACKEND").as_deref() {
+        Ok("ollama") | Err(_) => vec![Arc::new(OllamaModeration::new(llm))],
+        Ok("openai") => match OpenAiModeration::from_env(http) {
+            Some(openai) => vec![Arc::new(openai)],
+            None => {
+                tracing::error!("MODERATION_BACKEND=openai needs OPENAI_API_KEY, using keyword moderation");
+                Vec::new()
+            }
+        },
+        Ok("keyword") => Vec::new(),
+        Ok(other) => {
+            tracing::error!("Unknown MODERATION_BACKEND {:?}, using Ollama", other);
+            vec![Arc::new(OllamaModeration::new(llm))]
+        }
+    };
+    providers.push(Arc::new(KeywordModeration::new(keywords)));
+    providers
+}
+
+/// Asks the Ollama model for a JSON verdict, accepting a single verdict word if that's all it gives
+pub struct OllamaModeration {
+    llm: OllamaClient,
+    breaker: CircuitBreaker,
+}
+
+impl OllamaModeration {
+    pub fn new(llm: OllamaClient) -> Self {
+        Self {
+            llm,
+            breaker: CircuitBreaker::from_env("ollama"),
+        }
+    }
+}
+
+#[async_trait]
+impl ModerationProvider for OllamaModeration {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(OLLAMA_TIMEOUT_SECS)
+    }
+
+    async fn check(&self, content: &str, language: Language) -> Result<ProviderResponse> {
+        let prompt = moderation_prompt(content, language);
+
+        info!("Sending moderation request to Ollama at {}", self.llm.base_url());
+        let response = match self.llm.generate(&prompt, OLLAMA_TIMEOUT_SECS).await {
+            // One quick retry when the connection drops mid-request, e.g. as Ollama restarts
+            Err(e) if is_connection_reset(&e) => {
+                warn!("Ollama moderation connection dropped ({:#}); retrying once", e);
+                tokio::time::sleep(Duration::from_millis(RETRY_DELAY_MS)).await;
+                self.llm.generate(&prompt, OLLAMA_TIMEOUT_SECS).await?
+            }
+            result => result?,
+        };
+        info!("Ollama moderation response: {}", response.trim());
+
+        let verdict = parse_verdict_json(self.name(), &response)
+            .unwrap_or_else(|| ModerationVerdict::new(self.name(), parse_verdict(&response)));
+        Ok(ProviderResponse {
+            verdict,
+            prompt_hash: Some(prompt_hash(&prompt)),
+            raw_response: Some(response),
+        })
+    }
+
+    fn breaker(&self) -> Option<&CircuitBreaker> {
+        Some(&self.breaker)
+    }
+}
+
+/// The moderation prompt for a post, with notes on reading Hindi and Hinglish. The response
+/// format and rationale stay in English whatever the post's language.
+fn moderation_prompt(content: &str, language: Language) -> String {
+    let language_note = match language {
+        Language::Hindi => {
+            "\n\nThe post is written in Hindi (Devanagari script). Judge it as a Hindi speaker would, including \
+             slang and insults. Opposing a bill, e.g. \"इस बिल को खत्म करो\" (scrap this bill), is legitimate \
+             debate. Write the rationale in English."
+        }
+        Language::Hinglish => {
+            "\n\nThe post is written in Hinglish: Hindi in Latin script, often mixed with English. Read romanised \
+             Hindi words for their Hindi meaning, e.g. \"maar dalo\" (kill them) or \"gaddar\" (traitor). Opposing \
+             a bill, e.g. \"bill ko khatam karo\" (scrap the bill), is legitimate debate. Write the rationale in English."
+        }
+        Language::English | Language::Unsupported(_) => "",
+    };
+
+    format!(
         r#"You are a content moderator. Analyze the following user post for a civic legislation forum and determine if it contains:
-1. Hate speech or discriminatory language
-2. Threats
-3. Toxic language
-4. Advertisements and similar spam
+1. Hate speech or discriminatory language ("hate")
+2. Threats ("threat")
+3. Toxic language ("toxicity")
+4. Advertisements and similar spam ("spam"){}
 
 Post to analyze:
 "{}"
 
-Respond with ONLY one of these three words:
-- "Falafel" if the content is appropriate for the forum
-- "Popcorn" if the content clearly violates the guidelines (hate speech, threats, spam)
-- "REVIEW" if you're unsure or the content is borderline
+Respond with ONLY a JSON object, with no other text:
+{{"verdict": "...", "categories": [...], "confidence": 0.0, "rationale": "..."}}
+
+- "verdict": "Falafel" if the content is appropriate for the forum, "Popcorn" if it clearly violates the guidelines, or "REVIEW" if you're unsure or the content is borderline
+- "categories": which of "hate", "threat", "toxicity", "spam" apply; empty if none
+- "confidence": how sure you are of the verdict, from 0.0 to 1.0
+- "rationale": one short sentence explaining the verdict
 
 Response:"#,
-        content
-    );
-
-    // Try to connect to Ollama
-    let client = reqwest::Client::new();
-    let ollama_url = std::env::var("OLLAMA_URL").unwrap_or_else(|_| "http://localhost:11434".to_string());
-    
-    let request = OllamaRequest {
-        model: std::env::var("OLLAMA_MODEL").unwrap_or_else(|_| "llama3.2".to_string()),
-        prompt,
-        stream: false,
-    };
+        language_note, content
+    )
+}
 
-    info!("Sending moderation request to Ollama at {}", ollama_url);
-
-    match client
-        .post(format!("{}/api/generate", ollama_url))
-        .json(&request)
-        .timeout(std::time::Duration::from_secs(30))
-        .send()
-        .await
-    {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.json::<OllamaResponse>().await {
-                    Ok(ollama_response) => {
-                        let response_text = ollama_response.response.trim().to_uppercase();
-                        info!("Ollama moderation response: {}", response_text);
-                        
-                        // Switch case logic as requested
-                        let result = match response_text.as_str() {
-                            s if s.contains("Falafel") => {
-                                info!("Falafel - Post is approved");
-                                ModerationResult::Falafel
-                            }
-                            s if s.contains("Popcorn") => {
-                                info!("Popcorn - Post is rejected");
-                                ModerationResult::Popcorn
-                            }
-                            _ => {
-                                info!("Default - Post marked for admin review");
-                                ModerationResult::AdminReview
-                            }
-                        };
-                        
-                        return Ok(result);
-                    }
+/// Whether a request failed because an open connection was reset or closed, rather than refused
+/// or timed out; worth one retry since a fresh connection usually works
+fn is_connection_reset(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.downcast_ref::<std::io::Error>().is_some_and(|io| {
+            matches!(
+                io.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof
+            )
+        })
+    })
+}
+
+/// An OpenAI-compatible `/moderations` endpoint, configured from OPENAI_API_KEY, OPENAI_BASE_URL
+/// (default https://api.openai.com/v1) and OPENAI_MODERATION_MODEL (default omni-moderation-latest)
+pub struct OpenAiModeration {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Deserialize)]
+struct OpenAiModerationResponse {
+    results: Vec<OpenAiModerationResult>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiModerationResult {
+    flagged: bool,
+    #[serde(default)]
+    categories: HashMap<String, bool>,
+    #[serde(default)]
+    category_scores: HashMap<String, f32>,
+}
+
+impl OpenAiModeration {
+    pub fn from_env(http: reqwest::Client) -> Option<Self> {
+        let api_key = std::env::var("OPENAI_API_KEY").ok().filter(|k| !k.is_empty())?;
+        let base_url = std::env::var("OPENAI_BASE_URL").unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
+        let model = std::env::var("OPENAI_MODERATION_MODEL").unwrap_or_else(|_| "omni-moderation-latest".to_string());
+
+        Some(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+        })
+    }
+}
+
+#[async_trait]
+impl ModerationProvider for OpenAiModeration {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(OPENAI_TIMEOUT_SECS)
+    }
+
+    // The omni moderation models read Hindi and Hinglish themselves
+    async fn check(&self, content: &str, _language: Language) -> Result<ProviderResponse> {
+        let request = serde_json::json!({ "model": self.model, "input": content }).to_string();
+        let response = self
+            .http
+            .post(format!("{}/moderations", self.base_url))
+            .bearer_auth(&self.api_key)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(request.clone())
+            .send()
+            .await
+            .context("Failed to reach the moderation API")?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .context("Failed to read the moderation API response")?;
+        if !status.is_success() {
+            anyhow::bail!("The moderation API returned {}: {}", status, body);
+        }
+
+        let parsed: OpenAiModerationResponse =
+            serde_json::from_str(&body).context("Failed to parse the moderation API response")?;
+        let result = parsed
+            .results
+            .into_iter()
+            .next()
+            .context("The moderation API returned no results")?;
+
+        Ok(ProviderResponse {
+            verdict: openai_verdict(self.name(), &result),
+            prompt_hash: Some(prompt_hash(&request)),
+            raw_response: Some(body),
+        })
+    }
+}
+
+/// Map an OpenAI moderation result onto a verdict: flagged posts are rejections whose confidence
+/// is the highest score among the flagged categories, and the categories are folded into ours
+fn openai_verdict(provider: &'static str, result: &OpenAiModerationResult) -> ModerationVerdict {
+    if !result.flagged {
+        return ModerationVerdict::new(provider, ModerationResult::Falafel);
+    }
+
+    let mut flagged: Vec<(&str, f32)> = result
+        .categories
+        .iter()
+        .filter(|(_, on)| **on)
+        .map(|(name, _)| (name.as_str(), result.category_scores.get(name).copied().unwrap_or(0.0)))
+        .collect();
+    flagged.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+
+    let mut categories: Vec<String> = Vec::new();
+    for (name, _) in &flagged {
+        let ours = if name.contains("threatening") || name.starts_with("violence") {
+            "threat"
+        } else if name.starts_with("hate") {
+            "hate"
+        } else if name.starts_with("harassment") {
+            "toxicity"
+        } else {
+            continue;
+        };
+        if !categories.iter().any(|c| c == ours) {
+            categories.push(ours.to_string());
+        }
+    }
+
+    let rationale = flagged
+        .iter()
+        .map(|(name, score)| format!("{} ({:.2})", name, score))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    ModerationVerdict {
+        provider,
+        result: ModerationResult::Popcorn,
+        categories,
+        confidence: flagged.first().map(|(_, score)| *score),
+        rationale: Some(format!("Flagged by the moderation API: {}", rationale)),
+        attempts: Vec::new(),
+        audit_sample: false,
+    }
+}
+
+/// Check a keyword pattern before it's saved: not blank, not too long, and for regexes, one
+/// that compiles. The error is shown to the admin as is.
+pub fn validate_keyword(pattern: &str, is_regex: bool) -> Result<(), String> {
+    if pattern.trim().is_empty() {
+        return Err("The pattern is empty".to_string());
+    }
+    if pattern.chars().count() > KEYWORD_PATTERN_MAX_CHARS {
+        return Err(format!("Patterns can be at most {} characters", KEYWORD_PATTERN_MAX_CHARS));
+    }
+    if is_regex {
+        compile_regex(pattern).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(KEYWORD_REGEX_SIZE_LIMIT)
+        .build()
+}
+
+/// Devanagari with nukta letters folded into their base letters, since the same word is written
+/// both ways: "बेवक़ूफ़" becomes "बेवकूफ"
+fn fold_nukta(text: &str) -> String {
+    text.chars()
+        .filter(|&c| c != '\u{093C}')
+        .map(|c| match c {
+            // Precomposed letters with a nukta
+            '\u{0958}' => '\u{0915}',
+            '\u{0959}' => '\u{0916}',
+            '\u{095A}' => '\u{0917}',
+            '\u{095B}' => '\u{091C}',
+            '\u{095C}' => '\u{0921}',
+            '\u{095D}' => '\u{0922}',
+            '\u{095E}' => '\u{092B}',
+            '\u{095F}' => '\u{092F}',
+            other => other,
+        })
+        .collect()
+}
+
+/// A plain pattern as a regex matching it as whole words, with any run of whitespace between
+/// them: "kill" matches "Kill" but not "skilled", "buy now" matches "buy  now"
+fn whole_word_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let pattern = fold_nukta(pattern);
+    let words: Vec<String> = pattern.split_whitespace().map(regex::escape).collect();
+    let mut expression = words.join(r"\s+");
+    if pattern.trim_start().starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+        expression.insert_str(0, r"\b");
+    }
+    if pattern.trim_end().ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+        expression.push_str(r"\b");
+    }
+    compile_regex(&expression)
+}
+
+struct KeywordRule {
+    pattern: String,
+    language: String,
+    kind: String,
+    reject: bool,
+    high_severity: bool,
+    regex: Regex,
+}
+
+/// Compiled keyword patterns. A post is checked against its language's patterns and the English
+/// ones. The filter acts on a post with a high-severity match or at least `KEYWORD_MIN_HITS`
+/// distinct matches; then any review pattern among them holds the post, so it is only rejected
+/// outright when nothing in it needs a moderator's judgement.
+struct KeywordList {
+    rules: Vec<KeywordRule>,
+}
+
+impl KeywordList {
+    fn builtin() -> Self {
+        let rules = BUILTIN_KEYWORDS
+            .iter()
+            .map(|&(pattern, language, kind, action, severity)| KeywordRule {
+                pattern: pattern.to_string(),
+                language: language.to_string(),
+                kind: kind.to_string(),
+                reject: action == "reject",
+                high_severity: severity == "high",
+                regex: whole_word_regex(pattern).expect("built-in keyword pattern"),
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Compile rows from moderation_keywords, skipping any pattern that no longer compiles
+    fn from_rows(rows: Vec<ModerationKeyword>) -> Self {
+        let rules = rows
+            .into_iter()
+            .filter_map(|row| {
+                let regex = if row.is_regex {
+                    compile_regex(&fold_nukta(&row.pattern))
+                } else {
+                    whole_word_regex(&row.pattern)
+                };
+                let regex = match regex {
+                    Ok(regex) => regex,
                     Err(e) => {
-                        warn!("Failed to parse Ollama response: {}", e);
+                        warn!("Skipping invalid keyword pattern {:?}: {}", row.pattern, e);
+                        return None;
                     }
-                }
+                };
+                Some(KeywordRule {
+                    reject: row.action == "reject",
+                    high_severity: row.severity == "high",
+                    pattern: row.pattern,
+                    language: row.language,
+                    kind: row.kind,
+                    regex,
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    fn verdict(&self, content: &str, language: Language) -> ModerationVerdict {
+        let mut text = fold_nukta(content);
+        for idiom in ALLOWED_IDIOMS.iter() {
+            text = idiom.replace_all(&text, " ").into_owned();
+        }
+
+        let hits: Vec<&KeywordRule> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.language == "en" || Some(rule.language.as_str()) == language.code())
+            .filter(|rule| rule.regex.is_match(&text))
+            .collect();
+        let patterns = hits.iter().map(|rule| format!("\"{}\"", rule.pattern)).collect::<Vec<_>>().join(", ");
+        if hits.len() < KEYWORD_MIN_HITS && !hits.iter().any(|rule| rule.high_severity) {
+            if hits.is_empty() {
+                info!("Keyword moderation: No issues found, approving");
             } else {
-                warn!("Ollama returned error status: {}", response.status());
+                info!("Keyword moderation: Only found {}, approving", patterns);
             }
+            return ModerationVerdict::new("keyword", ModerationResult::Falafel);
         }
-        Err(e) => {
-            warn!("Failed to connect to Ollama: {}", e);
+
+        let result = if hits.iter().any(|rule| !rule.reject) {
+            info!("Keyword moderation: Found {}, marking for review", patterns);
+            ModerationResult::AdminReview
+        } else {
+            info!("Keyword moderation: Found {}, rejecting", patterns);
+            ModerationResult::Popcorn
+        };
+
+        let mut verdict = ModerationVerdict::new("keyword", result);
+        for rule in &hits {
+            if !verdict.categories.contains(&rule.kind) {
+                verdict.categories.push(rule.kind.clone());
+            }
         }
+        verdict.rationale = Some(format!("Matched the keyword patterns {}", patterns));
+        verdict
     }
+}
 
-    // Fallback: If Ollama is not available, use simple keyword-based filtering
-    info!("Using fallback keyword-based moderation");
-    Ok(fallback_moderation(content))
+/// The keyword lists from moderation_keywords, read again once they're older than the TTL so
+/// admin changes apply without a restart. If the database can't be read, the last lists read
+/// (or the built-in ones) stay in use.
+pub struct KeywordCache {
+    pool: PgPool,
+    ttl: Duration,
+    cached: Mutex<Option<(Instant, Arc<KeywordList>)>>,
 }
 
-/// Simple keyword-based fallback moderation when LLM is unavailable
-fn fallback_moderation(content: &str) -> ModerationResult {
-    let content_lower = content.to_lowercase();
-    
-    // List of obviously toxic patterns
-    let toxic_patterns = [
-        "kill", "murder", "hate", "terrorist", "bomb", "die",
-        "stupid", "idiot", "moron", "racist", "sexist",
-    ];
-    
-    // List of spam patterns
-    let spam_patterns = [
-        "buy now", "click here", "free money", "lottery",
-        "crypto", "bitcoin", "investment opportunity",
-    ];
-    
-    // Check for toxic content
-    for pattern in toxic_patterns {
-        if content_lower.contains(pattern) {
-            info!("Fallback moderation: Found toxic pattern '{}', marking for review", pattern);
-            return ModerationResult::AdminReview;
+impl KeywordCache {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            ttl: Duration::from_secs(KEYWORD_CACHE_TTL_SECS),
+            cached: Mutex::new(None),
         }
     }
-    
-    // Check for spam content
-    for pattern in spam_patterns {
-        if content_lower.contains(pattern) {
-            info!("Fallback moderation: Found spam pattern '{}', rejecting", pattern);
-            return ModerationResult::Popcorn;
+
+    /// Read the lists again on next use, e.g. right after an admin changes them
+    pub fn invalidate(&self) {
+        *self.cached.lock() = None;
+    }
+
+    async fn current(&self) -> Arc<KeywordList> {
+        let stale = match &*self.cached.lock() {
+            Some((loaded, list)) if loaded.elapsed() < self.ttl => return list.clone(),
+            Some((_, list)) => Some(list.clone()),
+            None => None,
+        };
+
+        match db::get_enabled_moderation_keywords(&self.pool).await {
+            Ok(rows) => {
+                let list = Arc::new(KeywordList::from_rows(rows));
+                *self.cached.lock() = Some((Instant::now(), list.clone()));
+                list
+            }
+            Err(e) => {
+                warn!("Failed to load moderation keywords, using the previous lists: {:#}", e);
+                stale.unwrap_or_else(|| Arc::new(KeywordList::builtin()))
+            }
         }
     }
-    
-    // If no concerning patterns found, approve
-    info!("Fallback moderation: No issues found, approving");
-    ModerationResult::Falafel
+}
+
+/// Keyword lists for hate, threats, toxicity and spam from moderation_keywords; runs after the
+/// other providers, and `check_content` falls back to the built-in lists if even this fails
+pub struct KeywordModeration {
+    keywords: Arc<KeywordCache>,
+}
+
+impl KeywordModeration {
+    pub fn new(keywords: Arc<KeywordCache>) -> Self {
+        Self { keywords }
+    }
+}
+
+#[async_trait]
+impl ModerationProvider for KeywordModeration {
+    fn name(&self) -> &'static str {
+        "keyword"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(KEYWORD_TIMEOUT_SECS)
+    }
+
+    async fn check(&self, content: &str, language: Language) -> Result<ProviderResponse> {
+        Ok(ProviderResponse {
+            verdict: self.keywords.current().await.verdict(content, language),
+            prompt_hash: None,
+            raw_response: None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonVerdict {
+    verdict: String,
+    #[serde(default)]
+    categories: Vec<String>,
+    confidence: Option<f32>,
+    rationale: Option<String>,
+}
+
+/// Read a JSON verdict out of the model's response, skipping anything before the first `{`
+/// (such as a markdown fence) and anything after the object. None if there is no usable object.
+fn parse_verdict_json(provider: &'static str, response: &str) -> Option<ModerationVerdict> {
+    let start = response.find('{')?;
+    let raw: JsonVerdict = serde_json::Deserializer::from_str(&response[start..])
+        .into_iter()
+        .next()?
+        .ok()?;
+
+    let mut categories: Vec<String> = Vec::new();
+    for category in raw.categories.iter().map(|c| c.trim().to_lowercase()) {
+        if CATEGORIES.contains(&category.as_str()) && !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+
+    Some(ModerationVerdict {
+        provider,
+        result: parse_verdict(&raw.verdict),
+        categories,
+        confidence: raw.confidence.filter(|c| c.is_finite()).map(|c| c.clamp(0.0, 1.0)),
+        rationale: raw.rationale.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+        attempts: Vec::new(),
+        audit_sample: false,
+    })
+}
+
+/// Send a rejection the model isn't sure enough of to admin review, keeping what it flagged.
+/// Verdicts without a confidence (single-word answers) stand as given.
+fn apply_reject_confidence(mut verdict: ModerationVerdict, threshold: f32) -> ModerationVerdict {
+    if verdict.result == ModerationResult::Popcorn && verdict.confidence.is_some_and(|c| c < threshold) {
+        verdict.result = ModerationResult::AdminReview;
+    }
+    verdict
+}
+
+/// Read the model's verdict, ignoring case and punctuation. A response that starts with a verdict
+/// word is taken at that word; otherwise it counts only if exactly one of Falafel and Popcorn
+/// appears as a whole word, so an answer that mentions both goes to admin review.
+fn parse_verdict(response: &str) -> ModerationResult {
+    let response = response.to_lowercase();
+    let words: Vec<&str> = response
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    match words.first().copied() {
+        Some("falafel") => return ModerationResult::Falafel,
+        Some("popcorn") => return ModerationResult::Popcorn,
+        Some("review") => return ModerationResult::AdminReview,
+        _ => {}
+    }
+
+    match (words.contains(&"falafel"), words.contains(&"popcorn")) {
+        (true, false) => ModerationResult::Falafel,
+        (false, true) => ModerationResult::Popcorn,
+        _ => ModerationResult::AdminReview,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_verdict_matches_any_case() {
+        assert_eq!(parse_verdict("Falafel"), ModerationResult::Falafel);
+        assert_eq!(parse_verdict("popcorn."), ModerationResult::Popcorn);
+        assert_eq!(parse_verdict("I think this is FALAFEL because it discusses the bill"), ModerationResult::Falafel);
+        assert_eq!(parse_verdict("  \"REVIEW\"\n"), ModerationResult::AdminReview);
+    }
+
+    #[test]
+    fn test_parse_verdict_prefers_first_word_and_rejects_ambiguity() {
+        assert_eq!(parse_verdict("Popcorn. It is not Falafel."), ModerationResult::Popcorn);
+        assert_eq!(parse_verdict("Either falafel or popcorn"), ModerationResult::AdminReview);
+        assert_eq!(parse_verdict("Popcorns falafelly"), ModerationResult::AdminReview);
+        assert_eq!(parse_verdict(""), ModerationResult::AdminReview);
+    }
+
+    #[test]
+    fn test_parse_verdict_json_tolerates_fences_and_prose() {
+        let response = r#"```json
+{"verdict": "Popcorn", "categories": ["Spam", "spam", "other"], "confidence": 0.93, "rationale": " Advertises a lottery. "}
+```
+The post is an advertisement {not allowed}."#;
+        let verdict = parse_verdict_json("ollama", response).unwrap();
+        assert_eq!(verdict.result, ModerationResult::Popcorn);
+        assert_eq!(verdict.categories, vec!["spam".to_string()]);
+        assert_eq!(verdict.confidence, Some(0.93));
+        assert_eq!(verdict.rationale.as_deref(), Some("Advertises a lottery."));
+
+        let bare = parse_verdict_json("ollama", r#"{"verdict": "falafel"}"#).unwrap();
+        assert_eq!(bare, ModerationVerdict::new("ollama", ModerationResult::Falafel));
+
+        assert_eq!(parse_verdict_json("ollama", "Falafel"), None);
+        assert_eq!(parse_verdict_json("ollama", r#"{"verdict": "Popcorn", "confidence": "#), None);
+    }
+
+    #[test]
+    fn test_low_confidence_rejection_goes_to_review() {
+        let verdict = |confidence| ModerationVerdict {
+            provider: "ollama",
+            result: ModerationResult::Popcorn,
+            categories: vec!["toxicity".to_string()],
+            confidence,
+            rationale: Some("Insults another user.".to_string()),
+            attempts: Vec::new(),
+            audit_sample: false,
+        };
+
+        let unsure = apply_reject_confidence(verdict(Some(0.55)), 0.8);
+        assert_eq!(unsure.result, ModerationResult::AdminReview);
+        assert_eq!(
+            unsure.reason().as_deref(),
+            Some("Categories: toxicity. Confidence: 55%. Insults another user.")
+        );
+
+        assert_eq!(apply_reject_confidence(verdict(Some(0.9)), 0.8).result, ModerationResult::Popcorn);
+        assert_eq!(apply_reject_confidence(verdict(None), 0.8).result, ModerationResult::Popcorn);
+    }
+
+    #[test]
+    fn test_openai_verdict_maps_flagged_categories() {
+        let result: OpenAiModerationResult = serde_json::from_str(
+            r#"{
+                "flagged": true,
+                "categories": {"harassment": true, "harassment/threatening": true, "hate": false, "sexual": false},
+                "category_scores": {"harassment": 0.91, "harassment/threatening": 0.62, "hate": 0.2, "sexual": 0.01}
+            }"#,
+        )
+        .unwrap();
+
+        let verdict = openai_verdict("openai", &result);
+        assert_eq!(verdict.result, ModerationResult::Popcorn);
+        assert_eq!(verdict.categories, vec!["toxicity".to_string(), "threat".to_string()]);
+        assert_eq!(verdict.confidence, Some(0.91));
+        assert_eq!(
+            verdict.rationale.as_deref(),
+            Some("Flagged by the moderation API: harassment (0.91), harassment/threatening (0.62)")
+        );
+
+        let clean: OpenAiModerationResult = serde_json::from_str(r#"{"flagged": false}"#).unwrap();
+        assert_eq!(openai_verdict("openai", &clean), ModerationVerdict::new("openai", ModerationResult::Falafel));
+    }
+
+    #[test]
+    fn test_circuit_breaker_opens_and_probes() {
+        let breaker = CircuitBreaker::new("test", 2, Duration::from_secs(60));
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(!breaker.allow());
+
+        // Once cooled down, one probe goes through and its result decides
+        let breaker = CircuitBreaker::new("test", 1, Duration::ZERO);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_only_dropped_connections_are_retried() {
+        let reset = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::ConnectionReset))
+            .context("Failed to connect to Ollama");
+        assert!(is_connection_reset(&reset));
+
+        let refused = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
+        assert!(!is_connection_reset(&refused));
+        assert!(!is_connection_reset(&anyhow::anyhow!("Ollama returned error status: 500")));
+    }
+
     #[test]
     fn test_fallback_moderation_safe() {
-        let result = fallback_moderation("This is a thoughtful comment about the legislation.");
-        assert_eq!(result, ModerationResult::Falafel);
+        let verdict = KeywordList::builtin().verdict("This is a thoughtful comment about the legislation.", Language::English);
+        assert_eq!(verdict.result, ModerationResult::Falafel);
     }
 
     #[test]
     fn test_fallback_moderation_spam() {
-        let result = fallback_moderation("Click here for free money!");
-        assert_eq!(result, ModerationResult::Popcorn);
+        let verdict = KeywordList::builtin().verdict("Click here for free money!", Language::English);
+        assert_eq!(verdict.result, ModerationResult::Popcorn);
+        assert_eq!(verdict.categories, vec!["spam".to_string()]);
     }
 
     #[test]
     fn test_fallback_moderation_toxic() {
-        let result = fallback_moderation("This is a hateful message");
-        assert_eq!(result, ModerationResult::AdminReview);
+        let verdict = KeywordList::builtin().verdict("Only a stupid idiot would vote for this", Language::English);
+        assert_eq!(verdict.result, ModerationResult::AdminReview);
+        assert_eq!(verdict.categories, vec!["toxicity".to_string()]);
+
+        // One high-severity match is enough on its own
+        let verdict = KeywordList::builtin().verdict("He is a terrorist", Language::English);
+        assert_eq!(verdict.result, ModerationResult::AdminReview);
+    }
+
+    #[test]
+    fn test_fallback_moderation_ignores_civic_speech() {
+        let list = KeywordList::builtin();
+        for content in [
+            "Skilled workers deserve better protections under this act.",
+            "The opposition killed the amendment in committee.",
+            "We must kill the bill before it reaches the Rajya Sabha.",
+            "This bill will kill small businesses.",
+            "Crypto regulation is overdue.",
+        ] {
+            assert_eq!(list.verdict(content, Language::English).result, ModerationResult::Falafel, "{}", content);
+        }
+
+        // An idiom doesn't excuse the rest of the post
+        let verdict = list.verdict("Kill the bill, and kill the idiot minister too", Language::English);
+        assert_eq!(verdict.result, ModerationResult::AdminReview);
+    }
+
+    fn keyword(pattern: &str, is_regex: bool, action: &str) -> ModerationKeyword {
+        ModerationKeyword {
+            id: Uuid::new_v4(),
+            pattern: pattern.to_string(),
+            is_regex,
+            kind: "spam".to_string(),
+            action: action.to_string(),
+            severity: "high".to_string(),
+            language: "en".to_string(),
+            enabled: true,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_database_keywords_match_words_and_regexes() {
+        let list = KeywordList::from_rows(vec![
+            keyword("Limited Offer", false, "reject"),
+            keyword(r"t\.me/\w+", true, "review"),
+            keyword("(unclosed", true, "reject"),
+        ]);
+        assert_eq!(list.rules.len(), 2);
+
+        assert_eq!(list.verdict("a LIMITED  offer today", Language::English).result, ModerationResult::Popcorn);
+        assert_eq!(list.verdict("unlimited offers", Language::English).result, ModerationResult::Falafel);
+        let held = list.verdict("Limited offer, join T.me/deals", Language::English);
+        assert_eq!(held.result, ModerationResult::AdminReview);
+        assert_eq!(
+            held.rationale.as_deref(),
+            Some(r#"Matched the keyword patterns "Limited Offer", "t\.me/\w+""#)
+        );
+        assert_eq!(list.verdict("A thoughtful comment", Language::English).result, ModerationResult::Falafel);
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("I think this bill is good for farmers"), Language::English);
+        assert_eq!(detect_language("Let them die"), Language::English);
+        assert_eq!(detect_language(""), Language::English);
+        assert_eq!(detect_language("यह बिल किसानों के लिए अच्छा है"), Language::Hindi);
+        assert_eq!(detect_language("Yeh bill kisano ke liye bahut accha hai"), Language::Hinglish);
+        assert_eq!(detect_language("இந்த மசோதா நல்லது"), Language::Unsupported("Tamil"));
+        assert_eq!(
+            detect_language("Esta ley es muy buena para el pueblo"),
+            Language::Unsupported("Spanish")
+        );
+    }
+
+    #[test]
+    fn test_keyword_lists_follow_language() {
+        let list = KeywordList::builtin();
+
+        // Nukta spellings match the plain patterns
+        let verdict = list.verdict("यह सरकार बेवक़ूफ़ और गद्दार है", Language::Hindi);
+        assert_eq!(verdict.result, ModerationResult::AdminReview);
+        assert_eq!(verdict.categories, vec!["toxicity".to_string()]);
+
+        let verdict = list.verdict("Inko maar dalo", Language::Hinglish);
+        assert_eq!(verdict.result, ModerationResult::AdminReview);
+        assert_eq!(verdict.categories, vec!["threat".to_string()]);
+        assert_eq!(list.verdict("Inko maar dalo", Language::English).result, ModerationResult::Falafel);
+
+        // English patterns apply whatever the language
+        let verdict = list.verdict("Click here, free money milega", Language::Hinglish);
+        assert_eq!(verdict.result, ModerationResult::Popcorn);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_language_goes_to_review() {
+        let verdict = check_content(&[], "இந்த மசோதா நல்லது").await;
+        assert_eq!(verdict.result, ModerationResult::AdminReview);
+        assert_eq!(verdict.provider, "language");
+        assert_eq!(
+            verdict.rationale.as_deref(),
+            Some("Written in Tamil, which automatic moderation doesn't support")
+        );
+        assert_eq!(verdict.attempts.len(), 1);
+
+        assert!(moderation_prompt("A post", Language::Hinglish).contains("Hinglish"));
+        assert!(!moderation_prompt("A post", Language::English).contains("written in"));
+    }
+
+    #[test]
+    fn test_validate_keyword() {
+        assert!(validate_keyword("free money", false).is_ok());
+        assert!(validate_keyword("(unclosed", false).is_ok());
+        assert!(validate_keyword("(unclosed", true).is_err());
+        assert!(validate_keyword("   ", false).is_err());
+        assert!(validate_keyword(&"a".repeat(KEYWORD_PATTERN_MAX_CHARS + 1), false).is_err());
+    }
+
+    #[test]
+    fn test_trust_levels() {
+        let thresholds = TrustThresholds::default();
+        let now = chrono::Utc::now();
+        let stats = |age_days, approved, rejected| TrustStats {
+            created_at: now - chrono::Duration::days(age_days),
+            approved,
+            rejected,
+        };
+
+        assert_eq!(thresholds.classify(&stats(2, 0, 0), now), TrustLevel::Low);
+        assert_eq!(thresholds.classify(&stats(30, 5, 0), now), TrustLevel::Normal);
+        // Too few decided posts for the ratio to count
+        assert_eq!(thresholds.classify(&stats(30, 1, 1), now), TrustLevel::Normal);
+        assert_eq!(thresholds.classify(&stats(30, 4, 2), now), TrustLevel::Low);
+        assert_eq!(thresholds.classify(&stats(120, 40, 1), now), TrustLevel::High);
+        assert_eq!(thresholds.classify(&stats(120, 40, 5), now), TrustLevel::Normal);
+        assert_eq!(thresholds.classify(&stats(60, 40, 0), now), TrustLevel::Normal);
+    }
+
+    #[test]
+    fn test_trust_biases_only_borderline_verdicts() {
+        let thresholds = TrustThresholds::default();
+        let verdict = |provider, result, confidence| ModerationVerdict {
+            confidence,
+            ..ModerationVerdict::new(provider, result)
+        };
+
+        let held = apply_trust(
+            verdict("ollama", ModerationResult::Falafel, Some(0.5)),
+            TrustLevel::Low,
+            &thresholds,
+            false,
+        );
+        assert_eq!(held.result, ModerationResult::AdminReview);
+        assert_eq!(held.attempts.last().and_then(|a| a.trust_bias), Some("held"));
+        let sure = verdict("ollama", ModerationResult::Falafel, Some(0.9));
+        assert_eq!(apply_trust(sure, TrustLevel::Low, &thresholds, false).result, ModerationResult::Falafel);
+
+        let approved = apply_trust(
+            verdict("openai", ModerationResult::AdminReview, Some(0.6)),
+            TrustLevel::High,
+            &thresholds,
+            true,
+        );
+        assert_eq!(approved.result, ModerationResult::Falafel);
+        assert!(approved.audit_sample);
+        assert_eq!(approved.attempts.last().and_then(|a| a.trust_bias), Some("approved_audit"));
+
+        // Keyword matches, suspected threats and normal-trust authors are left alone
+        for (provider, trust, categories) in [
+            ("keyword", TrustLevel::High, vec![]),
+            ("ollama", TrustLevel::High, vec!["threat".to_string()]),
+            ("ollama", TrustLevel::Normal, vec![]),
+        ] {
+            let mut held = verdict(provider, ModerationResult::AdminReview, None);
+            held.categories = categories;
+            let after = apply_trust(held, trust, &thresholds, false);
+            assert_eq!(after.result, ModerationResult::AdminReview);
+            assert!(after.attempts.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0, 0.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_most_similar_picks_closest_above_threshold() {
+        let (near, nearer, far) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let candidates = vec![
+            (near, vec![0.9, 0.1]),
+            (nearer, vec![0.99, 0.01]),
+            (far, vec![0.0, 1.0]),
+        ];
+
+        let (id, similarity) = most_similar(&[1.0, 0.0], &candidates, 0.9).unwrap();
+        assert_eq!(id, nearer);
+        assert!(similarity > 0.99);
+        assert_eq!(most_similar(&[0.7, 0.7], &candidates, 0.99), None);
+        assert_eq!(most_similar(&[1.0, 0.0], &[], 0.5), None);
     }
 }
 
@@ -0,0 +1,175 @@
+use anyhow::{Context, Result};
+use oauth2::{
+    basic::BasicClient, reqwest::async_http_client, AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken,
+    PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, Scope, TokenResponse, TokenUrl,
+};
+use serde::Deserialize;
+
+pub const GOOGLE: &str = "google";
+
+const GOOGLE_AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GOOGLE_USERINFO_URL: &str = "https://openidconnect.googleapis.com/v1/userinfo";
+
+// Longest username derived from a Google profile, leaving room for a numeric suffix
+const DERIVED_USERNAME_MAX_CHARS: usize = 20;
+
+/// Whether GOOGLE_CLIENT_ID and GOOGLE_CLIENT_SECRET are set, so templates can offer Google sign-in
+pub fn google_configured() -> bool {
+    ["GOOGLE_CLIENT_ID", "GOOGLE_CLIENT_SECRET"]
+        .iter()
+        .all(|name| std::env::var(name).is_ok_and(|v| !v.is_empty()))
+}
+
+/// The parts of a Google account we keep: the stable subject id, and the email if Google has verified it
+#[derive(Debug, Clone)]
+pub struct GoogleProfile {
+    pub subject: String,
+    pub email: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    sub: String,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+/// A started sign-in: where to send the browser, and what to keep until it comes back
+pub struct Authorization {
+    pub url: String,
+    pub csrf_state: String,
+    pub pkce_verifier: String,
+}
+
+/// Authorization-code flow with PKCE against Google's OAuth2 endpoints
+pub struct GoogleOAuth {
+    client: BasicClient,
+    http: reqwest::Client,
+}
+
+impl GoogleOAuth {
+    /// Configured from GOOGLE_CLIENT_ID and GOOGLE_CLIENT_SECRET; the callback is
+    /// `<base_url>/auth/google/callback`, which has to be registered with Google
+    pub fn from_env(base_url: &str, http: reqwest::Client) -> Result<Option<Self>> {
+        if !google_configured() {
+            return Ok(None);
+        }
+
+        let client = BasicClient::new(
+            ClientId::new(std::env::var("GOOGLE_CLIENT_ID")?),
+            Some(ClientSecret::new(std::env::var("GOOGLE_CLIENT_SECRET")?)),
+            AuthUrl::new(GOOGLE_AUTH_URL.to_string())?,
+            Some(TokenUrl::new(GOOGLE_TOKEN_URL.to_string())?),
+        )
+        .set_redirect_uri(
+            RedirectUrl::new(format!("{}/auth/google/callback", base_url)).context("Invalid PUBLIC_BASE_URL")?,
+        );
+
+        Ok(Some(Self { client, http }))
+    }
+
+    pub fn authorize(&self) -> Authorization {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let (url, csrf_state) = self
+            .client
+            .authorize_url(CsrfToken::new_random)
+            .add_scope(Scope::new("openid".to_string()))
+            .add_scope(Scope::new("email".to_string()))
+            .add_scope(Scope::new("profile".to_string()))
+            .set_pkce_challenge(pkce_challenge)
+            .url();
+
+        Authorization {
+            url: url.to_string(),
+            csrf_state: csrf_state.secret().clone(),
+            pkce_verifier: pkce_verifier.secret().clone(),
+        }
+    }
+
+    /// Trade the callback's code for an access token and look up who signed in
+    pub async fn exchange(&self, code: String, pkce_verifier: String) -> Result<GoogleProfile> {
+        let token = self
+            .client
+            .exchange_code(AuthorizationCode::new(code))
+            .set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier))
+            .request_async(async_http_client)
+            .await
+            .context("Failed to exchange the Google authorization code")?;
+
+        let info: UserInfo = self
+            .http
+            .get(GOOGLE_USERINFO_URL)
+            .bearer_auth(token.access_token().secret())
+            .send()
+            .await
+            .context("Failed to reach Google's userinfo endpoint")?
+            .error_for_status()
+            .context("Google rejected the userinfo request")?
+            .json()
+            .await
+            .context("Failed to parse Google's userinfo response")?;
+
+        Ok(GoogleProfile {
+            subject: info.sub,
+            email: info.email.filter(|_| info.email_verified),
+            name: info.name,
+        })
+    }
+}
+
+/// A username to try for a new account: the display name cut down to lowercase letters, digits
+/// and underscores, else "citizen". Never the email, since usernames are public. Callers add a
+/// suffix if it's taken.
+pub fn username_base(profile: &GoogleProfile) -> String {
+    let source = profile.name.as_deref().unwrap_or_default();
+
+    let mut base = String::new();
+    for c in source.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            base.push(c);
+        } else if !base.is_empty() && !base.ends_with('_') {
+            base.push('_');
+        }
+    }
+
+    let base: String = base.trim_end_matches('_').chars().take(DERIVED_USERNAME_MAX_CHARS).collect();
+    let base = base.trim_end_matches('_');
+    if base.len() < 3 {
+        "citizen".to_string()
+    } else {
+        base.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(email: Option<&str>, name: Option<&str>) -> GoogleProfile {
+        GoogleProfile {
+            subject: "1234567890".to_string(),
+            email: email.map(str::to_string),
+            name: name.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_username_base_is_derived_from_name_only() {
+        assert_eq!(username_base(&profile(None, Some("Arjun Rao"))), "arjun_rao");
+        assert_eq!(username_base(&profile(None, Some("अर्जुन"))), "citizen");
+        assert_eq!(
+            username_base(&profile(None, Some("A Very Long Display Name Indeed Yes"))),
+            "a_very_long_display"
+        );
+        // The email never shows up in the username
+        assert_eq!(username_base(&profile(Some("Meera.Iyer+news@gmail.com"), None)), "citizen");
+        assert_eq!(
+            username_base(&profile(Some("Meera.Iyer+news@gmail.com"), Some("Meera I"))),
+            "meera_i"
+        );
+    }
+}
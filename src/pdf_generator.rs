@@ -1,138 +1,314 @@
 use genpdf::elements;
 use genpdf::fonts;
+use genpdf::render;
 use genpdf::style;
-use genpdf::Element;
-use sqlx::PgPool;
-use chrono::{DateTime, Utc};
-
-#[derive(Debug, sqlx::FromRow)]
-pub struct BillSentiment {
-    pub bill_id: uuid::Uuid,
-    pub bill_number: String,
-    pub bill_title: String,
-    pub introduction_date: Option<DateTime<Utc>>,
-    pub support_count: i64,
-    pub oppose_count: i64,
-    pub critique_count: i64,
-}
-
-#[derive(Debug, sqlx::FromRow)]
-pub struct ConstituencyPost {
-    pub bill_id: uuid::Uuid,
-    pub bill_number: String,
-    pub bill_title: String,
-    pub username: String,
-    pub content: String,
-    pub stance: String,
-    pub created_at: DateTime<Utc>,
-    pub upvotes: i32,
-    pub downvotes: i32,
-    pub introduction_date: Option<chrono::NaiveDate>,
-}
-
-pub async fn get_constituency_sentiment(
-    pool: &PgPool,
-    constituency_id: i32,
-) -> Result<Vec<BillSentiment>, sqlx::Error> {
-    sqlx::query_as::<_, BillSentiment>(
-        r#"
-        SELECT 
-            b.id as bill_id,
-            b.bill_number,
-            b.title as bill_title,
-            b.introduction_date,
-            COUNT(CASE WHEN p.stance = 'Support' THEN 1 END) as support_count,
-            COUNT(CASE WHEN p.stance = 'Oppose' THEN 1 END) as oppose_count,
-            COUNT(CASE WHEN p.stance = 'Critique' THEN 1 END) as critique_count
-        FROM bills b
-        INNER JOIN posts p ON b.id = p.bill_id
-        INNER JOIN users u ON p.user_id = u.id
-        WHERE u.constituency_id = $1
-        AND p.moderation_status = 'approved'
-        GROUP BY b.id, b.bill_number, b.title, b.introduction_date
-        HAVING COUNT(p.id) > 0
-        ORDER BY b.introduction_date DESC
-        "#,
-    )
-    .bind(constituency_id)
-    .fetch_all(pool)
-    .await
-}
-
-pub async fn get_constituency_posts(
-    pool: &PgPool,
-    constituency_id: i32,
-) -> Result<Vec<ConstituencyPost>, sqlx::Error> {
-    sqlx::query_as::<_, ConstituencyPost>(
-        r#"
-        SELECT 
-            b.id as bill_id,
-            b.bill_number,
-            b.title as bill_title,
-            b.introduction_date,
-            u.username,
-            p.content,
-            p.stance,
-            p.created_at,
-            p.upvotes,
-            p.downvotes
-        FROM posts p
-        INNER JOIN users u ON p.user_id = u.id
-        INNER JOIN bills b ON p.bill_id = b.id
-        WHERE u.constituency_id = $1
-        AND p.moderation_status = 'approved'
-        ORDER BY b.bill_number, p.created_at DESC
-        "#,
-    )
-    .bind(constituency_id)
-    .fetch_all(pool)
-    .await
+use genpdf::{Element, Position, RenderResult, Size};
+use chrono::{Datelike, NaiveDate, Utc};
+
+use crate::models::{
+    BillConstituencySentiment, BillReportPost, BillSentiment, ConstituencyPost, ConstituencySentiment, DbBill,
+    NationalBillSentiment, NationalTotals, ReportPeriod, StateSentiment,
+};
+
+// Where Noto Sans Devanagari is read from (REPORT_FONT_DIR, default ./fonts)
+fn report_font_dir() -> std::path::PathBuf {
+    std::env::var("REPORT_FONT_DIR").unwrap_or_else(|_| "fonts".to_string()).into()
 }
 
-pub fn generate_constituency_report(
-    constituency_name: &str,
-    state: &str,
-    sentiments: Vec<BillSentiment>,
-    posts: Vec<ConstituencyPost>,
-) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-    // Embed Liberation Sans font (public domain)
-    // Download from: https://github.com/liberationfonts/liberation-fonts/releases
-    let font_data = include_bytes!("../LiberationSans-Regular.ttf");
-    let regular = fonts::FontData::new(font_data.to_vec(), None)?;
-    
+/// Noto Sans Devanagari regular and bold (SIL OFL, see fonts/OFL.txt), read once. None when the
+/// files aren't there, in which case Devanagari is drawn as empty boxes.
+fn devanagari_font_files() -> Option<&'static (Vec<u8>, Vec<u8>)> {
+    static FILES: std::sync::OnceLock<Option<(Vec<u8>, Vec<u8>)>> = std::sync::OnceLock::new();
+    FILES
+        .get_or_init(|| {
+            let dir = report_font_dir();
+            let read = |name: &str| std::fs::read(dir.join(name));
+            match (read("NotoSansDevanagari-Regular.ttf"), read("NotoSansDevanagari-Bold.ttf")) {
+                (Ok(regular), Ok(bold)) => Some((regular, bold)),
+                (Err(e), _) | (_, Err(e)) => {
+                    tracing::warn!(
+                        "Noto Sans Devanagari not found in {} ({}); Hindi in reports will show as boxes",
+                        dir.display(),
+                        e
+                    );
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// Faces a report can set text in besides its default Noto Sans
+#[derive(Clone, Copy, Default)]
+struct ReportFonts {
+    devanagari: Option<fonts::FontFamily<fonts::Font>>,
+}
+
+impl ReportFonts {
+    /// `text` as a paragraph, with runs of Devanagari set in Noto Sans Devanagari when it's loaded
+    fn paragraph(&self, text: &str) -> elements::Paragraph {
+        let Some(devanagari) = self.devanagari else {
+            return elements::Paragraph::new(text);
+        };
+
+        let mut paragraph = elements::Paragraph::default();
+        for (run, is_devanagari) in script_runs(text) {
+            if is_devanagari {
+                paragraph.push_styled(run, style::Style::new().with_font_family(devanagari));
+            } else {
+                paragraph.push(run);
+            }
+        }
+        paragraph
+    }
+}
+
+fn is_devanagari(c: char) -> bool {
+    matches!(c, '\u{0900}'..='\u{097F}' | '\u{A8E0}'..='\u{A8FF}' | '\u{1CD0}'..='\u{1CFF}')
+}
+
+/// Splits `text` into runs that are Devanagari or not, as (run, is_devanagari). Whitespace and
+/// joiners stay in the run they follow, so a line of Hindi words is one run.
+fn script_runs(text: &str) -> Vec<(&str, bool)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut current: Option<bool> = None;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() || c == '\u{200C}' || c == '\u{200D}' {
+            continue;
+        }
+        let devanagari = is_devanagari(c);
+        match current {
+            Some(script) if script != devanagari => {
+                runs.push((&text[start..i], script));
+                start = i;
+                current = Some(devanagari);
+            }
+            Some(_) => {}
+            None => current = Some(devanagari),
+        }
+    }
+    if start < text.len() {
+        runs.push((&text[start..], current.unwrap_or(false)));
+    }
+    runs
+}
+
+// A document in the report font with the usual margins and `header` atop every page, along with
+// the other faces its text can be set in
+fn new_document(title: String, header: String) -> Result<(genpdf::Document, ReportFonts), Box<dyn std::error::Error>> {
+    // Embed Noto Sans (SIL OFL, see fonts/OFL.txt) so text beyond WinAnsi
+    // is written with real glyphs rather than mangled
+    let regular = fonts::FontData::new(include_bytes!("../fonts/NotoSans-Regular.ttf").to_vec(), None)?;
+    let bold = fonts::FontData::new(include_bytes!("../fonts/NotoSans-Bold.ttf").to_vec(), None)?;
+
     let font_family = fonts::FontFamily {
         regular: regular.clone(),
-        bold: regular.clone(),
-        italic: regular.clone(),
-        bold_italic: regular,
+        bold: bold.clone(),
+        italic: regular,
+        bold_italic: bold,
     };
-    
-    // Create document
+
     let mut doc = genpdf::Document::new(font_family);
-    doc.set_title(format!("Constituency Report: {} ({})", constituency_name, state));
+    doc.set_title(title);
     doc.set_minimal_conformance();
     doc.set_line_spacing(1.25);
-    
+
+    // Hindi in posts and bill titles is set in Noto Sans Devanagari, as genpdf can't fall back
+    // to another font for glyphs Noto Sans lacks
+    let mut report_fonts = ReportFonts::default();
+    if let Some((regular, bold)) = devanagari_font_files() {
+        let regular = fonts::FontData::new(regular.clone(), None)?;
+        let bold = fonts::FontData::new(bold.clone(), None)?;
+        report_fonts.devanagari = Some(doc.add_font_family(fonts::FontFamily {
+            regular: regular.clone(),
+            bold: bold.clone(),
+            italic: regular,
+            bold_italic: bold,
+        }));
+    }
+
     // Set page margins
     let mut decorator = genpdf::SimplePageDecorator::new();
     decorator.set_margins(10);
+    decorator.set_header(move |_page| {
+        report_fonts
+            .paragraph(&header)
+            .styled(style::Style::new().with_font_size(8))
+            .padded(genpdf::Margins::trbl(0, 0, 4, 0))
+    });
     doc.set_page_decorator(decorator);
+    Ok((doc, report_fonts))
+}
+
+// Name of the footer font in each page's resources
+const FOOTER_FONT: &[u8] = b"FReportFooter";
+
+/// Renders `doc`, then goes over the finished pages to stamp "Page N of M" and the generation
+/// time at the foot of each, as the page count isn't known until everything has been laid out
+fn render_with_footers(doc: genpdf::Document, generated: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    use lopdf::content::{Content, Operation};
+    use lopdf::{dictionary, Object};
+
+    let mut buffer = Vec::new();
+    doc.render(&mut buffer)?;
+
+    let mut pdf = lopdf::Document::load_mem(&buffer)?;
+    // The footer is plain ASCII, so one of the standard fonts does without embedding anything
+    let font_id = pdf.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+
+    let pages = pdf.get_pages();
+    let total = pages.len();
+    for (number, page_id) in pages {
+        let text = format!("Page {} of {}  |  Generated {}", number, total, generated);
+        let content = Content {
+            operations: vec![
+                Operation::new("q", vec![]),
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec![Object::Name(FOOTER_FONT.to_vec()), 8.into()]),
+                // About 10 mm in from the left and up from the bottom, matching the margins
+                Operation::new("Td", vec![28.into(), 20.into()]),
+                Operation::new("Tj", vec![Object::string_literal(text)]),
+                Operation::new("ET", vec![]),
+                Operation::new("Q", vec![]),
+            ],
+        };
+        pdf.add_page_contents(page_id, content.encode()?)?;
+
+        // Register the font with the page, wherever its font dictionary lives
+        let shared_fonts = {
+            let resources = pdf.get_or_create_resources(page_id)?.as_dict_mut()?;
+            match resources.get(b"Font") {
+                Ok(Object::Reference(id)) => Some(*id),
+                Ok(_) => None,
+                Err(_) => {
+                    resources.set("Font", lopdf::Dictionary::new());
+                    None
+                }
+            }
+        };
+        let fonts = match shared_fonts {
+            Some(id) => pdf.get_object_mut(id)?.as_dict_mut()?,
+            None => pdf.get_or_create_resources(page_id)?.as_dict_mut()?.get_mut(b"Font")?.as_dict_mut()?,
+        };
+        fonts.set(FOOTER_FONT, font_id);
+    }
+
+    let mut out = Vec::new();
+    pdf.save_to(&mut out)?;
+    Ok(out)
+}
+
+pub fn generate_constituency_report(
+    constituency_name: &str,
+    state: &str,
+    period: &ReportPeriod,
+    ai_summary: Option<&str>,
+    sentiments: Vec<BillSentiment>,
+    posts: Vec<ConstituencyPost>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let generated = Utc::now().format("%Y-%m-%d %H:%M UTC").to_string();
+    let (mut doc, report_fonts) = new_document(
+        format!("Constituency Report: {} ({})", constituency_name, state),
+        format!("{}, {} | {}", constituency_name, state, period.describe()),
+    )?;
     
     // Title
     doc.push(
-        elements::Paragraph::new(format!("Constituency Report: {} ({})", constituency_name, state))
+        report_fonts.paragraph(&format!("Constituency Report: {} ({})", constituency_name, state))
             .styled(style::Style::new().bold().with_font_size(18))
     );
     
     doc.push(elements::Break::new(0.5));
     
     doc.push(
-        elements::Paragraph::new(format!("Generated: {}", Utc::now().format("%Y-%m-%d %H:%M UTC")))
+        elements::Paragraph::new(format!("Generated: {}", generated))
             .styled(style::Style::new().with_font_size(10))
     );
     
+    doc.push(
+        elements::Paragraph::new(format!("Period: {}", period.describe()))
+            .styled(style::Style::new().with_font_size(10))
+    );
+    
+    doc.push(elements::Break::new(1.0));
+    
+    // What constituents think, in the LLM's words; left out when it couldn't be written
+    if let Some(text) = ai_summary {
+        doc.push(
+            elements::Paragraph::new("What Your Constituents Think")
+                .styled(style::Style::new().bold().with_font_size(14))
+        );
+        doc.push(
+            elements::Paragraph::new("AI-generated summary of the most upvoted posts; check it against the posts below.")
+                .styled(style::Style::new().italic().with_font_size(9))
+        );
+        doc.push(elements::Break::new(0.5));
+        for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+            doc.push(
+                report_fonts.paragraph(&paragraph.replace('\n', " "))
+                    .styled(style::Style::new().with_font_size(10))
+            );
+            doc.push(elements::Break::new(0.5));
+        }
+        doc.push(elements::Break::new(0.5));
+    }
+    
+    if posts.is_empty() {
+        doc.push(
+            elements::Paragraph::new("No posts in this period.")
+                .styled(style::Style::new().with_font_size(11))
+        );
+        
+        return render_with_footers(doc, &generated);
+    }
+    
+    // Overview page: overall stance split and how many posts came in each week
+    doc.push(
+        elements::Paragraph::new("Overview")
+            .styled(style::Style::new().bold().with_font_size(14))
+    );
+    
+    doc.push(elements::Break::new(0.5));
+    
+    let stance_totals = [
+        sentiments.iter().map(|s| s.support_count).sum::<i64>(),
+        sentiments.iter().map(|s| s.oppose_count).sum::<i64>(),
+        sentiments.iter().map(|s| s.critique_count).sum::<i64>(),
+    ];
+    push_stance_pie(&mut doc, stance_totals);
+    
     doc.push(elements::Break::new(1.0));
     
+    let weeks = weekly_post_counts(&posts);
+    doc.push(
+        elements::Paragraph::new("Posts per Week")
+            .styled(style::Style::new().bold().with_font_size(12))
+    );
+    doc.push(BarChart {
+        values: weeks.iter().map(|&(_, n)| n as f64).collect(),
+        color: style::Color::Rgb(60, 90, 160),
+    });
+    if let (Some((first, _)), Some((last, _))) = (weeks.first(), weeks.last()) {
+        let peak = weeks.iter().map(|&(_, n)| n).max().unwrap_or(0);
+        doc.push(
+            elements::Paragraph::new(format!(
+                "Weeks starting {} to {}; busiest week had {} post(s)",
+                first.format("%d %b %Y"),
+                last.format("%d %b %Y"),
+                peak
+            ))
+            .styled(style::Style::new().with_font_size(9))
+        );
+    }
+    
+    doc.push(elements::PageBreak::new());
+    
     // Summary section
     doc.push(
         elements::Paragraph::new("Summary of Constituency Stance")
@@ -143,14 +319,10 @@ pub fn generate_constituency_report(
     
     // Sentiment bars for each bill
     for sentiment in &sentiments {
-        let title_text = if sentiment.bill_title.len() > 70 {
-            format!("{}...", &sentiment.bill_title[..70])
-        } else {
-            sentiment.bill_title.clone()
-        };
+        let title_text = truncate_title(&sentiment.bill_title, 70);
         
         doc.push(
-            elements::Paragraph::new(format!("{}: {}", sentiment.bill_number, title_text))
+            report_fonts.paragraph(&format!("{}: {}", sentiment.bill_number, title_text))
                 .styled(style::Style::new().bold().with_font_size(11))
         );
         
@@ -206,14 +378,10 @@ pub fn generate_constituency_report(
             
             doc.push(elements::Break::new(0.5));
             
-            let title_text = if post.bill_title.len() > 80 {
-                format!("{}...", &post.bill_title[..80])
-            } else {
-                post.bill_title.clone()
-            };
+            let title_text = truncate_title(&post.bill_title, 80);
             
             doc.push(
-                elements::Paragraph::new(format!("{}: {}", post.bill_number, title_text))
+                report_fonts.paragraph(&format!("{}: {}", post.bill_number, title_text))
                     .styled(style::Style::new().bold().with_font_size(12))
             );
             
@@ -222,7 +390,7 @@ pub fn generate_constituency_report(
         
         // Post header
         doc.push(
-            elements::Paragraph::new(format!(
+            report_fonts.paragraph(&format!(
                 "@{} - {} (↑{} ↓{})",
                 post.username,
                 post.stance.to_uppercase(),
@@ -234,15 +402,1019 @@ pub fn generate_constituency_report(
         
         // Post content
         doc.push(
-            elements::Paragraph::new(format!("  {}", post.content))
+            report_fonts.paragraph(&format!("  {}", post.content))
                 .styled(style::Style::new().with_font_size(9))
         );
         
         doc.push(elements::Break::new(0.3));
     }
     
-    // Render to bytes
-    let mut buffer = Vec::new();
-    doc.render(&mut buffer)?;
-    Ok(buffer)
+    render_with_footers(doc, &generated)
+}
+
+/// A bill's reception broken down by constituency: overall totals, a table of stance counts for
+/// each constituency with posts on it, and the most upvoted posts for each stance
+pub fn generate_bill_report(
+    bill: &DbBill,
+    rows: Vec<ConstituencySentiment>,
+    posts: Vec<BillReportPost>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let generated = Utc::now().format("%Y-%m-%d %H:%M UTC").to_string();
+    let (mut doc, report_fonts) = new_document(
+        format!("Bill Report: {}", bill.bill_number),
+        format!("Bill {}: {} | By constituency", bill.bill_number, truncate_title(&bill.title, 80)),
+    )?;
+    
+    doc.push(
+        report_fonts.paragraph(&format!("Bill Report: {}: {}", bill.bill_number, truncate_title(&bill.title, 120)))
+            .styled(style::Style::new().bold().with_font_size(18))
+    );
+    
+    doc.push(elements::Break::new(0.5));
+    
+    doc.push(
+        elements::Paragraph::new(format!("Generated: {}", generated))
+            .styled(style::Style::new().with_font_size(10))
+    );
+    
+    if let Some(date) = bill.introduction_date {
+        doc.push(
+            elements::Paragraph::new(format!("Introduced: {}", date.format("%d %B %Y")))
+                .styled(style::Style::new().with_font_size(10))
+        );
+    }
+    
+    doc.push(elements::Break::new(1.0));
+    
+    if rows.is_empty() {
+        doc.push(
+            elements::Paragraph::new("No constituents have posted on this bill yet.")
+                .styled(style::Style::new().with_font_size(11))
+        );
+        
+        return render_with_footers(doc, &generated);
+    }
+    
+    // Overall totals
+    let support: i64 = rows.iter().map(|r| r.support_count).sum();
+    let oppose: i64 = rows.iter().map(|r| r.oppose_count).sum();
+    let critique: i64 = rows.iter().map(|r| r.critique_count).sum();
+    let total = support + oppose + critique;
+    let (support_pct, oppose_pct, critique_pct) = crate::summary::stance_percentages(support, oppose, critique);
+    
+    doc.push(
+        elements::Paragraph::new("Overall")
+            .styled(style::Style::new().bold().with_font_size(14))
+    );
+    
+    doc.push(
+        elements::Paragraph::new(format!(
+            "{} posts from {} constituencies: Support {} ({}%) | Oppose {} ({}%) | Critique {} ({}%)",
+            total, rows.len(), support, support_pct, oppose, oppose_pct, critique, critique_pct
+        ))
+        .styled(style::Style::new().with_font_size(10))
+    );
+    
+    doc.push(elements::Break::new(1.0));
+    
+    // Per-constituency table
+    doc.push(
+        elements::Paragraph::new("Stance by Constituency")
+            .styled(style::Style::new().bold().with_font_size(14))
+    );
+    
+    doc.push(elements::Break::new(0.5));
+    
+    let cell = |text: String, style: style::Style| elements::Paragraph::new(text).styled(style).padded(1);
+    let header = style::Style::new().bold().with_font_size(9);
+    let body = style::Style::new().with_font_size(9);
+    
+    let mut table = elements::TableLayout::new(vec![4, 1, 1, 1, 1]);
+    table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+    let mut heading = table.row();
+    for title in ["Constituency", "Support", "Oppose", "Critique", "Total"] {
+        heading.push_element(cell(title.to_string(), header));
+    }
+    heading.push()?;
+    for row in &rows {
+        table
+            .row()
+            .element(cell(format!("{} ({})", row.constituency_name, row.state), body))
+            .element(cell(row.support_count.to_string(), body))
+            .element(cell(row.oppose_count.to_string(), body))
+            .element(cell(row.critique_count.to_string(), body))
+            .element(cell((row.support_count + row.oppose_count + row.critique_count).to_string(), body))
+            .push()?;
+    }
+    table
+        .row()
+        .element(cell("All constituencies".to_string(), header))
+        .element(cell(support.to_string(), header))
+        .element(cell(oppose.to_string(), header))
+        .element(cell(critique.to_string(), header))
+        .element(cell(total.to_string(), header))
+        .push()?;
+    doc.push(table);
+    
+    doc.push(elements::Break::new(1.0));
+    
+    // Top posts from each side
+    doc.push(
+        elements::Paragraph::new("Most Upvoted Posts")
+            .styled(style::Style::new().bold().with_font_size(14))
+    );
+    
+    for stance in ["Support", "Oppose", "Critique"] {
+        let stance_posts: Vec<&BillReportPost> = posts.iter().filter(|p| p.stance == stance).collect();
+        if stance_posts.is_empty() {
+            continue;
+        }
+        
+        doc.push(elements::Break::new(0.5));
+        doc.push(
+            elements::Paragraph::new(stance)
+                .styled(style::Style::new().bold().with_font_size(12))
+        );
+        doc.push(elements::Break::new(0.3));
+        
+        for post in stance_posts {
+            doc.push(
+                report_fonts.paragraph(&format!(
+                    "@{}, {} (↑{} ↓{})",
+                    post.username,
+                    post.constituency_name.as_deref().unwrap_or("no constituency"),
+                    post.upvotes,
+                    post.downvotes
+                ))
+                .styled(style::Style::new().bold().with_font_size(9))
+            );
+            
+            doc.push(
+                report_fonts.paragraph(&format!("  {}", post.content))
+                    .styled(style::Style::new().with_font_size(9))
+            );
+            
+            doc.push(elements::Break::new(0.3));
+        }
+    }
+    
+    render_with_footers(doc, &generated)
+}
+
+// Constituencies named under each bill in the national report, for each side
+const NATIONAL_TOP_CONSTITUENCIES: usize = 3;
+
+/// Sentiment on every bill with posts across the country: participation totals, a per-state table,
+/// and for each bill its stance counts and the constituencies most for and against it
+pub fn generate_national_report(
+    totals: &NationalTotals,
+    bills: Vec<NationalBillSentiment>,
+    states: Vec<StateSentiment>,
+    constituencies: Vec<BillConstituencySentiment>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let generated = Utc::now().format("%Y-%m-%d %H:%M UTC").to_string();
+    let (mut doc, report_fonts) = new_document("National Report".to_string(), "National Report | All constituencies".to_string())?;
+    
+    doc.push(
+        elements::Paragraph::new("National Report: Sentiment on Active Bills")
+            .styled(style::Style::new().bold().with_font_size(18))
+    );
+    
+    doc.push(elements::Break::new(0.5));
+    
+    doc.push(
+        elements::Paragraph::new(format!("Generated: {}", generated))
+            .styled(style::Style::new().with_font_size(10))
+    );
+    
+    doc.push(elements::Break::new(1.0));
+    
+    if bills.is_empty() {
+        doc.push(
+            elements::Paragraph::new("No posts on any bill yet.")
+                .styled(style::Style::new().with_font_size(11))
+        );
+        return render_with_footers(doc, &generated);
+    }
+    
+    // Participation and the overall split
+    doc.push(
+        elements::Paragraph::new("Overview")
+            .styled(style::Style::new().bold().with_font_size(14))
+    );
+    
+    doc.push(
+        elements::Paragraph::new(format!(
+            "{} posts on {} bills from {} citizens in {} constituencies",
+            totals.post_count,
+            bills.len(),
+            totals.participant_count,
+            totals.constituency_count
+        ))
+        .styled(style::Style::new().with_font_size(10))
+    );
+    
+    doc.push(elements::Break::new(0.5));
+    
+    push_stance_pie(&mut doc, [
+        bills.iter().map(|b| b.support_count).sum(),
+        bills.iter().map(|b| b.oppose_count).sum(),
+        bills.iter().map(|b| b.critique_count).sum(),
+    ]);
+    
+    doc.push(elements::Break::new(1.0));
+    
+    // Per-state table
+    if !states.is_empty() {
+        doc.push(
+            elements::Paragraph::new("By State")
+                .styled(style::Style::new().bold().with_font_size(14))
+        );
+        
+        doc.push(elements::Break::new(0.5));
+        
+        let cell = |text: String, style: style::Style| elements::Paragraph::new(text).styled(style).padded(1);
+        let header = style::Style::new().bold().with_font_size(9);
+        let body = style::Style::new().with_font_size(9);
+        
+        let mut table = elements::TableLayout::new(vec![4, 1, 1, 1, 1, 1]);
+        table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+        let mut heading = table.row();
+        for title in ["State", "Support", "Oppose", "Critique", "Total", "Citizens"] {
+            heading.push_element(cell(title.to_string(), header));
+        }
+        heading.push()?;
+        for state in &states {
+            table
+                .row()
+                .element(cell(state.state.clone(), body))
+                .element(cell(state.support_count.to_string(), body))
+                .element(cell(state.oppose_count.to_string(), body))
+                .element(cell(state.critique_count.to_string(), body))
+                .element(cell((state.support_count + state.oppose_count + state.critique_count).to_string(), body))
+                .element(cell(state.participant_count.to_string(), body))
+                .push()?;
+        }
+        doc.push(table);
+    }
+    
+    doc.push(elements::PageBreak::new());
+    
+    // One section per bill
+    doc.push(
+        elements::Paragraph::new("Bills")
+            .styled(style::Style::new().bold().with_font_size(14))
+    );
+    
+    for bill in &bills {
+        doc.push(elements::Break::new(0.5));
+        
+        doc.push(
+            report_fonts.paragraph(&format!("{}: {}", bill.bill_number, truncate_title(&bill.bill_title, 80)))
+                .styled(style::Style::new().bold().with_font_size(11))
+        );
+        
+        let (support_pct, oppose_pct, critique_pct) =
+            crate::summary::stance_percentages(bill.support_count, bill.oppose_count, bill.critique_count);
+        doc.push(
+            elements::Paragraph::new(format!(
+                "  Support {} ({}%) | Oppose {} ({}%) | Critique {} ({}%)",
+                bill.support_count, support_pct, bill.oppose_count, oppose_pct, bill.critique_count, critique_pct
+            ))
+            .styled(style::Style::new().with_font_size(9))
+        );
+        
+        doc.push(
+            elements::Paragraph::new(format!(
+                "  {} citizens from {} constituencies",
+                bill.participant_count, bill.constituency_count
+            ))
+            .styled(style::Style::new().with_font_size(9))
+        );
+        
+        let rows: Vec<&BillConstituencySentiment> =
+            constituencies.iter().filter(|c| c.bill_id == bill.bill_id).collect();
+        for (label, leaning) in [
+            ("Most supportive", top_constituencies(&rows, |c| c.support_count)),
+            ("Most opposed", top_constituencies(&rows, |c| c.oppose_count)),
+        ] {
+            if leaning.is_empty() {
+                continue;
+            }
+            let names = leaning
+                .iter()
+                .map(|(c, pct)| format!("{} ({}, {}%)", c.constituency_name, c.state, pct))
+                .collect::<Vec<_>>()
+                .join("; ");
+            doc.push(
+                elements::Paragraph::new(format!("  {}: {}", label, names))
+                    .styled(style::Style::new().with_font_size(9))
+            );
+        }
+    }
+    
+    render_with_footers(doc, &generated)
+}
+
+// The constituencies where `count` makes up the largest share of posts, with that share as a whole
+// percentage; more posts breaks ties. Constituencies where it is zero are left out.
+fn top_constituencies<'a>(
+    rows: &[&'a BillConstituencySentiment],
+    count: impl Fn(&BillConstituencySentiment) -> i64,
+) -> Vec<(&'a BillConstituencySentiment, i64)> {
+    let mut ranked: Vec<(&BillConstituencySentiment, i64, i64)> = rows
+        .iter()
+        .filter(|c| count(c) > 0)
+        .map(|&c| {
+            let total = c.support_count + c.oppose_count + c.critique_count;
+            (c, count(c) * 100 / total, count(c))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+    ranked
+        .into_iter()
+        .take(NATIONAL_TOP_CONSTITUENCIES)
+        .map(|(c, pct, _)| (c, pct))
+        .collect()
+}
+
+// Support, oppose and critique, as in the report's bar text
+const STANCE_COLORS: [style::Color; 3] = [
+    style::Color::Rgb(46, 160, 67),
+    style::Color::Rgb(207, 34, 46),
+    style::Color::Rgb(212, 167, 44),
+];
+
+const PIE_RADIUS_MM: f64 = 25.0;
+const BAR_CHART_WIDTH_MM: f64 = 170.0;
+const BAR_CHART_HEIGHT_MM: f64 = 40.0;
+
+// The stance pie with a legend of counts and shares beneath it
+fn push_stance_pie(doc: &mut genpdf::Document, stance_totals: [i64; 3]) {
+    let all_posts: i64 = stance_totals.iter().sum();
+    doc.push(PieChart {
+        slices: stance_totals.iter().zip(STANCE_COLORS).map(|(&n, c)| (n as f64, c)).collect(),
+    });
+    
+    let mut legend = elements::Paragraph::default();
+    for ((label, count), color) in ["Support", "Oppose", "Critique"].iter().zip(stance_totals).zip(STANCE_COLORS) {
+        let pct = if all_posts > 0 { count as f64 / all_posts as f64 * 100.0 } else { 0.0 };
+        legend.push_styled(
+            format!("{}: {} ({:.0}%)    ", label, count, pct),
+            style::Style::new().bold().with_font_size(10).with_color(color),
+        );
+    }
+    doc.push(legend);
+}
+
+/// Pie chart of `slices` (value and colour), clockwise from twelve o'clock
+struct PieChart {
+    slices: Vec<(f64, style::Color)>,
+}
+
+impl Element for PieChart {
+    fn render(
+        &mut self,
+        _context: &genpdf::Context,
+        area: render::Area<'_>,
+        _style: style::Style,
+    ) -> Result<RenderResult, genpdf::error::Error> {
+        render_chart(&area, PIE_RADIUS_MM * 2.0, || {
+            draw_pie(&area, (PIE_RADIUS_MM, PIE_RADIUS_MM), PIE_RADIUS_MM, &self.slices)
+        })
+    }
+}
+
+/// Column chart of `values`, scaled so the largest reaches the top
+struct BarChart {
+    values: Vec<f64>,
+    color: style::Color,
+}
+
+impl Element for BarChart {
+    fn render(
+        &mut self,
+        _context: &genpdf::Context,
+        area: render::Area<'_>,
+        _style: style::Style,
+    ) -> Result<RenderResult, genpdf::error::Error> {
+        render_chart(&area, BAR_CHART_HEIGHT_MM, || {
+            draw_bars(
+                &area,
+                (0.0, BAR_CHART_HEIGHT_MM),
+                BAR_CHART_WIDTH_MM,
+                BAR_CHART_HEIGHT_MM,
+                &self.values,
+                self.color,
+            )
+        })
+    }
+}
+
+// Draws a chart `height` mm tall, or asks for the next page if it doesn't fit in what's left
+fn render_chart(area: &render::Area<'_>, height: f64, draw: impl FnOnce()) -> Result<RenderResult, genpdf::error::Error> {
+    let mut result = RenderResult::default();
+    // A little room below so following text doesn't touch the chart
+    let needed = height + 2.0;
+    if area.size().height < genpdf::Mm::from(needed) {
+        result.has_more = true;
+        return Ok(result);
+    }
+    draw();
+    result.size = Size::new(area.size().width, needed);
+    Ok(result)
+}
+
+/// Start and end angle in degrees, clockwise from twelve o'clock, of each value's slice. Empty
+/// when there is nothing to show.
+fn pie_angles(values: &[f64]) -> Vec<(f64, f64)> {
+    let total: f64 = values.iter().filter(|v| **v > 0.0).sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    let mut start = 0.0;
+    values
+        .iter()
+        .map(|&v| {
+            let end = start + v.max(0.0) / total * 360.0;
+            let slice = (start, end);
+            start = end;
+            slice
+        })
+        .collect()
+}
+
+/// Fills each slice of a pie centred on `center` (mm from the area's top left) with closely
+/// spaced radial strokes, as genpdf can only draw lines
+fn draw_pie(area: &render::Area<'_>, center: (f64, f64), radius: f64, slices: &[(f64, style::Color)]) {
+    let values: Vec<f64> = slices.iter().map(|(v, _)| *v).collect();
+    for ((start, end), (_, color)) in pie_angles(&values).into_iter().zip(slices) {
+        let line = style::LineStyle::new().with_thickness(0.6).with_color(*color);
+        let mut angle = start;
+        while angle < end {
+            let (x, y) = pie_point(center, radius, angle);
+            area.draw_line(
+                vec![Position::new(center.0, center.1), Position::new(x, y)],
+                line,
+            );
+            angle += 0.5;
+        }
+    }
+}
+
+// The point on the rim at `angle` degrees clockwise from twelve o'clock; y grows downwards
+fn pie_point(center: (f64, f64), radius: f64, angle: f64) -> (f64, f64) {
+    let radians = angle.to_radians();
+    (center.0 + radius * radians.sin(), center.1 - radius * radians.cos())
+}
+
+/// Centre x, width and height of each column in a chart `width` by `height` mm, with the
+/// largest value reaching the full height and a gap between columns
+fn bar_layout(values: &[f64], width: f64, height: f64) -> Vec<(f64, f64, f64)> {
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    if values.is_empty() || max <= 0.0 {
+        return Vec::new();
+    }
+    let slot = width / values.len() as f64;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| (slot * (i as f64 + 0.5), slot * 0.8, v.max(0.0) / max * height))
+        .collect()
+}
+
+/// Draws a column chart on a baseline at `origin` (its bottom left, mm from the area's top left),
+/// each column one stroke as wide as the column
+fn draw_bars(
+    area: &render::Area<'_>,
+    origin: (f64, f64),
+    width: f64,
+    height: f64,
+    values: &[f64],
+    color: style::Color,
+) {
+    let (left, bottom) = origin;
+    for (x, bar_width, bar_height) in bar_layout(values, width, height) {
+        if bar_height <= 0.0 {
+            continue;
+        }
+        area.draw_line(
+            vec![Position::new(left + x, bottom), Position::new(left + x, bottom - bar_height)],
+            style::LineStyle::new().with_thickness(bar_width).with_color(color),
+        );
+    }
+    area.draw_line(
+        vec![Position::new(left, bottom), Position::new(left + width, bottom)],
+        style::LineStyle::new().with_thickness(0.3),
+    );
+}
+
+/// Posts per week, keyed by the Monday starting it, with every week from the first post's to the
+/// last post's included even when it had none
+fn weekly_post_counts(posts: &[ConstituencyPost]) -> Vec<(NaiveDate, usize)> {
+    let week_of = |p: &ConstituencyPost| {
+        let day = p.created_at.date_naive();
+        day - chrono::Duration::days(day.weekday().num_days_from_monday() as i64)
+    };
+    let (Some(first), Some(last)) = (posts.iter().map(week_of).min(), posts.iter().map(week_of).max()) else {
+        return Vec::new();
+    };
+    let mut weeks = Vec::new();
+    let mut week = first;
+    while week <= last {
+        weeks.push((week, posts.iter().filter(|p| week_of(p) == week).count()));
+        week += chrono::Duration::weeks(1);
+    }
+    weeks
+}
+
+/// A constituency report as two CSV files, for analysts who want to pivot the data themselves
+pub struct ConstituencyCsv {
+    /// One row per bill with its stance counts
+    pub sentiment: String,
+    /// One row per post, in the same order as the PDF lists them
+    pub posts: String,
+}
+
+pub fn generate_constituency_csv(
+    sentiments: &[BillSentiment],
+    posts: &[ConstituencyPost],
+) -> Result<ConstituencyCsv, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "bill_id", "bill_number", "bill_title", "introduction_date", "support", "oppose", "critique", "total",
+    ])?;
+    for s in sentiments {
+        writer.write_record([
+            s.bill_id.to_string(),
+            s.bill_number.clone(),
+            csv_text(&s.bill_title),
+            s.introduction_date.map(|d| d.to_string()).unwrap_or_default(),
+            s.support_count.to_string(),
+            s.oppose_count.to_string(),
+            s.critique_count.to_string(),
+            (s.support_count + s.oppose_count + s.critique_count).to_string(),
+        ])?;
+    }
+    let sentiment = String::from_utf8(writer.into_inner()?)?;
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "bill_id", "bill_number", "bill_title", "username", "stance", "content", "upvotes", "downvotes", "created_at",
+    ])?;
+    for p in posts {
+        writer.write_record([
+            p.bill_id.to_string(),
+            p.bill_number.clone(),
+            csv_text(&p.bill_title),
+            csv_text(&p.username),
+            p.stance.clone(),
+            csv_text(&p.content),
+            p.upvotes.to_string(),
+            p.downvotes.to_string(),
+            p.created_at.to_rfc3339(),
+        ])?;
+    }
+    let posts = String::from_utf8(writer.into_inner()?)?;
+
+    Ok(ConstituencyCsv { sentiment, posts })
+}
+
+/// Text for a CSV cell that spreadsheets won't run as a formula: anything starting with
+/// `=`, `+`, `-`, `@`, a tab or a carriage return gets a leading `'`.
+fn csv_text(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Shortens `title` to at most `max_chars` characters, counting chars rather
+/// than bytes so Hindi titles are never cut mid-character.
+fn truncate_title(title: &str, max_chars: usize) -> String {
+    match title.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &title[..end]),
+        None => title.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_text(content: &[u8]) -> bool {
+        content.windows(2).any(|op| op == b"Tj" || op == b"TJ")
+    }
+
+    #[test]
+    fn test_long_report_flows_onto_later_pages() {
+        let bill_id = uuid::Uuid::new_v4();
+        let sentiments = vec![BillSentiment {
+            bill_id,
+            bill_number: "42".to_string(),
+            bill_title: "The Test Bill".to_string(),
+            introduction_date: None,
+            support_count: 40,
+            oppose_count: 35,
+            critique_count: 25,
+        }];
+        let posts = (0..100)
+            .map(|i| ConstituencyPost {
+                bill_id,
+                bill_number: "42".to_string(),
+                bill_title: "The Test Bill".to_string(),
+                username: format!("citizen{}", i),
+                content: "This clause needs clearer safeguards for small traders. ".repeat(3),
+                stance: "critique".to_string(),
+                created_at: Utc::now(),
+                upvotes: i,
+                downvotes: 0,
+                introduction_date: None,
+            })
+            .collect();
+
+        let period = ReportPeriod::default();
+        let pdf = generate_constituency_report("Test Nagar", "Test State", &period, Some("Most posts ask for safeguards.\n\nTraders lead the critique."), sentiments, posts).unwrap();
+        let doc = lopdf::Document::load_mem(&pdf).unwrap();
+        let pages = doc.get_pages();
+        assert!(pages.len() > 1, "{} page(s)", pages.len());
+        // Every page, the last included, carries its own share of the posts
+        for (number, id) in pages {
+            let content = doc.get_page_content(id).unwrap();
+            assert!(has_text(&content), "page {} is blank", number);
+        }
+    }
+
+    fn test_bill() -> DbBill {
+        DbBill {
+            id: uuid::Uuid::new_v4(),
+            title: "The Test Bill".to_string(),
+            bill_number: "42".to_string(),
+            year: 2026,
+            session: None,
+            status: None,
+            introduction_date: NaiveDate::from_ymd_opt(2026, 2, 1),
+            pdf_url: None,
+            extracted_text: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_bill_report_lists_constituencies_and_top_posts() {
+        let rows = vec![
+            ConstituencySentiment {
+                constituency_id: 1,
+                constituency_name: "Test Nagar".to_string(),
+                state: "Test State".to_string(),
+                support_count: 3,
+                oppose_count: 1,
+                critique_count: 0,
+            },
+            ConstituencySentiment {
+                constituency_id: 2,
+                constituency_name: "Other Pur".to_string(),
+                state: "Test State".to_string(),
+                support_count: 0,
+                oppose_count: 2,
+                critique_count: 1,
+            },
+        ];
+        let posts = ["Support", "Oppose", "Critique"]
+            .iter()
+            .map(|stance| BillReportPost {
+                constituency_name: Some("Test Nagar".to_string()),
+                username: "citizen".to_string(),
+                content: format!("A {} post", stance),
+                stance: stance.to_string(),
+                upvotes: 4,
+                downvotes: 1,
+                created_at: Utc::now(),
+            })
+            .collect();
+
+        let pdf = generate_bill_report(&test_bill(), rows, posts).unwrap();
+        let doc = lopdf::Document::load_mem(&pdf).unwrap();
+        assert!(!doc.get_pages().is_empty());
+    }
+
+    #[test]
+    fn test_bill_report_without_posts() {
+        let pdf = generate_bill_report(&test_bill(), Vec::new(), Vec::new()).unwrap();
+        let doc = lopdf::Document::load_mem(&pdf).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+    }
+
+    fn constituency_row(bill_id: uuid::Uuid, name: &str, support: i64, oppose: i64) -> BillConstituencySentiment {
+        BillConstituencySentiment {
+            bill_id,
+            constituency_name: name.to_string(),
+            state: "Test State".to_string(),
+            support_count: support,
+            oppose_count: oppose,
+            critique_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_top_constituencies_rank_by_share() {
+        let bill_id = uuid::Uuid::new_v4();
+        let rows = vec![
+            constituency_row(bill_id, "Big", 60, 40),
+            constituency_row(bill_id, "Small", 3, 0),
+            constituency_row(bill_id, "Even", 5, 5),
+            constituency_row(bill_id, "Against", 0, 9),
+            constituency_row(bill_id, "Tied", 6, 0),
+        ];
+        let rows: Vec<&BillConstituencySentiment> = rows.iter().collect();
+
+        let supportive = top_constituencies(&rows, |c| c.support_count);
+        let names: Vec<(&str, i64)> = supportive.iter().map(|(c, pct)| (c.constituency_name.as_str(), *pct)).collect();
+        // A full share beats a large count, and more posts breaks the tie
+        assert_eq!(names, vec![("Tied", 100), ("Small", 100), ("Big", 60)]);
+
+        let opposed = top_constituencies(&rows, |c| c.oppose_count);
+        assert_eq!(opposed[0].0.constituency_name, "Against");
+        assert_eq!(opposed.len(), NATIONAL_TOP_CONSTITUENCIES);
+        assert!(opposed.iter().all(|(c, _)| c.oppose_count > 0));
+    }
+
+    #[test]
+    fn test_national_report_renders() {
+        let bill_id = uuid::Uuid::new_v4();
+        let bills = vec![NationalBillSentiment {
+            bill_id,
+            bill_number: "42".to_string(),
+            bill_title: "The Test Bill".to_string(),
+            introduction_date: None,
+            support_count: 63,
+            oppose_count: 49,
+            critique_count: 0,
+            participant_count: 80,
+            constituency_count: 2,
+        }];
+        let states = vec![StateSentiment {
+            state: "Test State".to_string(),
+            support_count: 63,
+            oppose_count: 49,
+            critique_count: 0,
+            participant_count: 80,
+        }];
+        let constituencies = vec![constituency_row(bill_id, "Big", 60, 40), constituency_row(bill_id, "Small", 3, 9)];
+        let totals = NationalTotals {
+            post_count: 112,
+            participant_count: 80,
+            constituency_count: 2,
+        };
+
+        let pdf = generate_national_report(&totals, bills, states, constituencies).unwrap();
+        let doc = lopdf::Document::load_mem(&pdf).unwrap();
+        // Overview, then the bills
+        assert_eq!(doc.get_pages().len(), 2);
+
+        let empty = generate_national_report(&NationalTotals::default(), Vec::new(), Vec::new(), Vec::new()).unwrap();
+        assert_eq!(lopdf::Document::load_mem(&empty).unwrap().get_pages().len(), 1);
+    }
+
+    #[test]
+    fn test_pie_angles_cover_the_circle() {
+        let angles = pie_angles(&[40.0, 35.0, 25.0]);
+        assert_eq!(angles.len(), 3);
+        assert_eq!(angles[0].0, 0.0);
+        assert!((angles[2].1 - 360.0).abs() < 1e-9);
+        // Slices follow on from each other and are sized by their share
+        assert_eq!(angles[0].1, angles[1].0);
+        assert!((angles[0].1 - 144.0).abs() < 1e-9);
+        let swept: f64 = angles.iter().map(|(start, end)| end - start).sum();
+        assert!((swept - 360.0).abs() < 1e-9);
+
+        assert!(pie_angles(&[0.0, 0.0]).is_empty());
+        let one = pie_angles(&[0.0, 7.0, 0.0]);
+        assert_eq!(one[0].0, one[0].1);
+        assert!((one[1].1 - one[1].0 - 360.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bars_scale_to_largest_value() {
+        let bars = bar_layout(&[2.0, 8.0, 4.0, 0.0], 100.0, 40.0);
+        assert_eq!(bars.len(), 4);
+        assert_eq!(bars[1].2, 40.0);
+        assert_eq!(bars[0].2, 10.0);
+        assert_eq!(bars[2].2, 20.0);
+        assert_eq!(bars[3].2, 0.0);
+        // Columns stay inside the chart and don't overlap
+        for (x, width, _) in &bars {
+            assert!(x - width / 2.0 >= 0.0 && x + width / 2.0 <= 100.0);
+        }
+        assert!(bars[0].0 + bars[0].1 / 2.0 < bars[1].0 - bars[1].1 / 2.0);
+
+        assert!(bar_layout(&[], 100.0, 40.0).is_empty());
+        assert!(bar_layout(&[0.0, 0.0], 100.0, 40.0).is_empty());
+    }
+
+    #[test]
+    fn test_weekly_post_counts_fill_quiet_weeks() {
+        let post_on = |day: &str| ConstituencyPost {
+            bill_id: uuid::Uuid::nil(),
+            bill_number: "1".to_string(),
+            bill_title: "Bill".to_string(),
+            username: "citizen".to_string(),
+            content: "Post".to_string(),
+            stance: "support".to_string(),
+            created_at: format!("{}T12:00:00Z", day).parse().unwrap(),
+            upvotes: 0,
+            downvotes: 0,
+            introduction_date: None,
+        };
+        // 2026-03-04 is a Wednesday, 2026-03-22 a Sunday
+        let posts = vec![post_on("2026-03-22"), post_on("2026-03-04"), post_on("2026-03-08")];
+        let weeks = weekly_post_counts(&posts);
+        let day = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(
+            weeks,
+            vec![(day("2026-03-02"), 2), (day("2026-03-09"), 0), (day("2026-03-16"), 1)]
+        );
+        assert!(weekly_post_counts(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_truncate_title_counts_chars() {
+        assert_eq!(truncate_title("Short title", 70), "Short title");
+        let hindi = "डिजिटल व्यक्तिगत डेटा संरक्षण विधेयक";
+        assert_eq!(truncate_title(hindi, 6), "डिजिटल...");
+        assert_eq!(truncate_title(hindi, 100), hindi);
+    }
+
+    #[test]
+    fn test_script_runs() {
+        assert_eq!(
+            script_runs("डेटा संरक्षण Bill, 2023"),
+            vec![("डेटा संरक्षण ", true), ("Bill, 2023", false)]
+        );
+        assert_eq!(
+            script_runs("@नागरिक - SUPPORT। ठीक"),
+            vec![("@", false), ("नागरिक ", true), ("- SUPPORT", false), ("। ठीक", true)]
+        );
+        assert_eq!(script_runs("Plain English"), vec![("Plain English", false)]);
+        assert!(script_runs("").is_empty());
+    }
+
+    #[test]
+    fn test_hindi_post_and_emoji_username_render() {
+        let bill_id = uuid::Uuid::new_v4();
+        // Long enough that the old byte slice at 70/80 landed mid-character
+        let bill_title = "डिजिटल व्यक्तिगत डेटा संरक्षण विधेयक, 2023 — संशोधन".repeat(2);
+        let sentiments = vec![BillSentiment {
+            bill_id,
+            bill_number: "7".to_string(),
+            bill_title: bill_title.clone(),
+            introduction_date: None,
+            support_count: 1,
+            oppose_count: 0,
+            critique_count: 0,
+        }];
+        let posts = vec![ConstituencyPost {
+            bill_id,
+            bill_number: "7".to_string(),
+            bill_title,
+            username: "नागरिक_🇮🇳🎉".to_string(),
+            content: "यह विधेयक छोटे व्यापारियों के लिए अच्छा है 👍".to_string(),
+            stance: "support".to_string(),
+            created_at: Utc::now(),
+            upvotes: 3,
+            downvotes: 0,
+            introduction_date: None,
+        }];
+
+        let period = ReportPeriod::default();
+        let pdf = generate_constituency_report("वाराणसी", "उत्तर प्रदेश", &period, None, sentiments, posts).unwrap();
+        let doc = lopdf::Document::load_mem(&pdf).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+    }
+
+    #[test]
+    fn test_csv_round_trips_awkward_fields() {
+        let bill_id = uuid::Uuid::new_v4();
+        let title = "The Bill, \"Amendment\" 2026".to_string();
+        let sentiments = vec![BillSentiment {
+            bill_id,
+            bill_number: "42".to_string(),
+            bill_title: title.clone(),
+            introduction_date: chrono::NaiveDate::from_ymd_opt(2026, 2, 1),
+            support_count: 2,
+            oppose_count: 1,
+            critique_count: 0,
+        }];
+        let content = "First line, with a comma\nSecond line with \"quotes\"".to_string();
+        let posts = vec![ConstituencyPost {
+            bill_id,
+            bill_number: "42".to_string(),
+            bill_title: title.clone(),
+            username: "citizen".to_string(),
+            content: content.clone(),
+            stance: "support".to_string(),
+            created_at: Utc::now(),
+            upvotes: 5,
+            downvotes: 1,
+            introduction_date: None,
+        }];
+
+        let csv = generate_constituency_csv(&sentiments, &posts).unwrap();
+
+        let mut reader = csv::Reader::from_reader(csv.sentiment.as_bytes());
+        let rows: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(&rows[0][2], title);
+        assert_eq!(&rows[0][3], "2026-02-01");
+        assert_eq!(&rows[0][7], "3");
+
+        let mut reader = csv::Reader::from_reader(csv.posts.as_bytes());
+        assert_eq!(&reader.headers().unwrap()[5], "content");
+        let rows: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(&rows[0][0], bill_id.to_string());
+        assert_eq!(&rows[0][5], content);
+        assert_eq!(&rows[0][6], "5");
+    }
+
+    #[test]
+    fn test_csv_neutralises_formulas() {
+        let post = |username: &str, content: &str| ConstituencyPost {
+            bill_id: uuid::Uuid::new_v4(),
+            bill_number: "42".to_string(),
+            bill_title: "The Test Bill".to_string(),
+            username: username.to_string(),
+            content: content.to_string(),
+            stance: "oppose".to_string(),
+            created_at: Utc::now(),
+            upvotes: 0,
+            downvotes: 0,
+            introduction_date: None,
+        };
+        let posts = vec![
+            post("@admin", "=HYPERLINK(\"http://example.com\",\"click\")"),
+            post("citizen", "+1 from me"),
+            post("citizen", "-2+3"),
+            post("citizen", "\tindented"),
+            post("citizen", "\r=1+1"),
+            post("citizen", "A plain review - with a dash"),
+        ];
+
+        let csv = generate_constituency_csv(&[], &posts).unwrap();
+
+        let mut reader = csv::Reader::from_reader(csv.posts.as_bytes());
+        let rows: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+        assert_eq!(&rows[0][3], "'@admin");
+        assert_eq!(&rows[0][5], "'=HYPERLINK(\"http://example.com\",\"click\")");
+        assert_eq!(&rows[1][5], "'+1 from me");
+        assert_eq!(&rows[2][5], "'-2+3");
+        assert_eq!(&rows[3][5], "'\tindented");
+        assert_eq!(&rows[4][5], "'\r=1+1");
+        // Ordinary text is left alone
+        assert_eq!(&rows[5][3], "citizen");
+        assert_eq!(&rows[5][5], "A plain review - with a dash");
+    }
+
+    #[test]
+    fn test_pages_are_numbered() {
+        let bill_id = uuid::Uuid::new_v4();
+        let posts = (0..60)
+            .map(|i| ConstituencyPost {
+                bill_id,
+                bill_number: "42".to_string(),
+                bill_title: "The Test Bill".to_string(),
+                username: format!("citizen{}", i),
+                content: "This clause needs clearer safeguards for small traders. ".repeat(3),
+                stance: "Support".to_string(),
+                created_at: Utc::now(),
+                upvotes: i,
+                downvotes: 0,
+                introduction_date: None,
+            })
+            .collect();
+
+        let period = ReportPeriod::default();
+        let pdf = generate_constituency_report("Test Nagar", "Test State", &period, None, Vec::new(), posts).unwrap();
+        let doc = lopdf::Document::load_mem(&pdf).unwrap();
+        let total = doc.get_pages().len();
+        assert!(total > 2, "{} page(s)", total);
+
+        let text = doc.extract_text(&[2]).unwrap();
+        assert!(text.contains(&format!("Page 2 of {}", total)), "{}", text);
+        let text = doc.extract_text(&[1]).unwrap();
+        assert!(text.contains(&format!("Page 1 of {}", total)), "{}", text);
+    }
+
+    #[test]
+    fn test_empty_period_still_renders() {
+        let period = ReportPeriod {
+            from: chrono::NaiveDate::from_ymd_opt(2026, 1, 1),
+            to: chrono::NaiveDate::from_ymd_opt(2026, 3, 31),
+        };
+        let pdf = generate_constituency_report("Test Nagar", "Test State", &period, None, Vec::new(), Vec::new()).unwrap();
+        let doc = lopdf::Document::load_mem(&pdf).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+    }
 }
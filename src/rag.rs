@@ -0,0 +1,118 @@
+use anyhow::Result;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::models::SearchResult;
+use crate::embedder::Embedder;
+use crate::llm::OllamaClient;
+use crate::vector_store::{SearchFilter, VectorStore};
+
+const ANSWER_TIMEOUT_SECS: u64 = 120;
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct Answer {
+    pub question: String,
+    /// Generated answer, None when the LLM could not be reached
+    pub answer: Option<String>,
+    /// Explains why no answer was generated
+    pub message: Option<String>,
+    pub sources: Vec<SearchResult>,
+}
+
+/// Answer a question using the most relevant bill chunks as grounding
+pub async fn answer_question(
+    embedder: &dyn Embedder,
+    store: &dyn VectorStore,
+    llm: &OllamaClient,
+    question: &str,
+    limit: usize,
+) -> Result<Answer> {
+    let query_vector = embedder.embed_query(question).await?;
+    let sources = store.search(&query_vector, limit, &SearchFilter::default()).await?;
+
+    if sources.is_empty() {
+        return Ok(Answer {
+            question: question.to_string(),
+            answer: None,
+            message: Some("No relevant bill sections were found for this question.".to_string()),
+            sources,
+        });
+    }
+
+    let prompt = build_prompt(question, &sources);
+
+    match llm.generate(&prompt, ANSWER_TIMEOUT_SECS).await {
+        Ok(text) => Ok(Answer {
+            question: question.to_string(),
+            answer: Some(text.trim().to_string()),
+            message: None,
+            sources,
+        }),
+        Err(e) => {
+            warn!("Answer generation failed: {:#}", e);
+            Ok(Answer {
+                question: question.to_string(),
+                answer: None,
+                message: Some(
+                    "The answer service is unavailable right now. Showing the most relevant bill sections instead."
+                        .to_string(),
+                ),
+                sources,
+            })
+        }
+    }
+}
+
+fn build_prompt(question: &str, sources: &[SearchResult]) -> String {
+    let context = sources
+        .iter()
+        .map(|s| format!("[{}, {}]\n{}", s.bill_number, s.chunk_identifier, s.content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        r#"You are helping Indian citizens understand legislation. Answer the question in plain language using ONLY the bill excerpts below.
+After every statement, cite the excerpt it came from using its label exactly as written, e.g. [12/2024, Clause 5].
+If the excerpts do not answer the question, say so instead of guessing.
+
+Excerpts:
+{}
+
+Question: {}
+
+Answer:"#,
+        context, question
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_labels_each_source() {
+        let sources = vec![
+            SearchResult {
+                bill_title: "The Digital Personal Data Protection Bill, 2023".to_string(),
+                bill_number: "DPDP/2023".to_string(),
+                chunk_index: 7,
+                chunk_identifier: "Clause 7".to_string(),
+                content: "The Data Principal shall have the right to correction.".to_string(),
+                score: 0.9,
+            },
+            SearchResult {
+                bill_title: "The Digital Personal Data Protection Bill, 2023".to_string(),
+                bill_number: "DPDP/2023".to_string(),
+                chunk_index: 8,
+                chunk_identifier: "Clause 8".to_string(),
+                content: "Every Data Principal shall have the right to grievance redressal.".to_string(),
+                score: 0.8,
+            },
+        ];
+
+        let prompt = build_prompt("Can I correct my data?", &sources);
+        assert!(prompt.contains("[DPDP/2023, Clause 7]\nThe Data Principal shall have the right to correction."));
+        assert!(prompt.contains("[DPDP/2023, Clause 8]"));
+        assert!(prompt.contains("Question: Can I correct my data?"));
+    }
+}
@@ -1,62 +1,571 @@
 use anyhow::{Context, Result};
-use chrono::{Duration, Utc};
+use axum::http::HeaderMap;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use dashmap::DashMap;
 use sqlx::PgPool;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::ops::RangeInclusive;
 use uuid::Uuid;
 
-const MAX_POSTS_PER_HOUR: i64 = 5;
+use crate::models::User;
 
-/// Check if user can create a new post (rate limiting)
-pub async fn can_create_post(pool: &PgPool, user_id: Uuid) -> Result<bool> {
-    let one_hour_ago = Utc::now() - Duration::hours(1);
-    
-    let count: (i64,) = sqlx::query_as(
+const MAX_LOGIN_FAILURES: usize = 5;
+const LOGIN_LOCKOUT_MINUTES: i64 = 15;
+const ACCOUNT_EMAIL_INTERVAL_MINUTES: i64 = 5;
+const MAX_APPEALS_PER_DAY: i64 = 3;
+// Records older than a day are deleted, so no window can be longer
+const MAX_WINDOW_MINUTES: i64 = 24 * 60;
+
+/// A signed-in user's action counted in rate_limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionType {
+    PostCreate,
+    Vote,
+    Report,
+    Search,
+    ReportJob,
+    Ask,
+}
+
+impl ActionType {
+    pub const ALL: [ActionType; 6] = [
+        ActionType::PostCreate,
+        ActionType::Vote,
+        ActionType::Report,
+        ActionType::Search,
+        ActionType::ReportJob,
+        ActionType::Ask,
+    ];
+
+    /// Stored as rate_limits.action_type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionType::PostCreate => "post_create",
+            ActionType::Vote => "vote",
+            ActionType::Report => "report",
+            ActionType::Search => "search",
+            ActionType::ReportJob => "report_job",
+            ActionType::Ask => "ask",
+        }
+    }
+}
+
+/// How many times an action may be taken within a window, and optionally within a day as well, so
+/// a burst allowance can't be repeated all day long
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionLimit {
+    pub limit: i64,
+    pub window: Duration,
+    pub daily: Option<i64>,
+}
+
+/// Which of an action's limits refused it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitWindow {
+    /// `limit` per `window`
+    Burst,
+    /// `daily` per day
+    Daily,
+}
+
+/// A refused action: the seconds until it may be tried again and the limit it ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Throttled {
+    pub retry_after: i64,
+    pub window: LimitWindow,
+}
+
+impl ActionLimit {
+    pub fn per_hour(limit: i64) -> Self {
+        Self {
+            limit,
+            window: Duration::hours(1),
+            daily: None,
+        }
+    }
+
+    pub fn and_per_day(self, daily: i64) -> Self {
+        Self {
+            daily: Some(daily),
+            ..self
+        }
+    }
+
+    fn windows(&self) -> impl Iterator<Item = (i64, Duration, LimitWindow)> {
+        let day = Duration::minutes(MAX_WINDOW_MINUTES);
+        std::iter::once((self.limit, self.window, LimitWindow::Burst))
+            .chain(self.daily.map(|daily| (daily, day, LimitWindow::Daily)))
+    }
+
+    /// How far back actions count toward any of the windows
+    fn span(&self) -> Duration {
+        self.windows().map(|(_, window, _)| window).max().unwrap_or(self.window)
+    }
+
+    /// How many of the newest actions decide every window
+    fn depth(&self) -> i64 {
+        self.windows().map(|(limit, _, _)| limit).max().unwrap_or(self.limit)
+    }
+
+    /// The longest wait any window imposes, given recent actions newest first
+    pub fn throttled(&self, recent: &[DateTime<Utc>], now: DateTime<Utc>) -> Option<Throttled> {
+        self.windows()
+            .filter_map(|(limit, window, which)| {
+                retry_after(recent, limit, window, now).map(|retry_after| Throttled {
+                    retry_after,
+                    window: which,
+                })
+            })
+            .max_by_key(|throttled| throttled.retry_after)
+    }
+
+    /// Actions left before a window fills up, given how many were taken inside `window` and inside
+    /// the last day
+    pub fn remaining_after(&self, in_window: i64, in_day: i64) -> i64 {
+        let burst = self.limit - in_window;
+        let daily = self.daily.map_or(burst, |daily| daily - in_day);
+        burst.min(daily).max(0)
+    }
+
+    /// Actions left before a window fills up, given recent actions
+    pub fn remaining(&self, recent: &[DateTime<Utc>], now: DateTime<Utc>) -> i64 {
+        let taken = |window: Duration| recent.iter().filter(|t| **t > now - window).count() as i64;
+        self.remaining_after(taken(self.window), taken(Duration::minutes(MAX_WINDOW_MINUTES)))
+    }
+}
+
+/// Per-user limits for each `ActionType`
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Reviews and replies
+    pub posts: ActionLimit,
+    pub votes: ActionLimit,
+    pub reports: ActionLimit,
+    /// Search suggestions fire as the user types, so this allows plenty a minute
+    pub searches: ActionLimit,
+    /// Searches by signed-out visitors, counted per client address
+    pub anonymous_searches: ActionLimit,
+    /// Constituency PDFs queued from the forum, each of which runs the report queries and the AI summary
+    pub report_jobs: ActionLimit,
+    /// Questions to /api/ask, each answered by the LLM, so far fewer are allowed than searches
+    pub asks: ActionLimit,
+    /// Questions from signed-out callers, counted per client address
+    pub anonymous_asks: ActionLimit,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            posts: ActionLimit::per_hour(5).and_per_day(20),
+            votes: ActionLimit::per_hour(60).and_per_day(300),
+            reports: ActionLimit::per_hour(10).and_per_day(30),
+            searches: ActionLimit {
+                limit: 60,
+                window: Duration::minutes(1),
+                daily: None,
+            },
+            anonymous_searches: ActionLimit {
+                limit: 30,
+                window: Duration::minutes(1),
+                daily: None,
+            },
+            report_jobs: ActionLimit::per_hour(10),
+            asks: ActionLimit {
+                limit: 10,
+                window: Duration::minutes(1),
+                daily: None,
+            },
+            anonymous_asks: ActionLimit {
+                limit: 3,
+                window: Duration::minutes(1),
+                daily: None,
+            },
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Limits from RATE_LIMIT_POSTS_PER_HOUR, RATE_LIMIT_VOTES_PER_HOUR, RATE_LIMIT_REPORTS_PER_HOUR,
+    /// RATE_LIMIT_SEARCHES_PER_MINUTE, RATE_LIMIT_ANON_SEARCHES_PER_MINUTE, RATE_LIMIT_REPORT_JOBS_PER_HOUR
+    /// (always a real hour), RATE_LIMIT_ASKS_PER_MINUTE and RATE_LIMIT_ANON_ASKS_PER_MINUTE. RATE_LIMIT_WINDOW_MINUTES (default 60, at most a day) changes the
+    /// "hour" that posts, votes and reports are counted over. RATE_LIMIT_POSTS_PER_DAY, RATE_LIMIT_VOTES_PER_DAY and
+    /// RATE_LIMIT_REPORTS_PER_DAY cap them over a day as well; 0 turns a daily cap off. Unset or invalid values keep the defaults.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok();
+        let window = Duration::minutes(parse_limit(
+            "RATE_LIMIT_WINDOW_MINUTES",
+            var("RATE_LIMIT_WINDOW_MINUTES"),
+            60,
+            1..=MAX_WINDOW_MINUTES,
+        ));
+        let hourly = |name: &str, daily: &str, default: ActionLimit| ActionLimit {
+            limit: parse_limit(name, var(name), default.limit, 1..=i64::MAX),
+            window,
+            daily: Some(parse_limit(daily, var(daily), default.daily.unwrap_or(0), 0..=i64::MAX)).filter(|n| *n > 0),
+        };
+        // Only the count is configurable; the window stays the default's
+        let fixed_window = |name: &str, default: ActionLimit| ActionLimit {
+            limit: parse_limit(name, var(name), default.limit, 1..=i64::MAX),
+            ..default
+        };
+
+        Self {
+            posts: hourly("RATE_LIMIT_POSTS_PER_HOUR", "RATE_LIMIT_POSTS_PER_DAY", defaults.posts),
+            votes: hourly("RATE_LIMIT_VOTES_PER_HOUR", "RATE_LIMIT_VOTES_PER_DAY", defaults.votes),
+            reports: hourly("RATE_LIMIT_REPORTS_PER_HOUR", "RATE_LIMIT_REPORTS_PER_DAY", defaults.reports),
+            searches: fixed_window("RATE_LIMIT_SEARCHES_PER_MINUTE", defaults.searches),
+            anonymous_searches: fixed_window("RATE_LIMIT_ANON_SEARCHES_PER_MINUTE", defaults.anonymous_searches),
+            report_jobs: fixed_window("RATE_LIMIT_REPORT_JOBS_PER_HOUR", defaults.report_jobs),
+            asks: fixed_window("RATE_LIMIT_ASKS_PER_MINUTE", defaults.asks),
+            anonymous_asks: fixed_window("RATE_LIMIT_ANON_ASKS_PER_MINUTE", defaults.anonymous_asks),
+        }
+    }
+
+    pub fn limit(&self, action: ActionType) -> ActionLimit {
+        match action {
+            ActionType::PostCreate => self.posts,
+            ActionType::Vote => self.votes,
+            ActionType::Report => self.reports,
+            ActionType::Search => self.searches,
+            ActionType::ReportJob => self.report_jobs,
+            ActionType::Ask => self.asks,
+        }
+    }
+
+    /// The limit for this user, scaled by their override, or None when they're exempt
+    pub fn limit_for(&self, user: &User, action: ActionType) -> Option<ActionLimit> {
+        self.scaled(action, user.rate_limit_scale())
+    }
+
+    /// The limit multiplied by a `rate_limit_scale`; None stays exempt
+    pub fn scaled(&self, action: ActionType, scale: Option<i64>) -> Option<ActionLimit> {
+        let limit = self.limit(action);
+        let scale = scale?;
+        Some(ActionLimit {
+            limit: limit.limit.saturating_mul(scale),
+            daily: limit.daily.map(|daily| daily.saturating_mul(scale)),
+            ..limit
+        })
+    }
+}
+
+// A whole number in `allowed`, or the default with a warning when the variable is set to anything else
+fn parse_limit(name: &str, value: Option<String>, default: i64, allowed: RangeInclusive<i64>) -> i64 {
+    let Some(value) = value else {
+        return default;
+    };
+    match value.trim().parse::<i64>() {
+        Ok(n) if allowed.contains(&n) => n,
+        _ => {
+            tracing::warn!("Ignoring invalid {}={:?}, using {}", name, value, default);
+            default
+        }
+    }
+}
+
+/// Recent actions per user and action type, so most checks skip the rate_limits table. An entry is
+/// read from the table on first use and trusted for `ttl` after that, counting this instance's own
+/// actions as they happen; their rows are written in the background.
+///
+/// The table stays the source of truth when several instances run. An instance only sees the others'
+/// actions when it next reads the table, so it never grants the last slot of a limit from the cache:
+/// that one always re-reads first. Each instance can therefore grant at most `limit - 1` actions
+/// unseen by the others, and N instances let through at most `N * (limit - 1) + 1` per window
+/// (plus whatever is still being written) instead of `limit`. The same holds for a daily limit.
+///
+/// Entries are sharded by key, so checks for different users rarely wait on each other. Entries
+/// older than any window are dropped by `purge_stale`, which the maintenance job calls.
+pub struct RateLimitCache {
+    ttl: Duration,
+    entries: DashMap<(Uuid, ActionType), CachedActions>,
+}
+
+struct CachedActions {
+    /// When rate_limits was last read for this entry
+    loaded_at: DateTime<Utc>,
+    /// Newest first, like the rows read from the table
+    recent: Vec<DateTime<Utc>>,
+}
+
+impl Default for RateLimitCache {
+    fn default() -> Self {
+        Self::new(Duration::seconds(5))
+    }
+}
+
+impl RateLimitCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: DashMap::new(),
+        }
+    }
+
+    /// RATE_LIMIT_CACHE_SECS (default 5, at most an hour); 0 reads the table for every check
+    pub fn from_env() -> Self {
+        let secs = parse_limit(
+            "RATE_LIMIT_CACHE_SECS",
+            std::env::var("RATE_LIMIT_CACHE_SECS").ok(),
+            5,
+            0..=3600,
+        );
+        Self::new(Duration::seconds(secs))
+    }
+
+    /// Actions inside the window, newest first, while the entry can be trusted
+    fn fresh(&self, key: (Uuid, ActionType), window: Duration, now: DateTime<Utc>) -> Option<Vec<DateTime<Utc>>> {
+        let entry = self.entries.get(&key)?;
+        if now - entry.loaded_at >= self.ttl {
+            return None;
+        }
+        Some(entry.recent.iter().copied().filter(|t| *t > now - window).collect())
+    }
+
+    /// Merge rows just read from the table into the entry and trust it for another `ttl`. Actions this
+    /// instance recorded since the last read are kept even if their write hasn't landed yet; older
+    /// ones must be in the table, so a cleared window stays cleared. Returns the merged actions.
+    fn load(
+        &self,
+        key: (Uuid, ActionType),
+        rows: Vec<DateTime<Utc>>,
+        window: Duration,
+        now: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        // The entry stays locked from reading the cached actions to replacing them
+        let mut entry = self.entries.entry(key).or_insert_with(|| CachedActions {
+            loaded_at: now,
+            recent: Vec::new(),
+        });
+
+        let mut recent = rows;
+        recent.extend(entry.recent.iter().copied().filter(|t| *t >= entry.loaded_at));
+        recent.retain(|t| *t > now - window);
+        recent.sort_unstable_by(|a, b| b.cmp(a));
+        recent.dedup();
+
+        *entry = CachedActions {
+            loaded_at: now,
+            recent: recent.clone(),
+        };
+        recent
+    }
+
+    /// Drop everything cached for a user, after their rows were deleted
+    pub fn forget_user(&self, user_id: Uuid) {
+        self.entries.retain(|(id, _), _| *id != user_id);
+    }
+
+    /// Drop entries last read from the table longer ago than any window, which can no longer
+    /// decide a check. Returns how many were dropped.
+    pub fn purge_stale(&self, now: DateTime<Utc>) -> usize {
+        let before = self.entries.len();
+        let max_age = Duration::minutes(MAX_WINDOW_MINUTES);
+        self.entries.retain(|_, entry| now - entry.loaded_at < max_age);
+        before.saturating_sub(self.entries.len())
+    }
+
+    /// Count an action taken on this instance. Without an entry the next check reads the table anyway.
+    fn record(&self, key: (Uuid, ActionType), at: DateTime<Utc>) {
+        if let Some(mut entry) = self.entries.get_mut(&key) {
+            entry.recent.insert(0, at);
+        }
+    }
+
+    /// Decide from the cache alone: Some(Some(throttled)) to refuse, Some(None) when the action was
+    /// granted and recorded, and None when the table has to be read first
+    fn try_take(&self, key: (Uuid, ActionType), limit: ActionLimit, now: DateTime<Utc>) -> Option<Option<Throttled>> {
+        let mut entry = self.entries.get_mut(&key)?;
+        // Even a refusal is read again once stale, in case an admin cleared the window
+        if now - entry.loaded_at >= self.ttl {
+            return None;
+        }
+        if let Some(throttled) = limit.throttled(&entry.recent, now) {
+            return Some(Some(throttled));
+        }
+        let last_slot = limit.windows().any(|(limit, window, _)| {
+            let taken = entry.recent.iter().filter(|t| **t > now - window).count() as i64;
+            taken + 1 >= limit
+        });
+        if last_slot {
+            return None;
+        }
+        entry.recent.insert(0, now);
+        Some(None)
+    }
+
+    /// Decide after reading the table, recording the action if it's granted
+    fn take_loaded(
+        &self,
+        key: (Uuid, ActionType),
+        rows: Vec<DateTime<Utc>>,
+        limit: ActionLimit,
+        now: DateTime<Utc>,
+    ) -> Option<Throttled> {
+        let recent = self.load(key, rows, limit.span(), now);
+        let throttled = limit.throttled(&recent, now);
+        if throttled.is_none() {
+            self.record(key, now);
+        }
+        throttled
+    }
+}
+
+// Timestamps as Postgres stores them, so rows read back match the cached ones exactly
+fn now_micros() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(6)
+}
+
+// The newest actions that count toward any of the limit's windows, read from the table
+async fn fetch_recent(
+    pool: &PgPool,
+    user_id: Uuid,
+    action: ActionType,
+    limit: ActionLimit,
+    now: DateTime<Utc>,
+) -> Result<Vec<DateTime<Utc>>> {
+    let recent: Vec<(DateTime<Utc>,)> = sqlx::query_as(
         r#"
-        SELECT COUNT(*) FROM rate_limits
-        WHERE user_id = $1 AND action_type = 'post_create' AND timestamp > $2
+        SELECT timestamp FROM rate_limits
+        WHERE user_id = $1 AND action_type = $2 AND timestamp > $3
+        ORDER BY timestamp DESC
+        LIMIT $4
         "#,
     )
     .bind(user_id)
-    .bind(one_hour_ago)
-    .fetch_one(pool)
+    .bind(action.as_str())
+    .bind(now - limit.span())
+    .bind(limit.depth())
+    .fetch_all(pool)
     .await
     .context("Failed to check rate limit")?;
 
-    Ok(count.0 < MAX_POSTS_PER_HOUR)
+    Ok(recent.into_iter().map(|(t,)| t).collect())
 }
 
-/// Get remaining posts allowed for user this hour
-pub async fn get_remaining_posts(pool: &PgPool, user_id: Uuid) -> Result<i64> {
-    let one_hour_ago = Utc::now() - Duration::hours(1);
-    
-    let count: (i64,) = sqlx::query_as(
-        r#"
-        SELECT COUNT(*) FROM rate_limits
-        WHERE user_id = $1 AND action_type = 'post_create' AND timestamp > $2
-        "#,
-    )
-    .bind(user_id)
-    .bind(one_hour_ago)
-    .fetch_one(pool)
-    .await
-    .context("Failed to get rate limit count")?;
+// The user's recent actions, from the cache while it's fresh
+async fn recent_actions(
+    pool: &PgPool,
+    cache: &RateLimitCache,
+    user_id: Uuid,
+    action: ActionType,
+    limit: ActionLimit,
+    now: DateTime<Utc>,
+) -> Result<Vec<DateTime<Utc>>> {
+    let key = (user_id, action);
+    if let Some(recent) = cache.fresh(key, limit.span(), now) {
+        return Ok(recent);
+    }
+    let rows = fetch_recent(pool, user_id, action, limit, now).await?;
+    Ok(cache.load(key, rows, limit.span(), now))
+}
+
+/// Check if user can create a new post (rate limiting)
+pub async fn can_create_post(
+    pool: &PgPool,
+    cache: &RateLimitCache,
+    user: &User,
+    config: &RateLimitConfig,
+) -> Result<bool> {
+    let Some(posts) = config.limit_for(user, ActionType::PostCreate) else {
+        return Ok(true);
+    };
+    let now = now_micros();
+    let recent = recent_actions(pool, cache, user.id, ActionType::PostCreate, posts, now).await?;
 
-    Ok(MAX_POSTS_PER_HOUR - count.0)
+    Ok(posts.throttled(&recent, now).is_none())
+}
+
+/// Get remaining posts allowed for user, the fewest left in any window, or None when they're exempt
+pub async fn get_remaining_posts(
+    pool: &PgPool,
+    cache: &RateLimitCache,
+    user: &User,
+    config: &RateLimitConfig,
+) -> Result<Option<i64>> {
+    let Some(posts) = config.limit_for(user, ActionType::PostCreate) else {
+        return Ok(None);
+    };
+    let now = now_micros();
+    let recent = recent_actions(pool, cache, user.id, ActionType::PostCreate, posts, now).await?;
+
+    Ok(Some(posts.remaining(&recent, now)))
+}
+
+/// Get time until next post is allowed and the limit that's holding it up (returns None if user can post now)
+pub async fn get_time_until_next_post(
+    pool: &PgPool,
+    cache: &RateLimitCache,
+    user: &User,
+    config: &RateLimitConfig,
+) -> Result<Option<Throttled>> {
+    let Some(posts) = config.limit_for(user, ActionType::PostCreate) else {
+        return Ok(None);
+    };
+    let now = now_micros();
+    let recent = recent_actions(pool, cache, user.id, ActionType::PostCreate, posts, now).await?;
+
+    Ok(posts.throttled(&recent, now))
 }
 
 /// Record a post action for rate limiting
-pub async fn record_post_action(pool: &PgPool, user_id: Uuid) -> Result<()> {
-    let id = Uuid::new_v4();
-    let now = Utc::now();
-    
+pub fn record_post_action(pool: &PgPool, cache: &RateLimitCache, user_id: Uuid) {
+    let now = now_micros();
+    cache.record((user_id, ActionType::PostCreate), now);
+    count_outcome(ActionType::PostCreate.as_str(), true);
+    record_in_background(pool, user_id, ActionType::PostCreate, now);
+}
+
+/// Note an action a rate limit refused, for the admin rate limit view. Written in the background.
+pub fn record_rejection(pool: &PgPool, user_id: Uuid, action: ActionType) {
+    count_outcome(action.as_str(), false);
+    let pool = pool.clone();
+    tokio::spawn(async move {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO rate_limit_rejections (id, user_id, action_type, timestamp)
+            VALUES ($1, $2, $3, NOW())
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(action.as_str())
+        .execute(&pool)
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to record rate limit rejection: {}", e);
+        }
+    });
+}
+
+// Allowed and refused actions, through the metrics facade for whichever recorder the deployment installs
+fn count_outcome(action: &str, allowed: bool) {
+    let outcome = if allowed { "allowed" } else { "rejected" };
+    metrics::counter!("rate_limit_actions_total", "action" => action.to_owned(), "outcome" => outcome).increment(1);
+}
+
+// Write the row without holding up the request; a failed write only loosens the limit
+fn record_in_background(pool: &PgPool, user_id: Uuid, action: ActionType, at: DateTime<Utc>) {
+    let pool = pool.clone();
+    tokio::spawn(async move {
+        if let Err(e) = record_action(&pool, user_id, action, at).await {
+            tracing::warn!("Failed to record {} for rate limiting: {:#}", action.as_str(), e);
+        }
+    });
+}
+
+async fn record_action(pool: &PgPool, user_id: Uuid, action: ActionType, at: DateTime<Utc>) -> Result<()> {
     sqlx::query(
         r#"
         INSERT INTO rate_limits (id, user_id, action_type, timestamp)
-        VALUES ($1, $2, 'post_create', $3)
+        VALUES ($1, $2, $3, $4)
         "#,
     )
-    .bind(id)
+    .bind(Uuid::new_v4())
     .bind(user_id)
-    .bind(now)
+    .bind(action.as_str())
+    .bind(at)
     .execute(pool)
     .await
     .context("Failed to record rate limit action")?;
@@ -64,8 +573,50 @@ pub async fn record_post_action(pool: &PgPool, user_id: Uuid) -> Result<()> {
     Ok(())
 }
 
+/// Seconds until the oldest of the last `limit` attempts, given newest first, leaves the window;
+/// None while fewer than `limit` fall inside it
+fn retry_after(recent: &[DateTime<Utc>], limit: i64, window: Duration, now: DateTime<Utc>) -> Option<i64> {
+    let window_start = now - window;
+    let in_window: Vec<&DateTime<Utc>> = recent.iter().filter(|t| **t > window_start).collect();
+    if limit < 1 || (in_window.len() as i64) < limit {
+        return None;
+    }
+
+    let oldest = in_window[limit as usize - 1];
+    Some(((*oldest + window) - now).num_seconds().max(1))
+}
+
+/// Count an action by a signed-in user against each of its limits. Returns how long to wait and
+/// which limit refused it if they're over one; otherwise records the action and returns None.
+/// Windows longer than a day are cut short by `cleanup_old_records`.
+pub async fn check_and_record(
+    pool: &PgPool,
+    cache: &RateLimitCache,
+    user_id: Uuid,
+    action: ActionType,
+    limit: ActionLimit,
+) -> Result<Option<Throttled>> {
+    let now = now_micros();
+    let key = (user_id, action);
+
+    let wait = match cache.try_take(key, limit, now) {
+        Some(wait) => wait,
+        None => {
+            let rows = fetch_recent(pool, user_id, action, limit, now).await?;
+            cache.take_loaded(key, rows, limit, now)
+        }
+    };
+    match wait {
+        Some(_) => record_rejection(pool, user_id, action),
+        None => {
+            count_outcome(action.as_str(), true);
+            record_in_background(pool, user_id, action, now);
+        }
+    }
+    Ok(wait)
+}
+
 /// Clean up old rate limit records (older than 1 day)
-#[allow(dead_code)]
 pub async fn cleanup_old_records(pool: &PgPool) -> Result<u64> {
     let one_day_ago = Utc::now() - Duration::days(1);
     
@@ -75,24 +626,132 @@ pub async fn cleanup_old_records(pool: &PgPool) -> Result<u64> {
         .await
         .context("Failed to cleanup old rate limit records")?;
 
-    Ok(result.rows_affected())
+    let logins = sqlx::query("DELETE FROM login_failures WHERE timestamp < $1")
+        .bind(one_day_ago)
+        .execute(pool)
+        .await
+        .context("Failed to cleanup old login failures")?;
+
+    let rejections = sqlx::query("DELETE FROM rate_limit_rejections WHERE timestamp < $1")
+        .bind(one_day_ago)
+        .execute(pool)
+        .await
+        .context("Failed to cleanup old rate limit rejections")?;
+
+    Ok(result.rows_affected() + logins.rows_affected() + rejections.rows_affected())
 }
 
-/// Get time until next post is allowed (returns None if user can post now)
-#[allow(dead_code)]
-pub async fn get_time_until_next_post(pool: &PgPool, user_id: Uuid) -> Result<Option<i64>> {
-    if can_create_post(pool, user_id).await? {
-        return Ok(None);
-    }
+fn action_names() -> Vec<&'static str> {
+    ActionType::ALL.iter().map(|action| action.as_str()).collect()
+}
+
+/// Allowed and refused member actions of each rate-limited kind since `since`
+pub async fn action_totals(pool: &PgPool, since: DateTime<Utc>) -> Result<Vec<crate::models::RateLimitTotals>> {
+    sqlx::query_as::<_, crate::models::RateLimitTotals>(
+        r#"
+        SELECT action_type,
+               COUNT(*) FILTER (WHERE allowed)::BIGINT AS allowed,
+               COUNT(*) FILTER (WHERE NOT allowed)::BIGINT AS rejected
+        FROM (
+            SELECT action_type, TRUE AS allowed FROM rate_limits
+            WHERE user_id IS NOT NULL AND action_type = ANY($1) AND timestamp > $2
+            UNION ALL
+            SELECT action_type, FALSE AS allowed FROM rate_limit_rejections
+            WHERE timestamp > $2
+        ) actions
+        GROUP BY action_type
+        ORDER BY action_type
+        "#,
+    )
+    .bind(action_names())
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .context("Failed to total rate-limited actions")
+}
+
+/// The members with the most refused actions since `since`
+pub async fn most_throttled_users(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<crate::models::ThrottledUser>> {
+    sqlx::query_as::<_, crate::models::ThrottledUser>(
+        r#"
+        SELECT u.id AS user_id, u.username, u.role, u.rate_limit_multiplier,
+               COUNT(*)::BIGINT AS rejected, MAX(r.timestamp) AS last_rejected
+        FROM rate_limit_rejections r
+        JOIN users u ON u.id = r.user_id
+        WHERE r.timestamp > $1
+        GROUP BY u.id
+        ORDER BY rejected DESC, last_rejected DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list throttled users")
+}
+
+/// How many actions of each kind the users took inside that kind's current window, and in the last
+/// day, for `ActionLimit::remaining_after`
+pub async fn actions_in_window(
+    pool: &PgPool,
+    user_ids: &[Uuid],
+    config: &RateLimitConfig,
+) -> Result<HashMap<(Uuid, String), (i64, i64)>> {
+    let now = Utc::now();
+    let since: Vec<DateTime<Utc>> = ActionType::ALL.iter().map(|action| now - config.limit(*action).window).collect();
+
+    let rows: Vec<(Uuid, String, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT r.user_id, r.action_type,
+               COUNT(*) FILTER (WHERE r.timestamp > w.since)::BIGINT,
+               COUNT(*)::BIGINT
+        FROM rate_limits r
+        JOIN UNNEST($2::TEXT[], $3::TIMESTAMPTZ[]) AS w(action_type, since) ON w.action_type = r.action_type
+        WHERE r.user_id = ANY($1) AND r.timestamp > $4
+        GROUP BY r.user_id, r.action_type
+        "#,
+    )
+    .bind(user_ids)
+    .bind(action_names())
+    .bind(since)
+    .bind(now - Duration::minutes(MAX_WINDOW_MINUTES))
+    .fetch_all(pool)
+    .await
+    .context("Failed to count actions in the current window")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(user_id, action, in_window, in_day)| ((user_id, action), (in_window, in_day)))
+        .collect())
+}
+
+/// Start a member's rate limit windows afresh. Returns how many recorded actions were dropped.
+/// Other instances may keep refusing from their cache for up to its TTL.
+pub async fn clear_user_window(pool: &PgPool, cache: &RateLimitCache, user_id: Uuid) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM rate_limits WHERE user_id = $1 AND action_type = ANY($2)")
+        .bind(user_id)
+        .bind(action_names())
+        .execute(pool)
+        .await
+        .context("Failed to clear rate limit window")?;
+    cache.forget_user(user_id);
+    Ok(result.rows_affected())
+}
 
-    // Get the oldest rate limit record within the last hour
+/// Seconds until the user may download another data export (one per hour), or None if they can now
+pub async fn get_time_until_next_export(pool: &PgPool, user_id: Uuid) -> Result<Option<i64>> {
     let one_hour_ago = Utc::now() - Duration::hours(1);
-    
-    let oldest: Option<(chrono::DateTime<Utc>,)> = sqlx::query_as(
+
+    let latest: Option<(chrono::DateTime<Utc>,)> = sqlx::query_as(
         r#"
         SELECT timestamp FROM rate_limits
-        WHERE user_id = $1 AND action_type = 'post_create' AND timestamp > $2
-        ORDER BY timestamp ASC
+        WHERE user_id = $1 AND action_type = 'data_export' AND timestamp > $2
+        ORDER BY timestamp DESC
         LIMIT 1
         "#,
     )
@@ -100,16 +759,556 @@ pub async fn get_time_until_next_post(pool: &PgPool, user_id: Uuid) -> Result<Op
     .bind(one_hour_ago)
     .fetch_optional(pool)
     .await
-    .context("Failed to get oldest rate limit record")?;
+    .context("Failed to check export rate limit")?;
+
+    Ok(latest.map(|(timestamp,)| ((timestamp + Duration::hours(1)) - Utc::now()).num_seconds().max(0)))
+}
+
+/// Record a data export for rate limiting
+pub async fn record_export_action(pool: &PgPool, user_id: Uuid) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO rate_limits (id, user_id, action_type, timestamp)
+        VALUES ($1, $2, 'data_export', $3)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .context("Failed to record export action")?;
+
+    Ok(())
+}
+
+/// Check if user can appeal another rejected post today
+pub async fn can_appeal(pool: &PgPool, user_id: Uuid) -> Result<bool> {
+    let one_day_ago = Utc::now() - Duration::days(1);
+
+    let count: (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM rate_limits
+        WHERE user_id = $1 AND action_type = 'appeal' AND timestamp > $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(one_day_ago)
+    .fetch_one(pool)
+    .await
+    .context("Failed to check appeal rate limit")?;
+
+    Ok(count.0 < MAX_APPEALS_PER_DAY)
+}
+
+/// Record an appeal for rate limiting
+pub async fn record_appeal_action(pool: &PgPool, user_id: Uuid) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO rate_limits (id, user_id, action_type, timestamp)
+        VALUES ($1, $2, 'appeal', $3)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .context("Failed to record appeal action")?;
+
+    Ok(())
+}
+
+/// Seconds until the user may be sent another account email (verification or password reset),
+/// or None if they can now
+pub async fn get_time_until_next_account_email(pool: &PgPool, user_id: Uuid) -> Result<Option<i64>> {
+    let window_start = Utc::now() - Duration::minutes(ACCOUNT_EMAIL_INTERVAL_MINUTES);
+
+    let latest: Option<(DateTime<Utc>,)> = sqlx::query_as(
+        r#"
+        SELECT timestamp FROM rate_limits
+        WHERE user_id = $1 AND action_type = 'account_email' AND timestamp > $2
+        ORDER BY timestamp DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(window_start)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to check account email rate limit")?;
+
+    Ok(latest.map(|(timestamp,)| {
+        ((timestamp + Duration::minutes(ACCOUNT_EMAIL_INTERVAL_MINUTES)) - Utc::now())
+            .num_seconds()
+            .max(0)
+    }))
+}
+
+/// Record an account email for rate limiting
+pub async fn record_account_email(pool: &PgPool, user_id: Uuid) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO rate_limits (id, user_id, action_type, timestamp)
+        VALUES ($1, $2, 'account_email', $3)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .context("Failed to record account email")?;
+
+    Ok(())
+}
+
+/// Seconds left on a lockout, given failure times newest first; None once fewer than
+/// MAX_LOGIN_FAILURES fall inside the window
+fn lockout_remaining(failures: &[DateTime<Utc>], now: DateTime<Utc>) -> Option<i64> {
+    let window_start = now - Duration::minutes(LOGIN_LOCKOUT_MINUTES);
+    let recent: Vec<&DateTime<Utc>> = failures.iter().filter(|t| **t > window_start).collect();
+    if recent.len() < MAX_LOGIN_FAILURES {
+        return None;
+    }
+
+    // Unlocks once the oldest of the last MAX_LOGIN_FAILURES failures leaves the window
+    let unlocks_at = *recent[MAX_LOGIN_FAILURES - 1] + Duration::minutes(LOGIN_LOCKOUT_MINUTES);
+    Some((unlocks_at - now).num_seconds().max(1))
+}
+
+/// Seconds until logins are allowed again for this username or address, or None if they are now.
+/// Unknown usernames are counted the same way, so a lockout says nothing about whether an account exists.
+pub async fn get_login_lockout(pool: &PgPool, username: &str, ip_address: Option<&str>) -> Result<Option<i64>> {
+    let window_start = Utc::now() - Duration::minutes(LOGIN_LOCKOUT_MINUTES);
+
+    let by_username: Vec<(DateTime<Utc>,)> = sqlx::query_as(
+        r#"
+        SELECT timestamp FROM login_failures
+        WHERE username = $1 AND timestamp > $2
+        ORDER BY timestamp DESC
+        "#,
+    )
+    .bind(username.to_lowercase())
+    .bind(window_start)
+    .fetch_all(pool)
+    .await
+    .context("Failed to check login failures")?;
+
+    let by_ip: Vec<(DateTime<Utc>,)> = match ip_address {
+        Some(ip) => sqlx::query_as(
+            r#"
+            SELECT timestamp FROM login_failures
+            WHERE ip_address = $1 AND timestamp > $2
+            ORDER BY timestamp DESC
+            "#,
+        )
+        .bind(ip)
+        .bind(window_start)
+        .fetch_all(pool)
+        .await
+        .context("Failed to check login failures")?,
+        None => Vec::new(),
+    };
+
+    let now = Utc::now();
+    let by_username: Vec<DateTime<Utc>> = by_username.into_iter().map(|(t,)| t).collect();
+    let by_ip: Vec<DateTime<Utc>> = by_ip.into_iter().map(|(t,)| t).collect();
+
+    Ok(lockout_remaining(&by_username, now).max(lockout_remaining(&by_ip, now)))
+}
+
+/// Record a failed login attempt
+pub async fn record_login_failure(pool: &PgPool, username: &str, ip_address: Option<&str>) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO login_failures (id, username, ip_address, timestamp)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(username.to_lowercase())
+    .bind(ip_address)
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .context("Failed to record login failure")?;
+
+    Ok(())
+}
+
+/// Forget a username's failed logins after it signs in successfully
+pub async fn clear_login_failures(pool: &PgPool, username: &str) -> Result<()> {
+    sqlx::query("DELETE FROM login_failures WHERE username = $1")
+        .bind(username.to_lowercase())
+        .execute(pool)
+        .await
+        .context("Failed to clear login failures")?;
+
+    Ok(())
+}
+
+/// Count an action from a client address against `limit` per `window`. Returns the seconds until
+/// the address may try again if it is over the limit; otherwise records the attempt and returns None.
+/// Windows longer than a day are cut short by `cleanup_old_records`.
+pub async fn check_ip_action(
+    pool: &PgPool,
+    ip_address: &str,
+    action: &str,
+    limit: i64,
+    window: Duration,
+) -> Result<Option<i64>> {
+    let now = Utc::now();
+
+    let recent: Vec<(DateTime<Utc>,)> = sqlx::query_as(
+        r#"
+        SELECT timestamp FROM rate_limits
+        WHERE ip_address = $1 AND action_type = $2 AND timestamp > $3
+        ORDER BY timestamp DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(ip_address)
+    .bind(action)
+    .bind(now - window)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to check address rate limit")?;
+
+    // Frees up once the oldest of the last `limit` attempts leaves the window
+    if recent.len() as i64 >= limit {
+        if let Some((oldest,)) = recent.last() {
+            count_outcome(action, false);
+            return Ok(Some(((*oldest + window) - now).num_seconds().max(1)));
+        }
+    }
+    count_outcome(action, true);
+
+    sqlx::query(
+        r#"
+        INSERT INTO rate_limits (id, ip_address, action_type, timestamp)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(ip_address)
+    .bind(action)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("Failed to record address rate limit action")?;
+
+    Ok(None)
+}
+
+/// Reverse proxies whose X-Forwarded-For header is believed, from the comma-separated TRUSTED_PROXIES
+fn trusted_proxies() -> Vec<IpAddr> {
+    std::env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|entry| {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                return None;
+            }
+            let parsed = entry.parse().ok();
+            if parsed.is_none() {
+                tracing::warn!("Ignoring invalid TRUSTED_PROXIES entry {:?}", entry);
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// The address a request came from: the peer itself, unless the peer is a trusted proxy, in which
+/// case the rightmost X-Forwarded-For entry that isn't one. Entries left of that are client-supplied
+/// and could be anything.
+pub fn client_ip(peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+    client_ip_behind(peer, headers, &trusted_proxies())
+}
+
+fn client_ip_behind(peer: Option<IpAddr>, headers: &HeaderMap, trusted: &[IpAddr]) -> Option<IpAddr> {
+    let peer = peer?;
+    if !trusted.contains(&peer) {
+        return Some(peer);
+    }
 
-    match oldest {
-        Some((timestamp,)) => {
-            // Time until the oldest record expires (1 hour from its creation)
-            let expires_at = timestamp + Duration::hours(1);
-            let seconds_remaining = (expires_at - Utc::now()).num_seconds();
-            Ok(Some(seconds_remaining.max(0)))
+    let forwarded: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+
+    for entry in forwarded.into_iter().rev() {
+        match entry.parse::<IpAddr>() {
+            Ok(ip) if trusted.contains(&ip) => continue,
+            Ok(ip) => return Some(ip),
+            Err(_) => break,
         }
-        None => Ok(None),
     }
+
+    Some(peer)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn forwarded_for(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_client_ip_ignores_forwarded_for_from_untrusted_peer() {
+        let peer: IpAddr = "203.0.113.7".parse().unwrap();
+        let headers = forwarded_for(&["198.51.100.1"]);
+        assert_eq!(client_ip_behind(Some(peer), &headers, &[]), Some(peer));
+        assert_eq!(client_ip_behind(None, &headers, &[]), None);
+    }
+
+    #[test]
+    fn test_client_ip_takes_rightmost_untrusted_forwarded_entry() {
+        let proxy: IpAddr = "10.0.0.2".parse().unwrap();
+        let inner: IpAddr = "10.0.0.3".parse().unwrap();
+        let trusted = [proxy, inner];
+
+        // The leftmost entry is whatever the client claimed; only the hops our proxies added count
+        let headers = forwarded_for(&["1.2.3.4, 198.51.100.9", "10.0.0.3"]);
+        assert_eq!(client_ip_behind(Some(proxy), &headers, &trusted), "198.51.100.9".parse().ok());
+
+        // No usable header falls back to the proxy itself
+        assert_eq!(client_ip_behind(Some(proxy), &HeaderMap::new(), &trusted), Some(proxy));
+        let garbage = forwarded_for(&["not-an-ip"]);
+        assert_eq!(client_ip_behind(Some(proxy), &garbage, &trusted), Some(proxy));
+    }
+
+    fn minutes_ago(now: DateTime<Utc>, minutes: &[i64]) -> Vec<DateTime<Utc>> {
+        minutes.iter().map(|m| now - Duration::minutes(*m)).collect()
+    }
+
+    #[test]
+    fn test_lockout_after_max_failures() {
+        let now = Utc::now();
+        assert_eq!(lockout_remaining(&minutes_ago(now, &[1, 2, 3, 4]), now), None);
+
+        let remaining = lockout_remaining(&minutes_ago(now, &[1, 2, 3, 4, 5]), now);
+        assert_eq!(remaining, Some((LOGIN_LOCKOUT_MINUTES - 5) * 60));
+    }
+
+    #[test]
+    fn test_lockout_expires_with_window() {
+        let now = Utc::now();
+        let failures = minutes_ago(now, &[1, 2, 3, 4, 14]);
+        assert!(lockout_remaining(&failures, now).is_some());
+
+        // Two minutes later the oldest failure has left the window
+        let later = now + Duration::minutes(2);
+        assert_eq!(lockout_remaining(&failures, later), None);
+
+        // Older failures outside the window never count
+        let stale = minutes_ago(now, &[1, 2, 3, 4, LOGIN_LOCKOUT_MINUTES + 1, LOGIN_LOCKOUT_MINUTES + 2]);
+        assert_eq!(lockout_remaining(&stale, now), None);
+    }
+
+    #[test]
+    fn test_lockout_uses_most_recent_failures() {
+        let now = Utc::now();
+        // Six failures: lockout lasts until the fifth most recent one expires
+        let failures = minutes_ago(now, &[1, 2, 3, 4, 6, 10]);
+        assert_eq!(lockout_remaining(&failures, now), Some((LOGIN_LOCKOUT_MINUTES - 6) * 60));
+    }
+
+    #[test]
+    fn test_action_limits() {
+        let config = RateLimitConfig::default();
+        assert_eq!(config.limit(ActionType::PostCreate), ActionLimit::per_hour(5).and_per_day(20));
+        assert_eq!(config.limit(ActionType::Vote), ActionLimit::per_hour(60).and_per_day(300));
+
+        let now = Utc::now();
+        let window = Duration::hours(1);
+        assert_eq!(retry_after(&minutes_ago(now, &[1, 2]), 3, window, now), None);
+
+        // Frees up once the oldest of the last three leaves the hour
+        let recent = minutes_ago(now, &[1, 2, 50, 70]);
+        assert_eq!(retry_after(&recent, 3, window, now), Some(10 * 60));
+        assert_eq!(retry_after(&recent, 4, window, now), None);
+    }
+
+    #[test]
+    fn test_daily_limit_caps_repeated_bursts() {
+        let limit = ActionLimit::per_hour(2).and_per_day(4);
+        let now = Utc::now();
+        let day = 24 * 60;
+
+        // Within the hourly limit but at the daily one: the wait runs until the oldest leaves the day
+        let recent = minutes_ago(now, &[30, 120, 600, 900]);
+        let throttled = limit.throttled(&recent, now).unwrap();
+        assert_eq!(throttled.window, LimitWindow::Daily);
+        assert_eq!(throttled.retry_after, (day - 900) * 60);
+        assert_eq!(limit.remaining(&recent, now), 0);
+
+        // Both full: the longer wait wins
+        let recent = minutes_ago(now, &[10, 20, 600, 1000]);
+        let throttled = limit.throttled(&recent, now).unwrap();
+        assert_eq!(throttled.window, LimitWindow::Daily);
+        assert_eq!(throttled.retry_after, (day - 1000) * 60);
+
+        // Only the burst is used up
+        let recent = minutes_ago(now, &[10, 20]);
+        assert_eq!(
+            limit.throttled(&recent, now),
+            Some(Throttled {
+                retry_after: 40 * 60,
+                window: LimitWindow::Burst
+            })
+        );
+        assert_eq!(limit.remaining(&minutes_ago(now, &[10, 700, 800]), now), 1);
+    }
+
+    #[test]
+    fn test_parse_limit_falls_back_on_invalid_values() {
+        let parse = |value: &str| parse_limit("RATE_LIMIT_TEST", Some(value.to_string()), 5, 1..=MAX_WINDOW_MINUTES);
+        assert_eq!(parse_limit("RATE_LIMIT_TEST", None, 5, 1..=MAX_WINDOW_MINUTES), 5);
+        assert_eq!(parse(" 12 "), 12);
+        assert_eq!(parse("0"), 5);
+        assert_eq!(parse("-3"), 5);
+        assert_eq!(parse("ten"), 5);
+        assert_eq!(parse("2.5"), 5);
+        assert_eq!(parse("1441"), 5);
+    }
+
+    // One instance's check_and_record against a table shared by every instance. Writes land at once.
+    fn take(
+        cache: &RateLimitCache,
+        table: &mut Vec<DateTime<Utc>>,
+        reads: &mut usize,
+        limit: ActionLimit,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let key = (Uuid::nil(), ActionType::Vote);
+        let wait = cache.try_take(key, limit, now).unwrap_or_else(|| {
+            *reads += 1;
+            let mut rows: Vec<DateTime<Utc>> = table.iter().copied().filter(|t| *t > now - limit.span()).collect();
+            rows.sort_unstable_by(|a, b| b.cmp(a));
+            rows.truncate(limit.depth() as usize);
+            cache.take_loaded(key, rows, limit, now)
+        });
+        if wait.is_none() {
+            table.push(now);
+        }
+        wait.is_none()
+    }
+
+    #[test]
+    fn test_cache_skips_table_reads() {
+        let cache = RateLimitCache::new(Duration::seconds(5));
+        let limit = ActionLimit::per_hour(5);
+        let (mut table, mut reads) = (Vec::new(), 0);
+        let start = Utc::now().trunc_subsecs(6);
+
+        let granted = (0..20)
+            .filter(|i| take(&cache, &mut table, &mut reads, limit, start + Duration::seconds(*i)))
+            .count();
+        assert_eq!(granted, 5);
+        // The first check and the last slot read the table; refusals come from the cache until it's stale
+        assert_eq!(reads, 5);
+    }
+
+    #[test]
+    fn test_cache_applies_daily_limit() {
+        let cache = RateLimitCache::new(Duration::seconds(5));
+        let limit = ActionLimit::per_hour(3).and_per_day(4);
+        let (mut table, mut reads) = (Vec::new(), 0);
+        let start = Utc::now().trunc_subsecs(6);
+
+        // One attempt every ten minutes for three hours: three in the first hour, then one more
+        let granted: Vec<i64> = (0..18)
+            .filter(|i| take(&cache, &mut table, &mut reads, limit, start + Duration::minutes(10 * i)))
+            .collect();
+        assert_eq!(granted, vec![0, 1, 2, 6]);
+    }
+
+    #[test]
+    fn test_cache_keeps_actions_still_being_written() {
+        let cache = RateLimitCache::new(Duration::seconds(5));
+        let limit = ActionLimit::per_hour(3);
+        let key = (Uuid::nil(), ActionType::Vote);
+        let now = Utc::now().trunc_subsecs(6);
+
+        assert_eq!(cache.take_loaded(key, Vec::new(), limit, now), None);
+        assert_eq!(cache.try_take(key, limit, now + Duration::seconds(1)), Some(None));
+
+        // Neither row has reached the table when the entry is read again
+        let later = now + Duration::seconds(10);
+        assert_eq!(cache.try_take(key, limit, later), None);
+        assert_eq!(cache.take_loaded(key, Vec::new(), limit, later), None);
+        let landed = vec![now + Duration::seconds(1), now];
+        assert!(cache.take_loaded(key, landed, limit, later + Duration::seconds(1)).is_some());
+    }
+
+    #[test]
+    fn test_cache_drops_cleared_actions() {
+        let cache = RateLimitCache::new(Duration::seconds(5));
+        let limit = ActionLimit::per_hour(2);
+        let key = (Uuid::nil(), ActionType::Vote);
+        let now = Utc::now().trunc_subsecs(6);
+        let table = vec![now - Duration::seconds(30), now - Duration::seconds(60)];
+
+        assert!(cache.take_loaded(key, table, limit, now).is_some());
+        assert!(cache.try_take(key, limit, now + Duration::seconds(1)).unwrap().is_some());
+
+        // Another instance cleared the window: once stale, the entry is read again and comes back empty
+        let later = now + Duration::seconds(10);
+        assert_eq!(cache.try_take(key, limit, later), None);
+        assert_eq!(cache.take_loaded(key, Vec::new(), limit, later), None);
+
+        cache.forget_user(Uuid::nil());
+        assert_eq!(cache.try_take(key, limit, later), None);
+    }
+
+    #[test]
+    fn test_cache_purges_entries_older_than_a_day() {
+        let cache = RateLimitCache::new(Duration::seconds(5));
+        let limit = ActionLimit::per_hour(2);
+        let now = Utc::now().trunc_subsecs(6);
+        let (old, recent) = ((Uuid::new_v4(), ActionType::Vote), (Uuid::new_v4(), ActionType::Vote));
+
+        cache.take_loaded(old, Vec::new(), limit, now - Duration::minutes(MAX_WINDOW_MINUTES + 1));
+        cache.take_loaded(recent, Vec::new(), limit, now - Duration::minutes(30));
+
+        assert_eq!(cache.purge_stale(now), 1);
+        assert!(!cache.entries.contains_key(&old));
+        assert!(cache.entries.contains_key(&recent));
+    }
+
+    #[test]
+    fn test_cache_bounds_double_spend_across_instances() {
+        let limit = ActionLimit::per_hour(10);
+        let start = Utc::now().trunc_subsecs(6);
+        let hammer = |ttl: Duration| {
+            let instances: Vec<RateLimitCache> = (0..3).map(|_| RateLimitCache::new(ttl)).collect();
+            let (mut table, mut reads) = (Vec::new(), 0);
+            (0..600)
+                .filter(|i| {
+                    let now = start + Duration::milliseconds(100 * i);
+                    take(&instances[*i as usize % 3], &mut table, &mut reads, limit, now)
+                })
+                .count()
+        };
+
+        // Each instance grants at most limit - 1 the others haven't seen
+        let granted = hammer(Duration::seconds(5));
+        assert!(granted >= 10 && granted <= 3 * 9 + 1, "granted {}", granted);
+
+        // Without the cache every check reads the table, so the limit is exact
+        assert_eq!(hammer(Duration::zero()), 10);
+    }
+}
@@ -0,0 +1,282 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::ingest::JobStatus;
+use crate::llm::OllamaClient;
+use crate::models::ReportPeriod;
+use crate::shutdown::ShutdownToken;
+use crate::web;
+
+/// Where finished reports are written (REPORTS_DIR, default ./reports)
+fn reports_dir() -> PathBuf {
+    std::env::var("REPORTS_DIR").unwrap_or_else(|_| "reports".to_string()).into()
+}
+
+/// How long a finished report can be downloaded (REPORT_TTL_HOURS, default 24)
+fn report_ttl() -> Duration {
+    let hours = std::env::var("REPORT_TTL_HOURS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|h| *h > 0)
+        .unwrap_or(24);
+    Duration::hours(hours)
+}
+
+// How often expired reports are looked for
+const CLEANUP_INTERVAL_SECS: u64 = 600;
+
+/// A constituency report being made in the background
+#[derive(Debug, Clone)]
+pub struct ReportJob {
+    pub id: Uuid,
+    pub constituency_id: i32,
+    pub period: ReportPeriod,
+    pub status: JobStatus,
+    /// The download filename once the report is ready
+    pub filename: Option<String>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Report jobs and the directory their PDFs are kept in. Each constituency has at most one job
+/// running, since a second would only repeat the same queries.
+pub struct ReportJobs {
+    jobs: Mutex<HashMap<Uuid, ReportJob>>,
+    dir: PathBuf,
+}
+
+impl ReportJobs {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+            dir,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(reports_dir())
+    }
+
+    /// Where a job's PDF is written
+    pub fn path(&self, id: Uuid) -> PathBuf {
+        self.dir.join(format!("{}.pdf", id))
+    }
+
+    /// Register a new running job, or return the id of the one already running for the constituency
+    fn start(&self, constituency_id: i32, period: ReportPeriod) -> Result<Uuid, Uuid> {
+        let mut jobs = self.jobs.lock();
+        if let Some(running) = jobs
+            .values()
+            .find(|j| j.constituency_id == constituency_id && j.status == JobStatus::Running)
+        {
+            return Err(running.id);
+        }
+
+        let id = Uuid::new_v4();
+        jobs.insert(
+            id,
+            ReportJob {
+                id,
+                constituency_id,
+                period,
+                status: JobStatus::Running,
+                filename: None,
+                error: None,
+                started_at: Utc::now(),
+                finished_at: None,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Record the outcome: the download filename, or why the report couldn't be made
+    fn finish(&self, id: Uuid, result: Result<String>) {
+        if let Some(job) = self.jobs.lock().get_mut(&id) {
+            match result {
+                Ok(filename) => {
+                    job.status = JobStatus::Succeeded;
+                    job.filename = Some(filename);
+                }
+                Err(e) => {
+                    warn!("[report {}] Failed: {:#}", id, e);
+                    job.status = JobStatus::Failed;
+                    job.error = Some(format!("{:#}", e));
+                }
+            }
+            job.finished_at = Some(Utc::now());
+        }
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<ReportJob> {
+        self.jobs.lock().get(&id).cloned()
+    }
+
+    /// Forget jobs that finished more than `ttl` ago and delete their PDFs, along with any PDF in
+    /// the directory older than that, such as those left by an earlier run. Returns how many
+    /// jobs were dropped.
+    pub fn cleanup(&self, ttl: Duration) -> usize {
+        let cutoff = Utc::now() - ttl;
+        let expired: Vec<Uuid> = {
+            let mut jobs = self.jobs.lock();
+            let expired: Vec<Uuid> = jobs
+                .values()
+                .filter(|j| j.finished_at.is_some_and(|t| t <= cutoff))
+                .map(|j| j.id)
+                .collect();
+            for id in &expired {
+                jobs.remove(id);
+            }
+            expired
+        };
+
+        for id in &expired {
+            let path = self.path(*id);
+            if let Err(e) = std::fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Failed to delete expired report {}: {}", path.display(), e);
+                }
+            }
+        }
+
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return expired.len();
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("pdf") {
+                continue;
+            }
+            let modified = entry.metadata().and_then(|m| m.modified()).map(DateTime::<Utc>::from);
+            if modified.is_ok_and(|t| t <= cutoff) {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    warn!("Failed to delete expired report {}: {}", path.display(), e);
+                }
+            }
+        }
+
+        expired.len()
+    }
+}
+
+/// Start making a constituency's report in the background.
+/// Returns the new job's id, or Err with the id of the job already running for that constituency.
+pub fn spawn(
+    jobs: Arc<ReportJobs>,
+    pool: PgPool,
+    llm: OllamaClient,
+    constituency_id: i32,
+    period: ReportPeriod,
+) -> Result<Uuid, Uuid> {
+    let id = jobs.start(constituency_id, period)?;
+
+    tokio::spawn(async move {
+        // The work runs in a task of its own so that a panic, say in the PDF layout, fails the job
+        // instead of leaving it running and blocking the constituency's next report
+        let dir = jobs.dir.clone();
+        let path = jobs.path(id);
+        let work = tokio::spawn(async move {
+            let (filename, pdf) = web::constituency_report(&pool, &llm, constituency_id, &period)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No constituency with ID {}", constituency_id))?;
+            tokio::fs::create_dir_all(&dir).await?;
+            tokio::fs::write(path, pdf).await?;
+            info!("[report {}] Report for constituency {} is ready", id, constituency_id);
+            Ok::<_, anyhow::Error>(filename)
+        });
+
+        let result = match work.await {
+            Ok(result) => result,
+            Err(e) => Err(anyhow::anyhow!("Report task stopped unexpectedly: {}", e)),
+        };
+        jobs.finish(id, result);
+    });
+
+    Ok(id)
+}
+
+/// Delete expired reports every few minutes until shutdown
+pub async fn run_cleanup_job(jobs: Arc<ReportJobs>, shutdown: ShutdownToken) {
+    let ttl = report_ttl();
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(CLEANUP_INTERVAL_SECS));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let cancelled = shutdown.cancelled();
+    tokio::pin!(cancelled);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let removed = jobs.cleanup(ttl);
+                if removed > 0 {
+                    info!("Removed {} expired reports", removed);
+                }
+            }
+            _ = &mut cancelled => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_job_per_constituency() {
+        let jobs = ReportJobs::new(std::env::temp_dir().join(Uuid::new_v4().to_string()));
+        let period = ReportPeriod::default();
+        let first = jobs.start(1, period).unwrap();
+        assert_eq!(jobs.start(1, period), Err(first));
+        // Other constituencies aren't held up
+        let other = jobs.start(2, period).unwrap();
+        assert_ne!(first, other);
+
+        jobs.finish(first, Ok("report.pdf".to_string()));
+        let job = jobs.get(first).unwrap();
+        assert_eq!(job.status, JobStatus::Succeeded);
+        assert_eq!(job.filename.as_deref(), Some("report.pdf"));
+
+        let again = jobs.start(1, period).unwrap();
+        assert_ne!(first, again);
+
+        jobs.finish(other, Err(anyhow::anyhow!("database unavailable")));
+        let job = jobs.get(other).unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.error.as_deref(), Some("database unavailable"));
+    }
+
+    #[test]
+    fn test_cleanup_removes_expired_reports() {
+        let dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        let jobs = ReportJobs::new(dir.clone());
+        let period = ReportPeriod::default();
+
+        let done = jobs.start(1, period).unwrap();
+        std::fs::write(jobs.path(done), b"%PDF").unwrap();
+        jobs.finish(done, Ok("report.pdf".to_string()));
+        let running = jobs.start(2, period).unwrap();
+        // Left behind by an earlier run of the server
+        let stray = dir.join(format!("{}.pdf", Uuid::new_v4()));
+        std::fs::write(&stray, b"%PDF").unwrap();
+
+        // Nothing has expired yet
+        assert_eq!(jobs.cleanup(Duration::hours(1)), 0);
+        assert!(jobs.path(done).exists());
+
+        assert_eq!(jobs.cleanup(Duration::zero()), 1);
+        assert!(jobs.get(done).is_none());
+        assert!(!jobs.path(done).exists());
+        assert!(!stray.exists());
+        // Running jobs are kept
+        assert!(jobs.get(running).is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
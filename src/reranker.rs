@@ -0,0 +1,331 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use candle_core::{Device, IndexOp, Tensor};
+use candle_nn::{Linear, Module, VarBuilder};
+use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+use std::sync::Arc;
+use tokenizers::{Tokenizer, TruncationParams};
+use tokio::sync::Mutex;
+
+use crate::embedder::download_file;
+use crate::llm::OllamaClient;
+use crate::models::SearchResult;
+
+const MODEL_ID: &str = "cross-encoder/ms-marco-MiniLM-L-6-v2";
+
+/// How many dense results are retrieved and rescored when reranking
+pub const RERANK_CANDIDATES: usize = 20;
+
+// Query and passage together are cut to this many tokens. Bill sections are often longer, but
+// the opening is what identifies them, and it keeps 20 candidates within about a second on CPU.
+const MAX_PAIR_TOKENS: usize = 256;
+
+const OLLAMA_RERANK_TIMEOUT_SECS: u64 = 30;
+// Passages are shortened in the Ollama prompt so 20 of them fit in the context window
+const OLLAMA_PASSAGE_CHARS: usize = 600;
+
+/// Scores how well each passage answers a query; the web server holds one behind an Arc
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// One relevance score between 0 and 1 per passage, in the same order
+    async fn score(&self, query: &str, passages: &[String]) -> Result<Vec<f32>>;
+}
+
+struct CrossEncoderModel {
+    model: BertModel,
+    pooler: Linear,
+    classifier: Linear,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+/// The Candle cross-encoder (ms-marco-MiniLM-L-6-v2), which reads query and passage together.
+/// Falls back to scoring with Ollama when the model can't be downloaded or loaded.
+pub struct CrossEncoderReranker {
+    model: Mutex<Option<Arc<std::sync::Mutex<CrossEncoderModel>>>>,
+    fallback: Option<OllamaReranker>,
+}
+
+impl CrossEncoderReranker {
+    pub fn new(fallback: Option<OllamaReranker>) -> Self {
+        Self {
+            model: Mutex::new(None),
+            fallback,
+        }
+    }
+
+    async fn get_or_init_model(&self) -> Result<Arc<std::sync::Mutex<CrossEncoderModel>>> {
+        let mut guard = self.model.lock().await;
+        if let Some(model) = guard.as_ref() {
+            return Ok(model.clone());
+        }
+
+        tracing::info!("Initializing cross-encoder reranker (downloading {} on first run)...", MODEL_ID);
+        let model = tokio::task::spawn_blocking(load_model).await??;
+        let model = Arc::new(std::sync::Mutex::new(model));
+        *guard = Some(model.clone());
+        tracing::info!("✓ Cross-encoder reranker initialized");
+
+        Ok(model)
+    }
+
+    async fn score_with_model(&self, query: &str, passages: &[String]) -> Result<Vec<f32>> {
+        let model = self.get_or_init_model().await?;
+        let query = query.to_string();
+        let passages = passages.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let model = model.lock().map_err(|_| anyhow::anyhow!("Cross-encoder lock poisoned"))?;
+            score_pairs(&model, &query, &passages)
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl Reranker for CrossEncoderReranker {
+    async fn score(&self, query: &str, passages: &[String]) -> Result<Vec<f32>> {
+        if passages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        match self.score_with_model(query, passages).await {
+            Ok(scores) => Ok(scores),
+            Err(e) => match &self.fallback {
+                Some(fallback) => {
+                    tracing::warn!("Cross-encoder unavailable, reranking with Ollama: {:#}", e);
+                    fallback.score(query, passages).await
+                }
+                None => Err(e),
+            },
+        }
+    }
+}
+
+fn load_model() -> Result<CrossEncoderModel> {
+    let cache_dir = dirs::cache_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("huggingface")
+        .join("hub")
+        .join("models--cross-encoder--ms-marco-MiniLM-L-6-v2");
+    std::fs::create_dir_all(&cache_dir)?;
+
+    let base_url = format!("https://huggingface.co/{}/resolve/main", MODEL_ID);
+    let config_path = download_file(&cache_dir, &base_url, "config.json")?;
+    let tokenizer_path = download_file(&cache_dir, &base_url, "tokenizer.json")?;
+    let weights_path = download_file(&cache_dir, &base_url, "model.safetensors")?;
+
+    let config_json = std::fs::read_to_string(config_path)?;
+    let config: Config = serde_json::from_str(&config_json)?;
+    // The classification head isn't part of Candle's BertModel, so its size is read separately
+    let hidden_size = serde_json::from_str::<serde_json::Value>(&config_json)?["hidden_size"]
+        .as_u64()
+        .ok_or_else(|| anyhow::anyhow!("config.json has no hidden_size"))? as usize;
+
+    let mut tokenizer =
+        Tokenizer::from_file(tokenizer_path).map_err(|e| anyhow::anyhow!("Failed to load tokenizer: {}", e))?;
+    tokenizer
+        .with_truncation(Some(TruncationParams {
+            max_length: MAX_PAIR_TOKENS,
+            ..Default::default()
+        }))
+        .map_err(|e| anyhow::anyhow!("Failed to configure tokenizer: {}", e))?;
+
+    let device = Device::Cpu;
+    let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[weights_path], DTYPE, &device)? };
+
+    Ok(CrossEncoderModel {
+        model: BertModel::load(vb.clone(), &config)?,
+        pooler: candle_nn::linear(hidden_size, hidden_size, vb.pp("bert.pooler.dense"))?,
+        classifier: candle_nn::linear(hidden_size, 1, vb.pp("classifier"))?,
+        tokenizer,
+        device,
+    })
+}
+
+fn score_pairs(model: &CrossEncoderModel, query: &str, passages: &[String]) -> Result<Vec<f32>> {
+    let encodings = passages
+        .iter()
+        .map(|passage| {
+            model
+                .tokenizer
+                .encode((query.to_string(), passage.clone()), true)
+                .map_err(|e| anyhow::anyhow!("Tokenization failed: {}", e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let max_len = encodings.iter().map(|e| e.get_ids().len()).max().unwrap_or(0);
+    let padded = |values: &[u32]| {
+        let mut values = values.to_vec();
+        values.resize(max_len, 0);
+        values
+    };
+
+    let ids: Vec<u32> = encodings.iter().flat_map(|e| padded(e.get_ids())).collect();
+    let type_ids: Vec<u32> = encodings.iter().flat_map(|e| padded(e.get_type_ids())).collect();
+    let mask: Vec<u32> = encodings.iter().flat_map(|e| padded(e.get_attention_mask())).collect();
+
+    let shape = (passages.len(), max_len);
+    let tensor = |values: Vec<u32>| -> Result<Tensor> {
+        Ok(Tensor::from_vec(values, shape, &model.device)?.to_dtype(candle_core::DType::I64)?)
+    };
+
+    let hidden = model.model.forward(&tensor(ids)?, &tensor(type_ids)?, Some(&tensor(mask)?))?;
+
+    // BertForSequenceClassification: tanh pooler over the [CLS] token, then a single logit
+    let cls = hidden.i((.., 0))?;
+    let pooled = model.pooler.forward(&cls)?.tanh()?;
+    let logits: Vec<f32> = model.classifier.forward(&pooled)?.squeeze(1)?.to_dtype(candle_core::DType::F32)?.to_vec1()?;
+
+    Ok(logits.into_iter().map(sigmoid).collect())
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// Asks the Ollama model to rate every passage in one prompt; much slower than the cross-encoder
+pub struct OllamaReranker {
+    llm: OllamaClient,
+}
+
+impl OllamaReranker {
+    pub fn new(llm: OllamaClient) -> Self {
+        Self { llm }
+    }
+}
+
+#[async_trait]
+impl Reranker for OllamaReranker {
+    async fn score(&self, query: &str, passages: &[String]) -> Result<Vec<f32>> {
+        if passages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let response = self
+            .llm
+            .generate(&build_rerank_prompt(query, passages), OLLAMA_RERANK_TIMEOUT_SECS)
+            .await?;
+        Ok(parse_rerank_scores(&response, passages.len()))
+    }
+}
+
+fn build_rerank_prompt(query: &str, passages: &[String]) -> String {
+    let mut prompt = format!(
+        "Rate how well each passage from Indian legislation answers the search query, \
+         from 0 (irrelevant) to 10 (directly answers it).\n\
+         Reply with one line per passage in the form \"<number>: <rating>\" and nothing else.\n\n\
+         Query: {}\n",
+        query
+    );
+    for (i, passage) in passages.iter().enumerate() {
+        let passage: String = passage.chars().take(OLLAMA_PASSAGE_CHARS).collect();
+        prompt.push_str(&format!("\n[{}] {}\n", i + 1, passage.replace('\n', " ")));
+    }
+    prompt
+}
+
+/// Ratings from "<number>: <rating>" lines, scaled to 0–1; passages the model skipped score 0
+fn parse_rerank_scores(response: &str, count: usize) -> Vec<f32> {
+    let mut scores = vec![0.0; count];
+    for line in response.lines() {
+        let Some((index, rating)) = line.split_once(':') else {
+            continue;
+        };
+        let index = index.trim().trim_matches(|c| c == '[' || c == ']').parse::<usize>();
+        let rating = rating.split_whitespace().next().and_then(|r| r.parse::<f32>().ok());
+        if let (Ok(index), Some(rating)) = (index, rating) {
+            if (1..=count).contains(&index) {
+                scores[index - 1] = (rating / 10.0).clamp(0.0, 1.0);
+            }
+        }
+    }
+    scores
+}
+
+/// Rescore dense search candidates and keep the best `limit`; each result's score becomes its
+/// rerank score
+pub async fn rerank(
+    reranker: &dyn Reranker,
+    query: &str,
+    candidates: Vec<SearchResult>,
+    limit: usize,
+) -> Result<Vec<SearchResult>> {
+    let passages: Vec<String> = candidates
+        .iter()
+        .map(|r| format!("{}\n{}", r.chunk_identifier, r.content))
+        .collect();
+    let scores = reranker.score(query, &passages).await?;
+    if scores.len() != candidates.len() {
+        anyhow::bail!("Reranker returned {} scores for {} passages", scores.len(), candidates.len());
+    }
+
+    let mut results: Vec<SearchResult> = candidates
+        .into_iter()
+        .zip(scores)
+        .map(|(result, score)| SearchResult { score, ..result })
+        .collect();
+    // Stable, so ties keep their dense retrieval order
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results.truncate(limit);
+
+    Ok(results)
+}
+
+/// The reranker named by RERANKER: "cross-encoder" (the default, falling back to Ollama) or "ollama"
+pub fn from_env(llm: OllamaClient) -> Arc<dyn Reranker> {
+    match std::env::var("RERANKER").as_deref() {
+        Ok("ollama") => Arc::new(OllamaReranker::new(llm)),
+        Ok("cross-encoder") | Err(_) => Arc::new(CrossEncoderReranker::new(Some(OllamaReranker::new(llm)))),
+        Ok(other) => {
+            tracing::error!("Unknown RERANKER {:?}, using the cross-encoder", other);
+            Arc::new(CrossEncoderReranker::new(Some(OllamaReranker::new(llm))))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Scores passages by how often they mention the query
+    struct CountingReranker;
+
+    #[async_trait]
+    impl Reranker for CountingReranker {
+        async fn score(&self, query: &str, passages: &[String]) -> Result<Vec<f32>> {
+            Ok(passages.iter().map(|p| p.matches(query).count() as f32).collect())
+        }
+    }
+
+    fn result(identifier: &str, content: &str, score: f32) -> SearchResult {
+        SearchResult {
+            bill_title: "The Data Bill, 2024".to_string(),
+            bill_number: "1/2024".to_string(),
+            chunk_index: 0,
+            chunk_identifier: identifier.to_string(),
+            content: content.to_string(),
+            score,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rerank_orders_by_rerank_score() {
+        let candidates = vec![
+            result("Clause 1", "definitions", 0.9),
+            result("Clause 2", "consent and consent withdrawal", 0.8),
+            result("Clause 3", "consent managers", 0.7),
+        ];
+
+        let reranked = rerank(&CountingReranker, "consent", candidates, 2).await.unwrap();
+        let order: Vec<_> = reranked.iter().map(|r| r.chunk_identifier.as_str()).collect();
+        assert_eq!(order, ["Clause 2", "Clause 3"]);
+        assert_eq!(reranked[0].score, 2.0);
+    }
+
+    #[test]
+    fn test_parse_rerank_scores() {
+        let response = "1: 9\n[2]: 3/10\nnot a rating\n3: 12\n7: 5";
+        assert_eq!(parse_rerank_scores(response, 4), vec![0.9, 0.0, 1.0, 0.0]);
+    }
+}
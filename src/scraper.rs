@@ -38,6 +38,72 @@ pub async fn fetch_recent_bills(count: usize) -> Result<Vec<Bill>> {
     Ok(bills)
 }
 
+/// Builds a bill from a single URL: either a PRS bill page or a direct link to the bill PDF.
+/// A PDF has no page to read the title from, so it uses `title` or falls back to the file name.
+pub async fn fetch_bill_from_url(url: &str, title: Option<&str>) -> Result<Bill> {
+    let title = title.map(str::trim).filter(|t| !t.is_empty());
+
+    if is_pdf_url(url) {
+        let title = title.map(str::to_string).unwrap_or_else(|| title_from_pdf_url(url));
+        return Ok(Bill::new(title.clone(), extract_bill_number(&title), extract_year_from_title(&title), url.to_string()));
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .context("Failed to fetch bill page")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP error {}: Failed to fetch bill page", response.status());
+    }
+
+    let html = response.text().await?;
+    let page_title = {
+        let document = Html::parse_document(&html);
+        let h1_selector = Selector::parse("h1").unwrap();
+        document
+            .select(&h1_selector)
+            .map(|h1| h1.text().collect::<String>().trim().to_string())
+            .find(|t| t.len() >= 10)
+    };
+
+    let title = match (title, page_title) {
+        (Some(title), _) => title.to_string(),
+        (None, Some(page_title)) => page_title,
+        (None, None) => anyhow::bail!("Could not find the bill title on the page; enter it manually"),
+    };
+
+    let pdf_url = fetch_pdf_url_from_bill_page(url, &client)
+        .await
+        .context("No PDF link found on the bill page")?;
+
+    Ok(Bill::new(title.clone(), extract_bill_number(&title), extract_year_from_title(&title), pdf_url))
+}
+
+fn is_pdf_url(url: &str) -> bool {
+    url.split(['?', '#']).next().unwrap_or(url).to_lowercase().ends_with(".pdf")
+}
+
+// "https://prsindia.org/files/The_Data_Bill%2C_2024.pdf" -> "The Data Bill, 2024"
+fn title_from_pdf_url(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let file = path.rsplit('/').next().unwrap_or(path);
+    let stem = &file[..file.len().saturating_sub(".pdf".len())];
+
+    stem.replace("%20", " ")
+        .replace("%2C", ",")
+        .replace(['_', '-'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 async fn parse_bills_from_html(document: &Html, count: usize, client: &reqwest::Client) -> Result<Vec<Bill>> {
     let mut bills = Vec::new();
     
@@ -174,3 +240,24 @@ fn simple_hash(input: &[u8]) -> u64 {
     hasher.finish()
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_urls_are_recognized() {
+        assert!(is_pdf_url("https://prsindia.org/files/bill.pdf"));
+        assert!(is_pdf_url("https://example.org/Bill.PDF?download=1"));
+        assert!(!is_pdf_url("https://prsindia.org/billtrack/the-data-bill-2024"));
+    }
+
+    #[test]
+    fn test_title_from_pdf_file_name() {
+        assert_eq!(
+            title_from_pdf_url("https://prsindia.org/files/The_Data_Protection_Bill%2C_2024.pdf?x=1"),
+            "The Data Protection Bill, 2024"
+        );
+        assert_eq!(extract_year_from_title(&title_from_pdf_url("https://a.in/Finance-Bill-2023.pdf")), 2023);
+    }
+}
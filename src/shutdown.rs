@@ -0,0 +1,57 @@
+use tokio::sync::watch;
+
+/// Fires the shutdown token for everything holding a copy
+pub struct ShutdownTrigger(watch::Sender<bool>);
+
+/// Handed to the server and background tasks so they can stop cleanly
+#[derive(Clone)]
+pub struct ShutdownToken(watch::Receiver<bool>);
+
+pub fn channel() -> (ShutdownTrigger, ShutdownToken) {
+    let (tx, rx) = watch::channel(false);
+    (ShutdownTrigger(tx), ShutdownToken(rx))
+}
+
+impl ShutdownTrigger {
+    pub fn trigger(&self) {
+        let _ = self.0.send(true);
+    }
+}
+
+impl ShutdownToken {
+    /// Resolves once shutdown has been triggered (or the trigger was dropped)
+    pub async fn cancelled(mut self) {
+        let _ = self.0.wait_for(|stopping| *stopping).await;
+    }
+}
+
+/// Wait for Ctrl+C or, on Unix, SIGTERM
+pub async fn wait_for_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
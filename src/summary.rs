@@ -0,0 +1,298 @@
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db;
+use crate::llm::OllamaClient;
+use crate::models::{BillSummary, ConstituencyPost, ReportPeriod};
+use crate::shutdown::ShutdownToken;
+
+/// Bills need this many approved reviews before they get a summary
+pub const MIN_REVIEWS: i64 = 10;
+
+const SUMMARY_TIMEOUT_SECS: u64 = 120;
+const PROMPT_MAX_REVIEWS: i64 = 50;
+const PROMPT_MAX_REVIEW_CHARS: usize = 600;
+// A report waits on this, so it gives up sooner than the background job
+const REPORT_SUMMARY_TIMEOUT_SECS: u64 = 60;
+const REPORT_PROMPT_POSTS_PER_BILL: usize = 5;
+const REPORT_PROMPT_MAX_BILLS: usize = 10;
+
+/// How far the review count may move before a summary is regenerated (SUMMARY_REGENERATE_DELTA, default 5)
+fn regenerate_delta() -> i64 {
+    std::env::var("SUMMARY_REGENERATE_DELTA")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5)
+}
+
+/// How often the background job looks for stale summaries (SUMMARY_INTERVAL_SECS, default 3600)
+fn job_interval() -> std::time::Duration {
+    let secs = std::env::var("SUMMARY_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&secs| secs > 0)
+        .unwrap_or(3600);
+    std::time::Duration::from_secs(secs)
+}
+
+/// Whole-number stance percentages that always add up to 100 (largest remainder method)
+pub fn stance_percentages(support: i64, oppose: i64, critique: i64) -> (i32, i32, i32) {
+    let total = support + oppose + critique;
+    if total == 0 {
+        return (0, 0, 0);
+    }
+
+    let counts = [support, oppose, critique];
+    let mut shares: Vec<i64> = counts.iter().map(|c| c * 100 / total).collect();
+    let mut by_remainder: Vec<usize> = (0..counts.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(counts[i] * 100 % total));
+
+    let shortfall = 100 - shares.iter().sum::<i64>();
+    for &i in by_remainder.iter().take(shortfall as usize) {
+        shares[i] += 1;
+    }
+
+    (shares[0] as i32, shares[1] as i32, shares[2] as i32)
+}
+
+fn build_prompt(bill_title: &str, reviews: &[(String, String)]) -> String {
+    let reviews = reviews
+        .iter()
+        .map(|(stance, content)| {
+            let content: String = content.chars().take(PROMPT_MAX_REVIEW_CHARS).collect();
+            format!("[{}] {}", stance, content.trim())
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        r#"You are summarizing citizen reviews of an Indian bill for a civic forum. Write a short, neutral summary of 3 to 5 sentences in plain language.
+Describe what supporters emphasize, what opponents worry about, and what critics suggest changing, e.g. "Supporters emphasize ..., while opponents worry ...".
+Do not take a side, do not quote or name reviewers, and do not add facts that are not in the reviews.
+
+Bill: {}
+
+Reviews (stance in brackets):
+{}
+
+Summary:"#,
+        bill_title, reviews
+    )
+}
+
+// The most upvoted posts on each bill, for the bills with the most posts, with the bill title
+fn top_posts_by_bill(posts: &[ConstituencyPost]) -> Vec<(&str, Vec<&ConstituencyPost>)> {
+    let mut bills: Vec<(&str, Vec<&ConstituencyPost>)> = Vec::new();
+    for post in posts {
+        match bills.iter_mut().find(|(title, _)| *title == post.bill_title) {
+            Some((_, bill_posts)) => bill_posts.push(post),
+            None => bills.push((&post.bill_title, vec![post])),
+        }
+    }
+    bills.sort_by_key(|(_, bill_posts)| std::cmp::Reverse(bill_posts.len()));
+    bills.truncate(REPORT_PROMPT_MAX_BILLS);
+    for (_, bill_posts) in &mut bills {
+        bill_posts.sort_by_key(|p| std::cmp::Reverse(p.upvotes));
+        bill_posts.truncate(REPORT_PROMPT_POSTS_PER_BILL);
+    }
+    bills
+}
+
+fn build_report_prompt(constituency_name: &str, posts: &[ConstituencyPost]) -> String {
+    let bills = top_posts_by_bill(posts)
+        .into_iter()
+        .map(|(title, bill_posts)| {
+            let reviews = bill_posts
+                .iter()
+                .map(|p| {
+                    let content: String = p.content.chars().take(PROMPT_MAX_REVIEW_CHARS).collect();
+                    format!("[{}] {}", p.stance, content.trim())
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("Bill: {}\n{}", title, reviews)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        r#"You are briefing a Member of Parliament on what citizens of their constituency, {}, said about bills on a civic forum. Write exactly three short paragraphs in plain language, separated by blank lines.
+The first gives the overall mood across bills, the second the bills people feel most strongly about and why, and the third the changes critics ask for.
+Stay neutral, do not quote or name anyone, and do not add facts that are not in the posts.
+
+Most upvoted posts per bill (stance in brackets):
+{}
+
+Summary:"#,
+        constituency_name, bills
+    )
+}
+
+/// A neutral AI-written overview of what a constituency's posts in `period` say, cached per
+/// constituency and period and written again only once the number of posts changes. None when
+/// there are no posts or the LLM can't be reached, so the report goes without it.
+pub async fn constituency_report_summary(
+    pool: &PgPool,
+    llm: &OllamaClient,
+    constituency_id: i32,
+    constituency_name: &str,
+    period: &ReportPeriod,
+    posts: &[ConstituencyPost],
+) -> Option<String> {
+    if posts.is_empty() {
+        return None;
+    }
+
+    match db::get_constituency_report_summary(pool, constituency_id, period).await {
+        Ok(Some((summary, post_count))) if post_count as usize == posts.len() => return Some(summary),
+        Ok(_) => {}
+        Err(e) => warn!("Failed to load report summary for constituency {}: {:#}", constituency_id, e),
+    }
+
+    let prompt = build_report_prompt(constituency_name, posts);
+    let text = match llm.generate(&prompt, REPORT_SUMMARY_TIMEOUT_SECS).await {
+        Ok(text) if !text.trim().is_empty() => text.trim().to_string(),
+        Ok(_) => {
+            warn!("Ollama returned an empty report summary for constituency {}", constituency_id);
+            return None;
+        }
+        Err(e) => {
+            warn!("Leaving the summary out of constituency {}'s report: {:#}", constituency_id, e);
+            return None;
+        }
+    };
+
+    if let Err(e) = db::upsert_constituency_report_summary(pool, constituency_id, period, &text, posts.len()).await {
+        warn!("Failed to cache report summary for constituency {}: {:#}", constituency_id, e);
+    }
+    Some(text)
+}
+
+/// Summarize a bill's approved reviews with the LLM and store the result.
+/// Returns None when the bill has fewer than MIN_REVIEWS reviews.
+pub async fn summarize_bill(pool: &PgPool, llm: &OllamaClient, bill_id: Uuid) -> Result<Option<BillSummary>> {
+    let bill = db::get_bill_by_id(pool, bill_id)
+        .await?
+        .with_context(|| format!("Bill {} not found", bill_id))?;
+
+    let (support, oppose, critique) = db::get_review_stance_counts(pool, bill_id).await?;
+    let post_count = support + oppose + critique;
+    if post_count < MIN_REVIEWS {
+        return Ok(None);
+    }
+
+    let reviews = db::get_reviews_for_summary(pool, bill_id, PROMPT_MAX_REVIEWS).await?;
+    let prompt = build_prompt(&bill.title, &reviews);
+
+    let text = llm.generate(&prompt, SUMMARY_TIMEOUT_SECS).await?;
+    let text = text.trim();
+    if text.is_empty() {
+        anyhow::bail!("Ollama returned an empty summary");
+    }
+
+    let percentages = stance_percentages(support, oppose, critique);
+    let summary = db::upsert_bill_summary(pool, bill_id, text, percentages, post_count).await?;
+    info!("Summarized {} reviews of {}", post_count, bill.bill_number);
+
+    Ok(Some(summary))
+}
+
+/// Regenerate every summary that is missing or out of date; returns how many were written
+pub async fn refresh_summaries(pool: &PgPool, llm: &OllamaClient) -> Result<usize> {
+    let bill_ids = db::get_bills_needing_summary(pool, MIN_REVIEWS, regenerate_delta()).await?;
+    let mut written = 0;
+
+    for bill_id in bill_ids {
+        match summarize_bill(pool, llm, bill_id).await {
+            Ok(Some(_)) => written += 1,
+            Ok(None) => {}
+            Err(e) => warn!("Failed to summarize bill {}: {:#}", bill_id, e),
+        }
+    }
+
+    Ok(written)
+}
+
+/// Periodically refresh stale summaries until shutdown
+pub async fn run_job(pool: PgPool, llm: OllamaClient, shutdown: ShutdownToken) {
+    let mut interval = tokio::time::interval(job_interval());
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let cancelled = shutdown.cancelled();
+    tokio::pin!(cancelled);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                match refresh_summaries(&pool, &llm).await {
+                    Ok(0) => {}
+                    Ok(n) => info!("Refreshed {} bill summaries", n),
+                    Err(e) => warn!("Summary refresh failed: {:#}", e),
+                }
+            }
+            _ = &mut cancelled => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stance_percentages_sum_to_100() {
+        assert_eq!(stance_percentages(1, 1, 1), (34, 33, 33));
+        assert_eq!(stance_percentages(7, 2, 1), (70, 20, 10));
+        assert_eq!(stance_percentages(2, 1, 0), (67, 33, 0));
+        assert_eq!(stance_percentages(0, 0, 0), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_prompt_includes_stances_and_truncates() {
+        let long = "x".repeat(PROMPT_MAX_REVIEW_CHARS + 100);
+        let reviews = vec![
+            ("Support".to_string(), "Protects privacy.".to_string()),
+            ("Oppose".to_string(), long),
+        ];
+
+        let prompt = build_prompt("The Data Bill, 2024", &reviews);
+        assert!(prompt.contains("Bill: The Data Bill, 2024"));
+        assert!(prompt.contains("[Support] Protects privacy."));
+        assert!(prompt.contains(&format!("[Oppose] {}\n", "x".repeat(PROMPT_MAX_REVIEW_CHARS))));
+        assert!(!prompt.contains(&"x".repeat(PROMPT_MAX_REVIEW_CHARS + 1)));
+    }
+
+    #[test]
+    fn test_report_prompt_uses_top_upvoted_posts_per_bill() {
+        let post = |bill: &str, content: &str, upvotes: i32| ConstituencyPost {
+            bill_id: Uuid::nil(),
+            bill_number: "1".to_string(),
+            bill_title: bill.to_string(),
+            username: "citizen".to_string(),
+            content: content.to_string(),
+            stance: "Support".to_string(),
+            created_at: chrono::Utc::now(),
+            upvotes,
+            downvotes: 0,
+            introduction_date: None,
+        };
+        let mut posts: Vec<ConstituencyPost> = (0..7)
+            .map(|i| post("The Data Bill", &format!("data post {}", i), i))
+            .collect();
+        posts.push(post("The Forest Bill", "forest post", 0));
+
+        let bills = top_posts_by_bill(&posts);
+        assert_eq!(bills.len(), 2);
+        // The bill with more posts comes first, its posts most upvoted first
+        assert_eq!(bills[0].0, "The Data Bill");
+        assert_eq!(bills[0].1.len(), REPORT_PROMPT_POSTS_PER_BILL);
+        assert_eq!(bills[0].1[0].upvotes, 6);
+
+        let prompt = build_report_prompt("Test Nagar", &posts);
+        assert!(prompt.contains("constituency, Test Nagar,"));
+        assert!(prompt.contains("Bill: The Data Bill\n[Support] data post 6"));
+        assert!(prompt.contains("Bill: The Forest Bill\n[Support] forest post"));
+        assert!(!prompt.contains("data post 1\n"));
+    }
+}
@@ -1,9 +1,16 @@
 use anyhow::{Context, Result};
-use crate::models::{Bill, EmbeddedChunk, SearchResult};
+use async_trait::async_trait;
+use crate::models::{ChunkType, EmbeddedChunk, SearchResult, SimilarBill, StoredChunk};
+use parking_lot::Mutex;
 use serde_json::json;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
 
 const COLLECTION_NAME: &str = "legislation_chunks";
 const VECTOR_SIZE: usize = 384; // all-MiniLM-L6-v2 dimension
+// Related bills only change when bills are ingested, so they're recomputed at most hourly
+const SIMILAR_BILLS_TTL: Duration = Duration::from_secs(3600);
 
 fn get_qdrant_url() -> String {
     std::env::var("QDRANT_URL").unwrap_or_else(|_| "http://localhost:6333".to_string())
@@ -69,24 +76,23 @@ pub async fn initialize_collection() -> Result<()> {
     Ok(())
 }
 
-/// Store embedded chunks in Qdrant
-pub async fn store_chunks(bill: &Bill, chunks: &[EmbeddedChunk]) -> Result<()> {
+/// Store a bill's embedded chunks in Qdrant under their chunk ids, then drop any other
+/// points the bill still has from an earlier ingest
+pub async fn store_chunks(bill_title: &str, year: i32, chunks: &[EmbeddedChunk]) -> Result<()> {
     let base_url = get_qdrant_url();
     let client = reqwest::Client::new();
     
     let mut points = Vec::new();
     
     for chunk in chunks {
-        let point_id = uuid::Uuid::new_v4().to_string();
-        
         let point = json!({
-            "id": point_id,
+            "id": chunk.chunk.id.to_string(),
             "vector": chunk.embedding,
             "payload": {
-                "bill_id": bill.id.to_string(),
-                "bill_title": bill.title,
-                "bill_number": bill.bill_number,
-                "year": bill.year,
+                "bill_id": chunk.chunk.bill_id.to_string(),
+                "bill_title": bill_title,
+                "bill_number": chunk.chunk.bill_number,
+                "year": year,
                 "chunk_index": chunk.chunk.chunk_index,
                 "chunk_type": chunk.chunk.chunk_type.to_string(),
                 "chunk_identifier": chunk.chunk.chunk_identifier,
@@ -118,37 +124,359 @@ pub async fn store_chunks(bill: &Bill, chunks: &[EmbeddedChunk]) -> Result<()> {
         }
     }
     
-    tracing::debug!("Stored {} chunks for bill: {}", chunks.len(), bill.title);
+    if let Some(first) = chunks.first() {
+        let ids: Vec<Uuid> = chunks.iter().map(|c| c.chunk.id).collect();
+        let delete_url = format!("{}/collections/{}/points/delete", base_url, COLLECTION_NAME);
+        let delete_body = json!({ "filter": stale_points_filter(&first.chunk.bill_number, &ids) });
+        
+        let response = client
+            .post(&delete_url)
+            .json(&delete_body)
+            .send()
+            .await
+            .context("Failed to delete old points")?;
+        
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to delete old points: {}", error_text);
+        }
+    }
+    
+    tracing::debug!("Stored {} chunks for bill: {}", chunks.len(), bill_title);
     Ok(())
 }
 
-/// Search for similar chunks using a query vector
-pub async fn search(query_vector: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
-    let base_url = get_qdrant_url();
-    let client = reqwest::Client::new();
+// Points of the bill other than `keep`. Matched on the bill number rather than bill_id, since
+// points from before bills were stored, or from `ingest --no-db`, carry a random bill_id.
+fn stale_points_filter(bill_number: &str, keep: &[Uuid]) -> serde_json::Value {
+    let keep: Vec<String> = keep.iter().map(Uuid::to_string).collect();
+    json!({
+        "must": [{ "key": "bill_number", "match": { "value": bill_number } }],
+        "must_not": [{ "has_id": keep }]
+    })
+}
+
+/// Payload filters that narrow a vector search
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchFilter {
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    pub chunk_type: Option<ChunkType>,
+    pub bill_number: Option<String>,
+}
+
+impl SearchFilter {
+    /// Build the Qdrant filter clause, or None when nothing is filtered
+    fn to_qdrant(&self) -> Option<serde_json::Value> {
+        let mut must = Vec::new();
+        
+        if self.year_from.is_some() || self.year_to.is_some() {
+            let mut range = serde_json::Map::new();
+            if let Some(from) = self.year_from {
+                range.insert("gte".to_string(), json!(from));
+            }
+            if let Some(to) = self.year_to {
+                range.insert("lte".to_string(), json!(to));
+            }
+            must.push(json!({ "key": "year", "range": range }));
+        }
+        
+        if let Some(chunk_type) = self.chunk_type {
+            must.push(json!({ "key": "chunk_type", "match": { "value": chunk_type.to_string() } }));
+        }
+        
+        if let Some(bill_number) = &self.bill_number {
+            must.push(json!({ "key": "bill_number", "match": { "value": bill_number } }));
+        }
+        
+        if must.is_empty() {
+            None
+        } else {
+            Some(json!({ "must": must }))
+        }
+    }
+}
+
+/// Read access to the vector index, shared by the web handlers
+#[async_trait]
+pub trait VectorStore: Send + Sync {
+    /// Search for similar chunks using a query vector
+    async fn search(&self, query_vector: &[f32], limit: usize, filter: &SearchFilter) -> Result<Vec<SearchResult>>;
     
-    let search_url = format!("{}/collections/{}/points/search", base_url, COLLECTION_NAME);
-    let search_body = json!({
-        "vector": query_vector,
-        "limit": limit,
-        "with_payload": true
-    });
+    /// Run several searches with the same limit and filter, one result list per query vector
+    async fn search_batch(
+        &self,
+        query_vectors: &[Vec<f32>],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<Vec<SearchResult>>> {
+        let mut results = Vec::with_capacity(query_vectors.len());
+        for query_vector in query_vectors {
+            results.push(self.search(query_vector, limit, filter).await?);
+        }
+        Ok(results)
+    }
     
-    let response = client
-        .post(&search_url)
-        .json(&search_body)
-        .send()
-        .await
-        .context("Failed to search vectors")?;
+    /// Fetch every stored chunk of a bill, ordered by chunk index
+    async fn get_bill_chunks(&self, bill_number: &str) -> Result<Vec<StoredChunk>>;
     
-    if !response.status().is_success() {
-        let error_text = response.text().await?;
-        anyhow::bail!("Failed to search: {}", error_text);
+    /// Other bills closest to the average of this bill's chunk vectors, best first.
+    /// Empty when the bill has no stored chunks.
+    async fn similar_bills(&self, bill_id: Uuid, limit: usize) -> Result<Vec<SimilarBill>>;
+    
+    /// Check whether the collection exists; errors mean the store is unreachable
+    async fn collection_exists(&self) -> Result<bool>;
+}
+
+/// Qdrant over its REST API, reusing one HTTP client for every call
+pub struct QdrantStore {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl QdrantStore {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            base_url: get_qdrant_url(),
+        }
     }
+}
+
+#[async_trait]
+impl VectorStore for QdrantStore {
+    #[tracing::instrument(name = "qdrant.search", skip_all, fields(limit = limit))]
+    async fn search(&self, query_vector: &[f32], limit: usize, filter: &SearchFilter) -> Result<Vec<SearchResult>> {
+        let base_url = &self.base_url;
+        let client = &self.client;
     
-    let search_result: serde_json::Value = response.json().await?;
+        let search_url = format!("{}/collections/{}/points/search", base_url, COLLECTION_NAME);
+        let mut search_body = json!({
+            "vector": query_vector,
+            "limit": limit,
+            "with_payload": true
+        });
+        if let Some(qdrant_filter) = filter.to_qdrant() {
+            search_body["filter"] = qdrant_filter;
+        }
+    
+        let response = client
+            .post(&search_url)
+            .json(&search_body)
+            .send()
+            .await
+            .context("Failed to search vectors")?;
     
-    let results: Vec<SearchResult> = search_result["result"]
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to search: {}", error_text);
+        }
+    
+        let search_result: serde_json::Value = response.json().await?;
+    
+        Ok(parse_scored_points(&search_result["result"]))
+    }
+    
+    #[tracing::instrument(name = "qdrant.search_batch", skip_all, fields(searches = query_vectors.len(), limit = limit))]
+    async fn search_batch(
+        &self,
+        query_vectors: &[Vec<f32>],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<Vec<SearchResult>>> {
+        if query_vectors.is_empty() {
+            return Ok(vec![]);
+        }
+        
+        let qdrant_filter = filter.to_qdrant();
+        let searches: Vec<serde_json::Value> = query_vectors
+            .iter()
+            .map(|vector| {
+                let mut search = json!({
+                    "vector": vector,
+                    "limit": limit,
+                    "with_payload": true
+                });
+                if let Some(qdrant_filter) = &qdrant_filter {
+                    search["filter"] = qdrant_filter.clone();
+                }
+                search
+            })
+            .collect();
+        
+        let batch_url = format!("{}/collections/{}/points/search/batch", self.base_url, COLLECTION_NAME);
+        let response = self.client
+            .post(&batch_url)
+            .json(&json!({ "searches": searches }))
+            .send()
+            .await
+            .context("Failed to batch search vectors")?;
+        
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to batch search: {}", error_text);
+        }
+        
+        let batch_result: serde_json::Value = response.json().await?;
+        
+        Ok(batch_result["result"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(parse_scored_points)
+            .collect())
+    }
+    
+    #[tracing::instrument(name = "qdrant.get_bill_chunks", skip(self))]
+    async fn get_bill_chunks(&self, bill_number: &str) -> Result<Vec<StoredChunk>> {
+        let base_url = &self.base_url;
+        let client = &self.client;
+    
+        let scroll_url = format!("{}/collections/{}/points/scroll", base_url, COLLECTION_NAME);
+        let mut chunks: Vec<StoredChunk> = Vec::new();
+        let mut offset = serde_json::Value::Null;
+    
+        loop {
+            let scroll_body = json!({
+                "filter": {
+                    "must": [
+                        { "key": "bill_number", "match": { "value": bill_number } }
+                    ]
+                },
+                "limit": 256,
+                "offset": offset,
+                "with_payload": true,
+                "with_vector": false
+            });
+        
+            let response = client
+                .post(&scroll_url)
+                .json(&scroll_body)
+                .send()
+                .await
+                .context("Failed to scroll points")?;
+        
+            if !response.status().is_success() {
+                let error_text = response.text().await?;
+                anyhow::bail!("Failed to scroll points: {}", error_text);
+            }
+        
+            let scroll_result: serde_json::Value = response.json().await?;
+        
+            if let Some(points) = scroll_result["result"]["points"].as_array() {
+                chunks.extend(points.iter().filter_map(|item| {
+                    let payload = &item["payload"];
+                    Some(StoredChunk {
+                        chunk_index: payload["chunk_index"].as_u64()? as usize,
+                        chunk_type: payload["chunk_type"].as_str()?.to_string(),
+                        chunk_identifier: payload["chunk_identifier"].as_str()?.to_string(),
+                        content: payload["content"].as_str()?.to_string(),
+                    })
+                }));
+            }
+        
+            offset = scroll_result["result"]["next_page_offset"].clone();
+            if offset.is_null() {
+                break;
+            }
+        }
+    
+        // Re-ingesting a bill stores its chunks again, keep one copy of each
+        chunks.sort_by_key(|c| c.chunk_index);
+        chunks.dedup_by_key(|c| c.chunk_index);
+    
+        Ok(chunks)
+    }
+    
+    #[tracing::instrument(name = "qdrant.similar_bills", skip(self))]
+    async fn similar_bills(&self, bill_id: Uuid, limit: usize) -> Result<Vec<SimilarBill>> {
+        let bill_filter = json!({ "key": "bill_id", "match": { "value": bill_id.to_string() } });
+        
+        // Every chunk vector of the bill, to average into one vector for the whole bill
+        let scroll_url = format!("{}/collections/{}/points/scroll", self.base_url, COLLECTION_NAME);
+        let mut vectors: Vec<Vec<f32>> = Vec::new();
+        let mut offset = serde_json::Value::Null;
+        loop {
+            let response = self.client
+                .post(&scroll_url)
+                .json(&json!({
+                    "filter": { "must": [bill_filter] },
+                    "limit": 256,
+                    "offset": offset,
+                    "with_payload": false,
+                    "with_vector": true
+                }))
+                .send()
+                .await
+                .context("Failed to scroll bill vectors")?;
+            
+            if !response.status().is_success() {
+                let error_text = response.text().await?;
+                anyhow::bail!("Failed to scroll bill vectors: {}", error_text);
+            }
+            
+            let scroll_result: serde_json::Value = response.json().await?;
+            if let Some(points) = scroll_result["result"]["points"].as_array() {
+                vectors.extend(points.iter().filter_map(|item| {
+                    item["vector"]
+                        .as_array()?
+                        .iter()
+                        .map(|v| v.as_f64().map(|v| v as f32))
+                        .collect::<Option<Vec<f32>>>()
+                }));
+            }
+            
+            offset = scroll_result["result"]["next_page_offset"].clone();
+            if offset.is_null() {
+                break;
+            }
+        }
+        
+        let Some(centroid) = mean_vector(&vectors) else {
+            return Ok(vec![]);
+        };
+        
+        // One hit per bill, so a long bill can't fill every slot with its own chunks
+        let groups_url = format!("{}/collections/{}/points/search/groups", self.base_url, COLLECTION_NAME);
+        let response = self.client
+            .post(&groups_url)
+            .json(&json!({
+                "vector": centroid,
+                "group_by": "bill_id",
+                "group_size": 1,
+                "limit": limit,
+                "with_payload": true,
+                "filter": { "must_not": [bill_filter] }
+            }))
+            .send()
+            .await
+            .context("Failed to search similar bills")?;
+        
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to search similar bills: {}", error_text);
+        }
+        
+        let groups_result: serde_json::Value = response.json().await?;
+        
+        Ok(parse_bill_groups(&groups_result["result"]["groups"]))
+    }
+    
+    async fn collection_exists(&self) -> Result<bool> {
+        let url = format!("{}/collections/{}", self.base_url, COLLECTION_NAME);
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to reach Qdrant")?;
+    
+        Ok(response.status().is_success())
+    }
+}
+
+// Turn a Qdrant list of scored points into search results, skipping malformed payloads
+fn parse_scored_points(points: &serde_json::Value) -> Vec<SearchResult> {
+    points
         .as_array()
         .unwrap_or(&vec![])
         .iter()
@@ -157,14 +485,135 @@ pub async fn search(query_vector: &[f32], limit: usize) -> Result<Vec<SearchResu
             Some(SearchResult {
                 bill_title: payload["bill_title"].as_str()?.to_string(),
                 bill_number: payload["bill_number"].as_str()?.to_string(),
+                chunk_index: payload["chunk_index"].as_u64()? as usize,
                 chunk_identifier: payload["chunk_identifier"].as_str()?.to_string(),
                 content: payload["content"].as_str()?.to_string(),
                 score: item["score"].as_f64()? as f32,
             })
         })
-        .collect();
+        .collect()
+}
+
+// Turn Qdrant search groups (one best hit per bill) into similar bills, skipping malformed payloads
+fn parse_bill_groups(groups: &serde_json::Value) -> Vec<SimilarBill> {
+    groups
+        .as_array()
+        .unwrap_or(&vec![])
+        .iter()
+        .filter_map(|group| {
+            let hit = group["hits"].as_array()?.first()?;
+            let payload = &hit["payload"];
+            Some(SimilarBill {
+                bill_id: payload["bill_id"].as_str()?.parse().ok()?,
+                title: payload["bill_title"].as_str()?.to_string(),
+                bill_number: payload["bill_number"].as_str()?.to_string(),
+                year: payload["year"].as_i64()? as i32,
+                score: hit["score"].as_f64()? as f32,
+            })
+        })
+        .collect()
+}
+
+// Unit-length average of the vectors, or None when there are none to average
+fn mean_vector(vectors: &[Vec<f32>]) -> Option<Vec<f32>> {
+    let first = vectors.first()?;
+    let mut mean = vec![0.0f32; first.len()];
+    for vector in vectors.iter().filter(|v| v.len() == mean.len()) {
+        for (m, x) in mean.iter_mut().zip(vector) {
+            *m += x;
+        }
+    }
+    
+    let norm = mean.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return None;
+    }
+    Some(mean.into_iter().map(|x| x / norm).collect())
+}
+
+/// Related bills per bill and limit, reused for SIMILAR_BILLS_TTL so forum page views
+/// don't each pull every chunk vector of the bill out of Qdrant
+#[derive(Default)]
+pub struct SimilarBillsCache {
+    entries: Mutex<HashMap<(Uuid, usize), (Instant, Vec<SimilarBill>)>>,
+}
+
+impl SimilarBillsCache {
+    /// The cached list, searching the store when there is none or it has expired. Failures aren't cached.
+    pub async fn get(&self, store: &dyn VectorStore, bill_id: Uuid, limit: usize) -> Result<Vec<SimilarBill>> {
+        let now = Instant::now();
+        if let Some((computed_at, bills)) = self.entries.lock().get(&(bill_id, limit)) {
+            if now.duration_since(*computed_at) < SIMILAR_BILLS_TTL {
+                return Ok(bills.clone());
+            }
+        }
+        
+        let bills = store.similar_bills(bill_id, limit).await?;
+        
+        let mut entries = self.entries.lock();
+        entries.retain(|_, (computed_at, _)| now.duration_since(*computed_at) < SIMILAR_BILLS_TTL);
+        entries.insert((bill_id, limit), (now, bills.clone()));
+        Ok(bills)
+    }
+}
+
+/// Vector store held in memory, for handler tests; only the bill number filter is applied
+#[cfg(test)]
+#[derive(Default)]
+pub struct InMemoryStore {
+    pub points: Vec<(Vec<f32>, SearchResult)>,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl VectorStore for InMemoryStore {
+    async fn search(&self, query_vector: &[f32], limit: usize, filter: &SearchFilter) -> Result<Vec<SearchResult>> {
+        let cosine = |a: &[f32], b: &[f32]| {
+            let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+            let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+            dot / (norm(a) * norm(b)).max(f32::EPSILON)
+        };
+        
+        let mut results: Vec<SearchResult> = self
+            .points
+            .iter()
+            .filter(|(_, result)| filter.bill_number.as_ref().is_none_or(|n| *n == result.bill_number))
+            .map(|(vector, result)| SearchResult {
+                score: cosine(query_vector, vector),
+                ..result.clone()
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit);
+        
+        Ok(results)
+    }
+    
+    async fn get_bill_chunks(&self, bill_number: &str) -> Result<Vec<StoredChunk>> {
+        let mut chunks: Vec<StoredChunk> = self
+            .points
+            .iter()
+            .filter(|(_, r)| r.bill_number == bill_number)
+            .map(|(_, r)| StoredChunk {
+                chunk_index: r.chunk_index,
+                chunk_type: "Clause".to_string(),
+                chunk_identifier: r.chunk_identifier.clone(),
+                content: r.content.clone(),
+            })
+            .collect();
+        chunks.sort_by_key(|c| c.chunk_index);
+        
+        Ok(chunks)
+    }
     
-    Ok(results)
+    // Points here carry no bill ids, so nothing is ever related
+    async fn similar_bills(&self, _bill_id: Uuid, _limit: usize) -> Result<Vec<SimilarBill>> {
+        Ok(vec![])
+    }
+    
+    async fn collection_exists(&self) -> Result<bool> {
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -179,4 +628,118 @@ mod tests {
         let response = client.get(format!("{}/collections", base_url)).send().await;
         assert!(response.is_ok());
     }
+    
+    #[test]
+    fn test_empty_filter_is_omitted() {
+        assert!(SearchFilter::default().to_qdrant().is_none());
+    }
+    
+    #[test]
+    fn test_filter_to_qdrant() {
+        let filter = SearchFilter {
+            year_from: Some(2020),
+            year_to: Some(2024),
+            chunk_type: Some(ChunkType::Clause),
+            bill_number: Some("12/2024".to_string()),
+        };
+        
+        assert_eq!(
+            filter.to_qdrant().unwrap(),
+            json!({
+                "must": [
+                    { "key": "year", "range": { "gte": 2020, "lte": 2024 } },
+                    { "key": "chunk_type", "match": { "value": "Clause" } },
+                    { "key": "bill_number", "match": { "value": "12/2024" } }
+                ]
+            })
+        );
+    }
+    
+    #[test]
+    fn test_stale_points_are_found_by_bill_number() {
+        let kept = Uuid::new_v4();
+        let filter = stale_points_filter("12/2024", &[kept]);
+        
+        // A point left by an earlier ingest under a random bill_id still matches
+        let legacy_point = json!({ "bill_id": Uuid::new_v4().to_string(), "bill_number": "12/2024" });
+        let must = &filter["must"][0];
+        assert_eq!(legacy_point[must["key"].as_str().unwrap()], must["match"]["value"]);
+        assert!(!filter.to_string().contains("bill_id"));
+        assert_eq!(filter["must_not"][0]["has_id"], json!([kept.to_string()]));
+    }
+    
+    #[test]
+    fn test_mean_vector_is_normalized() {
+        let mean = mean_vector(&[vec![1.0, 0.0], vec![0.0, 1.0]]).unwrap();
+        assert!((mean[0] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert!((mean[1] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert!(mean_vector(&[]).is_none());
+        assert!(mean_vector(&[vec![0.0, 0.0]]).is_none());
+    }
+    
+    #[test]
+    fn test_parse_bill_groups() {
+        let bill_id = Uuid::new_v4();
+        let groups = json!([
+            {
+                "id": bill_id.to_string(),
+                "hits": [{
+                    "score": 0.83,
+                    "payload": {
+                        "bill_id": bill_id.to_string(),
+                        "bill_title": "The Data Bill, 2024",
+                        "bill_number": "12/2024",
+                        "year": 2024
+                    }
+                }]
+            },
+            { "id": "broken", "hits": [{ "score": 0.5, "payload": { "bill_id": "not-a-uuid" } }] }
+        ]);
+        
+        let bills = parse_bill_groups(&groups);
+        assert_eq!(bills.len(), 1);
+        assert_eq!(bills[0].bill_id, bill_id);
+        assert_eq!(bills[0].year, 2024);
+        assert!((bills[0].score - 0.83).abs() < 1e-6);
+    }
+    
+    // Counts similar_bills calls to check the cache
+    #[derive(Default)]
+    struct CountingStore {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+    
+    #[async_trait]
+    impl VectorStore for CountingStore {
+        async fn search(&self, _query_vector: &[f32], _limit: usize, _filter: &SearchFilter) -> Result<Vec<SearchResult>> {
+            Ok(vec![])
+        }
+        
+        async fn get_bill_chunks(&self, _bill_number: &str) -> Result<Vec<StoredChunk>> {
+            Ok(vec![])
+        }
+        
+        async fn similar_bills(&self, _bill_id: Uuid, _limit: usize) -> Result<Vec<SimilarBill>> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(vec![])
+        }
+        
+        async fn collection_exists(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_similar_bills_are_cached_per_bill() {
+        let store = CountingStore::default();
+        let cache = SimilarBillsCache::default();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        
+        cache.get(&store, a, 5).await.unwrap();
+        cache.get(&store, a, 5).await.unwrap();
+        assert_eq!(store.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        
+        cache.get(&store, b, 5).await.unwrap();
+        assert_eq!(store.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
 }
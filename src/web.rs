@@ -1,68 +1,399 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::{Html, IntoResponse, Redirect, Response},
-    routing::{get, post},
-    Form, Router,
+    body::Body,
+    extract::{ConnectInfo, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Redirect, Response,
+    },
+    routing::{get, post, put},
+    Form, Json, Router,
 };
 use askama::Template;
-use axum_extra::extract::cookie::{Cookie, CookieJar};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tower::{ServiceBuilder, ServiceExt};
+use tower_http::request_id::{MakeRequestId, PropagateRequestIdLayer, RequestId, SetRequestIdLayer};
+use tower_http::compression::CompressionLayer;
 use tower_http::services::ServeDir;
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    IntoParams, Modify, OpenApi, ToSchema,
+};
+use utoipa_swagger_ui::SwaggerUi;
 use uuid::Uuid;
 
-use crate::{auth, db, embedder, moderation, models, rate_limit, vector_store};
+use crate::auth::RequireRole;
+use crate::embedder::Embedder;
+use crate::i18n::{Locale, LOCALE_COOKIE_NAME};
+use crate::llm::OllamaClient;
+use crate::ingest::{self, IngestJobs};
+use crate::mailer::{self, Mailer};
+use crate::shutdown::ShutdownToken;
+use crate::vector_store::VectorStore;
+use crate::{
+    assets, auth, challenge, compare, db, digest, embedder, maintenance, moderation, models, oauth, rag, rate_limit,
+    report_jobs, reranker, summary, vector_store,
+};
 
-const SESSION_COOKIE_NAME: &str = "session_token";
+pub(crate) const SESSION_COOKIE_NAME: &str = "session_token";
+const REQUEST_ID_HEADER: &str = "x-request-id";
+const DEFAULT_EDIT_WINDOW_MINUTES: i64 = 15;
+const DEFAULT_REPORT_THRESHOLD: i64 = 3;
+const REVIEWS_PER_PAGE: i64 = 20;
+const USERS_PER_PAGE: i64 = 50;
+const AUDIT_ENTRIES_PER_PAGE: i64 = 50;
+// Held posts re-moderated per click, so the request finishes in reasonable time
+const REMODERATE_BATCH_SIZE: i64 = 25;
+const FEED_MAX_ENTRIES: i64 = 50;
+const SITEMAP_MAX_URLS: i64 = 10_000;
+const SITEMAP_BATCH_SIZE: i64 = 1_000;
+const EXPORT_BATCH_SIZE: i64 = 500;
+// Unmatched sections per bill that get an embedding-based counterpart suggestion
+const COMPARE_MAX_SUGGESTIONS: usize = 40;
+const TRENDING_WINDOW_DAYS: i32 = 14;
+// Longest title search accepted by the bills list; longer input is cut
+const MAX_BILL_QUERY_CHARS: usize = 200;
+// Length of the bill text excerpt shown under each search suggestion
+const SNIPPET_CHARS: usize = 200;
+const TRENDING_LIMIT: i64 = 5;
+const RELATED_BILLS_LIMIT: usize = 5;
+// Longest search query kept in the search logs
+const SEARCH_LOG_MAX_CHARS: usize = 200;
+const SEARCH_STATS_DAYS: i64 = 30;
+const SEARCH_STATS_LIMIT: i64 = 25;
+// Members listed on the admin rate limit page, by refused actions over the last day
+const THROTTLED_USERS_LIMIT: i64 = 25;
+// The cross-encoder takes about a second for 20 candidates; this only cuts off a stalled model
+// or a slow Ollama fallback
+const RERANK_TIMEOUT_SECS: u64 = 5;
+const API_MAX_TRENDING_WINDOW_DAYS: i32 = 90;
+// Longest statement an author may attach to an appeal of a rejected post
+const APPEAL_STATEMENT_MAX_CHARS: usize = 1000;
+const REPORT_REASONS: [&str; 6] = ["spam", "harassment", "hate_speech", "misinformation", "off_topic", "other"];
 
 // Application State
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: PgPool,
+    pub vector_store: Arc<dyn VectorStore>,
+    pub embedder: Arc<dyn Embedder>,
+    pub llm: OllamaClient,
+    pub reranker: Arc<dyn reranker::Reranker>,
+    /// Tried in order, ending with the keyword filter; chosen by MODERATION_BACKEND
+    pub moderation: Vec<Arc<dyn moderation::ModerationProvider>>,
+    /// The keyword filter's lists, cleared when an admin changes them
+    pub keywords: Arc<moderation::KeywordCache>,
+    pub mailer: Arc<dyn Mailer>,
+    pub challenge: Arc<dyn challenge::Challenge>,
+    /// Google sign-in; None unless GOOGLE_CLIENT_ID and GOOGLE_CLIENT_SECRET are set
+    pub google_oauth: Option<Arc<oauth::GoogleOAuth>>,
+    pub similar_bills: Arc<vector_store::SimilarBillsCache>,
+    pub ingest_jobs: Arc<IngestJobs>,
+    /// Constituency reports being made in the background, and where finished ones are kept
+    pub report_jobs: Arc<report_jobs::ReportJobs>,
+    /// Newly approved reviews, fanned out to the forum pages' event streams
+    pub review_events: broadcast::Sender<ReviewEvent>,
+    /// Reviews waiting for a background verdict; None when MODERATION_MODE=sync
+    pub moderation_queue: Option<moderation::ModerationQueue>,
+    /// Per-user limits on posting, voting, reporting and searching, read from RATE_LIMIT_* at startup
+    pub rate_limits: rate_limit::RateLimitConfig,
+    /// Recent actions per user, so most rate limit checks skip the rate_limits table
+    pub rate_limit_cache: Arc<rate_limit::RateLimitCache>,
+    pub shutdown: ShutdownToken,
+}
+
+/// Something that happened to a review, for the open event streams of its bill's forum
+#[derive(Debug, Clone, Copy)]
+pub enum ReviewEvent {
+    /// A top-level review was just published
+    Published { bill_id: Uuid, post_id: Uuid },
+    /// The moderation queue decided a review; only its author's pages hear about it
+    Moderated { bill_id: Uuid, post_id: Uuid, author_id: Uuid },
+}
+
+impl ReviewEvent {
+    fn bill_id(&self) -> Uuid {
+        match self {
+            ReviewEvent::Published { bill_id, .. } | ReviewEvent::Moderated { bill_id, .. } => *bill_id,
+        }
+    }
 }
 
+// Slow subscribers that fall this far behind skip the missed reviews instead of blocking publishers
+const REVIEW_EVENTS_CAPACITY: usize = 256;
+
 // Templates
 #[derive(Template)]
 #[template(path = "index.html")]
 struct IndexTemplate {
-    recent_bills: Vec<RecentBill>,
+    bills: Vec<RecentBill>,
+    filters: BillFiltersView,
+    trending_bills: Vec<TrendingBill>,
     current_page: i64,
     total_pages: i64,
     has_prev: bool,
     has_next: bool,
     user: Option<CurrentUser>,
+    lang: Locale,
 }
 
 #[derive(Template)]
 #[template(path = "search_suggestions.html")]
 struct SearchSuggestionsTemplate {
-    results: Vec<SearchResult>,
+    results: Vec<SearchHit>,
+    chips: Vec<FilterChip>,
+    keyword_fallback: bool,
+    lang: Locale,
 }
 
 #[derive(Template)]
 #[template(path = "forum.html")]
 struct ForumTemplate {
     bill: BillInfo,
+    following: bool,
+    bookmark: BookmarkState,
+    summary: Option<ReviewSummary>,
+    can_regenerate_summary: bool,
+    own_unpublished: Vec<UnpublishedReview>,
+    reviews: Vec<Review>,
+    total_reviews: i64,
+    load_more_url: Option<String>,
+    user: Option<CurrentUser>,
+    /// None when the user is exempt from the posting limit
+    rate_limit_remaining: Option<i64>,
+    retry_message: Option<String>,
+    /// Seconds until another post is allowed, 0 while posts remain
+    retry_after_secs: i64,
+    /// The wait comes from the daily posting limit rather than the hourly one
+    daily_limit_reached: bool,
+    form_notice: Option<String>,
+    form_error: Option<String>,
+    sort: &'static str,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "review_replies.html")]
+struct ReviewRepliesTemplate {
+    reviews: Vec<Review>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "review_page.html")]
+struct ReviewPageTemplate {
     reviews: Vec<Review>,
+    load_more_url: Option<String>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "constituency.html")]
+struct ConstituencyTemplate {
+    constituency: ConstituencyOption,
+    constituencies: Vec<ConstituencyOption>,
+    bills: Vec<BillSentimentBar>,
+    participants: i64,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "constituencies.html")]
+struct ConstituenciesTemplate {
+    rows: Vec<ConstituencyRank>,
+    window: &'static str,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "review_form.html")]
+struct ReviewFormTemplate {
+    bill: BillInfo,
+    /// None when the user is exempt from the posting limit
+    rate_limit_remaining: Option<i64>,
+    retry_message: Option<String>,
+    /// Seconds until another post is allowed, 0 while posts remain
+    retry_after_secs: i64,
+    /// The wait comes from the daily posting limit rather than the hourly one
+    daily_limit_reached: bool,
+    form_notice: Option<String>,
+    form_error: Option<String>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "moderation_notice.html")]
+struct ModerationNoticeTemplate {
+    notice: String,
+}
+
+#[derive(Template)]
+#[template(path = "follow_button.html")]
+struct FollowButtonTemplate {
+    bill: BillInfo,
+    following: bool,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "bookmark_button.html")]
+struct BookmarkButtonTemplate {
+    bookmark: BookmarkState,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "related_bills.html")]
+struct RelatedBillsTemplate {
+    bills: Vec<RelatedBill>,
+    lang: Locale,
+}
+
+struct RelatedBill {
+    id: String,
+    title: String,
+    number: String,
+    year: i32,
+    // Cosine similarity as a whole percentage
+    similarity: i64,
+}
+
+#[derive(Template)]
+#[template(path = "bookmarks.html")]
+struct BookmarksTemplate {
+    bills: Vec<BookmarkedBillItem>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "notifications.html")]
+struct NotificationsTemplate {
+    notifications: Vec<NotificationItem>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "sessions.html")]
+struct SessionsTemplate {
+    sessions: Vec<SessionItem>,
+    // How many sessions "log out everywhere else" just ended
+    notice: Option<String>,
     user: Option<CurrentUser>,
-    rate_limit_remaining: i64,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "api_tokens.html")]
+struct ApiTokensTemplate {
+    tokens: Vec<ApiTokenItem>,
+    // The token just created; it's stored hashed, so this is the only time it can be shown
+    new_token: Option<String>,
+    error: Option<String>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "feed.xml")]
+struct FeedTemplate {
+    base_url: String,
+    updated: String,
+    entries: Vec<FeedEntry>,
+}
+
+#[derive(Template)]
+#[template(path = "vote_buttons.html")]
+struct VoteButtonsTemplate {
+    review: VoteState,
 }
 
 #[derive(Template)]
 #[template(path = "forum_page.html")]
 struct ForumPageTemplate {
     bill: BillInfo,
+    following: bool,
+    bookmark: BookmarkState,
+    summary: Option<ReviewSummary>,
+    can_regenerate_summary: bool,
+    own_unpublished: Vec<UnpublishedReview>,
     reviews: Vec<Review>,
+    total_reviews: i64,
+    load_more_url: Option<String>,
+    user: Option<CurrentUser>,
+    /// None when the user is exempt from the posting limit
+    rate_limit_remaining: Option<i64>,
+    retry_message: Option<String>,
+    /// Seconds until another post is allowed, 0 while posts remain
+    retry_after_secs: i64,
+    /// The wait comes from the daily posting limit rather than the hourly one
+    daily_limit_reached: bool,
+    form_notice: Option<String>,
+    form_error: Option<String>,
+    sort: &'static str,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "bill.html")]
+struct BillTemplate {
+    bill: BillDetail,
+    sections: Vec<BillSection>,
+    sections_unavailable: bool,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "compare.html")]
+struct CompareTemplate {
+    bill_a: BillInfo,
+    bill_b: BillInfo,
+    rows: Vec<CompareRow>,
+    matched: usize,
+    unmatched: usize,
+    sections_unavailable: bool,
     user: Option<CurrentUser>,
-    rate_limit_remaining: i64,
+    lang: Locale,
 }
 
 #[derive(Template)]
 #[template(path = "login.html")]
 struct LoginTemplate {
     error: Option<String>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "forgot_password.html")]
+struct ForgotPasswordTemplate {
+    sent: bool,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "reset_password.html")]
+struct ResetPasswordTemplate {
+    token: String,
+    token_valid: bool,
+    error: Option<String>,
+    done: bool,
+    lang: Locale,
 }
 
 #[derive(Template)]
@@ -70,6 +401,8 @@ struct LoginTemplate {
 struct RegisterTemplate {
     error: Option<String>,
     constituencies: Vec<ConstituencyOption>,
+    challenge: challenge::ChallengeWidget,
+    lang: Locale,
 }
 
 #[derive(Template)]
@@ -80,16 +413,226 @@ struct ProfileTemplate {
     is_own_profile: bool,
     user: Option<CurrentUser>,
     constituencies: Vec<ConstituencyOption>,
+    security: SecurityFeedback,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "admin_users.html")]
+struct AdminUsersTemplate {
+    users: Vec<AdminUserRow>,
+    current_page: i64,
+    total_pages: i64,
+    has_prev: bool,
+    has_next: bool,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "admin_search_stats.html")]
+struct AdminSearchStatsTemplate {
+    days: i64,
+    total_searches: i64,
+    zero_result_searches: i64,
+    volume: Vec<SearchVolumeRow>,
+    top_queries: Vec<SearchQueryRow>,
+    zero_result_queries: Vec<SearchQueryRow>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "admin_rate_limits.html")]
+struct AdminRateLimitsTemplate {
+    totals: Vec<RateLimitTotalsRow>,
+    throttled: Vec<ThrottledUserRow>,
+    action_labels: Vec<String>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+struct RateLimitTotalsRow {
+    action: String,
+    allowed: i64,
+    rejected: i64,
+}
+
+struct ThrottledUserRow {
+    id: String,
+    username: String,
+    rejected: i64,
+    last_rejected: String,
+    // "remaining / limit" per action in ActionType::ALL order, or None when the member is exempt.
+    // Remaining is the fewest left in any window; the limit shown is the burst one.
+    quotas: Vec<Option<String>>,
+}
+
+struct SearchVolumeRow {
+    day: String,
+    searches: i64,
+    zero_results: i64,
+    searchers: i64,
+    // Bar width relative to the busiest day
+    percent: i64,
+}
+
+struct SearchQueryRow {
+    query: String,
+    searches: i64,
+    avg_results: String,
+    last_searched: String,
+}
+
+#[derive(Template)]
+#[template(path = "admin_post_moderation.html")]
+struct AdminPostModerationTemplate {
+    post_id: String,
+    bill_id: String,
+    content: String,
+    status: String,
+    reason: Option<String>,
+    provider: Option<String>,
+    events: Vec<ModerationEventRow>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+struct ModerationEventRow {
+    date: String,
+    provider: String,
+    verdict: Option<String>,
+    trust_bias: Option<String>,
+    error: Option<String>,
+    latency_ms: i32,
+    prompt_hash: String,
+    raw_response: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "admin_audit.html")]
+struct AdminAuditTemplate {
+    entries: Vec<AuditRow>,
+    actions: Vec<String>,
+    // Echoed back into the filter form
+    filter_user: String,
+    filter_from: String,
+    filter_to: String,
+    filter_action: String,
+    current_page: i64,
+    total_pages: i64,
+    has_prev: bool,
+    has_next: bool,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+struct AuditRow {
+    date: String,
+    action: String,
+    actor: Option<AuditUser>,
+    // Set when the target is a user; other targets (posts) show their id
+    target: Option<AuditUser>,
+    target_id: String,
+    details: String,
+    ip_address: String,
+    user_agent: String,
+}
+
+struct AuditUser {
+    id: String,
+    username: String,
+}
+
+#[derive(Template)]
+#[template(path = "admin_ingest.html")]
+struct AdminIngestTemplate {
+    jobs: Vec<IngestJobView>,
+    error: Option<String>,
+    running_job_id: Option<String>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "admin_ingest_job.html")]
+struct AdminIngestJobTemplate {
+    job: IngestJobView,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "ingest_job_status.html")]
+struct IngestJobStatusTemplate {
+    job: IngestJobView,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "report_job.html")]
+struct ReportJobTemplate {
+    job: ReportJobView,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "report_job_status.html")]
+struct ReportJobStatusTemplate {
+    job: ReportJobView,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "admin_user.html")]
+struct AdminUserTemplate {
+    member: AdminUserRow,
+    roles: Vec<String>,
+    /// The member's rate limit multiplier as a form value, empty for their role's default
+    rate_limit: String,
+    rate_limit_choices: Vec<String>,
+    exempt_by_default: bool,
+    history: Vec<ModerationItem>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "admin_keywords.html")]
+struct AdminKeywordsTemplate {
+    keywords: Vec<KeywordRow>,
+    kinds: Vec<String>,
+    actions: Vec<String>,
+    severities: Vec<String>,
+    languages: Vec<String>,
+    error: Option<String>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "admin_moderation.html")]
+struct AdminModerationTemplate {
+    posts: Vec<PendingPost>,
+    // Approved on their authors' trust, waiting for a spot-check
+    sampled: Vec<PendingPost>,
+    appeals: Vec<AppealedPost>,
+    remoderated: Option<RemoderatedCounts>,
+    user: Option<CurrentUser>,
+    lang: Locale,
 }
 
 #[derive(Template)]
 #[template(path = "bills_list.html")]
 struct BillsListTemplate {
     bills: Vec<RecentBill>,
+    filters: BillFiltersView,
     current_page: i64,
     total_pages: i64,
     has_prev: bool,
     has_next: bool,
+    lang: Locale,
 }
 
 // Data structures
@@ -97,23 +640,121 @@ struct BillsListTemplate {
 struct CurrentUser {
     id: String,
     username: String,
+    is_admin: bool,
+    can_moderate: bool,
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, ToSchema)]
 struct RecentBill {
     id: String,
     title: String,
     number: String,
     year: i32,
+    // The viewer's bookmark on the bill; None for anonymous visitors and API clients
+    #[serde(skip)]
+    bookmark: Option<BookmarkState>,
 }
 
-#[derive(Clone, Serialize)]
-struct SearchResult {
+// Whether the viewer has bookmarked a bill, for the star button
+#[derive(Clone)]
+struct BookmarkState {
+    bill_id: String,
+    bookmarked: bool,
+}
+
+struct BookmarkedBillItem {
+    bookmark: BookmarkState,
+    id: String,
+    title: String,
+    number: String,
+    year: i32,
+    status: Option<String>,
+    saved: String,
+}
+
+// Options and current values for the bills list filter controls
+struct BillFiltersView {
+    years: Vec<String>,
+    statuses: Vec<String>,
+    year: String,
+    status: String,
+    q: String,
+    // "&year=..&status=..&q=.." for the pagination links, empty when unfiltered
+    query_string: String,
+}
+
+impl BillFiltersView {
+    fn new(filter: &models::BillFilter, years: &[i32], statuses: Vec<String>) -> Self {
+        let year = filter.year.map(|y| y.to_string()).unwrap_or_default();
+        let status = filter.status.clone().unwrap_or_default();
+        let q = filter.query.clone().unwrap_or_default();
+
+        let query_string = [("year", &year), ("status", &status), ("q", &q)]
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, value)| format!("&{}={}", key, encode_path_segment(value)))
+            .collect();
+
+        Self {
+            years: years.iter().map(|y| y.to_string()).collect(),
+            statuses,
+            year,
+            status,
+            q,
+            query_string,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        !self.query_string.is_empty()
+    }
+}
+
+/// A bill with recent forum activity; `score` decays each post and vote by its age
+#[derive(Clone, Serialize, ToSchema)]
+struct TrendingBill {
+    id: String,
+    title: String,
+    number: String,
+    year: i32,
+    post_count: i64,
+    score: f64,
+}
+
+impl From<models::TrendingBill> for TrendingBill {
+    fn from(b: models::TrendingBill) -> Self {
+        Self {
+            id: b.id.to_string(),
+            title: b.title,
+            number: b.bill_number,
+            year: b.year,
+            post_count: b.post_count,
+            score: b.score,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+struct SearchHit {
     bill_id: String,
     bill_title: String,
     bill_number: String,
     section: String,
+    section_anchor: String,
     score: String,
+    /// Whether `score` is a cross-encoder relevance score rather than vector similarity
+    reranked: bool,
+    // Escaped HTML excerpt with matched query terms in <mark>; empty for keyword fallback hits
+    #[serde(skip)]
+    snippet: String,
+}
+
+// A toggle in the suggestions dropdown; `vals` are the hx-vals for the search it triggers
+#[derive(Clone)]
+struct FilterChip {
+    label: String,
+    vals: String,
+    active: bool,
 }
 
 #[derive(Clone, Serialize)]
@@ -124,267 +765,891 @@ struct BillInfo {
     year: i32,
 }
 
+/// AI-generated synthesis of a bill's reviews, shown above the forum
 #[derive(Clone, Serialize)]
-struct Review {
+struct ReviewSummary {
+    text: String,
+    support_pct: i32,
+    oppose_pct: i32,
+    critique_pct: i32,
+    post_count: i32,
+    generated_at: String,
+}
+
+#[derive(Clone, Serialize)]
+struct BillDetail {
     id: String,
-    username: String,
-    constituency: String,
-    stance: String,
+    title: String,
+    number: String,
+    year: i32,
+    session: Option<String>,
+    status: Option<String>,
+    introduction_date: Option<String>,
+    pdf_url: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct BillSection {
+    anchor: String,
+    identifier: String,
+    chunk_type: String,
     content: String,
-    date: String,
-    upvotes: i32,
-    downvotes: i32,
 }
 
 #[derive(Clone, Serialize)]
-struct ConstituencyOption {
-    id: i32,
-    name: String,
-    state: String,
+struct CompareRow {
+    left: Option<BillSection>,
+    right: Option<BillSection>,
+    // Closest section of the other bill when this row has only one side
+    suggestion: Option<CompareSuggestion>,
 }
 
 #[derive(Clone, Serialize)]
-struct ProfileData {
-    username: String,
-    real_name: Option<String>,
-    age: Option<i32>,
-    gender: Option<String>,
-    pincode: Option<String>,
-    constituency_id: i32,  // 0 if not set
-    constituency_name: Option<String>,
-    member_since: String,
-    post_count: i64,
+struct CompareSuggestion {
+    anchor: String,
+    identifier: String,
+    score: String,
 }
 
 #[derive(Clone, Serialize)]
-struct UserPost {
+struct Review {
     id: String,
-    bill_title: String,
-    bill_number: String,
+    username: String,
+    constituency: String,
+    is_anonymous: bool,
     stance: String,
     content: String,
-    moderation_status: String,
+    // Escaped content with @mentions of existing users linked
+    #[serde(skip)]
+    content_html: String,
     date: String,
+    edited_date: Option<String>,
     upvotes: i32,
     downvotes: i32,
+    can_edit: bool,
+    can_delete: bool,
+    can_report: bool,
+    is_reply: bool,
+    reply_count: i64,
+    // "upvote", "downvote" or empty when the viewer hasn't voted
+    user_vote: String,
 }
 
-// Query parameters
-#[derive(Deserialize)]
-struct SearchQuery {
-    query: String,
+// The viewer's own review that is still in moderation or was rejected; shown only to its author
+struct UnpublishedReview {
+    stance: String,
+    content: String,
+    date: String,
+    moderation_status: String,
+    moderation_reason: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct PaginationQuery {
-    page: Option<i64>,
+// Vote counts and the viewer's vote after an upvote/downvote
+struct VoteState {
+    id: String,
+    upvotes: i32,
+    downvotes: i32,
+    user_vote: String,
 }
 
-#[derive(Deserialize)]
-struct ReviewForm {
-    stance: String,
-    content: String,
+#[derive(Clone, Serialize)]
+struct ConstituencyOption {
+    id: i32,
+    name: String,
+    state: String,
 }
 
-#[derive(Deserialize)]
-struct LoginForm {
-    username: String,
-    password: String,
+#[derive(Clone)]
+struct NotificationItem {
+    id: String,
+    bill_id: String,
+    kind: String,
+    message: String,
+    date: String,
 }
 
-#[derive(Deserialize)]
-struct RegisterForm {
+struct IngestJobView {
+    id: String,
+    url: String,
+    status: &'static str,
+    status_label: &'static str,
+    started: String,
+    finished: Option<String>,
+    log: Vec<String>,
+}
+
+impl IngestJobView {
+    fn new(job: ingest::IngestJob, lang: Locale) -> Self {
+        let status_label = match job.status {
+            ingest::JobStatus::Running => lang.t("ingest.status_running"),
+            ingest::JobStatus::Succeeded => lang.t("ingest.status_succeeded"),
+            ingest::JobStatus::Failed => lang.t("ingest.status_failed"),
+        };
+
+        Self {
+            id: job.id.to_string(),
+            url: job.url,
+            status: job.status.as_str(),
+            status_label,
+            started: job.started_at.format("%B %d, %Y %H:%M:%S").to_string(),
+            finished: job.finished_at.map(|t| t.format("%H:%M:%S").to_string()),
+            log: job.log,
+        }
+    }
+}
+
+struct ReportJobView {
+    id: String,
+    constituency_id: i32,
+    period: String,
+    status: &'static str,
+    status_label: &'static str,
+    started: String,
+}
+
+impl ReportJobView {
+    fn new(job: report_jobs::ReportJob, lang: Locale) -> Self {
+        let status_label = match job.status {
+            ingest::JobStatus::Running => lang.t("report_job.status_running"),
+            ingest::JobStatus::Succeeded => lang.t("report_job.status_succeeded"),
+            ingest::JobStatus::Failed => lang.t("report_job.status_failed"),
+        };
+
+        Self {
+            id: job.id.to_string(),
+            constituency_id: job.constituency_id,
+            period: job.period.describe(),
+            status: job.status.as_str(),
+            status_label,
+            started: job.started_at.format("%B %d, %Y %H:%M:%S").to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct AdminUserRow {
+    id: String,
     username: String,
-    password: String,
-    real_name: Option<String>,
-    age: Option<String>,
-    gender: Option<String>,
-    location_type: String, // "pincode" or "constituency"
-    pincode: Option<String>,
-    constituency_id: Option<String>,
+    role: String,
+    suspended: bool,
+    joined: String,
+    post_count: i64,
+    rejected_count: i64,
 }
 
-#[derive(Deserialize)]
-struct ProfileUpdateForm {
-    real_name: Option<String>,
-    age: Option<String>,
-    gender: Option<String>,
-    location_type: String,
-    pincode: Option<String>,
-    constituency_id: Option<String>,
+#[derive(Clone, Serialize)]
+struct ModerationItem {
+    bill_id: String,
+    bill_title: String,
+    content: String,
+    status: String,
+    reason: Option<String>,
+    moderator: Option<String>,
+    moderated: Option<String>,
+    report_count: i64,
+    date: String,
 }
 
-// Helper to get current user from session
-async fn get_current_user(jar: &CookieJar, pool: &PgPool) -> Option<models::User> {
-    let session_token = jar.get(SESSION_COOKIE_NAME)?.value().to_string();
-    auth::get_user_by_session(pool, &session_token).await.ok()?
+#[derive(Clone, Serialize)]
+struct FeedEntry {
+    id: String,
+    title: String,
+    published: String,
+    updated: String,
+    summary: String,
 }
 
-// Handlers
-async fn index(
-    State(state): State<Arc<AppState>>,
-    jar: CookieJar,
-    Query(params): Query<PaginationQuery>,
-) -> impl IntoResponse {
-    let page = params.page.unwrap_or(1).max(1);
-    let per_page = 5;
+#[derive(Clone, Serialize)]
+struct SessionItem {
+    id: String,
+    token_preview: String,
+    created: String,
+    expires: String,
+    current: bool,
+}
 
-    let user = get_current_user(&jar, &state.db_pool).await;
-    let current_user = user.map(|u| CurrentUser {
-        id: u.id.to_string(),
-        username: u.username,
-    });
+struct ApiTokenItem {
+    id: String,
+    name: String,
+    scope: &'static str,
+    created: String,
+    last_used: Option<String>,
+}
 
-    let (bills, total) = db::get_bills_paginated(&state.db_pool, page, per_page)
-        .await
-        .unwrap_or((vec![], 0));
+// One row of the constituency leaderboard; constituencies without posts have no rank
+struct ConstituencyRank {
+    rank: Option<usize>,
+    id: i32,
+    name: String,
+    state: String,
+    posts: i64,
+    participants: i64,
+}
 
-    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+// One bill's stance counts, with percentages for the stacked bar widths
+#[derive(Clone)]
+struct BillSentimentBar {
+    bill_id: String,
+    bill_number: String,
+    bill_title: String,
+    support: i64,
+    oppose: i64,
+    critique: i64,
+    total: i64,
+    support_pct: String,
+    oppose_pct: String,
+    critique_pct: String,
+}
 
-    let recent_bills: Vec<RecentBill> = bills
-        .into_iter()
-        .map(|b| RecentBill {
-            id: b.id.to_string(),
-            title: b.title,
-            number: b.bill_number,
-            year: b.year,
-        })
-        .collect();
+#[derive(Clone, Serialize)]
+struct ProfileData {
+    username: String,
+    real_name: Option<String>,
+    age: Option<i32>,
+    gender: Option<String>,
+    pincode: Option<String>,
+    visibility: models::ProfileVisibility,
+    email: Option<String>, // owner only
+    email_verified: bool,
+    digest_enabled: bool, // owner only
+    password_set: bool,   // owner only
+    // The linked Google account, shown by its email; owner only, and only when Google sign-in is on
+    google: Option<GoogleLink>,
+    constituency_id: i32,  // 0 if not set
+    constituency_name: Option<String>,
+    member_since: String,
+    post_count: i64,
+}
 
-    let template = IndexTemplate {
-        recent_bills,
-        current_page: page,
-        total_pages,
-        has_prev: page > 1,
-        has_next: page < total_pages,
-        user: current_user,
-    };
+struct GoogleLink {
+    linked: bool,
+    email: Option<String>,
+}
 
-    HtmlTemplate(template)
+#[derive(Clone, Serialize)]
+struct UserPost {
+    id: String,
+    bill_title: String,
+    bill_number: String,
+    stance: String,
+    content: String,
+    moderation_status: String,
+    moderation_reason: Option<String>,
+    is_anonymous: bool,
+    date: String,
+    upvotes: i32,
+    downvotes: i32,
+    // Rejected and never appealed; only set on the author's own profile
+    can_appeal: bool,
 }
 
-async fn bills_list_handler(
-    State(state): State<Arc<AppState>>,
-    Query(params): Query<PaginationQuery>,
-) -> impl IntoResponse {
-    let page = params.page.unwrap_or(1).max(1);
-    let per_page = 5;
+#[derive(Clone, Serialize)]
+struct PendingPost {
+    id: String,
+    // The real author, even for anonymous posts
+    username: String,
+    is_anonymous: bool,
+    bill_id: String,
+    bill_title: String,
+    bill_number: String,
+    stance: String,
+    content: String,
+    reason: Option<String>,
+    // The moderation provider that judged it; None for posts from before providers were recorded
+    provider: Option<String>,
+    report_count: i64,
+    report_reasons: Option<String>,
+    date: String,
+    duplicate: Option<DuplicateOriginal>,
+}
 
-    let (bills, total) = db::get_bills_paginated(&state.db_pool, page, per_page)
-        .await
-        .unwrap_or((vec![], 0));
+// A rejected post in the appeals queue, shown with the author's statement
+#[derive(Clone, Serialize)]
+struct AppealedPost {
+    id: String,
+    username: String,
+    is_anonymous: bool,
+    bill_id: String,
+    bill_title: String,
+    bill_number: String,
+    stance: String,
+    content: String,
+    reason: Option<String>,
+    provider: Option<String>,
+    statement: Option<String>,
+    date: String,
+    appealed: String,
+}
 
-    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+// A keyword moderation pattern on the admin page
+struct KeywordRow {
+    id: String,
+    pattern: String,
+    is_regex: bool,
+    kind: String,
+    action: String,
+    severity: String,
+    language: String,
+    enabled: bool,
+    updated: String,
+}
 
-    let bills: Vec<RecentBill> = bills
-        .into_iter()
-        .map(|b| RecentBill {
-            id: b.id.to_string(),
-            title: b.title,
-            number: b.bill_number,
-            year: b.year,
-        })
-        .collect();
+struct RemoderatedCounts {
+    approved: usize,
+    rejected: usize,
+    pending: usize,
+}
 
-    HtmlTemplate(BillsListTemplate {
-        bills,
-        current_page: page,
-        total_pages,
-        has_prev: page > 1,
-        has_next: page < total_pages,
-    })
+// The earlier post a queued review closely matched
+struct DuplicateOriginal {
+    username: String,
+    content: String,
+    status: String,
+    similarity: i64,
+    date: String,
 }
 
-async fn search_handler(
-    State(state): State<Arc<AppState>>,
-    Query(params): Query<SearchQuery>,
-) -> impl IntoResponse {
-    let query = params.query.trim();
+// Query parameters
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SearchQuery {
+    query: String,
+    limit: Option<usize>,
+    // Filters are kept as strings so a bad value is dropped instead of rejecting the request
+    year: Option<String>,
+    year_from: Option<String>,
+    year_to: Option<String>,
+    chunk_type: Option<String>,
+    /// Rescore the top 20 semantic matches with a cross-encoder before taking the best (`true` or `1`)
+    rerank: Option<String>,
+}
 
-    if query.is_empty() {
-        return HtmlTemplate(SearchSuggestionsTemplate { results: vec![] });
+impl SearchQuery {
+    fn rerank(&self) -> bool {
+        matches!(self.rerank.as_deref().map(str::trim), Some("true" | "1" | "on"))
     }
 
-    match perform_search(query, &state.db_pool).await {
-        Ok(results) => HtmlTemplate(SearchSuggestionsTemplate { results }),
-        Err(_) => HtmlTemplate(SearchSuggestionsTemplate { results: vec![] }),
+    fn filter(&self) -> vector_store::SearchFilter {
+        let parse_year = |value: &Option<String>| value.as_deref().and_then(|v| v.trim().parse::<i32>().ok());
+        let year = parse_year(&self.year);
+
+        vector_store::SearchFilter {
+            year_from: year.or_else(|| parse_year(&self.year_from)),
+            year_to: year.or_else(|| parse_year(&self.year_to)),
+            chunk_type: self.chunk_type.as_deref().and_then(models::ChunkType::parse),
+            bill_number: None,
+        }
     }
 }
 
-async fn bill_forum_handler(
-    State(state): State<Arc<AppState>>,
-    jar: CookieJar,
-    Path(bill_id): Path<String>,
-) -> impl IntoResponse {
-    let bill_uuid = match Uuid::parse_str(&bill_id) {
-        Ok(id) => id,
-        Err(_) => {
-            return (StatusCode::BAD_REQUEST, "Invalid bill ID").into_response();
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BillsQuery {
+    page: Option<i64>,
+    /// Only bills from this year; ignored if no stored bill has it
+    year: Option<String>,
+    /// Only bills with this status (case-insensitive); ignored if no stored bill has it
+    status: Option<String>,
+    /// Words or a substring to look for in bill titles
+    q: Option<String>,
+}
+
+impl BillsQuery {
+    /// The filters to apply, dropping values that don't match any stored year or status
+    fn filter(&self, years: &[i32], statuses: &[String]) -> models::BillFilter {
+        models::BillFilter {
+            year: self
+                .year
+                .as_deref()
+                .and_then(|y| y.trim().parse().ok())
+                .filter(|y| years.contains(y)),
+            status: self
+                .status
+                .as_deref()
+                .map(str::trim)
+                .and_then(|s| statuses.iter().find(|known| known.eq_ignore_ascii_case(s)))
+                .cloned(),
+            query: self
+                .q
+                .as_deref()
+                .map(str::trim)
+                .filter(|q| !q.is_empty())
+                .map(|q| q.chars().take(MAX_BILL_QUERY_CHARS).collect()),
         }
-    };
+    }
+}
 
-    let user = get_current_user(&jar, &state.db_pool).await;
-    let current_user = user.as_ref().map(|u| CurrentUser {
-        id: u.id.to_string(),
-        username: u.username.clone(),
+// Filters for a bills list request, with the view of them for the filter controls
+async fn bill_filters(pool: &PgPool, params: &BillsQuery) -> (models::BillFilter, BillFiltersView) {
+    let (years, statuses) = db::get_bill_facets(pool).await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to fetch bill filter options: {}", e);
+        (vec![], vec![])
     });
+    let filter = params.filter(&years, &statuses);
+    let view = BillFiltersView::new(&filter, &years, statuses);
+    (filter, view)
+}
 
-    let rate_limit_remaining = if let Some(ref u) = user {
-        rate_limit::get_remaining_posts(&state.db_pool, u.id)
-            .await
-            .unwrap_or(0)
-    } else {
-        0
-    };
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PaginationQuery {
+    page: Option<i64>,
+}
 
-    let bill = match db::get_bill_by_id(&state.db_pool, bill_uuid).await {
-        Ok(Some(b)) => BillInfo {
-            id: b.id.to_string(),
-            title: b.title,
-            number: b.bill_number,
-            year: b.year,
-        },
-        _ => {
-            return (StatusCode::NOT_FOUND, "Bill not found").into_response();
-        }
-    };
+#[derive(Deserialize)]
+struct AuditLogQuery {
+    user: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    action: Option<String>,
+    page: Option<i64>,
+}
 
-    let posts = db::get_posts_for_bill(&state.db_pool, bill_uuid)
-        .await
-        .unwrap_or_default();
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct TrendingQuery {
+    /// Days of forum activity to consider (default 14, max 90)
+    window_days: Option<i32>,
+    /// Number of bills to return (default 5, max 20)
+    limit: Option<i64>,
+}
 
-    let reviews: Vec<Review> = posts
-        .into_iter()
-        .map(|p| Review {
-            id: p.id.to_string(),
-            username: p.username,
-            constituency: p.constituency_name.unwrap_or_else(|| "Unknown".to_string()),
-            stance: p.stance,
-            content: p.content,
-            date: p.formatted_date,
-            upvotes: p.upvotes,
-            downvotes: p.downvotes,
+#[derive(Deserialize)]
+struct BookmarkForm {
+    // Missing means bookmark
+    bookmarked: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct LeaderboardQuery {
+    window: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ForumQuery {
+    sort: Option<String>,
+    page: Option<i64>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CompareQuery {
+    bill_a: Option<String>,
+    bill_b: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReviewForm {
+    stance: String,
+    content: String,
+    // Checkbox; present only when ticked
+    #[serde(default)]
+    anonymous: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReplyForm {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct EditReviewForm {
+    content: String,
+    stance: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReportForm {
+    reason: String,
+}
+
+#[derive(Deserialize)]
+struct AppealForm {
+    statement: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RejectForm {
+    reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GoogleStartQuery {
+    // Set when a signed-in user links Google from their profile instead of signing in
+    link: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GoogleCallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LoginForm {
+    username: String,
+    password: String,
+}
+
+#[derive(Deserialize)]
+struct RegisterForm {
+    username: String,
+    password: String,
+    real_name: Option<String>,
+    email: Option<String>,
+    age: Option<String>,
+    gender: Option<String>,
+    location_type: String, // "pincode" or "constituency"
+    pincode: Option<String>,
+    constituency_id: Option<String>,
+    // Answer to the registration challenge, filled in by the form's script
+    #[serde(default)]
+    challenge_response: String,
+}
+
+#[derive(Deserialize)]
+struct ApiTokenForm {
+    name: String,
+    scope: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PasswordChangeForm {
+    current_password: String,
+    new_password: String,
+    confirm_password: String,
+}
+
+#[derive(Deserialize)]
+struct ProfileQuery {
+    password: Option<String>,
+    // Other sessions signed out and API tokens revoked by a password change
+    revoked: Option<u64>,
+    tokens_revoked: Option<u64>,
+    email: Option<String>,
+    google: Option<String>,
+}
+
+// Counts from a re-moderation run, shown once on the queue after the redirect
+#[derive(Deserialize)]
+struct ModerationQueueQuery {
+    approved: Option<usize>,
+    rejected: Option<usize>,
+    pending: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct SessionsQuery {
+    revoked: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct EmailForm {
+    email: String,
+}
+
+#[derive(Deserialize)]
+struct DigestForm {
+    // Checkbox: present only when ticked
+    digest_enabled: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct VerifyEmailQuery {
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct IngestForm {
+    url: String,
+    title: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ForgotPasswordForm {
+    email: String,
+}
+
+#[derive(Deserialize)]
+struct ResetPasswordQuery {
+    #[serde(default)]
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct ResetPasswordForm {
+    new_password: String,
+    confirm_password: String,
+}
+
+#[derive(Deserialize)]
+struct RoleForm {
+    role: String,
+}
+
+#[derive(Deserialize)]
+struct RateLimitForm {
+    /// Empty for the role's default, 0 to exempt
+    multiplier: String,
+}
+
+#[derive(Deserialize)]
+struct KeywordForm {
+    pattern: String,
+    kind: String,
+    action: String,
+    severity: String,
+    language: String,
+    // Checkbox: present when the pattern is a regex
+    regex: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KeywordUpdateForm {
+    kind: String,
+    action: String,
+    severity: String,
+    // Checkbox: present when the pattern is applied
+    enabled: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DeleteAccountForm {
+    // Absent for accounts without a password
+    #[serde(default)]
+    password: String,
+}
+
+// Inline results of the profile page's forms
+#[derive(Default)]
+struct SecurityFeedback {
+    location_error: Option<String>,
+    current_error: Option<String>,
+    new_error: Option<String>,
+    password_notice: Option<String>,
+    email_notice: Option<String>,
+    email_error: Option<String>,
+    google_notice: Option<String>,
+    google_error: Option<String>,
+    delete_error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProfileUpdateForm {
+    real_name: Option<String>,
+    age: Option<String>,
+    gender: Option<String>,
+    location_type: String,
+    pincode: Option<String>,
+    constituency_id: Option<String>,
+    // Checkboxes: present only when ticked
+    real_name_public: Option<String>,
+    age_public: Option<String>,
+    gender_public: Option<String>,
+    pincode_public: Option<String>,
+}
+
+// Helper to get current user from session
+async fn get_current_user(jar: &CookieJar, pool: &PgPool) -> Option<models::User> {
+    let session_token = jar.get(SESSION_COOKIE_NAME)?.value().to_string();
+    auth::get_user_by_session(pool, &session_token).await.ok()?
+}
+
+// Handlers
+async fn index(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Query(params): Query<BillsQuery>,
+) -> impl IntoResponse {
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = 5;
+
+    let user = get_current_user(&jar, &state.db_pool).await;
+    let current_user = user.as_ref().map(|u| CurrentUser {
+        id: u.id.to_string(),
+        username: u.username.clone(),
+        is_admin: u.is_admin(),
+        can_moderate: u.can_moderate(),
+    });
+
+    let (filter, filters) = bill_filters(&state.db_pool, &params).await;
+    let (bills, total) = db::get_bills_paginated(&state.db_pool, page, per_page, &filter)
+        .await
+        .unwrap_or((vec![], 0));
+
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    let trending_bills = db::get_trending_bills(&state.db_pool, TRENDING_WINDOW_DAYS, TRENDING_LIMIT)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to fetch trending bills: {}", e);
+            vec![]
         })
+        .into_iter()
+        .map(TrendingBill::from)
         .collect();
 
-    HtmlTemplate(ForumTemplate {
-        bill,
-        reviews,
+    let bills = recent_bills(&state.db_pool, bills, user.as_ref()).await;
+
+    let template = IndexTemplate {
+        bills,
+        filters,
+        trending_bills,
+        current_page: page,
+        total_pages,
+        has_prev: page > 1,
+        has_next: page < total_pages,
         user: current_user,
-        rate_limit_remaining,
+        lang,
+    };
+
+    HtmlTemplate(template)
+}
+
+async fn bills_list_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Query(params): Query<BillsQuery>,
+) -> impl IntoResponse {
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = 5;
+
+    let (filter, filters) = bill_filters(&state.db_pool, &params).await;
+    let (bills, total) = db::get_bills_paginated(&state.db_pool, page, per_page, &filter)
+        .await
+        .unwrap_or((vec![], 0));
+
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    let user = get_current_user(&jar, &state.db_pool).await;
+    let bills = recent_bills(&state.db_pool, bills, user.as_ref()).await;
+
+    HtmlTemplate(BillsListTemplate {
+        bills,
+        filters,
+        current_page: page,
+        total_pages,
+        has_prev: page > 1,
+        has_next: page < total_pages,
+        lang,
     })
-    .into_response()
 }
 
-async fn forum_page_handler(
+async fn search_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Query(params): Query<SearchQuery>,
+) -> impl IntoResponse {
+    let query = params.query.trim();
+
+    if query.is_empty() {
+        return HtmlTemplate(SearchSuggestionsTemplate {
+            results: vec![],
+            chips: vec![],
+            keyword_fallback: false,
+            lang,
+        });
+    }
+
+    let filter = params.filter();
+    let rerank = params.rerank();
+    let chips = filter_chips(lang, query, &filter, rerank);
+
+    match perform_search(&state, query, 3, &filter, rerank).await {
+        Ok(outcome) => {
+            log_search(&state, jar, query, &outcome.results);
+            HtmlTemplate(SearchSuggestionsTemplate {
+                results: outcome.results,
+                chips,
+                keyword_fallback: outcome.keyword_fallback,
+                lang,
+            })
+        }
+        Err(e) => {
+            tracing::error!("Search failed: {:#}", e);
+            HtmlTemplate(SearchSuggestionsTemplate {
+                results: vec![],
+                chips,
+                keyword_fallback: false,
+                lang,
+            })
+        }
+    }
+}
+
+/// Lowercased, trimmed and with runs of whitespace collapsed, so trivially different searches count as one
+fn normalize_search_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .chars()
+        .take(SEARCH_LOG_MAX_CHARS)
+        .collect()
+}
+
+/// Stable per-user hash for counting distinct searchers, salted with SEARCH_LOG_SALT so it can't
+/// be matched back to a user id
+fn search_user_hash(user_id: Uuid) -> String {
+    use sha2::{Digest, Sha256};
+
+    let salt = std::env::var("SEARCH_LOG_SALT").unwrap_or_default();
+    Sha256::digest(format!("{}:{}", salt, user_id).as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Record a search for the admin analytics in the background, so logging never slows down or
+/// fails a search
+fn log_search(state: &AppState, jar: CookieJar, query: &str, results: &[SearchHit]) {
+    let pool = state.db_pool.clone();
+    let query = normalize_search_query(query);
+    let result_count = results.len() as i32;
+    // Keyword fallback results have no score
+    let top_score = results.iter().filter_map(|r| r.score.parse::<f32>().ok()).reduce(f32::max);
+
+    tokio::spawn(async move {
+        let user_hash = get_current_user(&jar, &pool).await.map(|u| search_user_hash(u.id));
+        if let Err(e) = db::record_search(&pool, &query, result_count, top_score, user_hash.as_deref()).await {
+            tracing::warn!("Failed to log search: {:#}", e);
+        }
+    });
+}
+
+async fn review_summary(pool: &PgPool, bill_id: Uuid) -> Option<ReviewSummary> {
+    let summary = db::get_bill_summary(pool, bill_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load summary for bill {}: {}", bill_id, e);
+            None
+        })?;
+
+    Some(ReviewSummary {
+        text: summary.summary,
+        support_pct: summary.support_pct,
+        oppose_pct: summary.oppose_pct,
+        critique_pct: summary.critique_pct,
+        post_count: summary.post_count,
+        generated_at: summary.generated_at.format("%B %d, %Y").to_string(),
+    })
+}
+
+async fn bill_forum_handler(
     State(state): State<Arc<AppState>>,
+    lang: Locale,
     jar: CookieJar,
     Path(bill_id): Path<String>,
+    Query(params): Query<ForumQuery>,
 ) -> impl IntoResponse {
     let bill_uuid = match Uuid::parse_str(&bill_id) {
         Ok(id) => id,
         Err(_) => {
-            return (StatusCode::BAD_REQUEST, "Invalid bill ID").into_response();
+            return AppError::bad_request("error.invalid_bill_id").into_response();
         }
     };
 
@@ -392,14 +1657,13 @@ async fn forum_page_handler(
     let current_user = user.as_ref().map(|u| CurrentUser {
         id: u.id.to_string(),
         username: u.username.clone(),
+        is_admin: u.is_admin(),
+        can_moderate: u.can_moderate(),
     });
 
-    let rate_limit_remaining = if let Some(ref u) = user {
-        rate_limit::get_remaining_posts(&state.db_pool, u.id)
-            .await
-            .unwrap_or(0)
-    } else {
-        0
+    let (rate_limit_remaining, retry_message, retry_after_secs, daily_limit_reached) = match user {
+        Some(ref u) => posting_quota(&state, u, lang).await,
+        None => (None, None, 0, false),
     };
 
     let bill = match db::get_bill_by_id(&state.db_pool, bill_uuid).await {
@@ -410,460 +1674,806 @@ async fn forum_page_handler(
             year: b.year,
         },
         _ => {
-            return (StatusCode::NOT_FOUND, "Bill not found").into_response();
+            return AppError::not_found("error.bill_not_found").into_response();
         }
     };
 
-    let posts = db::get_posts_for_bill(&state.db_pool, bill_uuid)
+    let following = match user {
+        Some(ref u) => db::is_subscribed(&state.db_pool, u.id, bill_uuid).await.unwrap_or(false),
+        None => false,
+    };
+    let bookmark = BookmarkState {
+        bill_id: bill.id.clone(),
+        bookmarked: match user {
+            Some(ref u) => db::get_bookmarked_bill_ids(&state.db_pool, u.id, &[bill_uuid])
+                .await
+                .is_some_and(|ids| ids.contains(&bill_uuid)),
+            None => false,
+        },
+    };
+
+    let sort = models::ReviewSort::from_param(params.sort.as_deref());
+
+    let (posts, total_reviews) = db::get_posts_for_bill(&state.db_pool, bill_uuid, sort, 1, REVIEWS_PER_PAGE)
         .await
-        .unwrap_or_default();
+        .unwrap_or((vec![], 0));
 
-    let reviews: Vec<Review> = posts
-        .into_iter()
-        .map(|p| Review {
-            id: p.id.to_string(),
-            username: p.username,
-            constituency: p.constituency_name.unwrap_or_else(|| "Unknown".to_string()),
-            stance: p.stance,
-            content: p.content,
-            date: p.formatted_date,
-            upvotes: p.upvotes,
-            downvotes: p.downvotes,
-        })
-        .collect();
+    let reviews = build_reviews(&state.db_pool, posts, user.as_ref()).await;
+    let own_unpublished = unpublished_reviews(&state.db_pool, user.as_ref(), bill_uuid).await;
+    let load_more_url = load_more_reviews_url(&bill.id, sort, 1, total_reviews);
+    let summary = review_summary(&state.db_pool, bill_uuid).await;
+    let can_regenerate_summary =
+        user.as_ref().is_some_and(|u| u.is_admin()) && total_reviews >= summary::MIN_REVIEWS;
 
-    HtmlTemplate(ForumPageTemplate {
+    HtmlTemplate(ForumTemplate {
         bill,
+        following,
+        bookmark,
+        summary,
+        can_regenerate_summary,
+        own_unpublished,
         reviews,
+        total_reviews,
+        load_more_url,
         user: current_user,
         rate_limit_remaining,
+        retry_message,
+        retry_after_secs,
+        daily_limit_reached,
+        form_notice: None,
+        form_error: review_form_error(lang, params.error.as_deref()),
+        sort: sort.as_str(),
+        lang,
     })
     .into_response()
 }
 
-async fn submit_review_handler(
+async fn forum_page_handler(
     State(state): State<Arc<AppState>>,
+    lang: Locale,
     jar: CookieJar,
     Path(bill_id): Path<String>,
-    Form(form): Form<ReviewForm>,
+    Query(params): Query<ForumQuery>,
 ) -> impl IntoResponse {
-    let user = match get_current_user(&jar, &state.db_pool).await {
-        Some(u) => u,
-        None => {
-            return Redirect::to("/login").into_response();
-        }
-    };
-
     let bill_uuid = match Uuid::parse_str(&bill_id) {
         Ok(id) => id,
         Err(_) => {
-            return Redirect::to("/").into_response();
+            return AppError::bad_request("error.invalid_bill_id").into_response();
         }
     };
 
-    // Check rate limit
-    if !rate_limit::can_create_post(&state.db_pool, user.id)
-        .await
-        .unwrap_or(false)
-    {
-        return Redirect::to(&format!("/f/{}?error=rate_limit", bill_id)).into_response();
-    }
+    let user = get_current_user(&jar, &state.db_pool).await;
+    let current_user = user.as_ref().map(|u| CurrentUser {
+        id: u.id.to_string(),
+        username: u.username.clone(),
+        is_admin: u.is_admin(),
+        can_moderate: u.can_moderate(),
+    });
 
-    // Moderate content
-    let moderation_result = moderation::check_content(&form.content)
-        .await
-        .unwrap_or(models::ModerationResult::AdminReview);
+    let (rate_limit_remaining, retry_message, retry_after_secs, daily_limit_reached) = match user {
+        Some(ref u) => posting_quota(&state, u, lang).await,
+        None => (None, None, 0, false),
+    };
 
-    let moderation_status = moderation_result.to_status();
-    let moderation_reason = match moderation_result {
-        models::ModerationResult::Popcorn => Some("Content rejected by moderation"),
-        models::ModerationResult::AdminReview => Some("Pending admin review"),
-        _ => None,
+    let bill = match db::get_bill_by_id(&state.db_pool, bill_uuid).await {
+        Ok(Some(b)) => BillInfo {
+            id: b.id.to_string(),
+            title: b.title,
+            number: b.bill_number,
+            year: b.year,
+        },
+        _ => {
+            return AppError::not_found("error.bill_not_found").into_response();
+        }
     };
 
-    // Normalize stance
-    let stance = match form.stance.to_lowercase().as_str() {
-        "support" => "Support",
-        "oppose" => "Oppose",
-        "critique" => "Critique",
-        _ => "Critique",
+    let following = match user {
+        Some(ref u) => db::is_subscribed(&state.db_pool, u.id, bill_uuid).await.unwrap_or(false),
+        None => false,
+    };
+    let bookmark = BookmarkState {
+        bill_id: bill.id.clone(),
+        bookmarked: match user {
+            Some(ref u) => db::get_bookmarked_bill_ids(&state.db_pool, u.id, &[bill_uuid])
+                .await
+                .is_some_and(|ids| ids.contains(&bill_uuid)),
+            None => false,
+        },
     };
 
-    // Create post
-    match db::create_post(
-        &state.db_pool,
-        user.id,
-        bill_uuid,
-        stance,
-        &form.content,
-        moderation_status,
-        moderation_reason,
-    )
-    .await
-    {
-        Ok(_) => {
-            // Record rate limit action
-            let _ = rate_limit::record_post_action(&state.db_pool, user.id).await;
+    let sort = models::ReviewSort::from_param(params.sort.as_deref());
 
-            Redirect::to(&format!("/f/{}", bill_id)).into_response()
-        }
-        Err(e) => {
-            tracing::error!("Failed to create post: {}", e);
-            Redirect::to(&format!("/f/{}?error=failed", bill_id)).into_response()
-        }
-    }
+    let (posts, total_reviews) = db::get_posts_for_bill(&state.db_pool, bill_uuid, sort, 1, REVIEWS_PER_PAGE)
+        .await
+        .unwrap_or((vec![], 0));
+
+    let reviews = build_reviews(&state.db_pool, posts, user.as_ref()).await;
+    let own_unpublished = unpublished_reviews(&state.db_pool, user.as_ref(), bill_uuid).await;
+    let load_more_url = load_more_reviews_url(&bill.id, sort, 1, total_reviews);
+    let summary = review_summary(&state.db_pool, bill_uuid).await;
+    let can_regenerate_summary =
+        user.as_ref().is_some_and(|u| u.is_admin()) && total_reviews >= summary::MIN_REVIEWS;
+
+    HtmlTemplate(ForumPageTemplate {
+        bill,
+        following,
+        bookmark,
+        summary,
+        can_regenerate_summary,
+        own_unpublished,
+        reviews,
+        total_reviews,
+        load_more_url,
+        user: current_user,
+        rate_limit_remaining,
+        retry_message,
+        retry_after_secs,
+        daily_limit_reached,
+        form_notice: None,
+        form_error: review_form_error(lang, params.error.as_deref()),
+        sort: sort.as_str(),
+        lang,
+    })
+    .into_response()
 }
 
-async fn upvote_handler(
+// "Related legislation" box, loaded after the forum and bill pages so Qdrant never delays them.
+// Renders nothing when there are no related bills or the vector store is unavailable.
+async fn related_bills_handler(
     State(state): State<Arc<AppState>>,
-    jar: CookieJar,
-    Path(review_id): Path<String>,
+    lang: Locale,
+    Path(bill_id): Path<String>,
 ) -> impl IntoResponse {
-    let user = match get_current_user(&jar, &state.db_pool).await {
-        Some(u) => u,
-        None => {
-            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
-        }
-    };
-
-    let post_uuid = match Uuid::parse_str(&review_id) {
+    let bill_uuid = match Uuid::parse_str(&bill_id) {
         Ok(id) => id,
         Err(_) => {
             return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
         }
     };
 
-    match db::upvote_post(&state.db_pool, post_uuid, user.id).await {
-        Ok((upvotes, downvotes, user_vote)) => {
-            let html = format!(
-                r#"<button class="vote-btn {}" 
-                        hx-post="/api/review/{}/upvote" 
-                        hx-swap="innerHTML"
-                        hx-target="closest .review-actions"
-                        hx-disabled-elt="this">
-                    ▲ <span class="vote-count">{}</span>
-                </button>
-                <button class="vote-btn {}" 
-                        hx-post="/api/review/{}/downvote" 
-                        hx-swap="innerHTML"
-                        hx-target="closest .review-actions"
-                        hx-disabled-elt="this">
-                    ▼ <span class="vote-count">{}</span>
-                </button>"#,
-                if user_vote == Some("upvote".to_string()) { "voted" } else { "" },
-                review_id,
-                upvotes,
-                if user_vote == Some("downvote".to_string()) { "voted" } else { "" },
-                review_id,
-                downvotes
-            );
-            (StatusCode::OK, Html(html)).into_response()
+    let bills = match state
+        .similar_bills
+        .get(state.vector_store.as_ref(), bill_uuid, RELATED_BILLS_LIMIT)
+        .await
+    {
+        Ok(bills) => bills,
+        Err(e) => {
+            tracing::warn!("Failed to find bills related to {}: {}", bill_uuid, e);
+            Vec::new()
         }
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response(),
+    };
+
+    if bills.is_empty() {
+        return Html("".to_string()).into_response();
     }
+
+    HtmlTemplate(RelatedBillsTemplate {
+        bills: bills
+            .into_iter()
+            .map(|b| RelatedBill {
+                id: b.bill_id.to_string(),
+                title: b.title,
+                number: b.bill_number,
+                year: b.year,
+                similarity: (b.score * 100.0).round() as i64,
+            })
+            .collect(),
+        lang,
+    })
+    .into_response()
 }
 
-async fn downvote_handler(
+async fn reviews_page_handler(
     State(state): State<Arc<AppState>>,
+    lang: Locale,
     jar: CookieJar,
-    Path(review_id): Path<String>,
+    Path(bill_id): Path<String>,
+    Query(params): Query<ForumQuery>,
 ) -> impl IntoResponse {
-    let user = match get_current_user(&jar, &state.db_pool).await {
-        Some(u) => u,
-        None => {
-            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
-        }
-    };
-
-    let post_uuid = match Uuid::parse_str(&review_id) {
+    let bill_uuid = match Uuid::parse_str(&bill_id) {
         Ok(id) => id,
         Err(_) => {
             return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
         }
     };
 
-    match db::downvote_post(&state.db_pool, post_uuid, user.id).await {
-        Ok((upvotes, downvotes, user_vote)) => {
-            let html = format!(
-                r#"<button class="vote-btn {}" 
-                        hx-post="/api/review/{}/upvote" 
-                        hx-swap="innerHTML"
-                        hx-target="closest .review-actions"
-                        hx-disabled-elt="this">
-                    ▲ <span class="vote-count">{}</span>
-                </button>
-                <button class="vote-btn {}" 
-                        hx-post="/api/review/{}/downvote" 
-                        hx-swap="innerHTML"
-                        hx-target="closest .review-actions"
-                        hx-disabled-elt="this">
-                    ▼ <span class="vote-count">{}</span>
-                </button>"#,
-                if user_vote == Some("upvote".to_string()) { "voted" } else { "" },
-                review_id,
-                upvotes,
-                if user_vote == Some("downvote".to_string()) { "voted" } else { "" },
-                review_id,
-                downvotes
-            );
-            (StatusCode::OK, Html(html)).into_response()
-        }
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response(),
-    }
-}
+    let page = params.page.unwrap_or(1).max(1);
+    let sort = models::ReviewSort::from_param(params.sort.as_deref());
 
-// Auth handlers
-async fn login_page() -> impl IntoResponse {
-    HtmlTemplate(LoginTemplate { error: None })
+    let user = get_current_user(&jar, &state.db_pool).await;
+
+    let (posts, total_reviews) =
+        match db::get_posts_for_bill(&state.db_pool, bill_uuid, sort, page, REVIEWS_PER_PAGE).await {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::error!("Failed to load reviews: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+            }
+        };
+
+    let reviews = build_reviews(&state.db_pool, posts, user.as_ref()).await;
+
+    HtmlTemplate(ReviewPageTemplate {
+        reviews,
+        load_more_url: load_more_reviews_url(&bill_id, sort, page, total_reviews),
+        user: user.map(|u| CurrentUser {
+            id: u.id.to_string(),
+            username: u.username,
+            is_admin: u.is_admin(),
+            can_moderate: u.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
 }
 
-async fn login_handler(
+// The review form on its own, re-fetched by a rate-limited form as its countdown runs down
+async fn review_form_handler(
     State(state): State<Arc<AppState>>,
+    lang: Locale,
     jar: CookieJar,
-    Form(form): Form<LoginForm>,
+    Path(bill_id): Path<String>,
 ) -> impl IntoResponse {
-    match auth::authenticate_user(&state.db_pool, &form.username, &form.password).await {
-        Ok(Some(user)) => {
-            match auth::create_session(&state.db_pool, user.id).await {
-                Ok(session) => {
-                    let cookie = Cookie::build((SESSION_COOKIE_NAME, session.session_token))
-                        .path("/")
-                        .http_only(true)
-                        .max_age(time::Duration::days(7))
-                        .build();
+    let Some(user) = get_current_user(&jar, &state.db_pool).await else {
+        return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+    };
+    let Ok(bill_uuid) = Uuid::parse_str(&bill_id) else {
+        return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+    };
 
-                    (jar.add(cookie), Redirect::to("/")).into_response()
-                }
-                Err(_) => HtmlTemplate(LoginTemplate {
-                    error: Some("Failed to create session".to_string()),
-                })
-                .into_response(),
-            }
+    let bill = match db::get_bill_by_id(&state.db_pool, bill_uuid).await {
+        Ok(Some(b)) => BillInfo {
+            id: b.id.to_string(),
+            title: b.title,
+            number: b.bill_number,
+            year: b.year,
+        },
+        Ok(None) => return (StatusCode::NOT_FOUND, Html("".to_string())).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch bill: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
         }
-        Ok(None) => HtmlTemplate(LoginTemplate {
-            error: Some("Invalid username or password".to_string()),
-        })
-        .into_response(),
-        Err(_) => HtmlTemplate(LoginTemplate {
-            error: Some("An error occurred".to_string()),
-        })
-        .into_response(),
-    }
-}
-
-async fn register_page(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let constituencies = auth::get_all_constituencies(&state.db_pool)
-        .await
-        .unwrap_or_default()
-        .into_iter()
-        .map(|c| ConstituencyOption {
-            id: c.id,
-            name: c.name,
-            state: c.state,
-        })
-        .collect();
+    };
 
-    HtmlTemplate(RegisterTemplate {
-        error: None,
-        constituencies,
+    let (rate_limit_remaining, retry_message, retry_after_secs, daily_limit_reached) =
+        posting_quota(&state, &user, lang).await;
+    HtmlTemplate(ReviewFormTemplate {
+        bill,
+        rate_limit_remaining,
+        retry_message,
+        retry_after_secs,
+        daily_limit_reached,
+        form_notice: None,
+        form_error: None,
+        lang,
     })
+    .into_response()
 }
 
-async fn register_handler(
+async fn submit_review_handler(
     State(state): State<Arc<AppState>>,
+    lang: Locale,
     jar: CookieJar,
-    Form(form): Form<RegisterForm>,
+    headers: HeaderMap,
+    Path(bill_id): Path<String>,
+    Form(form): Form<ReviewForm>,
 ) -> impl IntoResponse {
-    let constituencies = auth::get_all_constituencies(&state.db_pool)
-        .await
-        .unwrap_or_default()
-        .into_iter()
-        .map(|c| ConstituencyOption {
-            id: c.id,
-            name: c.name,
-            state: c.state,
-        })
-        .collect::<Vec<_>>();
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
 
-    // Validate username
-    if form.username.is_empty() {
-        return HtmlTemplate(RegisterTemplate {
-            error: Some("Username is required".to_string()),
-            constituencies,
-        })
-        .into_response();
-    }
+    let bill_uuid = match Uuid::parse_str(&bill_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return Redirect::to("/").into_response();
+        }
+    };
 
-    // Check if username exists
-    if auth::username_exists(&state.db_pool, &form.username)
+    let bill = match db::get_bill_by_id(&state.db_pool, bill_uuid).await {
+        Ok(Some(b)) => BillInfo {
+            id: b.id.to_string(),
+            title: b.title,
+            number: b.bill_number,
+            year: b.year,
+        },
+        _ => {
+            return AppError::not_found("error.bill_not_found").into_response();
+        }
+    };
+
+    let htmx = is_htmx_request(&headers);
+
+    // Check rate limit
+    if !rate_limit::can_create_post(&state.db_pool, &state.rate_limit_cache, &user, &state.rate_limits)
         .await
         .unwrap_or(false)
     {
-        return HtmlTemplate(RegisterTemplate {
-            error: Some("Username already taken".to_string()),
-            constituencies,
-        })
-        .into_response();
+        rate_limit::record_rejection(&state.db_pool, user.id, rate_limit::ActionType::PostCreate);
+        if !htmx {
+            return Redirect::to(&format!("/f/{}?error=rate_limit", bill_id)).into_response();
+        }
+
+        let throttled =
+            rate_limit::get_time_until_next_post(&state.db_pool, &state.rate_limit_cache, &user, &state.rate_limits)
+                .await
+                .ok()
+                .flatten();
+        let wait_secs = throttled.map_or(0, |t| t.retry_after);
+        let fragment = HtmlTemplate(ReviewFormTemplate {
+            bill,
+            rate_limit_remaining: Some(0),
+            retry_message: Some(retry_after_message(lang, wait_secs)),
+            retry_after_secs: wait_secs,
+            daily_limit_reached: throttled.is_some_and(|t| t.window == rate_limit::LimitWindow::Daily),
+            form_notice: None,
+            form_error: None,
+            lang,
+        });
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, wait_secs.max(1).to_string())],
+            fragment,
+        )
+            .into_response();
     }
 
-    // Parse age if provided
-    let age: Option<i32> = form.age.as_ref().and_then(|a| a.parse().ok());
+    // Moderate content, comparing it with recent posts on the bill in parallel. With a
+    // moderation queue the review is saved as held and judged in the background instead.
+    let queue = state.moderation_queue.as_ref();
+    let (mut moderation, duplicate) = tokio::join!(
+        async {
+            match queue {
+                Some(_) => models::PostModeration::queued(),
+                None => moderation::check_post(&state.db_pool, &state.moderation, user.id, &form.content)
+                    .await
+                    .to_post_moderation(),
+            }
+        },
+        moderation::check_duplicate(&state.db_pool, state.embedder.as_ref(), bill_uuid, &form.content),
+    );
+    let mut queued = queue.is_some();
 
-    // Determine pincode and constituency based on location_type
-    let (pincode, constituency_id) = if form.location_type == "pincode" {
-        (form.pincode.clone(), None)
-    } else {
-        (None, form.constituency_id.as_ref().and_then(|c| c.parse().ok()))
-    };
+    // A failed check shouldn't block posting; the review is judged on moderation alone
+    let duplicate = duplicate
+        .map_err(|e| tracing::warn!("Duplicate check failed for bill {}: {:#}", bill_uuid, e))
+        .ok();
+    if let Some((original, _)) = duplicate.as_ref().and_then(|d| d.original) {
+        // A likely copy waits for a moderator whatever the queue would decide
+        if moderation.status == "approved" || queued {
+            queued = false;
+            tracing::info!("Holding review on bill {} as a likely copy of post {}", bill_uuid, original);
+            moderation.status = "pending_review";
+            moderation.reason = Some(moderation::DUPLICATE_REASON.to_string());
+        }
+    }
 
-    match auth::create_user(
-        &state.db_pool,
-        &form.username,
-        &form.password,
-        form.real_name.as_deref(),
-        age,
-        form.gender.as_deref(),
-        pincode.as_deref(),
-        constituency_id,
-    )
-    .await
-    {
-        Ok(user) => {
-            match auth::create_session(&state.db_pool, user.id).await {
-                Ok(session) => {
-                    let cookie = Cookie::build((SESSION_COOKIE_NAME, session.session_token))
-                        .path("/")
-                        .http_only(true)
-                        .max_age(time::Duration::days(7))
-                        .build();
+    let stance = normalize_stance(&form.stance);
 
-                    (jar.add(cookie), Redirect::to("/")).into_response()
+    // Create post
+    let new_post = models::NewPost {
+        bill_id: bill_uuid,
+        parent_post_id: None,
+        stance: Some(stance),
+        content: &form.content,
+        is_anonymous: form.anonymous.is_some(),
+    };
+    match db::create_post(&state.db_pool, user.id, &new_post, &moderation).await {
+        Ok(post) => {
+            // Record rate limit action
+            rate_limit::record_post_action(&state.db_pool, &state.rate_limit_cache, user.id);
+            moderation::record_attempts(&state.db_pool, post.id, &moderation.attempts).await;
+
+            if let Some(duplicate) = duplicate {
+                if let Err(e) = db::set_post_embedding(&state.db_pool, post.id, &duplicate.embedding).await {
+                    tracing::warn!("Failed to store review embedding: {}", e);
+                }
+                if let Some((original, similarity)) = duplicate.original {
+                    if let Err(e) = db::set_duplicate_of(&state.db_pool, post.id, original, similarity).await {
+                        tracing::warn!("Failed to record duplicate review: {}", e);
+                    }
                 }
-                Err(_) => Redirect::to("/login").into_response(),
             }
-        }
-        Err(e) => {
-            tracing::error!("Failed to create user: {}", e);
-            HtmlTemplate(RegisterTemplate {
-                error: Some("Failed to create account".to_string()),
-                constituencies,
+
+            if post.moderation_status == "approved" {
+                notify_approved_post(&state.db_pool, &post).await;
+                publish_review(&state, &post);
+            }
+
+            if let Some(queue) = queue.filter(|_| queued) {
+                queue.enqueue(post.id);
+            }
+
+            if !htmx {
+                return Redirect::to(&format!("/f/{}", bill_id)).into_response();
+            }
+
+            // Re-render the form with the new quota so the counter stays accurate
+            let (rate_limit_remaining, retry_message, retry_after_secs, daily_limit_reached) =
+                posting_quota(&state, &user, lang).await;
+            let notice = if queued {
+                lang.t("review.checking")
+            } else {
+                review_status_notice(lang, &post.moderation_status)
+            };
+            let fragment = HtmlTemplate(ReviewFormTemplate {
+                bill,
+                rate_limit_remaining,
+                retry_message,
+                retry_after_secs,
+                daily_limit_reached,
+                form_notice: Some(notice.to_string()),
+                form_error: None,
+                lang,
+            });
+
+            if post.moderation_status == "approved" {
+                // Lets the review list reload itself to show the new review
+                ([("HX-Trigger", "review-posted")], fragment).into_response()
+            } else {
+                fragment.into_response()
+            }
+        }
+        Err(e) => {
+            tracing::error!("Failed to create post: {}", e);
+            if !htmx {
+                return Redirect::to(&format!("/f/{}?error=failed", bill_id)).into_response();
+            }
+
+            let (rate_limit_remaining, retry_message, retry_after_secs, daily_limit_reached) =
+                posting_quota(&state, &user, lang).await;
+            HtmlTemplate(ReviewFormTemplate {
+                bill,
+                rate_limit_remaining,
+                retry_message,
+                retry_after_secs,
+                daily_limit_reached,
+                form_notice: None,
+                form_error: review_form_error(lang, Some("failed")),
+                lang,
             })
             .into_response()
         }
     }
 }
 
-async fn logout_handler(
+// Tell open forum pages about a newly approved review; replies are not streamed
+// Notify the bill's followers of a newly approved post, and anyone it mentions
+pub(crate) async fn notify_approved_post(pool: &PgPool, post: &models::Post) {
+    if let Err(e) = db::notify_new_post(pool, post.id).await {
+        tracing::error!("Failed to notify followers: {}", e);
+    }
+    if let Err(e) = db::notify_mentions(pool, post.id, &mentioned_usernames(&post.content)).await {
+        tracing::error!("Failed to notify mentioned users: {}", e);
+    }
+}
+
+fn publish_review(state: &AppState, post: &models::Post) {
+    if post.moderation_status != "approved" || post.parent_post_id.is_some() {
+        return;
+    }
+
+    // Err only means nobody is watching this or any other forum right now
+    let _ = state.review_events.send(ReviewEvent::Published {
+        bill_id: post.bill_id,
+        post_id: post.id,
+    });
+}
+
+// Judge queued reviews one at a time until shutdown. Each runs in its own task so a panic
+// loses only that verdict; the review stays held for `remoderate` or a moderator.
+// How often the moderation worker looks for held reviews that never reached it, and how old
+// one must be before it's picked up, so reviews still waiting in the queue aren't judged twice
+const MODERATION_SWEEP_INTERVAL_SECS: u64 = 300;
+const MODERATION_SWEEP_GRACE_SECS: i64 = 120;
+const MODERATION_SWEEP_BATCH: i64 = 100;
+
+async fn run_moderation_worker(state: Arc<AppState>, mut jobs: mpsc::Receiver<Uuid>) {
+    let cancelled = state.shutdown.clone().cancelled();
+    tokio::pin!(cancelled);
+    // The first tick fires at once, picking up reviews left queued when the server last stopped
+    let mut sweep = tokio::time::interval(std::time::Duration::from_secs(MODERATION_SWEEP_INTERVAL_SECS));
+    sweep.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        let post_ids = tokio::select! {
+            job = jobs.recv() => match job {
+                Some(post_id) => vec![post_id],
+                None => break,
+            },
+            _ = sweep.tick() => unjudged_held_reviews(&state).await,
+            _ = &mut cancelled => break,
+        };
+
+        for post_id in post_ids {
+            if let Err(e) = tokio::spawn(moderate_queued_review(state.clone(), post_id)).await {
+                tracing::error!("Moderation of post {} failed: {}", post_id, e);
+            }
+        }
+    }
+}
+
+// Held reviews with no verdict and no recorded attempt, which the queue lost to a restart or
+// dropped when it was full
+async fn unjudged_held_reviews(state: &AppState) -> Vec<Uuid> {
+    let created_before = chrono::Utc::now() - chrono::Duration::seconds(MODERATION_SWEEP_GRACE_SECS);
+    match db::get_unjudged_held_posts(&state.db_pool, created_before, MODERATION_SWEEP_BATCH).await {
+        Ok(post_ids) => {
+            if !post_ids.is_empty() {
+                tracing::info!("Moderating {} held reviews the queue missed", post_ids.len());
+            }
+            post_ids
+        }
+        Err(e) => {
+            tracing::error!("Failed to look for held reviews awaiting moderation: {:#}", e);
+            Vec::new()
+        }
+    }
+}
+
+async fn moderate_queued_review(state: Arc<AppState>, post_id: Uuid) {
+    let post = match db::get_post_by_id(&state.db_pool, post_id).await {
+        Ok(Some(post)) => post,
+        // Deleted before its turn came
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("Failed to load queued post {}: {:#}", post_id, e);
+            return;
+        }
+    };
+
+    let verdict = moderation::check_post(&state.db_pool, &state.moderation, post.user_id, &post.content).await;
+    moderation::record_attempts(&state.db_pool, post.id, &verdict.attempts).await;
+    match db::set_remoderated_status(&state.db_pool, &post, &verdict.to_post_moderation()).await {
+        Ok(Some(post)) => {
+            if post.moderation_status == "approved" {
+                notify_approved_post(&state.db_pool, &post).await;
+                publish_review(&state, &post);
+            }
+            let _ = state.review_events.send(ReviewEvent::Moderated {
+                bill_id: post.bill_id,
+                post_id: post.id,
+                author_id: post.user_id,
+            });
+        }
+        Ok(None) => tracing::info!("Post {} changed while queued for moderation; leaving it alone", post_id),
+        Err(e) => tracing::error!("Failed to save verdict for post {}: {:#}", post_id, e),
+    }
+}
+
+// What the author is told about a review they just submitted
+fn review_status_notice(lang: Locale, status: &str) -> &'static str {
+    match status {
+        "approved" => lang.t("review.posted"),
+        "rejected" => lang.t("review.rejected"),
+        _ => lang.t("review.awaiting_moderation"),
+    }
+}
+
+// Server-sent events with each review published on a bill, rendered for the viewer.
+// The broadcast receiver lives inside the stream, so it is dropped (and unsubscribed)
+// as soon as the client disconnects and axum drops the response body.
+async fn review_stream_handler(
     State(state): State<Arc<AppState>>,
+    lang: Locale,
     jar: CookieJar,
+    Path(bill_id): Path<String>,
 ) -> impl IntoResponse {
-    if let Some(cookie) = jar.get(SESSION_COOKIE_NAME) {
-        let _ = auth::delete_session(&state.db_pool, cookie.value()).await;
-    }
+    let bill_uuid = match Uuid::parse_str(&bill_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
 
-    let cookie = Cookie::build((SESSION_COOKIE_NAME, ""))
-        .path("/")
-        .max_age(time::Duration::seconds(0))
-        .build();
+    let viewer = get_current_user(&jar, &state.db_pool).await;
 
-    (jar.remove(cookie), Redirect::to("/"))
+    Sse::new(review_stream(state, bill_uuid, viewer, lang))
+        .keep_alive(KeepAlive::default())
+        .into_response()
 }
 
-// Profile handlers
-async fn profile_handler(
+fn review_stream(
+    state: Arc<AppState>,
+    bill_id: Uuid,
+    viewer: Option<models::User>,
+    lang: Locale,
+) -> impl Stream<Item = Result<Event, std::convert::Infallible>> {
+    let receiver = state.review_events.subscribe();
+    let shutdown = state.shutdown.clone();
+
+    futures::stream::unfold((state, receiver, viewer), move |(state, mut receiver, viewer)| async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) if event.bill_id() == bill_id => event,
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Review stream for bill {} skipped {} events", bill_id, skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+
+            let post_id = match event {
+                ReviewEvent::Published { post_id, .. } => post_id,
+                ReviewEvent::Moderated { post_id, author_id, .. } => {
+                    if viewer.as_ref().map(|v| v.id) != Some(author_id) {
+                        continue;
+                    }
+                    let Ok(Some(post)) = db::get_post_by_id(&state.db_pool, post_id).await else {
+                        continue;
+                    };
+
+                    let notice = ModerationNoticeTemplate {
+                        notice: review_status_notice(lang, &post.moderation_status).to_string(),
+                    };
+                    match notice.render() {
+                        Ok(html) => {
+                            return Some((Ok(Event::default().event("moderated").data(html)), (state, receiver, viewer)))
+                        }
+                        Err(e) => tracing::error!("Failed to render moderation notice: {}", e),
+                    }
+                    continue;
+                }
+            };
+
+            // The review may have been deleted or re-moderated since it was published
+            let Ok(Some(post)) = db::get_review_with_user(&state.db_pool, post_id).await else {
+                continue;
+            };
+
+            let reviews = build_reviews(&state.db_pool, vec![post], viewer.as_ref()).await;
+            let page = ReviewPageTemplate {
+                reviews,
+                load_more_url: None,
+                user: viewer.as_ref().map(|u| CurrentUser {
+                    id: u.id.to_string(),
+                    username: u.username.clone(),
+                    is_admin: u.is_admin(),
+                    can_moderate: u.can_moderate(),
+                }),
+                lang,
+            };
+
+            match page.render() {
+                Ok(html) => return Some((Ok(Event::default().event("review").data(html)), (state, receiver, viewer))),
+                Err(e) => tracing::error!("Failed to render streamed review: {}", e),
+            }
+        }
+    })
+    // Ends open streams on shutdown so they don't hold up the graceful drain
+    .take_until(shutdown.cancelled())
+}
+
+async fn submit_reply_handler(
     State(state): State<Arc<AppState>>,
     jar: CookieJar,
-    Path(username): Path<String>,
+    Path(review_id): Path<String>,
+    Form(form): Form<ReplyForm>,
 ) -> impl IntoResponse {
-    let current_user = get_current_user(&jar, &state.db_pool).await;
-    let is_own_profile = current_user
-        .as_ref()
-        .map(|u| u.username == username)
-        .unwrap_or(false);
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    let parent_uuid = match Uuid::parse_str(&review_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return Redirect::to("/").into_response();
+        }
+    };
 
-    let profile = match db::get_user_profile(&state.db_pool, &username).await {
+    let parent = match db::get_post_by_id(&state.db_pool, parent_uuid).await {
         Ok(Some(p)) => p,
         Ok(None) => {
-            return (StatusCode::NOT_FOUND, "User not found").into_response();
+            return AppError::not_found("error.review_not_found").into_response();
         }
-        Err(_) => {
-            return (StatusCode::INTERNAL_SERVER_ERROR, "Error loading profile").into_response();
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load review")).into_response();
         }
     };
 
-    // Get constituency_id for the profile
-    let constituency_id = if let Some(ref u) = current_user {
-        if u.username == username {
-            u.constituency_id.unwrap_or(0)
-        } else {
-            0
-        }
-    } else {
-        0
+    // Threads are one level deep: replying to a reply answers the top-level review
+    let thread_root = parent.parent_post_id.unwrap_or(parent.id);
+
+    // Only published posts can be replied to, so held or rejected content never gets a public
+    // thread; the review a reply belongs to must still be published as well
+    let root_approved = match parent.parent_post_id {
+        None => true,
+        Some(root_id) => match db::get_post_by_id(&state.db_pool, root_id).await {
+            Ok(root) => root.is_some_and(|root| root.moderation_status == "approved"),
+            Err(e) => return AppError::internal(e.context("Failed to load review")).into_response(),
+        },
     };
+    if parent.moderation_status != "approved" || !root_approved {
+        return AppError::not_found("error.review_not_found").into_response();
+    }
 
-    let posts = db::get_posts_by_user(&state.db_pool, profile.id)
+    // Replies share the posting rate limit with reviews
+    if !rate_limit::can_create_post(&state.db_pool, &state.rate_limit_cache, &user, &state.rate_limits)
         .await
-        .unwrap_or_default()
-        .into_iter()
-        .map(|(post, bill_title, bill_number)| UserPost {
-            id: post.id.to_string(),
-            bill_title,
-            bill_number,
-            stance: post.stance,
-            content: post.content,
-            moderation_status: post.moderation_status,
-            date: post.created_at.format("%B %d, %Y").to_string(),
-            upvotes: post.upvotes,
-            downvotes: post.downvotes,
-        })
-        .collect();
+        .unwrap_or(false)
+    {
+        rate_limit::record_rejection(&state.db_pool, user.id, rate_limit::ActionType::PostCreate);
+        return Redirect::to(&format!("/f/{}?error=rate_limit", parent.bill_id)).into_response();
+    }
 
-    let constituencies = if is_own_profile {
-        auth::get_all_constituencies(&state.db_pool)
-            .await
-            .unwrap_or_default()
-            .into_iter()
-            .map(|c| ConstituencyOption {
-                id: c.id,
-                name: c.name,
-                state: c.state,
-            })
-            .collect()
-    } else {
-        vec![]
+    let moderation = moderation::check_post(&state.db_pool, &state.moderation, user.id, &form.content)
+        .await
+        .to_post_moderation();
+
+    let new_post = models::NewPost {
+        bill_id: parent.bill_id,
+        parent_post_id: Some(thread_root),
+        stance: None,
+        content: &form.content,
+        is_anonymous: false,
     };
+    match db::create_post(&state.db_pool, user.id, &new_post, &moderation).await {
+        Ok(reply) => {
+            rate_limit::record_post_action(&state.db_pool, &state.rate_limit_cache, user.id);
+            moderation::record_attempts(&state.db_pool, reply.id, &moderation.attempts).await;
 
-    let profile_data = ProfileData {
-        username: profile.username,
-        real_name: profile.real_name,
-        age: profile.age,
-        gender: profile.gender,
-        pincode: profile.pincode,
-        constituency_id,
-        constituency_name: profile.constituency_name,
-        member_since: profile.created_at.format("%B %Y").to_string(),
-        post_count: profile.post_count,
+            if reply.moderation_status == "approved" {
+                notify_approved_post(&state.db_pool, &reply).await;
+            }
+
+            Redirect::to(&format!("/f/{}", parent.bill_id)).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to create reply: {}", e);
+            Redirect::to(&format!("/f/{}?error=failed", parent.bill_id)).into_response()
+        }
+    }
+}
+
+async fn replies_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(review_id): Path<String>,
+) -> impl IntoResponse {
+    let post_uuid = match Uuid::parse_str(&review_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
     };
 
-    HtmlTemplate(ProfileTemplate {
-        profile: profile_data,
-        posts,
-        is_own_profile,
-        user: current_user.map(|u| CurrentUser {
+    let user = get_current_user(&jar, &state.db_pool).await;
+
+    let replies = match db::get_replies(&state.db_pool, post_uuid).await {
+        Ok(replies) => replies,
+        Err(e) => {
+            tracing::error!("Failed to load replies: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+        }
+    };
+
+    let reviews = build_reviews(&state.db_pool, replies, user.as_ref()).await;
+
+    HtmlTemplate(ReviewRepliesTemplate {
+        reviews,
+        user: user.map(|u| CurrentUser {
             id: u.id.to_string(),
             username: u.username,
+            is_admin: u.is_admin(),
+            can_moderate: u.can_moderate(),
         }),
-        constituencies,
+        lang,
     })
     .into_response()
 }
 
-async fn update_profile_handler(
+async fn edit_review_handler(
     State(state): State<Arc<AppState>>,
     jar: CookieJar,
-    Path(username): Path<String>,
-    Form(form): Form<ProfileUpdateForm>,
+    Path(review_id): Path<String>,
+    Form(form): Form<EditReviewForm>,
 ) -> impl IntoResponse {
     let user = match get_current_user(&jar, &state.db_pool).await {
         Some(u) => u,
@@ -872,178 +2482,6754 @@ async fn update_profile_handler(
         }
     };
 
-    // Only allow editing own profile
-    if user.username != username {
-        return (StatusCode::FORBIDDEN, "Cannot edit another user's profile").into_response();
+    let post_uuid = match Uuid::parse_str(&review_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return AppError::bad_request("error.invalid_review_id").into_response();
+        }
+    };
+
+    let post = match db::get_post_by_id(&state.db_pool, post_uuid).await {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            return AppError::not_found("error.review_not_found").into_response();
+        }
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load review")).into_response();
+        }
+    };
+
+    if post.user_id != user.id {
+        return AppError::forbidden("error.not_your_review").into_response();
     }
 
-    let age: Option<i32> = form.age.as_ref().and_then(|a| a.parse().ok());
+    if !is_editable(post.created_at, post.upvotes, post.downvotes) {
+        return Redirect::to(&format!("/f/{}?error=edit_window", post.bill_id)).into_response();
+    }
 
-    let (pincode, constituency_id) = if form.location_type == "pincode" {
-        (form.pincode.clone(), None)
+    let content = form.content.trim();
+    if content.is_empty() {
+        return Redirect::to(&format!("/f/{}?error=failed", post.bill_id)).into_response();
+    }
+
+    // Replies never carry a stance
+    let stance = if post.parent_post_id.is_some() {
+        None
     } else {
-        (None, form.constituency_id.as_ref().and_then(|c| c.parse().ok()))
+        form.stance
+            .as_deref()
+            .map(normalize_stance)
+            .or(post.stance.as_deref())
     };
 
-    match auth::update_user_profile(
+    // Edited content goes through moderation again
+    let moderation = moderation::check_post(&state.db_pool, &state.moderation, user.id, content)
+        .await
+        .to_post_moderation();
+
+    match db::update_post_content(
         &state.db_pool,
-        user.id,
-        form.real_name.as_deref(),
-        age,
-        form.gender.as_deref(),
-        pincode.as_deref(),
-        constituency_id,
+        post_uuid,
+        stance,
+        content,
+        &moderation,
     )
     .await
     {
-        Ok(_) => Redirect::to(&format!("/u/{}", username)).into_response(),
+        Ok(edited) => {
+            moderation::record_attempts(&state.db_pool, edited.id, &moderation.attempts).await;
+
+            // Only users newly mentioned by the edit hear about it
+            if edited.moderation_status == "approved" {
+                if let Err(e) = db::notify_mentions(&state.db_pool, edited.id, &mentioned_usernames(&edited.content)).await {
+                    tracing::error!("Failed to notify mentioned users: {}", e);
+                }
+            }
+            Redirect::to(&format!("/f/{}", post.bill_id)).into_response()
+        }
         Err(e) => {
-            tracing::error!("Failed to update profile: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update profile").into_response()
+            tracing::error!("Failed to edit post: {}", e);
+            Redirect::to(&format!("/f/{}?error=failed", post.bill_id)).into_response()
         }
     }
 }
 
-// Helper functions
-async fn perform_search(query: &str, pool: &PgPool) -> anyhow::Result<Vec<SearchResult>> {
-    let query_embedding = embedder::embed_query(query).await?;
-    let search_results = vector_store::search(&query_embedding, 3).await?;
+async fn delete_review_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(review_id): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
 
-    let mut results = Vec::new();
-    for r in search_results {
-        // Look up bill by bill_number to get the UUID
-        if let Ok(Some(bill)) = db::get_bill_by_number(pool, &r.bill_number).await {
-            results.push(SearchResult {
-                bill_id: bill.id.to_string(),
-                bill_title: r.bill_title,
-                bill_number: r.bill_number,
-                section: r.chunk_identifier,
-                score: format!("{:.2}", r.score),
-            });
+    let post_uuid = match Uuid::parse_str(&review_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
         }
-    }
+    };
 
-    Ok(results)
-}
+    let post = match db::get_post_by_id(&state.db_pool, post_uuid).await {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            return (StatusCode::NOT_FOUND, Html("".to_string())).into_response();
+        }
+        Err(_) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+        }
+    };
 
-// Template wrapper to handle errors
-struct HtmlTemplate<T>(T);
+    if post.user_id != user.id && !user.can_moderate() {
+        return (StatusCode::FORBIDDEN, Html("".to_string())).into_response();
+    }
 
-impl<T> IntoResponse for HtmlTemplate<T>
-where
-    T: Template,
-{
-    fn into_response(self) -> Response {
-        match self.0.render() {
-            Ok(html) => Html(html).into_response(),
-            Err(err) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to render template. Error: {}", err),
-            )
-                .into_response(),
+    // Empty fragment lets htmx remove the review card
+    match db::soft_delete_post(&state.db_pool, post_uuid).await {
+        Ok(_) => (StatusCode::OK, Html("".to_string())).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to delete post: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
         }
     }
 }
 
-// Router setup
-pub async fn create_router() -> Router {
-    let db_pool = db::create_pool().await.expect("Failed to create database pool");
-
-    let state = Arc::new(AppState { db_pool });
+async fn report_review_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(review_id): Path<String>,
+    Form(form): Form<ReportForm>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
+
+    let post_uuid = match Uuid::parse_str(&review_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    if !REPORT_REASONS.contains(&form.reason.as_str()) {
+        return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+    }
+
+    let post = match db::get_post_by_id(&state.db_pool, post_uuid).await {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            return (StatusCode::NOT_FOUND, Html("".to_string())).into_response();
+        }
+        Err(_) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+        }
+    };
+
+    if post.user_id == user.id {
+        return (StatusCode::FORBIDDEN, Html("".to_string())).into_response();
+    }
+
+    if let Some(throttled) = action_throttled(&state, &user, rate_limit::ActionType::Report).await {
+        let message = match throttled.window {
+            rate_limit::LimitWindow::Burst => lang.tf("review.report_rate_limited", (throttled.retry_after + 59) / 60),
+            rate_limit::LimitWindow::Daily => lang.tf("review.report_daily_limited", (throttled.retry_after + 3599) / 3600),
+        };
+        return too_many_requests(throttled.retry_after, format!(r#"<span class="report-done">{}</span>"#, message));
+    }
+
+    let report_count = match db::report_post(&state.db_pool, post_uuid, user.id, &form.reason).await {
+        Ok(count) => count,
+        Err(e) => {
+            tracing::error!("Failed to report post: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+        }
+    };
+
+    if report_count >= report_threshold() {
+        match db::send_post_to_review(&state.db_pool, post_uuid, "Flagged by community reports").await {
+            Ok(true) => tracing::info!("Post {} sent to review after {} reports", post_uuid, report_count),
+            Ok(false) => {}
+            Err(e) => tracing::error!("Failed to send reported post to review: {}", e),
+        }
+    }
+
+    (
+        StatusCode::OK,
+        Html(format!(r#"<span class="report-done">{}</span>"#, lang.t("review.reported"))),
+    )
+        .into_response()
+}
+
+// An author's one appeal of their rejected post. Responds with a notice that replaces the form.
+async fn appeal_review_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(review_id): Path<String>,
+    Form(form): Form<AppealForm>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
+
+    let post_uuid = match Uuid::parse_str(&review_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    let statement = form.statement.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    if statement.is_some_and(|s| s.chars().count() > APPEAL_STATEMENT_MAX_CHARS) {
+        return appeal_notice(StatusCode::BAD_REQUEST, lang.tf("appeal.too_long", APPEAL_STATEMENT_MAX_CHARS));
+    }
+
+    match rate_limit::can_appeal(&state.db_pool, user.id).await {
+        Ok(true) => {}
+        Ok(false) => return appeal_notice(StatusCode::TOO_MANY_REQUESTS, lang.t("appeal.rate_limited").to_string()),
+        Err(e) => {
+            tracing::error!("Failed to check appeal rate limit: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+        }
+    }
+
+    match db::create_appeal(&state.db_pool, post_uuid, user.id, statement).await {
+        Ok(true) => {
+            tracing::info!("Post {} appealed by {}", post_uuid, user.username);
+            if let Err(e) = rate_limit::record_appeal_action(&state.db_pool, user.id).await {
+                tracing::warn!("Failed to record appeal for rate limiting: {}", e);
+            }
+            appeal_notice(StatusCode::OK, lang.t("appeal.filed").to_string())
+        }
+        Ok(false) => appeal_notice(StatusCode::CONFLICT, lang.t("appeal.unavailable").to_string()),
+        Err(e) => {
+            tracing::error!("Failed to file appeal: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+fn appeal_notice(status: StatusCode, notice: String) -> Response {
+    match (ModerationNoticeTemplate { notice }).render() {
+        Ok(html) => (status, Html(html)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to render appeal notice: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+async fn upvote_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(review_id): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
+
+    let post_uuid = match Uuid::parse_str(&review_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    if let Some(throttled) = action_throttled(&state, &user, rate_limit::ActionType::Vote).await {
+        return too_many_requests(throttled.retry_after, String::new());
+    }
+
+    vote_response(review_id, db::upvote_post(&state.db_pool, post_uuid, user.id).await)
+}
+
+async fn downvote_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(review_id): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
+
+    let post_uuid = match Uuid::parse_str(&review_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    if let Some(throttled) = action_throttled(&state, &user, rate_limit::ActionType::Vote).await {
+        return too_many_requests(throttled.retry_after, String::new());
+    }
+
+    vote_response(review_id, db::downvote_post(&state.db_pool, post_uuid, user.id).await)
+}
+
+/// How long until a signed-in user may take this action again and which limit stops them, or None if
+/// they can now (and it was counted) or are exempt. A failed check lets the action through rather
+/// than blocking everyone.
+async fn action_throttled(
+    state: &AppState,
+    user: &models::User,
+    action: rate_limit::ActionType,
+) -> Option<rate_limit::Throttled> {
+    let limit = state.rate_limits.limit_for(user, action)?;
+    match rate_limit::check_and_record(&state.db_pool, &state.rate_limit_cache, user.id, action, limit).await {
+        Ok(wait) => wait,
+        Err(e) => {
+            tracing::warn!("Failed to check {} rate limit: {:#}", action.as_str(), e);
+            None
+        }
+    }
+}
+
+fn too_many_requests(seconds: i64, body: String) -> Response {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, seconds.to_string())],
+        Html(body),
+    )
+        .into_response()
+}
+
+// Re-render the vote buttons with the new counts and the user's current vote
+fn vote_response(review_id: String, result: anyhow::Result<(i32, i32, Option<String>)>) -> Response {
+    match result {
+        Ok((upvotes, downvotes, user_vote)) => HtmlTemplate(VoteButtonsTemplate {
+            review: VoteState {
+                id: review_id,
+                upvotes,
+                downvotes,
+                user_vote: user_vote.unwrap_or_default(),
+            },
+        })
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to record vote: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+// Auth handlers
+async fn login_page(lang: Locale) -> impl IntoResponse {
+    HtmlTemplate(LoginTemplate { error: None, lang })
+}
+
+async fn login_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    origin: auth::RequestOrigin,
+    Form(form): Form<LoginForm>,
+) -> impl IntoResponse {
+    let ip_address = origin.ip_address.as_deref();
+    let login_failed = |reason: &str, target: Option<Uuid>| {
+        auth::audit(
+            &state.db_pool,
+            None,
+            &origin,
+            models::AuditAction::LoginFailed,
+            target,
+            serde_json::json!({ "username": form.username, "reason": reason }),
+        )
+    };
+
+    // Checked before the password so a locked-out attempt costs no Argon2 work and reveals nothing
+    match rate_limit::get_login_lockout(&state.db_pool, &form.username, ip_address).await {
+        Ok(Some(seconds)) => {
+            login_failed("locked_out", None).await;
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, seconds.to_string())],
+                HtmlTemplate(LoginTemplate {
+                    error: Some(format!("{} {}", lang.t("login.too_many_attempts"), retry_after_message(lang, seconds))),
+                    lang,
+                }),
+            )
+                .into_response();
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to check login lockout: {:#}", e),
+    }
+
+    match auth::authenticate_user(&state.db_pool, &form.username, &form.password).await {
+        Ok(Some(user)) if user.is_suspended() => {
+            login_failed("suspended", Some(user.id)).await;
+            HtmlTemplate(LoginTemplate {
+                error: Some(lang.t("login.suspended").to_string()),
+                lang,
+            })
+            .into_response()
+        }
+        Ok(Some(user)) => {
+            if let Err(e) = rate_limit::clear_login_failures(&state.db_pool, &form.username).await {
+                tracing::warn!("Failed to clear login failures: {:#}", e);
+            }
+
+            match auth::create_session(&state.db_pool, user.id).await {
+                Ok(session) => {
+                    auth::audit(
+                        &state.db_pool,
+                        Some(user.id),
+                        &origin,
+                        models::AuditAction::LoginSucceeded,
+                        Some(user.id),
+                        serde_json::json!({ "method": "password" }),
+                    )
+                    .await;
+                    let cookie = session_cookie(session.session_token, session.expires_at);
+
+                    (jar.add(cookie), Redirect::to("/")).into_response()
+                }
+                Err(_) => HtmlTemplate(LoginTemplate {
+                    error: Some(lang.t("login.session_failed").to_string()),
+                    lang,
+                })
+                .into_response(),
+            }
+        }
+        Ok(None) => {
+            if let Err(e) = rate_limit::record_login_failure(&state.db_pool, &form.username, ip_address).await {
+                tracing::warn!("Failed to record login failure: {:#}", e);
+            }
+            login_failed("invalid_credentials", None).await;
+
+            HtmlTemplate(LoginTemplate {
+                error: Some(lang.t("login.invalid").to_string()),
+                lang,
+            })
+            .into_response()
+        }
+        Err(_) => HtmlTemplate(LoginTemplate {
+            error: Some(lang.t("error.generic").to_string()),
+            lang,
+        })
+        .into_response(),
+    }
+}
+
+async fn forgot_password_page(lang: Locale) -> impl IntoResponse {
+    HtmlTemplate(ForgotPasswordTemplate { sent: false, lang })
+}
+
+// Always answers the same way, and mails in the background, so the response says nothing
+// about whether the address belongs to an account
+async fn forgot_password_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    Form(form): Form<ForgotPasswordForm>,
+) -> impl IntoResponse {
+    let email = form.email.trim().to_string();
+    if auth::is_valid_email(&email) {
+        tokio::spawn(async move {
+            if let Err(e) = send_password_resets(&state, lang, &email).await {
+                tracing::warn!("Failed to send password reset email: {:#}", e);
+            }
+        });
+    }
+
+    HtmlTemplate(ForgotPasswordTemplate { sent: true, lang })
+}
+
+async fn send_password_resets(state: &AppState, lang: Locale, email: &str) -> anyhow::Result<()> {
+    for user in auth::get_users_by_verified_email(&state.db_pool, email).await? {
+        if rate_limit::get_time_until_next_account_email(&state.db_pool, user.id)
+            .await?
+            .is_some()
+        {
+            continue;
+        }
+
+        let token = auth::create_password_reset(&state.db_pool, user.id).await?;
+        let link = format!("{}/reset-password?token={}", public_base_url(), token);
+
+        rate_limit::record_account_email(&state.db_pool, user.id).await?;
+        state
+            .mailer
+            .send(
+                email,
+                lang.t("email.reset_subject"),
+                &lang.tf2("email.reset_body", &user.username, link),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn reset_password_page(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    Query(params): Query<ResetPasswordQuery>,
+) -> impl IntoResponse {
+    let token_valid = match auth::password_reset_is_valid(&state.db_pool, &params.token).await {
+        Ok(valid) => valid,
+        Err(e) => return AppError::internal(e.context("Failed to check password reset")).into_response(),
+    };
+
+    HtmlTemplate(ResetPasswordTemplate {
+        token: params.token,
+        token_valid,
+        error: None,
+        done: false,
+        lang,
+    })
+    .into_response()
+}
+
+async fn reset_password_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    origin: auth::RequestOrigin,
+    Query(params): Query<ResetPasswordQuery>,
+    Form(form): Form<ResetPasswordForm>,
+) -> impl IntoResponse {
+    let error = if let Err(reason) = auth::validate_password_strength(&form.new_password) {
+        Some(reason)
+    } else if form.new_password != form.confirm_password {
+        Some(lang.t("profile.passwords_mismatch").to_string())
+    } else {
+        None
+    };
+
+    if error.is_some() {
+        return HtmlTemplate(ResetPasswordTemplate {
+            token: params.token,
+            token_valid: true,
+            error,
+            done: false,
+            lang,
+        })
+        .into_response();
+    }
+
+    match auth::reset_password(&state.db_pool, &params.token, &form.new_password).await {
+        Ok(user_id) => {
+            if let Some(user_id) = user_id {
+                auth::audit(
+                    &state.db_pool,
+                    Some(user_id),
+                    &origin,
+                    models::AuditAction::PasswordReset,
+                    Some(user_id),
+                    serde_json::json!({}),
+                )
+                .await;
+            }
+            HtmlTemplate(ResetPasswordTemplate {
+                token: String::new(),
+                token_valid: user_id.is_some(),
+                error: None,
+                done: user_id.is_some(),
+                lang,
+            })
+            .into_response()
+        }
+        Err(e) => AppError::internal(e.context("Failed to reset password")).into_response(),
+    }
+}
+
+async fn register_page(State(state): State<Arc<AppState>>, lang: Locale) -> impl IntoResponse {
+    let constituencies = auth::get_all_constituencies(&state.db_pool)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| ConstituencyOption {
+            id: c.id,
+            name: c.name,
+            state: c.state,
+        })
+        .collect();
+
+    HtmlTemplate(RegisterTemplate {
+        error: None,
+        constituencies,
+        challenge: state.challenge.widget(),
+        lang,
+    })
+}
+
+async fn register_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Form(form): Form<RegisterForm>,
+) -> impl IntoResponse {
+    let constituencies = auth::get_all_constituencies(&state.db_pool)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| ConstituencyOption {
+            id: c.id,
+            name: c.name,
+            state: c.state,
+        })
+        .collect::<Vec<_>>();
+
+    // Bot check first, so scripted sign-ups cost no database work
+    let peer = connect_info.map(|ConnectInfo(addr)| addr.ip());
+    let ip_address = rate_limit::client_ip(peer, &headers).map(|ip| ip.to_string());
+    let solved = state
+        .challenge
+        .verify(&form.challenge_response, ip_address.as_deref())
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Registration challenge could not be verified: {:#}", e);
+            false
+        });
+    if !solved {
+        return HtmlTemplate(RegisterTemplate {
+            error: Some(lang.t("register.challenge_failed").to_string()),
+            constituencies,
+            challenge: state.challenge.widget(),
+            lang,
+        })
+        .into_response();
+    }
+
+    // Validate username
+    if form.username.is_empty() {
+        return HtmlTemplate(RegisterTemplate {
+            error: Some(lang.t("register.username_required").to_string()),
+            constituencies,
+            challenge: state.challenge.widget(),
+            lang,
+        })
+        .into_response();
+    }
+
+    // Check if username exists
+    if auth::username_exists(&state.db_pool, &form.username)
+        .await
+        .unwrap_or(false)
+    {
+        return HtmlTemplate(RegisterTemplate {
+            error: Some(lang.t("register.username_taken").to_string()),
+            constituencies,
+            challenge: state.challenge.widget(),
+            lang,
+        })
+        .into_response();
+    }
+
+    if let Err(reason) = auth::validate_password_strength(&form.password) {
+        return HtmlTemplate(RegisterTemplate {
+            error: Some(reason),
+            constituencies,
+            challenge: state.challenge.widget(),
+            lang,
+        })
+        .into_response();
+    }
+
+    let email = form.email.as_deref().map(str::trim).filter(|e| !e.is_empty());
+    if email.is_some_and(|e| !auth::is_valid_email(e)) {
+        return HtmlTemplate(RegisterTemplate {
+            error: Some(lang.t("profile.email_invalid").to_string()),
+            constituencies,
+            challenge: state.challenge.widget(),
+            lang,
+        })
+        .into_response();
+    }
+
+    // Parse age if provided
+    let age: Option<i32> = form.age.as_ref().and_then(|a| a.parse().ok());
+
+    // Determine pincode and constituency based on location_type
+    let (pincode, constituency_id) = if form.location_type == "pincode" {
+        (submitted_pincode(form.pincode.as_deref()), None)
+    } else {
+        (None, form.constituency_id.as_ref().and_then(|c| c.parse().ok()))
+    };
+
+    match auth::create_user(
+        &state.db_pool,
+        &form.username,
+        &form.password,
+        form.real_name.as_deref(),
+        age,
+        form.gender.as_deref(),
+        pincode.as_deref(),
+        constituency_id,
+    )
+    .await
+    {
+        Ok(user) => {
+            if let Some(email) = email {
+                if let Err(e) = start_email_verification(&state, lang, &user, email).await {
+                    tracing::warn!("Failed to start email verification for new user: {:#}", e);
+                }
+            }
+
+            match auth::create_session(&state.db_pool, user.id).await {
+                Ok(session) => {
+                    let cookie = session_cookie(session.session_token, session.expires_at);
+
+                    (jar.add(cookie), Redirect::to("/")).into_response()
+                }
+                Err(_) => Redirect::to("/login").into_response(),
+            }
+        }
+        Err(e) => {
+            let error = match e.downcast_ref::<auth::PincodeError>() {
+                Some(error) => pincode_error_message(&state, lang, error).await,
+                None => {
+                    tracing::error!("Failed to create user: {}", e);
+                    lang.t("register.failed").to_string()
+                }
+            };
+            HtmlTemplate(RegisterTemplate {
+                error: Some(error),
+                constituencies,
+                challenge: state.challenge.widget(),
+                lang,
+            })
+            .into_response()
+        }
+    }
+}
+
+// The pincode field, or None when it was left empty
+fn submitted_pincode(pincode: Option<&str>) -> Option<String> {
+    pincode.map(str::trim).filter(|p| !p.is_empty()).map(str::to_string)
+}
+
+// Form error for a pincode that can't be used, naming constituencies with nearby pincodes so the
+// user can pick one from the list instead
+async fn pincode_error_message(state: &AppState, lang: Locale, error: &auth::PincodeError) -> String {
+    match error {
+        auth::PincodeError::InvalidFormat => lang.t("form.pincode_invalid").to_string(),
+        auth::PincodeError::Unmapped(pincode) => {
+            let suggestions = auth::suggest_constituencies_for_pincode(&state.db_pool, pincode)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to suggest constituencies: {:#}", e);
+                    vec![]
+                });
+
+            let mut message = lang.tf("form.pincode_unmapped", pincode);
+            if !suggestions.is_empty() {
+                let names = suggestions
+                    .iter()
+                    .map(|c| format!("{} ({})", c.name, c.state))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                message.push(' ');
+                message.push_str(&lang.tf("form.pincode_suggestions", names));
+            }
+            message
+        }
+    }
+}
+
+// Holds the CSRF state and PKCE verifier while the browser is at Google; scoped to the
+// callback path and short-lived, since the sign-in either finishes in minutes or not at all
+const OAUTH_STATE_COOKIE_NAME: &str = "oauth_state";
+const OAUTH_STATE_MAX_AGE_SECS: i64 = 10 * 60;
+
+// Where a Google sign-in is going, read back from the state cookie on the callback
+struct PendingOAuth {
+    link: bool,
+    csrf_state: String,
+    pkce_verifier: String,
+}
+
+impl PendingOAuth {
+    fn to_cookie_value(&self) -> String {
+        let intent = if self.link { "link" } else { "login" };
+        format!("{}.{}.{}", intent, self.csrf_state, self.pkce_verifier)
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.splitn(3, '.');
+        let link = match parts.next()? {
+            "link" => true,
+            "login" => false,
+            _ => return None,
+        };
+        Some(Self {
+            link,
+            csrf_state: parts.next()?.to_string(),
+            pkce_verifier: parts.next()?.to_string(),
+        })
+    }
+}
+
+fn oauth_state_cookie(value: String, max_age_secs: i64) -> Cookie<'static> {
+    Cookie::build((OAUTH_STATE_COOKIE_NAME, value))
+        .path("/auth/google")
+        .http_only(true)
+        .secure(cookie_secure())
+        // Lax, so the cookie comes back on Google's top-level redirect to the callback
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::seconds(max_age_secs))
+        .build()
+}
+
+fn login_error(lang: Locale, message: &'static str) -> Response {
+    HtmlTemplate(LoginTemplate {
+        error: Some(lang.t(message).to_string()),
+        lang,
+    })
+    .into_response()
+}
+
+async fn google_start_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Query(params): Query<GoogleStartQuery>,
+) -> impl IntoResponse {
+    let Some(google) = state.google_oauth.as_ref() else {
+        return AppError::not_found("error.not_found_text").into_response();
+    };
+
+    let link = params.link.is_some();
+    if link && get_current_user(&jar, &state.db_pool).await.is_none() {
+        return Redirect::to("/login").into_response();
+    }
+
+    let authorization = google.authorize();
+    let pending = PendingOAuth {
+        link,
+        csrf_state: authorization.csrf_state,
+        pkce_verifier: authorization.pkce_verifier,
+    };
+    let cookie = oauth_state_cookie(pending.to_cookie_value(), OAUTH_STATE_MAX_AGE_SECS);
+
+    (jar.add(cookie), Redirect::to(&authorization.url)).into_response()
+}
+
+// Google sends the browser back here. A new Google account gets a user of its own; the session
+// is created exactly as a password login creates it.
+async fn google_callback_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    origin: auth::RequestOrigin,
+    Query(params): Query<GoogleCallbackQuery>,
+) -> impl IntoResponse {
+    let Some(google) = state.google_oauth.as_ref() else {
+        return AppError::not_found("error.not_found_text").into_response();
+    };
+
+    let pending = jar.get(OAUTH_STATE_COOKIE_NAME).and_then(|c| PendingOAuth::parse(c.value()));
+    let jar = jar.remove(oauth_state_cookie(String::new(), 0));
+
+    // No code means the user cancelled at Google; a state mismatch means this isn't our request
+    let (Some(pending), Some(code), Some(returned_state)) = (pending, params.code, params.state) else {
+        return (jar, login_error(lang, "login.google_failed")).into_response();
+    };
+    if returned_state != pending.csrf_state {
+        return (jar, login_error(lang, "login.google_failed")).into_response();
+    }
+
+    let profile = match google.exchange(code, pending.pkce_verifier).await {
+        Ok(profile) => profile,
+        Err(e) => {
+            tracing::warn!("Google sign-in failed: {:#}", e);
+            return (jar, login_error(lang, "login.google_failed")).into_response();
+        }
+    };
+
+    if pending.link {
+        let Some(user) = get_current_user(&jar, &state.db_pool).await else {
+            return (jar, Redirect::to("/login")).into_response();
+        };
+        let outcome = match auth::link_identity(
+            &state.db_pool,
+            user.id,
+            oauth::GOOGLE,
+            &profile.subject,
+            profile.email.as_deref(),
+        )
+        .await
+        {
+            Ok(true) => "linked",
+            Ok(false) => "taken",
+            Err(e) => return AppError::internal(e.context("Failed to link Google account")).into_response(),
+        };
+        return (jar, Redirect::to(&format!("/u/{}?google={}#security", user.username, outcome))).into_response();
+    }
+
+    let user = match auth::get_user_by_identity(&state.db_pool, oauth::GOOGLE, &profile.subject).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            match auth::create_user_from_identity(
+                &state.db_pool,
+                oauth::GOOGLE,
+                &profile.subject,
+                profile.email.as_deref(),
+                &oauth::username_base(&profile),
+            )
+            .await
+            {
+                Ok(user) => user,
+                Err(e) => return AppError::internal(e.context("Failed to create user from Google")).into_response(),
+            }
+        }
+        Err(e) => return AppError::internal(e.context("Failed to look up Google identity")).into_response(),
+    };
+
+    if user.is_suspended() {
+        auth::audit(
+            &state.db_pool,
+            None,
+            &origin,
+            models::AuditAction::LoginFailed,
+            Some(user.id),
+            serde_json::json!({ "username": user.username, "reason": "suspended", "method": "google" }),
+        )
+        .await;
+        return (jar, login_error(lang, "login.suspended")).into_response();
+    }
+
+    match auth::create_session(&state.db_pool, user.id).await {
+        Ok(session) => {
+            auth::audit(
+                &state.db_pool,
+                Some(user.id),
+                &origin,
+                models::AuditAction::LoginSucceeded,
+                Some(user.id),
+                serde_json::json!({ "method": "google" }),
+            )
+            .await;
+            let cookie = session_cookie(session.session_token, session.expires_at);
+            (jar.add(cookie), Redirect::to("/")).into_response()
+        }
+        Err(_) => (jar, login_error(lang, "login.session_failed")).into_response(),
+    }
+}
+
+// Unlinking is refused while Google is the account's only way in
+async fn unlink_google_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(username): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    if !user.password_set {
+        return Redirect::to(&format!("/u/{}?google=needs_password#security", username)).into_response();
+    }
+
+    if let Err(e) = auth::unlink_identity(&state.db_pool, user.id, oauth::GOOGLE).await {
+        return AppError::internal(e.context("Failed to unlink Google account")).into_response();
+    }
+
+    Redirect::to(&format!("/u/{}?google=unlinked#security", username)).into_response()
+}
+
+async fn logout_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    origin: auth::RequestOrigin,
+) -> impl IntoResponse {
+    if let Some(cookie) = jar.get(SESSION_COOKIE_NAME) {
+        if let Ok(Some(user)) = auth::get_user_by_session(&state.db_pool, cookie.value()).await {
+            auth::audit(
+                &state.db_pool,
+                Some(user.id),
+                &origin,
+                models::AuditAction::Logout,
+                Some(user.id),
+                serde_json::json!({}),
+            )
+            .await;
+        }
+        let _ = auth::delete_session(&state.db_pool, cookie.value()).await;
+    }
+
+    let cookie = Cookie::build((SESSION_COOKIE_NAME, ""))
+        .path("/")
+        .max_age(time::Duration::seconds(0))
+        .build();
+
+    (jar.remove(cookie), Redirect::to("/"))
+}
+
+// Language switcher - remembers the choice in a cookie and goes back to the page it came from
+async fn set_language_handler(
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(code): Path<String>,
+) -> impl IntoResponse {
+    let locale = match Locale::parse(&code) {
+        Some(locale) => locale,
+        None => {
+            return AppError::not_found("error.unsupported_language").into_response();
+        }
+    };
+
+    let cookie = Cookie::build((LOCALE_COOKIE_NAME, locale.code()))
+        .path("/")
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::days(365))
+        .build();
+
+    (jar.add(cookie), Redirect::to(&referer_path(&headers))).into_response()
+}
+
+// Path and query of the Referer, so the redirect can never leave the site
+fn referer_path(headers: &HeaderMap) -> String {
+    headers
+        .get(header::REFERER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|referer| {
+            let rest = referer.split_once("://").map_or(referer, |(_, rest)| rest);
+            rest.find('/').map(|i| rest[i..].to_string())
+        })
+        .filter(|path| !path.starts_with("//") && !path.starts_with("/\\"))
+        .unwrap_or_else(|| "/".to_string())
+}
+
+// Secure is on unless COOKIE_SECURE=false, so plain-http local dev can still log in
+fn cookie_secure() -> bool {
+    std::env::var("COOKIE_SECURE")
+        .map(|v| !v.eq_ignore_ascii_case("false") && v != "0")
+        .unwrap_or(true)
+}
+
+// Kept by the browser until the session expires
+fn session_cookie(token: String, expires_at: chrono::DateTime<chrono::Utc>) -> Cookie<'static> {
+    let remaining = (expires_at - chrono::Utc::now()).num_seconds().max(0);
+    Cookie::build((SESSION_COOKIE_NAME, token))
+        .path("/")
+        .http_only(true)
+        .secure(cookie_secure())
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::seconds(remaining))
+        .build()
+}
+
+// Slides an active user's session forward and sends the cookie again with the new expiry.
+// Runs after the handler, and leaves alone responses that set the session cookie themselves
+// (login, logout), so a replaced session's cookie can't override the new one.
+async fn slide_session(State(state): State<Arc<AppState>>, jar: CookieJar, request: Request, next: Next) -> Response {
+    let skip = request.uri().path().starts_with("/static/");
+    let mut response = next.run(request).await;
+
+    let sets_session_cookie = response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .any(|v| v.to_str().is_ok_and(|v| v.starts_with(&format!("{}=", SESSION_COOKIE_NAME))));
+    let Some(token) = jar.get(SESSION_COOKIE_NAME).map(|c| c.value().to_string()) else {
+        return response;
+    };
+    if skip || sets_session_cookie {
+        return response;
+    }
+
+    match auth::extend_session(&state.db_pool, &token).await {
+        Ok(Some(expires_at)) => {
+            if let Ok(value) = HeaderValue::from_str(&session_cookie(token, expires_at).to_string()) {
+                response.headers_mut().append(header::SET_COOKIE, value);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to extend session: {:#}", e),
+    }
+    response
+}
+
+// Per-address caps on sign-in and sign-up submissions, against credential stuffing and mass
+// registration. These sit in front of the per-username login lockout, which stops guessing at one
+// account but not one address trying many.
+const LOGIN_ATTEMPTS_PER_IP: i64 = 20;
+const LOGIN_ATTEMPT_WINDOW_MINUTES: i64 = 15;
+const REGISTRATIONS_PER_IP: i64 = 5;
+const REGISTRATION_WINDOW_MINUTES: i64 = 60;
+
+// Route layer for /login and /register: counts POSTs by client address and answers 429 with
+// Retry-After once an address is over its limit. Fails open if the database check errors.
+async fn limit_by_ip(
+    State(state): State<Arc<AppState>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.method() != axum::http::Method::POST {
+        return next.run(request).await;
+    }
+    let (action, limit, window) = match request.uri().path() {
+        "/login" => (
+            "login_attempt",
+            LOGIN_ATTEMPTS_PER_IP,
+            chrono::Duration::minutes(LOGIN_ATTEMPT_WINDOW_MINUTES),
+        ),
+        "/register" => (
+            "register",
+            REGISTRATIONS_PER_IP,
+            chrono::Duration::minutes(REGISTRATION_WINDOW_MINUTES),
+        ),
+        _ => return next.run(request).await,
+    };
+    let peer = connect_info.map(|ConnectInfo(addr)| addr.ip());
+    let Some(ip) = rate_limit::client_ip(peer, request.headers()) else {
+        return next.run(request).await;
+    };
+
+    match rate_limit::check_ip_action(&state.db_pool, &ip.to_string(), action, limit, window).await {
+        Ok(Some(seconds)) => (
+            [(header::RETRY_AFTER, seconds.to_string())],
+            AppError::new(StatusCode::TOO_MANY_REQUESTS, "error.ip_rate_limited"),
+        )
+            .into_response(),
+        Ok(None) => next.run(request).await,
+        Err(e) => {
+            tracing::warn!("Failed to check address rate limit: {:#}", e);
+            next.run(request).await
+        }
+    }
+}
+
+// Route layer for search and /api/ask, which embed the query and search Qdrant for anyone who asks;
+// asking also has the LLM write an answer. Signed-in users and API token holders are counted per
+// user, everyone else per client address with a lower limit, and 429 with Retry-After comes back
+// once over it. Fails open like limit_by_ip.
+async fn limit_search(
+    State(state): State<Arc<AppState>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (action, anonymous_limit, error_key) = match request.uri().path() {
+        "/api/ask" => (
+            rate_limit::ActionType::Ask,
+            state.rate_limits.anonymous_asks,
+            "error.ask_rate_limited",
+        ),
+        _ => (
+            rate_limit::ActionType::Search,
+            state.rate_limits.anonymous_searches,
+            "error.search_rate_limited",
+        ),
+    };
+
+    let wait = match auth::request_user(&state.db_pool, request.headers()).await {
+        Some(user) => action_throttled(&state, &user, action)
+            .await
+            .map(|throttled| throttled.retry_after),
+        None => {
+            let peer = connect_info.map(|ConnectInfo(addr)| addr.ip());
+            let Some(ip) = rate_limit::client_ip(peer, request.headers()) else {
+                return next.run(request).await;
+            };
+            let ip = ip.to_string();
+            let limit = anonymous_limit;
+            match rate_limit::check_ip_action(&state.db_pool, &ip, action.as_str(), limit.limit, limit.window).await {
+                Ok(wait) => wait,
+                Err(e) => {
+                    tracing::warn!("Failed to check address rate limit: {:#}", e);
+                    None
+                }
+            }
+        }
+    };
+
+    match wait {
+        Some(seconds) => (
+            [(header::RETRY_AFTER, seconds.to_string())],
+            AppError::new(StatusCode::TOO_MANY_REQUESTS, error_key),
+        )
+            .into_response(),
+        None => next.run(request).await,
+    }
+}
+
+// Profile handlers
+async fn profile_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(username): Path<String>,
+    Query(params): Query<ProfileQuery>,
+) -> impl IntoResponse {
+    let current_user = get_current_user(&jar, &state.db_pool).await;
+    let email_notice = match params.email.as_deref() {
+        Some("sent") => Some(lang.t("profile.email_sent")),
+        Some("verified") => Some(lang.t("profile.email_now_verified")),
+        Some("removed") => Some(lang.t("profile.email_removed")),
+        Some("digest") => Some(lang.t("profile.digest_saved")),
+        _ => None,
+    };
+    let (google_notice, google_error) = match params.google.as_deref() {
+        Some("linked") => (Some(lang.t("profile.google_linked")), None),
+        Some("unlinked") => (Some(lang.t("profile.google_unlinked")), None),
+        Some("taken") => (None, Some(lang.t("profile.google_taken"))),
+        Some("needs_password") => (None, Some(lang.t("profile.google_needs_password"))),
+        _ => (None, None),
+    };
+    let security = SecurityFeedback {
+        password_notice: (params.password.as_deref() == Some("changed")).then(|| {
+            let mut notice = lang.t("profile.password_changed").to_string();
+            if let Some(count) = params.revoked {
+                notice = format!("{} {}", notice, revoked_sessions_message(lang, count));
+            }
+            if let Some(count) = params.tokens_revoked.filter(|n| *n > 0) {
+                notice = format!("{} {}", notice, lang.tf("profile.api_tokens_revoked", count));
+            }
+            notice
+        }),
+        email_notice: email_notice.map(str::to_string),
+        google_notice: google_notice.map(str::to_string),
+        google_error: google_error.map(str::to_string),
+        ..Default::default()
+    };
+
+    profile_response(&state, lang, current_user, &username, security).await
+}
+
+async fn profile_response(
+    state: &AppState,
+    lang: Locale,
+    current_user: Option<models::User>,
+    username: &str,
+    security: SecurityFeedback,
+) -> Response {
+    let profile = match db::get_user_profile(&state.db_pool, username).await {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            return AppError::not_found("error.user_not_found").into_response();
+        }
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load profile")).into_response();
+        }
+    };
+
+    // The path may differ in case from the stored username, so ownership goes by id
+    let is_own_profile = current_user.as_ref().is_some_and(|u| u.id == profile.id);
+
+    // Get constituency_id for the profile
+    let constituency_id = current_user
+        .as_ref()
+        .filter(|_| is_own_profile)
+        .and_then(|u| u.constituency_id)
+        .unwrap_or(0);
+
+    let appealed = if is_own_profile {
+        db::get_appealed_post_ids(&state.db_pool, profile.id).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load appealed posts: {:#}", e);
+            HashSet::new()
+        })
+    } else {
+        HashSet::new()
+    };
+
+    // Anonymous posts are listed only for their author
+    let posts = db::get_posts_by_user(&state.db_pool, profile.id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(post, _, _)| is_own_profile || !post.is_anonymous)
+        .map(|(post, bill_title, bill_number)| {
+            let can_appeal = is_own_profile && post.moderation_status == "rejected" && !appealed.contains(&post.id);
+            UserPost {
+                id: post.id.to_string(),
+                bill_title,
+                bill_number,
+                stance: post.stance.unwrap_or_else(|| "Reply".to_string()),
+                content: post.content,
+                moderation_status: post.moderation_status,
+                moderation_reason: post.moderation_reason,
+                is_anonymous: post.is_anonymous,
+                date: post.created_at.format("%B %d, %Y").to_string(),
+                upvotes: post.upvotes,
+                downvotes: post.downvotes,
+                can_appeal,
+            }
+        })
+        .collect();
+
+    let constituencies = if is_own_profile {
+        auth::get_all_constituencies(&state.db_pool)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| ConstituencyOption {
+                id: c.id,
+                name: c.name,
+                state: c.state,
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    let google = match current_user.as_ref().filter(|_| is_own_profile && state.google_oauth.is_some()) {
+        Some(u) => {
+            let identity = auth::get_identity(&state.db_pool, u.id, oauth::GOOGLE).await.unwrap_or_else(|e| {
+                tracing::warn!("Failed to load Google identity: {:#}", e);
+                None
+            });
+            Some(GoogleLink {
+                linked: identity.is_some(),
+                email: identity.and_then(|i| i.email),
+            })
+        }
+        None => None,
+    };
+
+    // Private fields are only shown to the owner and admins
+    let sees_private = is_own_profile || current_user.as_ref().map(|u| u.is_admin()).unwrap_or(false);
+    let visibility = profile.visibility;
+
+    let profile_data = ProfileData {
+        username: profile.username,
+        real_name: profile.real_name.filter(|_| sees_private || visibility.real_name_public),
+        age: profile.age.filter(|_| sees_private || visibility.age_public),
+        gender: profile.gender.filter(|_| sees_private || visibility.gender_public),
+        pincode: profile.pincode.filter(|_| sees_private || visibility.pincode_public),
+        visibility,
+        email: current_user.as_ref().filter(|_| is_own_profile).and_then(|u| u.email.clone()),
+        email_verified: profile.email_verified,
+        digest_enabled: current_user.as_ref().filter(|_| is_own_profile).is_some_and(|u| u.digest_enabled),
+        password_set: current_user.as_ref().filter(|_| is_own_profile).is_none_or(|u| u.password_set),
+        google,
+        constituency_id,
+        constituency_name: profile.constituency_name,
+        member_since: profile.created_at.format("%B %Y").to_string(),
+        post_count: profile.post_count,
+    };
+
+    HtmlTemplate(ProfileTemplate {
+        profile: profile_data,
+        posts,
+        is_own_profile,
+        user: current_user.map(|u| CurrentUser {
+            id: u.id.to_string(),
+            username: u.username,
+            is_admin: u.is_admin(),
+            can_moderate: u.can_moderate(),
+        }),
+        constituencies,
+        security,
+        lang,
+    })
+    .into_response()
+}
+
+async fn update_profile_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    origin: auth::RequestOrigin,
+    Path(username): Path<String>,
+    Form(form): Form<ProfileUpdateForm>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    // Only allow editing own profile
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    let age: Option<i32> = form.age.as_ref().and_then(|a| a.parse().ok());
+
+    let (pincode, constituency_id) = if form.location_type == "pincode" {
+        (submitted_pincode(form.pincode.as_deref()), None)
+    } else {
+        (None, form.constituency_id.as_ref().and_then(|c| c.parse().ok()))
+    };
+
+    if let Err(e) = auth::update_user_profile(
+        &state.db_pool,
+        user.id,
+        form.real_name.as_deref(),
+        age,
+        form.gender.as_deref(),
+        pincode.as_deref(),
+        constituency_id,
+    )
+    .await
+    {
+        if let Some(error) = e.downcast_ref::<auth::PincodeError>() {
+            let feedback = SecurityFeedback {
+                location_error: Some(pincode_error_message(&state, lang, error).await),
+                ..Default::default()
+            };
+            return profile_response(&state, lang, Some(user), &username, feedback).await;
+        }
+        return AppError::internal(e.context("Failed to update profile")).into_response();
+    }
+
+    let visibility = models::ProfileVisibility {
+        real_name_public: form.real_name_public.is_some(),
+        age_public: form.age_public.is_some(),
+        gender_public: form.gender_public.is_some(),
+        pincode_public: form.pincode_public.is_some(),
+    };
+
+    match auth::update_profile_visibility(&state.db_pool, user.id, &visibility).await {
+        Ok(_) => {
+            auth::audit(
+                &state.db_pool,
+                Some(user.id),
+                &origin,
+                models::AuditAction::ProfileUpdated,
+                Some(user.id),
+                serde_json::json!({ "location_type": form.location_type }),
+            )
+            .await;
+            Redirect::to(&format!("/u/{}", username)).into_response()
+        }
+        Err(e) => {
+            AppError::internal(e.context("Failed to update profile visibility")).into_response()
+        }
+    }
+}
+
+// Store a new address and email it a verification link
+async fn start_email_verification(state: &AppState, lang: Locale, user: &models::User, email: &str) -> anyhow::Result<()> {
+    auth::set_email(&state.db_pool, user.id, Some(email)).await?;
+    send_verification_email(state, lang, user, email).await
+}
+
+async fn send_verification_email(state: &AppState, lang: Locale, user: &models::User, email: &str) -> anyhow::Result<()> {
+    let verification = auth::create_email_verification(&state.db_pool, user.id, email).await?;
+    let link = format!("{}/verify-email?token={}", public_base_url(), verification.token);
+
+    rate_limit::record_account_email(&state.db_pool, user.id).await?;
+    state
+        .mailer
+        .send(
+            email,
+            lang.t("email.verify_subject"),
+            &lang.tf2("email.verify_body", &user.username, link),
+        )
+        .await
+}
+
+async fn update_email_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(username): Path<String>,
+    Form(form): Form<EmailForm>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    let email = form.email.trim();
+    if email.is_empty() {
+        if let Err(e) = auth::set_email(&state.db_pool, user.id, None).await {
+            return AppError::internal(e.context("Failed to remove email")).into_response();
+        }
+        return Redirect::to(&format!("/u/{}?email=removed#email", username)).into_response();
+    }
+
+    let mut feedback = SecurityFeedback::default();
+    if !auth::is_valid_email(email) {
+        feedback.email_error = Some(lang.t("profile.email_invalid").to_string());
+        return profile_response(&state, lang, Some(user), &username, feedback).await;
+    }
+
+    // Saving the same address again changes nothing and sends nothing
+    if user.email.as_deref() == Some(email) {
+        return Redirect::to(&format!("/u/{}#email", username)).into_response();
+    }
+
+    match rate_limit::get_time_until_next_account_email(&state.db_pool, user.id).await {
+        Ok(None) => {}
+        Ok(Some(wait_secs)) => {
+            feedback.email_error = Some(retry_after_message(lang, wait_secs));
+            return profile_response(&state, lang, Some(user), &username, feedback).await;
+        }
+        Err(e) => {
+            return AppError::internal(e.context("Failed to check account email rate limit")).into_response();
+        }
+    }
+
+    match start_email_verification(&state, lang, &user, email).await {
+        Ok(()) => Redirect::to(&format!("/u/{}?email=sent#email", username)).into_response(),
+        Err(e) => AppError::internal(e.context("Failed to send verification email")).into_response(),
+    }
+}
+
+async fn update_digest_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(username): Path<String>,
+    Form(form): Form<DigestForm>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    let enabled = form.digest_enabled.is_some();
+    match auth::set_digest_enabled(&state.db_pool, user.id, enabled).await {
+        Ok(()) => Redirect::to(&format!("/u/{}?email=digest#email", username)).into_response(),
+        Err(e) => AppError::internal(e.context("Failed to update digest preference")).into_response(),
+    }
+}
+
+async fn resend_verification_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(username): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    // Resending the link is the only thing an unverified address is used for
+    let email = match user.email.clone() {
+        Some(email) if user.email_verified_at.is_none() => email,
+        _ => return Redirect::to(&format!("/u/{}#email", username)).into_response(),
+    };
+
+    match rate_limit::get_time_until_next_account_email(&state.db_pool, user.id).await {
+        Ok(None) => {}
+        Ok(Some(wait_secs)) => {
+            let feedback = SecurityFeedback {
+                email_error: Some(retry_after_message(lang, wait_secs)),
+                ..Default::default()
+            };
+            return profile_response(&state, lang, Some(user), &username, feedback).await;
+        }
+        Err(e) => {
+            return AppError::internal(e.context("Failed to check account email rate limit")).into_response();
+        }
+    }
+
+    match send_verification_email(&state, lang, &user, &email).await {
+        Ok(()) => Redirect::to(&format!("/u/{}?email=sent#email", username)).into_response(),
+        Err(e) => AppError::internal(e.context("Failed to send verification email")).into_response(),
+    }
+}
+
+// Verification links are opened from the email, so this works without being signed in
+async fn verify_email_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<VerifyEmailQuery>,
+) -> impl IntoResponse {
+    match auth::verify_email(&state.db_pool, &params.token).await {
+        Ok(Some(user)) => Redirect::to(&format!("/u/{}?email=verified#email", user.username)).into_response(),
+        Ok(None) => AppError::bad_request("error.email_link_invalid").into_response(),
+        Err(e) => AppError::internal(e.context("Failed to verify email")).into_response(),
+    }
+}
+
+async fn change_password_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    origin: auth::RequestOrigin,
+    Path(username): Path<String>,
+    Form(form): Form<PasswordChangeForm>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    let mut feedback = SecurityFeedback::default();
+
+    if !auth::verify_password(&form.current_password, &user.password_hash).unwrap_or(false) {
+        feedback.current_error = Some(lang.t("profile.current_password_incorrect").to_string());
+    } else if let Err(reason) = auth::validate_password_strength(&form.new_password) {
+        feedback.new_error = Some(reason);
+    } else if form.new_password != form.confirm_password {
+        feedback.new_error = Some(lang.t("profile.passwords_mismatch").to_string());
+    }
+
+    if feedback.current_error.is_some() || feedback.new_error.is_some() {
+        return profile_response(&state, lang, Some(user), &username, feedback).await;
+    }
+
+    let tokens_revoked = match auth::update_password(&state.db_pool, user.id, &form.new_password).await {
+        Ok(count) => count,
+        Err(e) => return AppError::internal(e.context("Failed to update password")).into_response(),
+    };
+
+    // Sign out every other device; the session making this request stays valid
+    let current_token = jar.get(SESSION_COOKIE_NAME).map(|c| c.value());
+    let revoked = match auth::delete_sessions_for_user(&state.db_pool, user.id, current_token).await {
+        Ok(count) => Some(count),
+        Err(e) => {
+            tracing::error!("Failed to invalidate sessions: {}", e);
+            None
+        }
+    };
+
+    auth::audit(
+        &state.db_pool,
+        Some(user.id),
+        &origin,
+        models::AuditAction::PasswordChanged,
+        Some(user.id),
+        serde_json::json!({ "sessions_revoked": revoked, "api_tokens_revoked": tokens_revoked }),
+    )
+    .await;
+
+    let revoked_param = revoked.map(|n| format!("&revoked={}", n)).unwrap_or_default();
+    Redirect::to(&format!(
+        "/u/{}?password=changed{}&tokens_revoked={}#security",
+        username, revoked_param, tokens_revoked
+    ))
+    .into_response()
+}
+
+async fn sessions_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(username): Path<String>,
+    Query(params): Query<SessionsQuery>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    let current_token = jar
+        .get(SESSION_COOKIE_NAME)
+        .map(|c| c.value().to_string())
+        .unwrap_or_default();
+
+    let sessions = auth::list_sessions(&state.db_pool, user.id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| SessionItem {
+            id: s.id.to_string(),
+            token_preview: format!("{}…", s.session_token.chars().take(8).collect::<String>()),
+            created: s.created_at.format("%B %d, %Y %H:%M").to_string(),
+            expires: s.expires_at.format("%B %d, %Y %H:%M").to_string(),
+            current: s.session_token == current_token,
+        })
+        .collect();
+
+    HtmlTemplate(SessionsTemplate {
+        sessions,
+        notice: params.revoked.map(|count| revoked_sessions_message(lang, count)),
+        user: Some(CurrentUser {
+            id: user.id.to_string(),
+            username: user.username,
+            is_admin: user.is_admin(),
+            can_moderate: user.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+async fn revoke_session_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path((username, session_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) if u.username == username => u,
+        Some(_) => {
+            return (StatusCode::FORBIDDEN, Html("".to_string())).into_response();
+        }
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
+
+    let session_uuid = match Uuid::parse_str(&session_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    match auth::delete_user_session(&state.db_pool, user.id, session_uuid).await {
+        // Empty body so htmx removes the session from the list
+        Ok(true) => (StatusCode::OK, Html("".to_string())).into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, Html("".to_string())).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to revoke session: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+async fn revoke_other_sessions_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(username): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    let current_token = jar.get(SESSION_COOKIE_NAME).map(|c| c.value());
+    match auth::delete_sessions_for_user(&state.db_pool, user.id, current_token).await {
+        Ok(count) => Redirect::to(&format!("/u/{}/sessions?revoked={}", username, count)).into_response(),
+        Err(e) => AppError::internal(e.context("Failed to revoke sessions")).into_response(),
+    }
+}
+
+async fn api_tokens_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(username): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    api_tokens_response(&state, lang, user, None, None).await
+}
+
+async fn create_api_token_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(username): Path<String>,
+    Form(form): Form<ApiTokenForm>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    let name = form.name.trim();
+    if name.is_empty() || name.chars().count() > auth::API_TOKEN_NAME_MAX_CHARS {
+        let error = lang.tf("api_tokens.name_invalid", auth::API_TOKEN_NAME_MAX_CHARS);
+        return api_tokens_response(&state, lang, user, None, Some(error)).await;
+    }
+
+    let scope = form
+        .scope
+        .as_deref()
+        .and_then(models::ApiScope::parse)
+        .unwrap_or(models::ApiScope::Read);
+
+    match auth::create_api_token(&state.db_pool, user.id, name, scope).await {
+        Ok(token) => api_tokens_response(&state, lang, user, Some(token), None).await,
+        Err(e) => AppError::internal(e.context("Failed to create API token")).into_response(),
+    }
+}
+
+// Render the token list; rendered directly rather than redirected to, so a new token can be shown
+async fn api_tokens_response(
+    state: &AppState,
+    lang: Locale,
+    user: models::User,
+    new_token: Option<String>,
+    error: Option<String>,
+) -> Response {
+    let tokens = auth::list_api_tokens(&state.db_pool, user.id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| ApiTokenItem {
+            id: t.id.to_string(),
+            scope: match t.scope() {
+                models::ApiScope::Read => "api_tokens.scope_read",
+                models::ApiScope::Write => "api_tokens.scope_write",
+            },
+            name: t.name,
+            created: t.created_at.format("%B %d, %Y").to_string(),
+            last_used: t.last_used_at.map(|d| d.format("%B %d, %Y %H:%M").to_string()),
+        })
+        .collect();
+
+    HtmlTemplate(ApiTokensTemplate {
+        tokens,
+        new_token,
+        error,
+        user: Some(CurrentUser {
+            id: user.id.to_string(),
+            username: user.username,
+            is_admin: user.is_admin(),
+            can_moderate: user.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+async fn revoke_api_token_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path((username, token_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) if u.username == username => u,
+        Some(_) => {
+            return (StatusCode::FORBIDDEN, Html("".to_string())).into_response();
+        }
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
+
+    let token_uuid = match Uuid::parse_str(&token_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    match auth::delete_api_token(&state.db_pool, user.id, token_uuid).await {
+        // Empty body so htmx removes the token from the list
+        Ok(true) => (StatusCode::OK, Html("".to_string())).into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, Html("".to_string())).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to revoke API token: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+// Posts of deleted accounts are kept under the anonymized name unless DELETED_ACCOUNT_POSTS=delete
+fn delete_posts_with_account() -> bool {
+    std::env::var("DELETED_ACCOUNT_POSTS")
+        .map(|v| v.eq_ignore_ascii_case("delete"))
+        .unwrap_or(false)
+}
+
+async fn delete_account_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(username): Path<String>,
+    Form(form): Form<DeleteAccountForm>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    // Accounts created through Google have no password to confirm with; the session has to do
+    if user.password_set && !auth::verify_password(&form.password, &user.password_hash).unwrap_or(false) {
+        let feedback = SecurityFeedback {
+            delete_error: Some(lang.t("profile.password_incorrect").to_string()),
+            ..Default::default()
+        };
+        return profile_response(&state, lang, Some(user), &username, feedback).await;
+    }
+
+    if let Err(e) = auth::delete_account(&state.db_pool, user.id, delete_posts_with_account()).await {
+        return AppError::internal(e.context("Failed to delete account")).into_response();
+    }
+
+    let cookie = Cookie::build((SESSION_COOKIE_NAME, ""))
+        .path("/")
+        .max_age(time::Duration::seconds(0))
+        .build();
+
+    (jar.remove(cookie), Redirect::to("/")).into_response()
+}
+
+// Profile fields included in a data export
+#[derive(Serialize)]
+struct ExportProfile {
+    id: Uuid,
+    username: String,
+    real_name: Option<String>,
+    age: Option<i32>,
+    gender: Option<String>,
+    pincode: Option<String>,
+    constituency_id: Option<i32>,
+    real_name_public: bool,
+    age_public: bool,
+    gender_public: bool,
+    pincode_public: bool,
+    email: Option<String>,
+    email_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    digest_enabled: bool,
+    role: String,
+    suspended_at: Option<chrono::DateTime<chrono::Utc>>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+// Session metadata included in a data export; the token itself is left out
+#[derive(Serialize)]
+struct ExportSession {
+    id: Uuid,
+    created_at: chrono::DateTime<chrono::Utc>,
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+// Everything held about the signed-in user as a JSON download, limited to one per hour
+async fn export_data_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(username): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if user.username != username {
+        return AppError::forbidden("error.not_your_account").into_response();
+    }
+
+    match rate_limit::get_time_until_next_export(&state.db_pool, user.id).await {
+        Ok(None) => {}
+        Ok(Some(wait_secs)) => {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, wait_secs.max(1).to_string())],
+                retry_after_message(lang, wait_secs),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return AppError::internal(e.context("Failed to check export rate limit")).into_response();
+        }
+    }
+
+    let sessions = match auth::list_sessions(&state.db_pool, user.id).await {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load sessions for export")).into_response();
+        }
+    };
+
+    let header = match export_header(&user, &sessions) {
+        Ok(header) => header,
+        Err(e) => {
+            return AppError::internal(anyhow::Error::new(e).context("Failed to serialize export")).into_response();
+        }
+    };
+
+    if let Err(e) = rate_limit::record_export_action(&state.db_pool, user.id).await {
+        tracing::warn!("Failed to record export action: {}", e);
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}-export.json\"", user.username),
+            ),
+        ],
+        Body::from_stream(export_document(state.db_pool.clone(), user.id, header)),
+    )
+        .into_response()
+}
+
+// Opening of the export document, up to the start of the "posts" array
+fn export_header(user: &models::User, sessions: &[models::Session]) -> serde_json::Result<String> {
+    let profile = ExportProfile {
+        id: user.id,
+        username: user.username.clone(),
+        real_name: user.real_name.clone(),
+        age: user.age,
+        gender: user.gender.clone(),
+        pincode: user.pincode.clone(),
+        constituency_id: user.constituency_id,
+        real_name_public: user.real_name_public,
+        age_public: user.age_public,
+        gender_public: user.gender_public,
+        pincode_public: user.pincode_public,
+        email: user.email.clone(),
+        email_verified_at: user.email_verified_at,
+        digest_enabled: user.digest_enabled,
+        role: user.role.clone(),
+        suspended_at: user.suspended_at,
+        created_at: user.created_at,
+        updated_at: user.updated_at,
+    };
+    let sessions: Vec<ExportSession> = sessions
+        .iter()
+        .map(|s| ExportSession {
+            id: s.id,
+            created_at: s.created_at,
+            expires_at: s.expires_at,
+        })
+        .collect();
+
+    Ok(format!(
+        "{{\"exported_at\":{},\"profile\":{},\"sessions\":{},\"posts\":[",
+        serde_json::to_string(&chrono::Utc::now())?,
+        serde_json::to_string(&profile)?,
+        serde_json::to_string(&sessions)?
+    ))
+}
+
+// Streams the rest of the export: posts, then votes cast, fetched in batches so
+// large accounts never sit in memory at once
+fn export_document(pool: PgPool, user_id: Uuid, header: String) -> impl Stream<Item = anyhow::Result<String>> {
+    enum Step {
+        Header(String),
+        Posts(i64),
+        Votes(i64),
+        Done,
+    }
+
+    // Comma-separated items of one batch, continuing an array that may already have entries
+    fn json_batch<T: Serialize>(items: &[T], continues: bool) -> serde_json::Result<String> {
+        let mut chunk = String::new();
+        for (i, item) in items.iter().enumerate() {
+            if continues || i > 0 {
+                chunk.push(',');
+            }
+            chunk.push_str(&serde_json::to_string(item)?);
+        }
+        Ok(chunk)
+    }
+
+    futures::stream::unfold(Step::Header(header), move |step| {
+        let pool = pool.clone();
+        async move {
+            let result = match step {
+                Step::Header(header) => Ok((header, Step::Posts(0))),
+                Step::Posts(offset) => match db::get_export_posts(&pool, user_id, offset, EXPORT_BATCH_SIZE).await {
+                    Ok(posts) if posts.is_empty() => Ok(("],\"votes_cast\":[".to_string(), Step::Votes(0))),
+                    Ok(posts) => json_batch(&posts, offset > 0)
+                        .map(|chunk| (chunk, Step::Posts(offset + posts.len() as i64)))
+                        .map_err(anyhow::Error::from),
+                    Err(e) => Err(e),
+                },
+                Step::Votes(offset) => match db::get_export_votes(&pool, user_id, offset, EXPORT_BATCH_SIZE).await {
+                    Ok(votes) if votes.is_empty() => Ok(("]}\n".to_string(), Step::Done)),
+                    Ok(votes) => json_batch(&votes, offset > 0)
+                        .map(|chunk| (chunk, Step::Votes(offset + votes.len() as i64)))
+                        .map_err(anyhow::Error::from),
+                    Err(e) => Err(e),
+                },
+                Step::Done => return None,
+            };
+
+            match result {
+                Ok((chunk, next)) => Some((Ok(chunk), next)),
+                Err(e) => {
+                    tracing::error!("Failed to stream data export: {}", e);
+                    Some((Err(e), Step::Done))
+                }
+            }
+        }
+    })
+}
+
+// Admin handlers
+// A held or spot-check post as a card in the moderation queue
+fn pending_post(p: models::PostForReview) -> PendingPost {
+    PendingPost {
+        id: p.id.to_string(),
+        username: p.username,
+        is_anonymous: p.is_anonymous,
+        bill_id: p.bill_id.to_string(),
+        bill_title: p.bill_title,
+        bill_number: p.bill_number,
+        stance: p.stance.unwrap_or_else(|| "Reply".to_string()),
+        content: p.content,
+        reason: p.moderation_reason,
+        provider: p.moderation_provider,
+        report_count: p.report_count,
+        report_reasons: p.report_reasons,
+        date: p.created_at.format("%B %d, %Y %H:%M").to_string(),
+        duplicate: p.duplicate_of.and(p.duplicate_content).map(|content| DuplicateOriginal {
+            username: p.duplicate_username.unwrap_or_default(),
+            content,
+            status: p.duplicate_status.unwrap_or_default(),
+            similarity: (p.duplicate_similarity.unwrap_or(0.0) * 100.0).round() as i64,
+            date: p
+                .duplicate_created_at
+                .map(|d| d.format("%B %d, %Y %H:%M").to_string())
+                .unwrap_or_default(),
+        }),
+    }
+}
+
+async fn admin_moderation_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(user, ..): auth::RequireModerator,
+    Query(params): Query<ModerationQueueQuery>,
+) -> impl IntoResponse {
+    let posts = match db::get_posts_by_status(&state.db_pool, "pending_review").await {
+        Ok(posts) => posts,
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load moderation queue")).into_response();
+        }
+    };
+
+    let posts = posts.into_iter().map(pending_post).collect();
+
+    let sampled = match db::get_audit_sampled_posts(&state.db_pool).await {
+        Ok(posts) => posts.into_iter().map(pending_post).collect(),
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load posts sampled for audit")).into_response();
+        }
+    };
+
+    let appeals = match db::get_pending_appeals(&state.db_pool).await {
+        Ok(appeals) => appeals,
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load appeals")).into_response();
+        }
+    };
+
+    let appeals = appeals
+        .into_iter()
+        .map(|a| AppealedPost {
+            id: a.post_id.to_string(),
+            username: a.username,
+            is_anonymous: a.is_anonymous,
+            bill_id: a.bill_id.to_string(),
+            bill_title: a.bill_title,
+            bill_number: a.bill_number,
+            stance: a.stance.unwrap_or_else(|| "Reply".to_string()),
+            content: a.content,
+            reason: a.moderation_reason,
+            provider: a.moderation_provider,
+            statement: a.statement,
+            date: a.posted_at.format("%B %d, %Y %H:%M").to_string(),
+            appealed: a.appealed_at.format("%B %d, %Y %H:%M").to_string(),
+        })
+        .collect();
+
+    let remoderated = match (params.approved, params.rejected, params.pending) {
+        (Some(approved), Some(rejected), Some(pending)) => Some(RemoderatedCounts {
+            approved,
+            rejected,
+            pending,
+        }),
+        _ => None,
+    };
+
+    HtmlTemplate(AdminModerationTemplate {
+        posts,
+        sampled,
+        appeals,
+        remoderated,
+        user: Some(CurrentUser {
+            id: user.id.to_string(),
+            username: user.username,
+            is_admin: user.is_admin(),
+            can_moderate: user.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+// Every automatic moderation attempt on a post, with the providers' answers verbatim
+async fn admin_post_moderation_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(user, ..): auth::RequireModerator,
+    Path(post_id): Path<String>,
+) -> impl IntoResponse {
+    let post_uuid = match Uuid::parse_str(&post_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return AppError::bad_request("error.invalid_review_id").into_response();
+        }
+    };
+
+    let post = match db::get_post_by_id(&state.db_pool, post_uuid).await {
+        Ok(Some(post)) => post,
+        Ok(None) => return AppError::not_found("error.review_not_found").into_response(),
+        Err(e) => return AppError::internal(e.context("Failed to load post")).into_response(),
+    };
+
+    let events = match db::get_moderation_events(&state.db_pool, post_uuid).await {
+        Ok(events) => events,
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load moderation events")).into_response();
+        }
+    };
+
+    let events = events
+        .into_iter()
+        .map(|e| ModerationEventRow {
+            date: e.created_at.format("%B %d, %Y %H:%M:%S").to_string(),
+            provider: e.provider,
+            verdict: e.verdict,
+            trust_bias: e.trust_bias,
+            error: e.error,
+            latency_ms: e.latency_ms,
+            prompt_hash: e.prompt_hash.unwrap_or_default(),
+            raw_response: e.raw_response,
+        })
+        .collect();
+
+    HtmlTemplate(AdminPostModerationTemplate {
+        post_id: post.id.to_string(),
+        bill_id: post.bill_id.to_string(),
+        content: post.content,
+        status: post.moderation_status,
+        reason: post.moderation_reason,
+        provider: post.moderation_provider,
+        events,
+        user: Some(CurrentUser {
+            id: user.id.to_string(),
+            username: user.username,
+            is_admin: user.is_admin(),
+            can_moderate: user.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+// Run moderation again on the oldest posts it couldn't decide, e.g. after an Ollama outage
+async fn remoderate_posts_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(user, ..): auth::RequireModerator,
+) -> impl IntoResponse {
+    let filter = moderation::RemoderateFilter {
+        limit: Some(REMODERATE_BATCH_SIZE),
+        ..Default::default()
+    };
+
+    match moderation::remoderate(&state.db_pool, &state.moderation, &filter).await {
+        Ok(report) => {
+            tracing::info!(
+                "Re-moderation by {}: {} approved, {} rejected, {} still pending, {} skipped",
+                user.username,
+                report.approved.len(),
+                report.rejected,
+                report.still_pending,
+                report.skipped
+            );
+            for post in &report.approved {
+                notify_approved_post(&state.db_pool, post).await;
+                publish_review(&state, post);
+            }
+            Redirect::to(&format!(
+                "/admin/moderation?approved={}&rejected={}&pending={}",
+                report.approved.len(),
+                report.rejected,
+                report.still_pending
+            ))
+            .into_response()
+        }
+        Err(e) => AppError::internal(e.context("Failed to re-moderate held posts")).into_response(),
+    }
+}
+
+async fn approve_post_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(user, ..): auth::RequireModerator,
+    origin: auth::RequestOrigin,
+    Path(post_id): Path<String>,
+) -> impl IntoResponse {
+    moderate_post(&state, &user, &origin, &post_id, "approved", None).await
+}
+
+async fn reject_post_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(user, ..): auth::RequireModerator,
+    origin: auth::RequestOrigin,
+    Path(post_id): Path<String>,
+    Form(form): Form<RejectForm>,
+) -> impl IntoResponse {
+    let reason = form
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .unwrap_or("Rejected by a moderator");
+
+    moderate_post(&state, &user, &origin, &post_id, "rejected", Some(reason)).await
+}
+
+// Shared by the approve/reject handlers - responds with an empty fragment so htmx drops the row
+async fn moderate_post(
+    state: &AppState,
+    user: &models::User,
+    origin: &auth::RequestOrigin,
+    post_id: &str,
+    status: &str,
+    reason: Option<&str>,
+) -> Response {
+    let post_uuid = match Uuid::parse_str(post_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    match db::set_post_moderation_status(&state.db_pool, post_uuid, status, reason, user.id).await {
+        Ok(true) => {
+            tracing::info!("Post {} marked {} by {}", post_uuid, status, user.username);
+            let action = if status == "approved" {
+                models::AuditAction::PostApproved
+            } else {
+                models::AuditAction::PostRejected
+            };
+            auth::audit(
+                &state.db_pool,
+                Some(user.id),
+                origin,
+                action,
+                Some(post_uuid),
+                serde_json::json!({ "reason": reason }),
+            )
+            .await;
+            if status == "approved" {
+                if let Ok(Some(post)) = db::get_post_by_id(&state.db_pool, post_uuid).await {
+                    notify_approved_post(&state.db_pool, &post).await;
+                    publish_review(state, &post);
+                }
+            }
+            (StatusCode::OK, Html("".to_string())).into_response()
+        }
+        Ok(false) => (StatusCode::NOT_FOUND, Html("".to_string())).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to moderate post: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+// A moderator agrees with a trusted author's post approved without them; it stays approved and
+// leaves the spot-check list. Responds with an empty fragment so htmx drops the card.
+async fn spot_check_post_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(user, ..): auth::RequireModerator,
+    origin: auth::RequestOrigin,
+    Path(post_id): Path<String>,
+) -> impl IntoResponse {
+    let post_uuid = match Uuid::parse_str(&post_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    match db::clear_audit_sample(&state.db_pool, post_uuid, user.id).await {
+        Ok(true) => {
+            tracing::info!("Spot-checked post {} confirmed by {}", post_uuid, user.username);
+            auth::audit(
+                &state.db_pool,
+                Some(user.id),
+                &origin,
+                models::AuditAction::PostApproved,
+                Some(post_uuid),
+                serde_json::json!({ "spot_check": true }),
+            )
+            .await;
+            (StatusCode::OK, Html("".to_string())).into_response()
+        }
+        Ok(false) => (StatusCode::NOT_FOUND, Html("".to_string())).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to confirm spot-checked post: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+async fn uphold_appeal_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(user, ..): auth::RequireModerator,
+    origin: auth::RequestOrigin,
+    Path(post_id): Path<String>,
+) -> impl IntoResponse {
+    decide_appeal(&state, &user, &origin, &post_id, false).await
+}
+
+async fn overturn_appeal_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(user, ..): auth::RequireModerator,
+    origin: auth::RequestOrigin,
+    Path(post_id): Path<String>,
+) -> impl IntoResponse {
+    decide_appeal(&state, &user, &origin, &post_id, true).await
+}
+
+// Shared by the uphold/overturn handlers - the author is notified either way, and an
+// overturned post is published like any other approval
+async fn decide_appeal(
+    state: &AppState,
+    user: &models::User,
+    origin: &auth::RequestOrigin,
+    post_id: &str,
+    overturn: bool,
+) -> Response {
+    let post_uuid = match Uuid::parse_str(post_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    match db::decide_appeal(&state.db_pool, post_uuid, overturn, user.id).await {
+        Ok(Some(post)) => {
+            tracing::info!(
+                "Appeal of post {} {} by {}",
+                post_uuid,
+                if overturn { "overturned" } else { "upheld" },
+                user.username
+            );
+            let action = if overturn {
+                models::AuditAction::AppealOverturned
+            } else {
+                models::AuditAction::AppealUpheld
+            };
+            auth::audit(
+                &state.db_pool,
+                Some(user.id),
+                origin,
+                action,
+                Some(post_uuid),
+                serde_json::json!({ "rejection_reason": post.moderation_reason }),
+            )
+            .await;
+            if let Err(e) = db::notify_appeal_decided(&state.db_pool, post_uuid, overturn).await {
+                tracing::error!("Failed to notify author of appeal decision: {}", e);
+            }
+            if overturn {
+                notify_approved_post(&state.db_pool, &post).await;
+                publish_review(state, &post);
+            }
+            (StatusCode::OK, Html("".to_string())).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Html("".to_string())).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to decide appeal: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+async fn admin_keywords_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(admin, ..): auth::RequireAdmin,
+) -> impl IntoResponse {
+    keywords_page(&state, lang, admin, None).await
+}
+
+async fn keywords_page(state: &AppState, lang: Locale, admin: models::User, error: Option<String>) -> Response {
+    let keywords = match db::get_moderation_keywords(&state.db_pool).await {
+        Ok(keywords) => keywords,
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load moderation keywords")).into_response();
+        }
+    };
+
+    let keywords = keywords
+        .into_iter()
+        .map(|k| KeywordRow {
+            id: k.id.to_string(),
+            pattern: k.pattern,
+            is_regex: k.is_regex,
+            kind: k.kind,
+            action: k.action,
+            severity: k.severity,
+            language: k.language,
+            enabled: k.enabled,
+            updated: k.updated_at.format("%B %d, %Y %H:%M").to_string(),
+        })
+        .collect();
+
+    HtmlTemplate(AdminKeywordsTemplate {
+        keywords,
+        kinds: moderation::CATEGORIES.iter().map(|k| k.to_string()).collect(),
+        actions: moderation::KEYWORD_ACTIONS.iter().map(|a| a.to_string()).collect(),
+        severities: moderation::KEYWORD_SEVERITIES.iter().map(|s| s.to_string()).collect(),
+        languages: moderation::KEYWORD_LANGUAGES.iter().map(|l| l.to_string()).collect(),
+        error,
+        user: Some(CurrentUser {
+            id: admin.id.to_string(),
+            username: admin.username,
+            is_admin: admin.is_admin(),
+            can_moderate: admin.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+fn valid_keyword_labels(kind: &str, action: &str, severity: &str) -> bool {
+    moderation::CATEGORIES.contains(&kind)
+        && moderation::KEYWORD_ACTIONS.contains(&action)
+        && moderation::KEYWORD_SEVERITIES.contains(&severity)
+}
+
+async fn add_keyword_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    origin: auth::RequestOrigin,
+    Form(form): Form<KeywordForm>,
+) -> impl IntoResponse {
+    if !valid_keyword_labels(&form.kind, &form.action, &form.severity)
+        || !moderation::KEYWORD_LANGUAGES.contains(&form.language.as_str())
+    {
+        return AppError::bad_request("error.invalid_keyword").into_response();
+    }
+
+    let is_regex = form.regex.is_some();
+    // Plain patterns are matched regardless of case, so they're stored lowercased
+    let pattern = if is_regex {
+        form.pattern.trim().to_string()
+    } else {
+        form.pattern.trim().to_lowercase()
+    };
+    if let Err(e) = moderation::validate_keyword(&pattern, is_regex) {
+        return keywords_page(&state, lang, admin, Some(lang.tf("keywords.invalid", e))).await;
+    }
+
+    let new_keyword = models::NewModerationKeyword {
+        pattern: &pattern,
+        is_regex,
+        kind: &form.kind,
+        action: &form.action,
+        severity: &form.severity,
+        language: &form.language,
+    };
+    match db::create_moderation_keyword(&state.db_pool, &new_keyword, admin.id).await {
+        Ok(Some(keyword)) => {
+            tracing::info!("Keyword pattern {:?} added by {}", keyword.pattern, admin.username);
+            auth::audit(
+                &state.db_pool,
+                Some(admin.id),
+                &origin,
+                models::AuditAction::KeywordsChanged,
+                Some(keyword.id),
+                serde_json::json!({
+                    "change": "added",
+                    "pattern": keyword.pattern,
+                    "regex": keyword.is_regex,
+                    "kind": keyword.kind,
+                    "action": keyword.action,
+                    "severity": keyword.severity,
+                    "language": keyword.language,
+                }),
+            )
+            .await;
+            state.keywords.invalidate();
+            Redirect::to("/admin/keywords").into_response()
+        }
+        Ok(None) => keywords_page(&state, lang, admin, Some(lang.t("keywords.duplicate").to_string())).await,
+        Err(e) => AppError::internal(e.context("Failed to add keyword")).into_response(),
+    }
+}
+
+async fn update_keyword_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    origin: auth::RequestOrigin,
+    Path(keyword_id): Path<String>,
+    Form(form): Form<KeywordUpdateForm>,
+) -> impl IntoResponse {
+    let keyword_uuid = match Uuid::parse_str(&keyword_id) {
+        Ok(id) => id,
+        Err(_) => return AppError::bad_request("error.invalid_keyword").into_response(),
+    };
+    if !valid_keyword_labels(&form.kind, &form.action, &form.severity) {
+        return AppError::bad_request("error.invalid_keyword").into_response();
+    }
+
+    let enabled = form.enabled.is_some();
+    match db::update_moderation_keyword(
+        &state.db_pool,
+        keyword_uuid,
+        &form.kind,
+        &form.action,
+        &form.severity,
+        enabled,
+    )
+    .await
+    {
+        Ok(Some(keyword)) => {
+            tracing::info!("Keyword pattern {:?} updated by {}", keyword.pattern, admin.username);
+            auth::audit(
+                &state.db_pool,
+                Some(admin.id),
+                &origin,
+                models::AuditAction::KeywordsChanged,
+                Some(keyword.id),
+                serde_json::json!({
+                    "change": "updated",
+                    "pattern": keyword.pattern,
+                    "kind": keyword.kind,
+                    "action": keyword.action,
+                    "severity": keyword.severity,
+                    "enabled": keyword.enabled,
+                }),
+            )
+            .await;
+            state.keywords.invalidate();
+            Redirect::to("/admin/keywords").into_response()
+        }
+        Ok(None) => AppError::not_found("error.keyword_not_found").into_response(),
+        Err(e) => AppError::internal(e.context("Failed to update keyword")).into_response(),
+    }
+}
+
+async fn delete_keyword_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    origin: auth::RequestOrigin,
+    Path(keyword_id): Path<String>,
+) -> impl IntoResponse {
+    let keyword_uuid = match Uuid::parse_str(&keyword_id) {
+        Ok(id) => id,
+        Err(_) => return AppError::bad_request("error.invalid_keyword").into_response(),
+    };
+
+    match db::delete_moderation_keyword(&state.db_pool, keyword_uuid).await {
+        Ok(Some(keyword)) => {
+            tracing::info!("Keyword pattern {:?} deleted by {}", keyword.pattern, admin.username);
+            auth::audit(
+                &state.db_pool,
+                Some(admin.id),
+                &origin,
+                models::AuditAction::KeywordsChanged,
+                Some(keyword.id),
+                serde_json::json!({ "change": "deleted", "pattern": keyword.pattern, "regex": keyword.is_regex }),
+            )
+            .await;
+            state.keywords.invalidate();
+            Redirect::to("/admin/keywords").into_response()
+        }
+        Ok(None) => AppError::not_found("error.keyword_not_found").into_response(),
+        Err(e) => AppError::internal(e.context("Failed to delete keyword")).into_response(),
+    }
+}
+
+async fn admin_users_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    Query(params): Query<PaginationQuery>,
+) -> impl IntoResponse {
+    let page = params.page.unwrap_or(1).max(1);
+
+    let (users, total) = match db::get_users_paginated(&state.db_pool, page, USERS_PER_PAGE).await {
+        Ok(result) => result,
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load users")).into_response();
+        }
+    };
+
+    let total_pages = (total as f64 / USERS_PER_PAGE as f64).ceil() as i64;
+
+    let users = users
+        .into_iter()
+        .map(|u| AdminUserRow {
+            id: u.id.to_string(),
+            username: u.username,
+            role: u.role,
+            suspended: u.suspended_at.is_some(),
+            joined: u.created_at.format("%B %d, %Y").to_string(),
+            post_count: u.post_count,
+            rejected_count: u.rejected_count,
+        })
+        .collect();
+
+    HtmlTemplate(AdminUsersTemplate {
+        users,
+        current_page: page,
+        total_pages,
+        has_prev: page > 1,
+        has_next: page < total_pages,
+        user: Some(CurrentUser {
+            id: admin.id.to_string(),
+            username: admin.username.clone(),
+            is_admin: admin.is_admin(),
+            can_moderate: admin.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+async fn admin_search_stats_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(admin, ..): auth::RequireAdmin,
+) -> impl IntoResponse {
+    let since = chrono::Utc::now() - chrono::Duration::days(SEARCH_STATS_DAYS);
+
+    let (volume, top_queries, zero_result_queries) = match tokio::try_join!(
+        db::get_search_volume(&state.db_pool, since),
+        db::get_top_searches(&state.db_pool, since, false, SEARCH_STATS_LIMIT),
+        db::get_top_searches(&state.db_pool, since, true, SEARCH_STATS_LIMIT),
+    ) {
+        Ok(stats) => stats,
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load search stats")).into_response();
+        }
+    };
+
+    let busiest = volume.iter().map(|v| v.searches).max().unwrap_or(0).max(1);
+    let query_rows = |stats: Vec<models::SearchQueryStat>| -> Vec<SearchQueryRow> {
+        stats
+            .into_iter()
+            .map(|s| SearchQueryRow {
+                query: s.query,
+                searches: s.searches,
+                avg_results: format!("{:.1}", s.avg_results),
+                last_searched: s.last_searched.format("%B %d, %Y %H:%M").to_string(),
+            })
+            .collect()
+    };
+
+    HtmlTemplate(AdminSearchStatsTemplate {
+        days: SEARCH_STATS_DAYS,
+        total_searches: volume.iter().map(|v| v.searches).sum(),
+        zero_result_searches: volume.iter().map(|v| v.zero_results).sum(),
+        volume: volume
+            .into_iter()
+            .map(|v| SearchVolumeRow {
+                day: v.day.format("%B %d").to_string(),
+                percent: v.searches * 100 / busiest,
+                searches: v.searches,
+                zero_results: v.zero_results,
+                searchers: v.searchers,
+            })
+            .collect(),
+        top_queries: query_rows(top_queries),
+        zero_result_queries: query_rows(zero_result_queries),
+        user: Some(CurrentUser {
+            id: admin.id.to_string(),
+            username: admin.username.clone(),
+            is_admin: admin.is_admin(),
+            can_moderate: admin.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+async fn admin_audit_log_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    Query(params): Query<AuditLogQuery>,
+) -> impl IntoResponse {
+    let page = params.page.unwrap_or(1).max(1);
+    let field = |value: Option<String>| value.map(|v| v.trim().to_string()).unwrap_or_default();
+    let (filter_user, filter_from, filter_to, filter_action) =
+        (field(params.user), field(params.from), field(params.to), field(params.action));
+
+    // Both ends are whole UTC days, so "to" includes the day it names
+    let day_start = |value: &str| -> Result<Option<chrono::DateTime<chrono::Utc>>, AppError> {
+        if value.is_empty() {
+            return Ok(None);
+        }
+        chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(|d| Some(d.and_time(chrono::NaiveTime::MIN).and_utc()))
+            .map_err(|_| AppError::bad_request("error.invalid_date"))
+    };
+    let (from, until) = match (day_start(&filter_from), day_start(&filter_to)) {
+        (Ok(from), Ok(to)) => (from, to.map(|t| t + chrono::Duration::days(1))),
+        (Err(e), _) | (_, Err(e)) => return e.into_response(),
+    };
+
+    let filter = models::AuditFilter {
+        username: Some(filter_user.clone()).filter(|u| !u.is_empty()),
+        from,
+        until,
+        action: models::AuditAction::parse(&filter_action),
+    };
+
+    let (entries, total) =
+        match db::get_audit_log_paginated(&state.db_pool, page, AUDIT_ENTRIES_PER_PAGE, &filter).await {
+            Ok(result) => result,
+            Err(e) => {
+                return AppError::internal(e.context("Failed to load audit log")).into_response();
+            }
+        };
+
+    let total_pages = (total as f64 / AUDIT_ENTRIES_PER_PAGE as f64).ceil() as i64;
+
+    let entries = entries
+        .into_iter()
+        .map(|e| AuditRow {
+            date: e.created_at.format("%B %d, %Y %H:%M:%S").to_string(),
+            details: audit_details(&e.metadata),
+            actor: e.actor_id.zip(e.actor_username).map(|(id, username)| AuditUser {
+                id: id.to_string(),
+                username,
+            }),
+            target: e.target_id.zip(e.target_username).map(|(id, username)| AuditUser {
+                id: id.to_string(),
+                username,
+            }),
+            target_id: e.target_id.map(|id| id.to_string()).unwrap_or_default(),
+            action: e.action,
+            ip_address: e.ip_address.unwrap_or_default(),
+            user_agent: e.user_agent.unwrap_or_default(),
+        })
+        .collect();
+
+    HtmlTemplate(AdminAuditTemplate {
+        entries,
+        actions: models::AuditAction::ALL.iter().map(|a| a.as_str().to_string()).collect(),
+        filter_user,
+        filter_from,
+        filter_to,
+        filter_action,
+        current_page: page,
+        total_pages,
+        has_prev: page > 1,
+        has_next: page < total_pages,
+        user: Some(CurrentUser {
+            id: admin.id.to_string(),
+            username: admin.username.clone(),
+            is_admin: admin.is_admin(),
+            can_moderate: admin.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+// An audit entry's metadata as "key: value" pairs, leaving out empty values
+fn audit_details(metadata: &serde_json::Value) -> String {
+    let Some(fields) = metadata.as_object() else {
+        return String::new();
+    };
+
+    fields
+        .iter()
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some(format!("{}: {}", key, s)),
+            other => Some(format!("{}: {}", key, other)),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn admin_user_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    let user_uuid = match Uuid::parse_str(&user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return AppError::bad_request("error.invalid_user_id").into_response();
+        }
+    };
+
+    let member = match auth::get_user_by_id(&state.db_pool, user_uuid).await {
+        Ok(Some(u)) if u.deleted_at.is_none() => u,
+        _ => {
+            return AppError::not_found("error.user_not_found").into_response();
+        }
+    };
+
+    let history: Vec<ModerationItem> = db::get_moderation_history(&state.db_pool, member.id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| ModerationItem {
+            bill_id: r.bill_id.to_string(),
+            bill_title: r.bill_title,
+            content: r.content,
+            status: r.moderation_status,
+            reason: r.moderation_reason,
+            moderator: r.moderator_username,
+            moderated: r.moderated_at.map(|t| t.format("%B %d, %Y %H:%M").to_string()),
+            report_count: r.report_count,
+            date: r.created_at.format("%B %d, %Y").to_string(),
+        })
+        .collect();
+
+    let post_count = db::get_user_profile(&state.db_pool, &member.username)
+        .await
+        .ok()
+        .flatten()
+        .map(|p| p.post_count)
+        .unwrap_or(0);
+    let rejected_count = history.iter().filter(|h| h.status == "rejected").count() as i64;
+
+    HtmlTemplate(AdminUserTemplate {
+        member: AdminUserRow {
+            id: member.id.to_string(),
+            username: member.username.clone(),
+            role: member.role.clone(),
+            suspended: member.is_suspended(),
+            joined: member.created_at.format("%B %d, %Y").to_string(),
+            post_count,
+            rejected_count,
+        },
+        roles: models::Role::ALL.iter().map(|r| r.as_str().to_string()).collect(),
+        rate_limit: member.rate_limit_multiplier.map(|m| m.to_string()).unwrap_or_default(),
+        rate_limit_choices: RATE_LIMIT_MULTIPLIERS.iter().map(|m| m.to_string()).collect(),
+        exempt_by_default: member.can_moderate(),
+        history,
+        user: Some(CurrentUser {
+            id: admin.id.to_string(),
+            username: admin.username.clone(),
+            is_admin: admin.is_admin(),
+            can_moderate: admin.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+async fn set_user_role_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    origin: auth::RequestOrigin,
+    Path(user_id): Path<String>,
+    Form(form): Form<RoleForm>,
+) -> impl IntoResponse {
+    let user_uuid = match Uuid::parse_str(&user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return AppError::bad_request("error.invalid_user_id").into_response();
+        }
+    };
+
+    let role = match models::Role::parse(&form.role) {
+        Some(r) => r,
+        None => {
+            return AppError::bad_request("error.unknown_role").into_response();
+        }
+    };
+
+    // Keeps at least the acting admin in place
+    if user_uuid == admin.id {
+        return AppError::bad_request("error.own_role").into_response();
+    }
+
+    match auth::set_role(&state.db_pool, user_uuid, role).await {
+        Ok(true) => {
+            tracing::info!("User {} set to {} by {}", user_uuid, role.as_str(), admin.username);
+            auth::audit(
+                &state.db_pool,
+                Some(admin.id),
+                &origin,
+                models::AuditAction::RoleChanged,
+                Some(user_uuid),
+                serde_json::json!({ "role": role.as_str() }),
+            )
+            .await;
+            Redirect::to(&format!("/admin/users/{}", user_id)).into_response()
+        }
+        Ok(false) => AppError::not_found("error.user_not_found").into_response(),
+        Err(e) => {
+            AppError::internal(e.context("Failed to change role")).into_response()
+        }
+    }
+}
+
+// Multipliers an admin can give a user, besides exempting them (0) or the role's default
+const RATE_LIMIT_MULTIPLIERS: [i32; 5] = [1, 2, 3, 5, 10];
+
+async fn admin_rate_limits_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(admin, ..): auth::RequireAdmin,
+) -> impl IntoResponse {
+    let since = chrono::Utc::now() - chrono::Duration::days(1);
+
+    let (totals, throttled) = match tokio::try_join!(
+        rate_limit::action_totals(&state.db_pool, since),
+        rate_limit::most_throttled_users(&state.db_pool, since, THROTTLED_USERS_LIMIT),
+    ) {
+        Ok(stats) => stats,
+        Err(e) => return AppError::internal(e.context("Failed to load rate limit stats")).into_response(),
+    };
+
+    let user_ids: Vec<Uuid> = throttled.iter().map(|t| t.user_id).collect();
+    let taken = match rate_limit::actions_in_window(&state.db_pool, &user_ids, &state.rate_limits).await {
+        Ok(taken) => taken,
+        Err(e) => return AppError::internal(e).into_response(),
+    };
+
+    let throttled = throttled
+        .into_iter()
+        .map(|t| {
+            let role = models::Role::parse(&t.role).unwrap_or(models::Role::User);
+            let scale = models::rate_limit_scale(role, t.rate_limit_multiplier);
+            let quotas = rate_limit::ActionType::ALL
+                .iter()
+                .map(|action| {
+                    let limit = state.rate_limits.scaled(*action, scale)?;
+                    let (in_window, in_day) =
+                        taken.get(&(t.user_id, action.as_str().to_string())).copied().unwrap_or((0, 0));
+                    Some(format!("{} / {}", limit.remaining_after(in_window, in_day), limit.limit))
+                })
+                .collect();
+            ThrottledUserRow {
+                id: t.user_id.to_string(),
+                username: t.username,
+                rejected: t.rejected,
+                last_rejected: t.last_rejected.format("%B %d, %Y %H:%M").to_string(),
+                quotas,
+            }
+        })
+        .collect();
+
+    HtmlTemplate(AdminRateLimitsTemplate {
+        totals: totals
+            .into_iter()
+            .map(|t| RateLimitTotalsRow {
+                action: lang.label("rate_limits.action", &t.action_type).to_string(),
+                allowed: t.allowed,
+                rejected: t.rejected,
+            })
+            .collect(),
+        throttled,
+        action_labels: rate_limit::ActionType::ALL
+            .iter()
+            .map(|action| lang.label("rate_limits.action", action.as_str()).to_string())
+            .collect(),
+        user: Some(CurrentUser {
+            id: admin.id.to_string(),
+            username: admin.username.clone(),
+            is_admin: admin.is_admin(),
+            can_moderate: admin.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+#[derive(Deserialize)]
+struct ClearRateLimitForm {
+    // The "not a bot" checkbox; browsers leave it out unless ticked
+    verified: Option<String>,
+}
+
+async fn clear_rate_limit_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    origin: auth::RequestOrigin,
+    Path(user_id): Path<String>,
+    Form(form): Form<ClearRateLimitForm>,
+) -> impl IntoResponse {
+    let user_uuid = match Uuid::parse_str(&user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return AppError::bad_request("error.invalid_user_id").into_response();
+        }
+    };
+    if form.verified.is_none() {
+        return AppError::bad_request("error.rate_limit_unverified").into_response();
+    }
+
+    match auth::get_user_by_id(&state.db_pool, user_uuid).await {
+        Ok(Some(u)) if u.deleted_at.is_none() => {}
+        Ok(_) => return AppError::not_found("error.user_not_found").into_response(),
+        Err(e) => return AppError::internal(e.context("Failed to load user")).into_response(),
+    }
+
+    match rate_limit::clear_user_window(&state.db_pool, &state.rate_limit_cache, user_uuid).await {
+        Ok(cleared) => {
+            tracing::info!("Rate limit window of {} cleared by {}", user_uuid, admin.username);
+            auth::audit(
+                &state.db_pool,
+                Some(admin.id),
+                &origin,
+                models::AuditAction::RateLimitCleared,
+                Some(user_uuid),
+                serde_json::json!({ "actions": cleared }),
+            )
+            .await;
+            Redirect::to("/admin/rate-limits").into_response()
+        }
+        Err(e) => AppError::internal(e).into_response(),
+    }
+}
+
+async fn set_rate_limit_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    origin: auth::RequestOrigin,
+    Path(user_id): Path<String>,
+    Form(form): Form<RateLimitForm>,
+) -> impl IntoResponse {
+    let user_uuid = match Uuid::parse_str(&user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return AppError::bad_request("error.invalid_user_id").into_response();
+        }
+    };
+
+    let multiplier = match form.multiplier.trim() {
+        "" => None,
+        value => match value.parse::<i32>() {
+            Ok(m) if m == 0 || RATE_LIMIT_MULTIPLIERS.contains(&m) => Some(m),
+            _ => return AppError::bad_request("error.invalid_rate_limit").into_response(),
+        },
+    };
+
+    let member = match auth::get_user_by_id(&state.db_pool, user_uuid).await {
+        Ok(Some(u)) if u.deleted_at.is_none() => u,
+        Ok(_) => return AppError::not_found("error.user_not_found").into_response(),
+        Err(e) => return AppError::internal(e.context("Failed to load user")).into_response(),
+    };
+    if member.rate_limit_multiplier == multiplier {
+        return Redirect::to(&format!("/admin/users/{}", user_id)).into_response();
+    }
+
+    match auth::set_rate_limit_multiplier(&state.db_pool, user_uuid, multiplier).await {
+        Ok(true) => {
+            tracing::info!("Rate limit multiplier of {} set to {:?} by {}", user_uuid, multiplier, admin.username);
+            auth::audit(
+                &state.db_pool,
+                Some(admin.id),
+                &origin,
+                models::AuditAction::RateLimitChanged,
+                Some(user_uuid),
+                serde_json::json!({ "multiplier": multiplier, "previous": member.rate_limit_multiplier }),
+            )
+            .await;
+            Redirect::to(&format!("/admin/users/{}", user_id)).into_response()
+        }
+        Ok(false) => AppError::not_found("error.user_not_found").into_response(),
+        Err(e) => {
+            AppError::internal(e.context("Failed to change rate limit")).into_response()
+        }
+    }
+}
+
+async fn suspend_user_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    origin: auth::RequestOrigin,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    set_user_suspended(&state, &admin, &origin, &user_id, true).await
+}
+
+async fn reinstate_user_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    origin: auth::RequestOrigin,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    set_user_suspended(&state, &admin, &origin, &user_id, false).await
+}
+
+// Shared by the suspend/reinstate handlers
+async fn set_user_suspended(
+    state: &AppState,
+    admin: &models::User,
+    origin: &auth::RequestOrigin,
+    user_id: &str,
+    suspended: bool,
+) -> Response {
+    let user_uuid = match Uuid::parse_str(user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return AppError::bad_request("error.invalid_user_id").into_response();
+        }
+    };
+
+    if user_uuid == admin.id {
+        return AppError::bad_request("error.suspend_self").into_response();
+    }
+
+    match auth::set_user_suspended(&state.db_pool, user_uuid, suspended).await {
+        Ok(true) => {
+            tracing::info!(
+                "User {} {} by {}",
+                user_uuid,
+                if suspended { "suspended" } else { "reinstated" },
+                admin.username
+            );
+            let action = if suspended {
+                models::AuditAction::UserSuspended
+            } else {
+                models::AuditAction::UserReinstated
+            };
+            auth::audit(&state.db_pool, Some(admin.id), origin, action, Some(user_uuid), serde_json::json!({})).await;
+            Redirect::to(&format!("/admin/users/{}", user_id)).into_response()
+        }
+        Ok(false) => AppError::not_found("error.user_not_found").into_response(),
+        Err(e) => {
+            AppError::internal(e.context("Failed to update suspension")).into_response()
+        }
+    }
+}
+
+async fn regenerate_summary_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    Path(bill_id): Path<String>,
+) -> impl IntoResponse {
+    let bill_uuid = match Uuid::parse_str(&bill_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return AppError::bad_request("error.invalid_bill_id").into_response();
+        }
+    };
+
+    match summary::summarize_bill(&state.db_pool, &state.llm, bill_uuid).await {
+        Ok(Some(_)) => {
+            tracing::info!("Summary for bill {} regenerated by {}", bill_uuid, admin.username);
+            Redirect::to(&format!("/f/{}", bill_id)).into_response()
+        }
+        Ok(None) => AppError::bad_request("error.not_enough_reviews").into_response(),
+        Err(e) => AppError::new(StatusCode::SERVICE_UNAVAILABLE, "error.summary_unavailable")
+            .with_source(e.context(format!("Failed to regenerate summary for bill {}", bill_uuid)))
+            .into_response(),
+    }
+}
+
+// Bill ingestion from the admin UI
+async fn admin_ingest_page(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(admin, ..): auth::RequireAdmin,
+) -> impl IntoResponse {
+    admin_ingest_response(&state, lang, &admin, None, None)
+}
+
+fn admin_ingest_response(
+    state: &AppState,
+    lang: Locale,
+    admin: &models::User,
+    error: Option<String>,
+    running_job_id: Option<Uuid>,
+) -> Response {
+    HtmlTemplate(AdminIngestTemplate {
+        jobs: state
+            .ingest_jobs
+            .recent()
+            .into_iter()
+            .map(|job| IngestJobView::new(job, lang))
+            .collect(),
+        error,
+        running_job_id: running_job_id.map(|id| id.to_string()),
+        user: Some(CurrentUser {
+            id: admin.id.to_string(),
+            username: admin.username.clone(),
+            is_admin: admin.is_admin(),
+            can_moderate: admin.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+async fn start_ingest_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    Form(form): Form<IngestForm>,
+) -> impl IntoResponse {
+    let url = form.url.trim().to_string();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        let page = admin_ingest_response(&state, lang, &admin, Some(lang.t("ingest.invalid_url").to_string()), None);
+        return (StatusCode::BAD_REQUEST, page).into_response();
+    }
+
+    match ingest::spawn(state.ingest_jobs.clone(), state.db_pool.clone(), url.clone(), form.title) {
+        Ok(job_id) => {
+            tracing::info!("Ingest job {} for {} started by {}", job_id, url, admin.username);
+            Redirect::to(&format!("/admin/ingest/{}", job_id)).into_response()
+        }
+        Err(running) => {
+            let error = Some(lang.t("ingest.already_running").to_string());
+            let page = admin_ingest_response(&state, lang, &admin, error, Some(running));
+            (StatusCode::CONFLICT, page).into_response()
+        }
+    }
+}
+
+// Polled by the job page through htmx while the job runs
+async fn ingest_job_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    headers: HeaderMap,
+    RequireRole(admin, ..): auth::RequireAdmin,
+    Path(job_id): Path<String>,
+) -> impl IntoResponse {
+    let job = match Uuid::parse_str(&job_id).ok().and_then(|id| state.ingest_jobs.get(id)) {
+        Some(job) => IngestJobView::new(job, lang),
+        None => return AppError::not_found("error.ingest_job_not_found").into_response(),
+    };
+
+    if is_htmx_request(&headers) {
+        return HtmlTemplate(IngestJobStatusTemplate { job, lang }).into_response();
+    }
+
+    HtmlTemplate(AdminIngestJobTemplate {
+        job,
+        user: Some(CurrentUser {
+            id: admin.id.to_string(),
+            username: admin.username.clone(),
+            is_admin: admin.is_admin(),
+            can_moderate: admin.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+// Subscription and notification handlers
+async fn follow_bill_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(bill_id): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
+
+    let bill_uuid = match Uuid::parse_str(&bill_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    let bill = match db::get_bill_by_id(&state.db_pool, bill_uuid).await {
+        Ok(Some(b)) => BillInfo {
+            id: b.id.to_string(),
+            title: b.title,
+            number: b.bill_number,
+            year: b.year,
+        },
+        _ => {
+            return (StatusCode::NOT_FOUND, Html("".to_string())).into_response();
+        }
+    };
+
+    match db::toggle_subscription(&state.db_pool, user.id, bill_uuid).await {
+        Ok(following) => HtmlTemplate(FollowButtonTemplate { bill, following, lang }).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to update subscription: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+// Sets the bookmark to the state in the form, so repeating a request is harmless
+async fn bookmark_bill_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(bill_id): Path<String>,
+    Form(form): Form<BookmarkForm>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None if is_htmx_request(&headers) => {
+            return ([("HX-Redirect", "/login")], Html("".to_string())).into_response();
+        }
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    let bill_uuid = match Uuid::parse_str(&bill_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    if !matches!(db::get_bill_by_id(&state.db_pool, bill_uuid).await, Ok(Some(_))) {
+        return (StatusCode::NOT_FOUND, Html("".to_string())).into_response();
+    }
+
+    let bookmarked = form.bookmarked.unwrap_or(true);
+    if let Err(e) = db::set_bookmark(&state.db_pool, user.id, bill_uuid, bookmarked).await {
+        tracing::error!("Failed to update bookmark: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response();
+    }
+
+    if !is_htmx_request(&headers) {
+        return Redirect::to("/bookmarks").into_response();
+    }
+
+    HtmlTemplate(BookmarkButtonTemplate {
+        bookmark: BookmarkState {
+            bill_id: bill_uuid.to_string(),
+            bookmarked,
+        },
+        lang,
+    })
+    .into_response()
+}
+
+async fn bookmarks_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    let bills = match db::get_bookmarks(&state.db_pool, user.id).await {
+        Ok(bills) => bills,
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load bookmarks")).into_response();
+        }
+    };
+
+    let bills = bills
+        .into_iter()
+        .map(|b| BookmarkedBillItem {
+            bookmark: BookmarkState {
+                bill_id: b.id.to_string(),
+                bookmarked: true,
+            },
+            id: b.id.to_string(),
+            title: b.title,
+            number: b.bill_number,
+            year: b.year,
+            status: b.status.filter(|s| !s.is_empty()),
+            saved: b.bookmarked_at.format("%B %d, %Y").to_string(),
+        })
+        .collect();
+
+    HtmlTemplate(BookmarksTemplate {
+        bills,
+        user: Some(CurrentUser {
+            id: user.id.to_string(),
+            username: user.username,
+            is_admin: user.is_admin(),
+            can_moderate: user.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+async fn notifications_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    let notifications = db::get_unread_notifications(&state.db_pool, user.id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|n| NotificationItem {
+            id: n.id.to_string(),
+            bill_id: n.bill_id.to_string(),
+            kind: n.kind,
+            message: n.message,
+            date: n.created_at.format("%B %d, %Y %H:%M").to_string(),
+        })
+        .collect();
+
+    HtmlTemplate(NotificationsTemplate {
+        notifications,
+        user: Some(CurrentUser {
+            id: user.id.to_string(),
+            username: user.username,
+            is_admin: user.is_admin(),
+            can_moderate: user.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+async fn mark_notification_read_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(notification_id): Path<String>,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return (StatusCode::UNAUTHORIZED, Html("".to_string())).into_response();
+        }
+    };
+
+    let notification_uuid = match Uuid::parse_str(&notification_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return (StatusCode::BAD_REQUEST, Html("".to_string())).into_response();
+        }
+    };
+
+    match db::mark_notification_read(&state.db_pool, notification_uuid, user.id).await {
+        // Empty body so htmx removes the notification from the list
+        Ok(_) => (StatusCode::OK, Html("".to_string())).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to mark notification read: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html("".to_string())).into_response()
+        }
+    }
+}
+
+async fn mark_all_notifications_read_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+) -> impl IntoResponse {
+    let user = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => u,
+        None => {
+            return Redirect::to("/login").into_response();
+        }
+    };
+
+    if let Err(e) = db::mark_all_notifications_read(&state.db_pool, user.id).await {
+        tracing::error!("Failed to mark notifications read: {}", e);
+    }
+
+    Redirect::to("/notifications").into_response()
+}
+
+// Unread count for the header badge, loaded by htmx on every page
+async fn notification_badge_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+) -> impl IntoResponse {
+    let unread = match get_current_user(&jar, &state.db_pool).await {
+        Some(u) => db::count_unread_notifications(&state.db_pool, u.id).await.unwrap_or(0),
+        None => 0,
+    };
+
+    if unread == 0 {
+        Html(String::new())
+    } else {
+        Html(format!(r#"<span class="notification-badge">{}</span>"#, unread))
+    }
+}
+
+// Bill text handlers
+async fn bill_detail_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(bill_id): Path<String>,
+) -> impl IntoResponse {
+    let bill_uuid = match Uuid::parse_str(&bill_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return AppError::bad_request("error.invalid_bill_id").into_response();
+        }
+    };
+
+    let bill = match db::get_bill_by_id(&state.db_pool, bill_uuid).await {
+        Ok(Some(b)) => b,
+        _ => {
+            return AppError::not_found("error.bill_not_found").into_response();
+        }
+    };
+
+    let user = get_current_user(&jar, &state.db_pool).await;
+
+    let (sections, sections_unavailable) = match bill_chunks(&state, &bill).await {
+        Ok(chunks) => (chunks.iter().map(bill_section).collect(), false),
+        Err(e) => {
+            tracing::warn!("Failed to load chunks for bill {}: {}", bill.bill_number, e);
+            (vec![], true)
+        }
+    };
+
+    let page = BillTemplate {
+        bill: BillDetail {
+            id: bill.id.to_string(),
+            title: bill.title,
+            number: bill.bill_number,
+            year: bill.year,
+            session: bill.session,
+            status: bill.status,
+            introduction_date: bill.introduction_date.map(|d| d.format("%B %d, %Y").to_string()),
+            pdf_url: bill.pdf_url,
+        },
+        sections,
+        sections_unavailable,
+        user: user.map(|u| CurrentUser {
+            id: u.id.to_string(),
+            username: u.username,
+            is_admin: u.is_admin(),
+            can_moderate: u.can_moderate(),
+        }),
+        lang,
+    };
+
+    match page.render() {
+        Ok(html) => html_with_etag(&headers, html),
+        Err(e) => AppError::internal(anyhow::Error::new(e).context("Failed to render bill page")).into_response(),
+    }
+}
+
+// The bill's chunks from the database, or from the vector index for bills ingested before
+// chunks were stored there
+async fn bill_chunks(state: &AppState, bill: &models::DbBill) -> anyhow::Result<Vec<models::StoredChunk>> {
+    let chunks = db::get_chunks_for_bill(&state.db_pool, bill.id).await?;
+    if chunks.is_empty() {
+        return state.vector_store.get_bill_chunks(&bill.bill_number).await;
+    }
+    Ok(chunks.into_iter().map(models::StoredChunk::from).collect())
+}
+
+// Sends a page with a weak ETag of its content, or 304 when the client already has that version
+fn html_with_etag(headers: &HeaderMap, html: String) -> Response {
+    let etag = format!("W/\"{}\"", assets::content_hash(html.as_bytes()));
+    let cached = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|tags| {
+            tags.split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag.trim_start_matches("W/"))
+        });
+    let cache_headers = [
+        (header::ETAG, etag),
+        (header::CACHE_CONTROL, "private, no-cache".to_string()),
+    ];
+
+    if cached {
+        (StatusCode::NOT_MODIFIED, cache_headers).into_response()
+    } else {
+        (cache_headers, Html(html)).into_response()
+    }
+}
+
+async fn compare_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Query(params): Query<CompareQuery>,
+) -> impl IntoResponse {
+    let parse_id = |id: &Option<String>| id.as_deref().and_then(|id| Uuid::parse_str(id.trim()).ok());
+    let (bill_a_uuid, bill_b_uuid) = match (parse_id(&params.bill_a), parse_id(&params.bill_b)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => {
+            return AppError::bad_request("error.compare_ids").into_response();
+        }
+    };
+
+    let (bill_a, bill_b) = match (
+        db::get_bill_by_id(&state.db_pool, bill_a_uuid).await,
+        db::get_bill_by_id(&state.db_pool, bill_b_uuid).await,
+    ) {
+        (Ok(Some(a)), Ok(Some(b))) => (a, b),
+        _ => {
+            return AppError::not_found("error.bill_not_found").into_response();
+        }
+    };
+
+    let user = get_current_user(&jar, &state.db_pool).await;
+
+    let chunks = tokio::try_join!(
+        state.vector_store.get_bill_chunks(&bill_a.bill_number),
+        state.vector_store.get_bill_chunks(&bill_b.bill_number),
+    );
+    let (rows, sections_unavailable) = match chunks {
+        Ok((left, right)) => (
+            compare_rows(&state, &bill_a.bill_number, &left, &bill_b.bill_number, &right).await,
+            false,
+        ),
+        Err(e) => {
+            tracing::warn!("Failed to load chunks to compare {} and {}: {}", bill_a.bill_number, bill_b.bill_number, e);
+            (vec![], true)
+        }
+    };
+
+    let matched = rows.iter().filter(|r| r.left.is_some() && r.right.is_some()).count();
+
+    HtmlTemplate(CompareTemplate {
+        bill_a: BillInfo {
+            id: bill_a.id.to_string(),
+            title: bill_a.title,
+            number: bill_a.bill_number,
+            year: bill_a.year,
+        },
+        bill_b: BillInfo {
+            id: bill_b.id.to_string(),
+            title: bill_b.title,
+            number: bill_b.bill_number,
+            year: bill_b.year,
+        },
+        unmatched: rows.len() - matched,
+        matched,
+        rows,
+        sections_unavailable,
+        user: user.map(|u| CurrentUser {
+            id: u.id.to_string(),
+            username: u.username,
+            is_admin: u.is_admin(),
+            can_moderate: u.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response()
+}
+
+// Align two bills by section identifier, suggesting the closest counterpart for sections left unmatched
+async fn compare_rows(
+    state: &AppState,
+    left_bill: &str,
+    left: &[models::StoredChunk],
+    right_bill: &str,
+    right: &[models::StoredChunk],
+) -> Vec<CompareRow> {
+    let aligned = compare::align_sections(left, right);
+
+    let left_only: Vec<&models::StoredChunk> = aligned
+        .iter()
+        .filter(|row| row.right.is_none())
+        .filter_map(|row| row.left)
+        .collect();
+    let right_only: Vec<&models::StoredChunk> = aligned
+        .iter()
+        .filter(|row| row.left.is_none())
+        .filter_map(|row| row.right)
+        .collect();
+
+    let (left_suggestions, right_suggestions) = tokio::join!(
+        counterpart_suggestions(state, &left_only, right_bill),
+        counterpart_suggestions(state, &right_only, left_bill),
+    );
+    let mut left_suggestions = left_suggestions.into_iter();
+    let mut right_suggestions = right_suggestions.into_iter();
+
+    aligned
+        .into_iter()
+        .map(|row| {
+            let suggestion = match (row.left, row.right) {
+                (Some(_), None) => left_suggestions.next().flatten(),
+                (None, Some(_)) => right_suggestions.next().flatten(),
+                _ => None,
+            };
+            CompareRow {
+                left: row.left.map(bill_section),
+                right: row.right.map(bill_section),
+                suggestion: suggestion.map(|s| CompareSuggestion {
+                    anchor: section_anchor(s.chunk_index),
+                    identifier: s.chunk_identifier,
+                    score: format!("{:.2}", s.score),
+                }),
+            }
+        })
+        .collect()
+}
+
+// Suggestions are a nicety, so an unavailable embedder or vector store just leaves them out
+async fn counterpart_suggestions(
+    state: &AppState,
+    sections: &[&models::StoredChunk],
+    other_bill: &str,
+) -> Vec<Option<models::SearchResult>> {
+    let sections = &sections[..sections.len().min(COMPARE_MAX_SUGGESTIONS)];
+    if sections.is_empty() {
+        return vec![];
+    }
+
+    match compare::closest_counterparts(state.embedder.as_ref(), state.vector_store.as_ref(), sections, other_bill).await {
+        Ok(suggestions) => suggestions,
+        Err(e) => {
+            tracing::warn!("Failed to suggest counterparts in bill {}: {:#}", other_bill, e);
+            vec![]
+        }
+    }
+}
+
+fn bill_section(chunk: &models::StoredChunk) -> BillSection {
+    BillSection {
+        anchor: section_anchor(chunk.chunk_index),
+        identifier: chunk.chunk_identifier.clone(),
+        chunk_type: chunk.chunk_type.clone(),
+        content: chunk.content.clone(),
+    }
+}
+
+// Anchor used to deep-link to a chunk on the bill page
+fn section_anchor(chunk_index: usize) -> String {
+    format!("section-{}", chunk_index)
+}
+
+// Helper functions
+// htmx sets HX-Request on every request it issues
+pub(crate) fn is_htmx_request(headers: &HeaderMap) -> bool {
+    headers.contains_key("HX-Request")
+}
+
+// Remaining posts, the fewest left in any window, plus a retry message, the seconds to wait and
+// whether the daily limit is the one holding them up once the quota is used up
+async fn posting_quota(
+    state: &AppState,
+    user: &models::User,
+    lang: Locale,
+) -> (Option<i64>, Option<String>, i64, bool) {
+    let (pool, cache, limits) = (&state.db_pool, &state.rate_limit_cache, &state.rate_limits);
+    let remaining = rate_limit::get_remaining_posts(pool, cache, user, limits)
+        .await
+        .unwrap_or(Some(0));
+    if remaining != Some(0) {
+        return (remaining, None, 0, false);
+    }
+
+    let throttled = rate_limit::get_time_until_next_post(pool, cache, user, limits)
+        .await
+        .ok()
+        .flatten();
+    let wait_secs = throttled.map_or(0, |t| t.retry_after);
+    let daily = throttled.is_some_and(|t| t.window == rate_limit::LimitWindow::Daily);
+    (Some(0), Some(retry_after_message(lang, wait_secs)), wait_secs, daily)
+}
+
+fn revoked_sessions_message(lang: Locale, count: u64) -> String {
+    match count {
+        0 => lang.t("sessions.revoked_none").to_string(),
+        1 => lang.tf("sessions.revoked_one", count),
+        n => lang.tf("sessions.revoked_many", n),
+    }
+}
+
+fn retry_after_message(lang: Locale, wait_secs: i64) -> String {
+    let minutes = (wait_secs + 59) / 60;
+    match minutes {
+        0 => lang.t("review.retry_moment").to_string(),
+        1 => lang.t("review.retry_minute").to_string(),
+        // Daily limits can mean waiting most of a day
+        n if n > 90 => lang.tf("review.retry_hours", (n + 59) / 60),
+        n => lang.tf("review.retry_minutes", n),
+    }
+}
+
+fn review_form_error(lang: Locale, error: Option<&str>) -> Option<String> {
+    match error {
+        Some("failed") => Some(lang.t("review.save_failed").to_string()),
+        _ => None,
+    }
+}
+
+fn normalize_stance(stance: &str) -> &'static str {
+    match stance.to_lowercase().as_str() {
+        "support" => "Support",
+        "oppose" => "Oppose",
+        "critique" => "Critique",
+        _ => "Critique",
+    }
+}
+
+// Distinct reports after which an approved post goes back to moderation (REPORT_THRESHOLD)
+fn report_threshold() -> i64 {
+    std::env::var("REPORT_THRESHOLD")
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(DEFAULT_REPORT_THRESHOLD)
+}
+
+// Minutes after posting during which a review can still be edited (REVIEW_EDIT_WINDOW_MINUTES)
+fn edit_window() -> chrono::Duration {
+    let minutes = std::env::var("REVIEW_EDIT_WINDOW_MINUTES")
+        .ok()
+        .and_then(|m| m.parse().ok())
+        .unwrap_or(DEFAULT_EDIT_WINDOW_MINUTES);
+    chrono::Duration::minutes(minutes)
+}
+
+// A review stays editable within the edit window, or for as long as nobody has voted on it
+fn is_editable(created_at: chrono::DateTime<chrono::Utc>, upvotes: i32, downvotes: i32) -> bool {
+    chrono::Utc::now() - created_at < edit_window() || (upvotes == 0 && downvotes == 0)
+}
+
+async fn recent_bills(pool: &PgPool, bills: Vec<models::DbBill>, viewer: Option<&models::User>) -> Vec<RecentBill> {
+    let bookmarked = match viewer {
+        Some(u) => {
+            let bill_ids: Vec<Uuid> = bills.iter().map(|b| b.id).collect();
+            Some(db::get_bookmarked_bill_ids(pool, u.id, &bill_ids).await.unwrap_or_default())
+        }
+        None => None,
+    };
+
+    bills
+        .into_iter()
+        .map(|b| RecentBill {
+            bookmark: bookmarked.as_ref().map(|ids| BookmarkState {
+                bill_id: b.id.to_string(),
+                bookmarked: ids.contains(&b.id),
+            }),
+            id: b.id.to_string(),
+            title: b.title,
+            number: b.bill_number,
+            year: b.year,
+        })
+        .collect()
+}
+
+async fn build_reviews(pool: &PgPool, posts: Vec<models::PostWithUser>, viewer: Option<&models::User>) -> Vec<Review> {
+    let mut votes = match viewer {
+        Some(u) => {
+            let post_ids: Vec<Uuid> = posts.iter().map(|p| p.id).collect();
+            db::get_user_votes(pool, u.id, &post_ids).await.unwrap_or_default()
+        }
+        None => HashMap::new(),
+    };
+
+    let mut mentioned: Vec<String> = posts.iter().flat_map(|p| mentioned_usernames(&p.content)).collect();
+    mentioned.sort_unstable();
+    mentioned.dedup();
+    let known = db::get_existing_usernames(pool, &mentioned).await.unwrap_or_default();
+
+    posts
+        .into_iter()
+        .map(|p| Review {
+            user_vote: votes.remove(&p.id).unwrap_or_default(),
+            id: p.id.to_string(),
+            can_edit: viewer.map(|u| u.id == p.user_id).unwrap_or(false)
+                && is_editable(p.created_at, p.upvotes, p.downvotes),
+            can_delete: viewer.map(|u| u.id == p.user_id || u.can_moderate()).unwrap_or(false),
+            can_report: viewer.map(|u| u.id != p.user_id).unwrap_or(false),
+            username: p.username,
+            constituency: p.constituency_name.unwrap_or_else(|| "Unknown".to_string()),
+            is_anonymous: p.is_anonymous,
+            is_reply: p.stance.is_none(),
+            stance: p.stance.unwrap_or_default(),
+            reply_count: p.reply_count,
+            content_html: render_mentions(&p.content, &known),
+            content: p.content,
+            date: p.formatted_date,
+            edited_date: p.edited_at.map(|e| e.format("%B %d, %Y %H:%M").to_string()),
+            upvotes: p.upvotes,
+            downvotes: p.downvotes,
+        })
+        .collect()
+}
+
+async fn unpublished_reviews(pool: &PgPool, viewer: Option<&models::User>, bill_id: Uuid) -> Vec<UnpublishedReview> {
+    let Some(viewer) = viewer else {
+        return Vec::new();
+    };
+
+    db::get_user_posts_for_bill(pool, viewer.id, bill_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| UnpublishedReview {
+            stance: p.stance.unwrap_or_else(|| "Reply".to_string()),
+            content: p.content,
+            date: p.created_at.format("%B %d, %Y").to_string(),
+            moderation_status: p.moderation_status,
+            moderation_reason: p.moderation_reason,
+        })
+        .collect()
+}
+
+// URL for the next page of reviews, if any remain after `page`
+fn load_more_reviews_url(bill_id: &str, sort: models::ReviewSort, page: i64, total: i64) -> Option<String> {
+    if page * REVIEWS_PER_PAGE < total {
+        Some(format!("/api/bill/{}/reviews?page={}&sort={}", bill_id, page + 1, sort.as_str()))
+    } else {
+        None
+    }
+}
+
+// Chips for the suggestions dropdown: the active year range (click to clear) and each chunk type (click to toggle)
+fn filter_chips(lang: Locale, query: &str, filter: &vector_store::SearchFilter, rerank: bool) -> Vec<FilterChip> {
+    let vals_with = |filter: &vector_store::SearchFilter, rerank: bool| {
+        let mut vals = serde_json::json!({ "query": query });
+        if rerank {
+            vals["rerank"] = "true".into();
+        }
+        if let Some(from) = filter.year_from {
+            vals["year_from"] = from.into();
+        }
+        if let Some(to) = filter.year_to {
+            vals["year_to"] = to.into();
+        }
+        if let Some(chunk_type) = filter.chunk_type {
+            vals["chunk_type"] = chunk_type.to_string().into();
+        }
+        vals.to_string()
+    };
+    let vals = |filter: &vector_store::SearchFilter| vals_with(filter, rerank);
+
+    let mut chips = Vec::new();
+
+    let year_label = match (filter.year_from, filter.year_to) {
+        (Some(from), Some(to)) if from == to => Some(from.to_string()),
+        (Some(from), Some(to)) => Some(format!("{}–{}", from, to)),
+        (Some(from), None) => Some(lang.tf("search.year_from", from)),
+        (None, Some(to)) => Some(lang.tf("search.year_to", to)),
+        (None, None) => None,
+    };
+    if let Some(label) = year_label {
+        chips.push(FilterChip {
+            label: format!("{} ×", label),
+            vals: vals(&vector_store::SearchFilter {
+                year_from: None,
+                year_to: None,
+                ..filter.clone()
+            }),
+            active: true,
+        });
+    }
+
+    for chunk_type in models::ChunkType::ALL {
+        let active = filter.chunk_type == Some(chunk_type);
+        chips.push(FilterChip {
+            label: lang.label("chunk_type", &chunk_type.to_string()).to_string(),
+            vals: vals(&vector_store::SearchFilter {
+                chunk_type: if active { None } else { Some(chunk_type) },
+                ..filter.clone()
+            }),
+            active,
+        });
+    }
+
+    chips.push(FilterChip {
+        label: lang.t("search.rerank").to_string(),
+        vals: vals_with(filter, !rerank),
+        active: rerank,
+    });
+
+    chips
+}
+
+// Search results, and whether they came from the keyword fallback
+struct SearchOutcome {
+    results: Vec<SearchHit>,
+    keyword_fallback: bool,
+}
+
+// Semantic search, falling back to keyword search when the embedder or vector store fails
+async fn perform_search(
+    state: &AppState,
+    query: &str,
+    limit: usize,
+    filter: &vector_store::SearchFilter,
+    rerank: bool,
+) -> anyhow::Result<SearchOutcome> {
+    match semantic_search(state, query, limit, filter, rerank).await {
+        Ok(results) => Ok(SearchOutcome {
+            results,
+            keyword_fallback: false,
+        }),
+        Err(e) => {
+            tracing::warn!("Semantic search unavailable, falling back to keyword search: {:#}", e);
+            let results = keyword_search(&state.db_pool, query, limit, filter).await?;
+            Ok(SearchOutcome {
+                results,
+                keyword_fallback: true,
+            })
+        }
+    }
+}
+
+async fn keyword_search(
+    pool: &PgPool,
+    query: &str,
+    limit: usize,
+    filter: &vector_store::SearchFilter,
+) -> anyhow::Result<Vec<SearchHit>> {
+    let rows = db::keyword_search_bills(pool, query, filter.year_from, filter.year_to, limit as i64).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, title, bill_number, matched_title)| SearchHit {
+            bill_id: id.to_string(),
+            bill_title: title,
+            bill_number,
+            section: if matched_title { "Title" } else { "Bill text" }.to_string(),
+            section_anchor: String::new(),
+            score: String::new(),
+            reranked: false,
+            snippet: String::new(),
+        })
+        .collect())
+}
+
+async fn semantic_search(
+    state: &AppState,
+    query: &str,
+    limit: usize,
+    filter: &vector_store::SearchFilter,
+    rerank: bool,
+) -> anyhow::Result<Vec<SearchHit>> {
+    let query_embedding = state.embedder.embed_query(query).await?;
+    let (search_results, reranked) = if rerank {
+        let candidates = state
+            .vector_store
+            .search(&query_embedding, reranker::RERANK_CANDIDATES.max(limit), filter)
+            .await?;
+        rerank_results(state, query, candidates, limit).await
+    } else {
+        (state.vector_store.search(&query_embedding, limit, filter).await?, false)
+    };
+
+    let mut results = Vec::new();
+    for r in search_results {
+        // Look up bill by bill_number to get the UUID
+        if let Ok(Some(bill)) = db::get_bill_by_number(&state.db_pool, &r.bill_number).await {
+            results.push(SearchHit {
+                bill_id: bill.id.to_string(),
+                bill_title: r.bill_title,
+                bill_number: r.bill_number,
+                section: r.chunk_identifier,
+                section_anchor: section_anchor(r.chunk_index),
+                score: format!("{:.2}", r.score),
+                reranked,
+                snippet: highlight_snippet(&r.content, query),
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+/// Rerank dense candidates within RERANK_TIMEOUT_SECS, or keep the dense order if reranking fails or
+/// takes too long; the flag says whether the results were reranked
+async fn rerank_results(
+    state: &AppState,
+    query: &str,
+    candidates: Vec<models::SearchResult>,
+    limit: usize,
+) -> (Vec<models::SearchResult>, bool) {
+    let reranking = reranker::rerank(state.reranker.as_ref(), query, candidates.clone(), limit);
+    match tokio::time::timeout(std::time::Duration::from_secs(RERANK_TIMEOUT_SECS), reranking).await {
+        Ok(Ok(results)) => (results, true),
+        Ok(Err(e)) => {
+            tracing::warn!("Reranking failed, keeping vector order: {:#}", e);
+            (candidates.into_iter().take(limit).collect(), false)
+        }
+        Err(_) => {
+            tracing::warn!("Reranking took over {}s, keeping vector order", RERANK_TIMEOUT_SECS);
+            (candidates.into_iter().take(limit).collect(), false)
+        }
+    }
+}
+
+/// Escaped excerpt of about SNIPPET_CHARS characters around the densest run of query terms,
+/// with each matching word wrapped in <mark>. Starts at the beginning when nothing matches.
+fn highlight_snippet(content: &str, query: &str) -> String {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.chars().count() >= 3)
+        .map(|t| t.to_lowercase())
+        .collect();
+
+    let chars: Vec<char> = content.chars().collect();
+
+    // Words of the content as char ranges, with the index of the query term they start with
+    let mut matches: Vec<(usize, usize, usize)> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_alphanumeric() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && chars[i].is_alphanumeric() {
+            i += 1;
+        }
+        let word: String = chars[start..i].iter().collect::<String>().to_lowercase();
+        if let Some(term) = terms.iter().position(|t| word.starts_with(t.as_str())) {
+            matches.push((start, i, term));
+        }
+    }
+
+    // Pick the window holding the most distinct terms, then centre it on the matches inside
+    let mut window_start = 0;
+    let mut best = 0;
+    for (i, &(start, _, _)) in matches.iter().enumerate() {
+        let inside: Vec<&(usize, usize, usize)> = matches[i..]
+            .iter()
+            .take_while(|(_, end, _)| *end <= start + SNIPPET_CHARS)
+            .collect();
+        let mut distinct: Vec<usize> = inside.iter().map(|(_, _, term)| *term).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+
+        if distinct.len() > best {
+            best = distinct.len();
+            let last_end = inside.last().map_or(start, |(_, end, _)| *end);
+            window_start = ((start + last_end) / 2).saturating_sub(SNIPPET_CHARS / 2);
+        }
+    }
+    let window_start = window_start.min(chars.len().saturating_sub(SNIPPET_CHARS));
+    let window_end = (window_start + SNIPPET_CHARS).min(chars.len());
+
+    let mut snippet = String::new();
+    if window_start > 0 {
+        snippet.push('…');
+    }
+    let mut pos = window_start;
+    for &(start, end, _) in matches.iter().filter(|(s, e, _)| *s >= window_start && *e <= window_end) {
+        snippet.push_str(&xml_escape(&chars[pos..start].iter().collect::<String>()));
+        snippet.push_str("<mark>");
+        snippet.push_str(&xml_escape(&chars[start..end].iter().collect::<String>()));
+        snippet.push_str("</mark>");
+        pos = end;
+    }
+    snippet.push_str(&xml_escape(&chars[pos..window_end].iter().collect::<String>()));
+    if window_end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+// Byte ranges of `code` spans and ``` fenced blocks, which mentions are never parsed inside.
+// A run of backticks is closed by the next run of the same length; an unclosed run is plain text.
+fn code_spans(content: &str) -> Vec<std::ops::Range<usize>> {
+    let bytes = content.as_bytes();
+    let run_end = |mut i: usize| {
+        while i < bytes.len() && bytes[i] == b'`' {
+            i += 1;
+        }
+        i
+    };
+
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i = run_end(i);
+        let fence = i - start;
+
+        let mut j = i;
+        while let Some(offset) = content[j..].find('`') {
+            let close_start = j + offset;
+            let close_end = run_end(close_start);
+            if close_end - close_start == fence {
+                spans.push(start..close_end);
+                i = close_end;
+                break;
+            }
+            j = close_end;
+        }
+    }
+    spans
+}
+
+// Each @username in the content with the byte range of the whole token (including the @).
+// An @ straight after a word character, as in an email address, doesn't start a mention,
+// and trailing dots or hyphens are treated as punctuation rather than part of the name.
+fn find_mentions(content: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '.' || c == '-';
+    let code = code_spans(content);
+
+    let mut mentions = Vec::new();
+    let mut prev = None;
+    for (i, c) in content.char_indices() {
+        let after_word = prev.is_some_and(|p: char| is_name_char(p) || p == '+' || p == '@' || p == '/');
+        prev = Some(c);
+        if c != '@' || after_word || code.iter().any(|span| span.contains(&i)) {
+            continue;
+        }
+
+        let rest = &content[i + 1..];
+        let len = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+        let name = rest[..len].trim_end_matches(['.', '-']);
+        // "@host.example@..." is part of an address, not a mention
+        if name.is_empty() || rest[name.len()..].starts_with('@') {
+            continue;
+        }
+        mentions.push((i..i + 1 + name.len(), name));
+    }
+    mentions
+}
+
+// Distinct usernames mentioned in the content, in order of first mention
+fn mentioned_usernames(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (_, name) in find_mentions(content) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+// Escaped post content with mentions of existing users linked to their profiles;
+// mentions of anyone else stay plain text
+fn render_mentions(content: &str, known: &HashSet<String>) -> String {
+    let mut html = String::with_capacity(content.len());
+    let mut pos = 0;
+    for (range, name) in find_mentions(content) {
+        if !known.contains(name) {
+            continue;
+        }
+        html.push_str(&xml_escape(&content[pos..range.start]));
+        html.push_str(&format!(
+            "<a href=\"/u/{}\" class=\"mention\">@{}</a>",
+            encode_path_segment(name),
+            xml_escape(name)
+        ));
+        pos = range.end;
+    }
+    html.push_str(&xml_escape(&content[pos..]));
+    html
+}
+
+// Template wrapper to handle errors
+struct HtmlTemplate<T>(T);
+
+impl<T> IntoResponse for HtmlTemplate<T>
+where
+    T: Template,
+{
+    fn into_response(self) -> Response {
+        match self.0.render() {
+            Ok(html) => Html(html).into_response(),
+            Err(err) => AppError::internal(anyhow::Error::new(err).context("Failed to render template")).into_response(),
+        }
+    }
+}
+
+// Error pages
+#[derive(Template)]
+#[template(path = "404.html")]
+struct NotFoundTemplate {
+    message: &'static str,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "error.html")]
+struct ErrorTemplate {
+    status: u16,
+    title: &'static str,
+    message: &'static str,
+    reference: Option<String>,
+    user: Option<CurrentUser>,
+    lang: Locale,
+}
+
+/// A failed page or API request. `message` is an i18n key shown to the user; `source`
+/// is logged but never shown. `render_errors` turns it into a styled page or problem+json.
+pub(crate) struct AppError {
+    status: StatusCode,
+    message: &'static str,
+    source: Option<anyhow::Error>,
+}
+
+impl AppError {
+    fn new(status: StatusCode, message: &'static str) -> Self {
+        Self {
+            status,
+            message,
+            source: None,
+        }
+    }
+
+    fn bad_request(message: &'static str) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub(crate) fn unauthorized(message: &'static str) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, message)
+    }
+
+    pub(crate) fn forbidden(message: &'static str) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    fn not_found(message: &'static str) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    fn internal(source: anyhow::Error) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: "error.server_text",
+            source: Some(source),
+        }
+    }
+
+    fn with_source(mut self, source: anyhow::Error) -> Self {
+        self.source = Some(source);
+        self
+    }
+}
+
+// Carried on the response so `render_errors`, which can see the request, does the rendering
+#[derive(Clone)]
+struct ErrorDetails {
+    message: &'static str,
+    source: Option<Arc<anyhow::Error>>,
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, Locale::En.t(self.message)).into_response();
+        response.extensions_mut().insert(ErrorDetails {
+            message: self.message,
+            source: self.source.map(Arc::new),
+        });
+        response
+    }
+}
+
+// Short random ids for X-Request-Id; an id already set by a proxy is kept
+#[derive(Clone, Copy)]
+struct MakeShortRequestId;
+
+impl MakeRequestId for MakeShortRequestId {
+    fn make_request_id<B>(&mut self, _request: &axum::http::Request<B>) -> Option<RequestId> {
+        let id = Uuid::new_v4().simple().to_string();
+        HeaderValue::from_str(&id[..12]).ok().map(RequestId::new)
+    }
+}
+
+fn request_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+// Every log line for a request, including vector store and Ollama calls, is nested in this span
+fn request_span(request: &axum::http::Request<Body>) -> tracing::Span {
+    tracing::info_span!(
+        "request",
+        request_id = request_id(request.headers()).as_deref().unwrap_or("-"),
+        method = %request.method(),
+        path = %request.uri().path(),
+    )
+}
+
+async fn not_found_handler() -> AppError {
+    AppError::not_found("error.not_found_text")
+}
+
+// Renders AppError responses as a styled page, or as problem+json for JSON clients under /api/,
+// and logs the underlying error with a reference the user can quote
+async fn render_errors(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let headers = request.headers().clone();
+
+    let mut response = next.run(request).await;
+    let Some(details) = response.extensions_mut().remove::<ErrorDetails>() else {
+        return response;
+    };
+    let status = response.status();
+    let retry_after = response.headers().get(header::RETRY_AFTER).cloned();
+
+    // Logged inside the request span, so the line carries the same id the page shows as its reference
+    let reference = request_id(&headers);
+    match &details.source {
+        Some(source) if status.is_server_error() => tracing::error!("{} {} failed: {:#}", method, path, source),
+        Some(source) => tracing::debug!("{} {} returned {}: {:#}", method, path, status, source),
+        None if status.is_server_error() => tracing::error!("{} {} returned {}", method, path, status),
+        None => {}
+    }
+
+    if path.starts_with("/api/") && !is_htmx_request(&headers) {
+        return with_retry_after(ApiError::new(status, Locale::En.t(details.message)).into_response(), retry_after);
+    }
+
+    let lang = Locale::from_headers(&headers);
+    let jar = CookieJar::from_headers(&headers);
+    let user = get_current_user(&jar, &state.db_pool).await.map(|u| CurrentUser {
+        id: u.id.to_string(),
+        username: u.username,
+        is_admin: u.is_admin(),
+        can_moderate: u.can_moderate(),
+    });
+
+    let page = if status == StatusCode::NOT_FOUND {
+        NotFoundTemplate {
+            message: lang.t(details.message),
+            user,
+            lang,
+        }
+        .render()
+    } else {
+        ErrorTemplate {
+            status: status.as_u16(),
+            title: lang.t(error_title(status)),
+            message: lang.t(details.message),
+            reference,
+            user,
+            lang,
+        }
+        .render()
+    };
+
+    let rendered = match page {
+        Ok(html) => (status, Html(html)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to render error page: {}", e);
+            (status, Locale::En.t(details.message)).into_response()
+        }
+    };
+    with_retry_after(rendered, retry_after)
+}
+
+// The rebuilt error response keeps the handler's Retry-After, so a 429 still says when to come back
+fn with_retry_after(mut response: Response, retry_after: Option<HeaderValue>) -> Response {
+    if let Some(value) = retry_after {
+        response.headers_mut().insert(header::RETRY_AFTER, value);
+    }
+    response
+}
+
+fn error_title(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "error.bad_request_title",
+        StatusCode::UNAUTHORIZED => "error.unauthorized_title",
+        StatusCode::FORBIDDEN => "error.forbidden_title",
+        StatusCode::TOO_MANY_REQUESTS => "error.rate_limited_title",
+        s if s.is_server_error() => "error.server_title",
+        _ => "error.generic",
+    }
+}
+
+// htmx is served from unpkg and templates use inline scripts and styles. A registration
+// CAPTCHA's origins are added to the sources its widget loads scripts, frames and requests from.
+fn content_security_policy(challenge_origins: &[&str]) -> HeaderValue {
+    let extra: String = challenge_origins.iter().map(|origin| format!(" {}", origin)).collect();
+    let policy = format!(
+        "default-src 'self'; \
+        script-src 'self' 'unsafe-inline' https://unpkg.com{extra}; \
+        style-src 'self' 'unsafe-inline'{extra}; \
+        img-src 'self' data:; \
+        frame-src 'self'{extra}; \
+        connect-src 'self'{extra}; \
+        frame-ancestors 'none'; \
+        base-uri 'self'; \
+        form-action 'self'"
+    );
+    HeaderValue::from_str(&policy).expect("CSP is a valid header value")
+}
+
+// Adds security headers to every response
+async fn security_headers(State(csp): State<HeaderValue>, request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+
+    headers.insert(header::CONTENT_SECURITY_POLICY, csp);
+    headers.insert(header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    headers.insert(header::REFERRER_POLICY, HeaderValue::from_static("strict-origin-when-cross-origin"));
+    headers.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+
+    response
+}
+
+// Static files. Content-hashed names (see assets.rs) are cached for a year; plain names are
+// revalidated on every use so an edited stylesheet shows up straight away
+async fn static_asset_handler(Path(path): Path<String>, mut request: Request) -> Response {
+    let (file, immutable) = match assets::ASSETS.resolve(&path) {
+        Some(file) => (file.to_string(), true),
+        None => (path, false),
+    };
+
+    *request.uri_mut() = match format!("/{}", file).parse() {
+        Ok(uri) => uri,
+        Err(_) => return AppError::not_found("error.not_found_text").into_response(),
+    };
+
+    let mut response = match ServeDir::new(assets::STATIC_DIR).oneshot(request).await {
+        Ok(response) => response.into_response(),
+        Err(never) => match never {},
+    };
+
+    if response.status().is_success() {
+        let cache_control = if immutable {
+            "public, max-age=31536000, immutable"
+        } else {
+            "no-cache"
+        };
+        response
+            .headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static(cache_control));
+    }
+
+    response
+}
+
+// Health checks
+const HEALTH_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+// Absolute URL the site is served from, for feeds, sitemaps and email links (PUBLIC_BASE_URL)
+pub fn public_base_url() -> String {
+    std::env::var("PUBLIC_BASE_URL")
+        .map(|u| u.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| "http://localhost:3000".to_string())
+}
+
+// Atom feed of the most recently ingested bills
+async fn feed_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let bills = match db::get_bills_paginated(&state.db_pool, 1, FEED_MAX_ENTRIES, &models::BillFilter::default()).await {
+        Ok((bills, _)) => bills,
+        Err(e) => {
+            tracing::error!("Failed to load bills for feed: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to generate feed").into_response();
+        }
+    };
+
+    let updated = bills
+        .iter()
+        .map(|b| b.updated_at)
+        .max()
+        .unwrap_or_else(chrono::Utc::now);
+
+    let entries = bills
+        .into_iter()
+        .map(|b| {
+            let mut summary = format!("Bill No. {}", b.bill_number);
+            if let Some(date) = b.introduction_date {
+                summary.push_str(&format!(" · Introduced {}", date.format("%d %B %Y")));
+            }
+            if let Some(status) = b.status.as_ref() {
+                summary.push_str(&format!(" · Status: {}", status));
+            }
+
+            FeedEntry {
+                id: b.id.to_string(),
+                title: b.title,
+                published: b.created_at.to_rfc3339(),
+                updated: b.updated_at.to_rfc3339(),
+                summary,
+            }
+        })
+        .collect();
+
+    let feed = FeedTemplate {
+        base_url: public_base_url(),
+        updated: updated.to_rfc3339(),
+        entries,
+    };
+
+    match feed.render() {
+        Ok(xml) => (
+            [
+                (header::CONTENT_TYPE, "application/atom+xml; charset=utf-8"),
+                (header::CACHE_CONTROL, "public, max-age=900"),
+            ],
+            xml,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to render feed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to generate feed").into_response()
+        }
+    }
+}
+
+// Sitemap of the index, every bill's detail and forum page, and user profiles.
+// Beyond SITEMAP_MAX_URLS this becomes a sitemap index pointing at /sitemap/:page
+async fn sitemap_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let total = match db::count_sitemap_urls(&state.db_pool).await {
+        // +1 for the index page
+        Ok(count) => count + 1,
+        Err(e) => {
+            tracing::error!("Failed to count sitemap URLs: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to generate sitemap").into_response();
+        }
+    };
+
+    let base_url = public_base_url();
+
+    if total <= SITEMAP_MAX_URLS {
+        return xml_response(Body::from_stream(sitemap_urls(state.db_pool.clone(), base_url, 0, total)));
+    }
+
+    let pages = (total + SITEMAP_MAX_URLS - 1) / SITEMAP_MAX_URLS;
+    let mut index = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for page in 1..=pages {
+        index.push_str(&format!(
+            "  <sitemap><loc>{}</loc></sitemap>\n",
+            xml_escape(&format!("{}/sitemap/{}", base_url, page))
+        ));
+    }
+    index.push_str("</sitemapindex>\n");
+
+    xml_response(Body::from(index))
+}
+
+// One page of a sitemap index
+async fn sitemap_page_handler(
+    State(state): State<Arc<AppState>>,
+    Path(page): Path<i64>,
+) -> impl IntoResponse {
+    let total = match db::count_sitemap_urls(&state.db_pool).await {
+        Ok(count) => count + 1,
+        Err(e) => {
+            tracing::error!("Failed to count sitemap URLs: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to generate sitemap").into_response();
+        }
+    };
+
+    if page < 1 {
+        return (StatusCode::NOT_FOUND, "Sitemap page not found").into_response();
+    }
+    let Some(start) = (page - 1).checked_mul(SITEMAP_MAX_URLS).filter(|start| *start < total) else {
+        return (StatusCode::NOT_FOUND, "Sitemap page not found").into_response();
+    };
+    let end = (start + SITEMAP_MAX_URLS).min(total);
+
+    xml_response(Body::from_stream(sitemap_urls(state.db_pool.clone(), public_base_url(), start, end)))
+}
+
+// Streams a <urlset> covering URLs [start, end), where URL 0 is the index page and the rest come
+// from the database in batches so large sitemaps never sit in memory at once
+fn sitemap_urls(
+    pool: PgPool,
+    base_url: String,
+    start: i64,
+    end: i64,
+) -> impl Stream<Item = anyhow::Result<String>> {
+    enum Step {
+        Header,
+        Entries(i64),
+        Done,
+    }
+
+    futures::stream::unfold(Step::Header, move |step| {
+        let pool = pool.clone();
+        let base_url = base_url.clone();
+        async move {
+            match step {
+                Step::Header => {
+                    let mut chunk = String::from(
+                        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+                    );
+                    if start == 0 {
+                        chunk.push_str(&format!("  <url><loc>{}/</loc></url>\n", xml_escape(&base_url)));
+                    }
+                    // Database rows are numbered from the URL after the index page
+                    Some((Ok(chunk), Step::Entries(start.max(1) - 1)))
+                }
+                Step::Entries(offset) if offset < end - 1 => {
+                    let limit = (end - 1 - offset).min(SITEMAP_BATCH_SIZE);
+                    match db::get_sitemap_entries(&pool, offset, limit).await {
+                        Ok(entries) => {
+                            let chunk: String = entries
+                                .iter()
+                                .map(|(kind, key, lastmod)| {
+                                    let path = match kind.as_str() {
+                                        "bill" => format!("/bill/{}", key),
+                                        "forum" => format!("/f/{}", key),
+                                        _ => format!("/u/{}", encode_path_segment(key)),
+                                    };
+                                    format!(
+                                        "  <url><loc>{}{}</loc><lastmod>{}</lastmod></url>\n",
+                                        xml_escape(&base_url),
+                                        xml_escape(&path),
+                                        lastmod.format("%Y-%m-%d")
+                                    )
+                                })
+                                .collect();
+                            Some((Ok(chunk), Step::Entries(offset + limit)))
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to stream sitemap: {}", e);
+                            Some((Err(e), Step::Done))
+                        }
+                    }
+                }
+                Step::Entries(_) => Some((Ok("</urlset>\n".to_string()), Step::Done)),
+                Step::Done => None,
+            }
+        }
+    })
+}
+
+fn xml_response(body: Body) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/xml; charset=utf-8"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// Percent-encodes everything outside the RFC 3986 unreserved set
+fn encode_path_segment(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+async fn robots_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        format!(
+            "User-agent: *\nDisallow: /admin/\nDisallow: /api/\nDisallow: /notifications\n\nSitemap: {}/sitemap.xml\n",
+            public_base_url()
+        ),
+    )
+}
+
+#[derive(Serialize)]
+struct HealthResponse {
+    status: &'static str,
+    database: bool,
+    qdrant_reachable: bool,
+    qdrant_collection: bool,
+    embedder_loaded: bool,
+    // State of the moderation provider's circuit breaker; "open" means posts get keyword moderation
+    moderation_breaker: Option<&'static str>,
+}
+
+// Liveness plus a report on each dependency; each check gives up after HEALTH_CHECK_TIMEOUT
+async fn healthz_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let database = async {
+        let ping = sqlx::query("SELECT 1").execute(&state.db_pool);
+        matches!(tokio::time::timeout(HEALTH_CHECK_TIMEOUT, ping).await, Ok(Ok(_)))
+    };
+    let qdrant = async {
+        match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, state.vector_store.collection_exists()).await {
+            Ok(Ok(exists)) => (true, exists),
+            _ => (false, false),
+        }
+    };
+
+    let (database, (qdrant_reachable, qdrant_collection)) = tokio::join!(database, qdrant);
+    let embedder_loaded = state.embedder.is_loaded();
+    let moderation_breaker = state.moderation.iter().find_map(|p| p.breaker()).map(|b| b.state());
+
+    let healthy = database
+        && qdrant_reachable
+        && qdrant_collection
+        && embedder_loaded
+        && moderation_breaker != Some(moderation::BreakerState::Open);
+
+    Json(HealthResponse {
+        status: if healthy { "ok" } else { "degraded" },
+        database,
+        qdrant_reachable,
+        qdrant_collection,
+        embedder_loaded,
+        moderation_breaker: moderation_breaker.map(|b| b.as_str()),
+    })
+}
+
+// Ready once the embedding model has been warmed up
+async fn readyz_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    if state.embedder.is_loaded() {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "warming up")
+    }
+}
+
+// Requests currently being handled, reported on shutdown
+static IN_FLIGHT_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn in_flight_requests() -> usize {
+    IN_FLIGHT_REQUESTS.load(Ordering::Relaxed)
+}
+
+// Decrements on drop so requests cancelled mid-flight are still counted out
+struct InFlightGuard;
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        IN_FLIGHT_REQUESTS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+async fn track_in_flight(request: Request, next: Next) -> Response {
+    IN_FLIGHT_REQUESTS.fetch_add(1, Ordering::Relaxed);
+    let _guard = InFlightGuard;
+    next.run(request).await
+}
+
+// Router setup
+pub async fn create_router(db_pool: PgPool, shutdown: ShutdownToken) -> Router {
+    // One HTTP client shared by Qdrant and Ollama calls
+    let http = reqwest::Client::new();
+
+    let (moderation_queue, moderation_jobs) = if moderation::async_mode() {
+        let (queue, jobs) = moderation::ModerationQueue::new();
+        (Some(queue), Some(jobs))
+    } else {
+        (None, None)
+    };
+
+    let keywords = Arc::new(moderation::KeywordCache::new(db_pool.clone()));
+
+    let state = Arc::new(AppState {
+        db_pool,
+        vector_store: Arc::new(vector_store::QdrantStore::new(http.clone())),
+        embedder: Arc::new(embedder::BertEmbedder),
+        llm: OllamaClient::new(http.clone()),
+        reranker: reranker::from_env(OllamaClient::new(http.clone())),
+        moderation: moderation::from_env(OllamaClient::new(http.clone()), http.clone(), keywords.clone()),
+        keywords,
+        mailer: mailer::from_env(),
+        challenge: challenge::from_env(),
+        google_oauth: oauth::GoogleOAuth::from_env(&public_base_url(), http)
+            .unwrap_or_else(|e| {
+                tracing::error!("Google sign-in is disabled: {:#}", e);
+                None
+            })
+            .map(Arc::new),
+        similar_bills: Arc::new(vector_store::SimilarBillsCache::default()),
+        rate_limits: rate_limit::RateLimitConfig::from_env(),
+        rate_limit_cache: Arc::new(rate_limit::RateLimitCache::from_env()),
+        ingest_jobs: Arc::new(IngestJobs::default()),
+        report_jobs: Arc::new(report_jobs::ReportJobs::from_env()),
+        review_events: broadcast::channel(REVIEW_EVENTS_CAPACITY).0,
+        moderation_queue,
+        shutdown: shutdown.clone(),
+    });
+
+    // Judge reviews queued for moderation in the background
+    if let Some(jobs) = moderation_jobs {
+        tokio::spawn(run_moderation_worker(state.clone(), jobs));
+    }
+
+    // Summarize forum reviews in the background as they accumulate
+    tokio::spawn(summary::run_job(state.db_pool.clone(), state.llm.clone(), shutdown.clone()));
+
+    // Weekly digests of followed bills; safe alongside the send-digests command
+    tokio::spawn(digest::run_job(
+        state.db_pool.clone(),
+        state.mailer.clone(),
+        public_base_url(),
+        shutdown.clone(),
+    ));
+
+    // Prune expired sessions, rate limit records and old search logs
+    tokio::spawn(maintenance::run_job(
+        state.db_pool.clone(),
+        state.rate_limit_cache.clone(),
+        shutdown.clone(),
+    ));
+
+    // Delete background reports once they expire
+    tokio::spawn(report_jobs::run_cleanup_job(state.report_jobs.clone(), shutdown.clone()));
+
+    // Load the embedding model in the background; /readyz reports 503 until it is done
+    let embedder = state.embedder.clone();
+    tokio::spawn(async move {
+        tokio::select! {
+            result = embedder.warm_up() => {
+                if let Err(e) = result {
+                    tracing::error!("Failed to warm up embedding model: {}", e);
+                }
+            }
+            _ = shutdown.cancelled() => {
+                tracing::info!("Shutdown before embedding model warmup finished");
+            }
+        }
+    });
+
+    Router::new()
+        // Main pages
+        .route("/", get(index))
+        .route(
+            "/login",
+            get(login_page)
+                .post(login_handler)
+                .route_layer(middleware::from_fn_with_state(state.clone(), limit_by_ip)),
+        )
+        .route("/forgot-password", get(forgot_password_page).post(forgot_password_handler))
+        .route("/reset-password", get(reset_password_page).post(reset_password_handler))
+        .route(
+            "/register",
+            get(register_page)
+                .post(register_handler)
+                .route_layer(middleware::from_fn_with_state(state.clone(), limit_by_ip)),
+        )
+        .route("/logout", get(logout_handler))
+        .route("/auth/google", get(google_start_handler))
+        .route("/auth/google/callback", get(google_callback_handler))
+        .route("/lang/:code", get(set_language_handler))
+        .route("/u/:username", get(profile_handler).post(update_profile_handler))
+        .route("/u/:username/password", post(change_password_handler))
+        .route("/u/:username/email", post(update_email_handler))
+        .route("/u/:username/email/resend", post(resend_verification_handler))
+        .route("/u/:username/digest", post(update_digest_handler))
+        .route("/verify-email", get(verify_email_handler))
+        .route("/u/:username/delete", post(delete_account_handler))
+        .route("/u/:username/export", get(export_data_handler))
+        .route("/u/:username/sessions", get(sessions_handler))
+        .route("/u/:username/sessions/revoke-others", post(revoke_other_sessions_handler))
+        .route("/u/:username/sessions/:id/revoke", post(revoke_session_handler))
+        .route("/u/:username/tokens", get(api_tokens_handler).post(create_api_token_handler))
+        .route("/u/:username/tokens/:id/revoke", post(revoke_api_token_handler))
+        .route("/u/:username/google/unlink", post(unlink_google_handler))
+        // Notifications
+        .route("/notifications", get(notifications_handler))
+        .route("/bookmarks", get(bookmarks_handler))
+        .route("/notifications/read-all", post(mark_all_notifications_read_handler))
+        .route("/notifications/:id/read", post(mark_notification_read_handler))
+        .route("/api/notifications/unread", get(notification_badge_handler))
+        // Forum pages
+        .route("/f/:bill_id", get(forum_page_handler))
+        // Bill text
+        .route("/bill/:id", get(bill_detail_handler))
+        .route("/bill/:id/report.pdf", get(bill_report_handler))
+        .route("/compare", get(compare_handler))
+        // Constituency dashboard and reports
+        .route("/constituencies", get(constituencies_leaderboard_handler))
+        .route("/constituency/:id", get(constituency_dashboard_handler))
+        .route("/constituency/:id/report.pdf", get(constituency_report_handler))
+        .route("/constituency/:id/report.csv", get(constituency_report_csv_handler))
+        .route("/constituency/:id/report", post(start_report_job_handler))
+        .route("/reports/:job_id", get(report_job_handler))
+        // API endpoints
+        .route(
+            "/api/search",
+            get(search_handler).route_layer(middleware::from_fn_with_state(state.clone(), limit_search)),
+        )
+        .route("/api/bills", get(bills_list_handler))
+        .route("/api/bill/:id/forum", get(bill_forum_handler))
+        .route("/api/bill/:id/reviews", get(reviews_page_handler))
+        .route("/api/bill/:id/stream", get(review_stream_handler))
+        .route("/api/bill/:id/related", get(related_bills_handler))
+        .route("/api/bill/:id/review", post(submit_review_handler))
+        .route("/api/bill/:id/review-form", get(review_form_handler))
+        .route("/api/bill/:id/follow", post(follow_bill_handler))
+        .route("/api/bill/:id/bookmark", post(bookmark_bill_handler))
+        .route("/api/review/:id/reply", post(submit_reply_handler))
+        .route("/api/review/:id/replies", get(replies_handler))
+        .route("/api/review/:id/edit", post(edit_review_handler))
+        .route("/api/review/:id/delete", post(delete_review_handler))
+        .route("/api/review/:id/report", post(report_review_handler))
+        .route("/api/review/:id/appeal", post(appeal_review_handler))
+        .route("/api/review/:id/upvote", post(upvote_handler))
+        .route("/api/review/:id/downvote", post(downvote_handler))
+        .route("/api/constituencies", get(constituencies_handler))
+        // Admin
+        .route("/admin/moderation", get(admin_moderation_handler))
+        .route("/admin/moderation/remoderate", post(remoderate_posts_handler))
+        .route("/admin/posts/:id/moderation", get(admin_post_moderation_handler))
+        .route("/admin/posts/:id/approve", post(approve_post_handler))
+        .route("/admin/posts/:id/reject", post(reject_post_handler))
+        .route("/admin/posts/:id/spot-check", post(spot_check_post_handler))
+        .route("/admin/appeals/:id/uphold", post(uphold_appeal_handler))
+        .route("/admin/appeals/:id/overturn", post(overturn_appeal_handler))
+        .route("/admin/keywords", get(admin_keywords_handler).post(add_keyword_handler))
+        .route("/admin/keywords/:id", post(update_keyword_handler))
+        .route("/admin/keywords/:id/delete", post(delete_keyword_handler))
+        .route("/admin/users", get(admin_users_handler))
+        .route("/admin/search-stats", get(admin_search_stats_handler))
+        .route("/admin/rate-limits", get(admin_rate_limits_handler))
+        .route("/admin/rate-limits/:id/clear", post(clear_rate_limit_handler))
+        .route("/admin/audit", get(admin_audit_log_handler))
+        .route("/admin/users/:id", get(admin_user_handler))
+        .route("/admin/users/:id/role", post(set_user_role_handler))
+        .route("/admin/users/:id/rate-limit", post(set_rate_limit_handler))
+        .route("/admin/users/:id/suspend", post(suspend_user_handler))
+        .route("/admin/users/:id/reinstate", post(reinstate_user_handler))
+        .route("/admin/bills/:id/summary", post(regenerate_summary_handler))
+        .route("/admin/ingest", get(admin_ingest_page).post(start_ingest_handler))
+        .route("/admin/ingest/:job_id", get(ingest_job_handler))
+        .route("/admin/reports/national.pdf", get(national_report_handler))
+        // JSON API
+        .route(
+            "/api/v1/search",
+            get(api_search_handler).route_layer(middleware::from_fn_with_state(state.clone(), limit_search)),
+        )
+        .route("/api/v1/bills", get(api_bills_handler))
+        .route("/api/v1/bills/trending", get(api_trending_bills_handler))
+        .route(
+            "/api/ask",
+            post(api_ask_handler).route_layer(middleware::from_fn_with_state(state.clone(), limit_search)),
+        )
+        .route("/api/v1/bookmarks", get(api_bookmarks_handler))
+        .route(
+            "/api/v1/bookmarks/:bill_id",
+            put(api_add_bookmark_handler).delete(api_remove_bookmark_handler),
+        )
+        // Feeds and crawlers
+        .route("/feed.xml", get(feed_handler))
+        .route("/sitemap.xml", get(sitemap_handler))
+        .route("/sitemap/:page", get(sitemap_page_handler))
+        .route("/robots.txt", get(robots_handler))
+        // Health checks
+        .route("/healthz", get(healthz_handler))
+        .route("/readyz", get(readyz_handler))
+        // API documentation
+        .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()))
+        // Static files
+        .route("/static/*path", get(static_asset_handler))
+        .fallback(not_found_handler)
+        .layer(middleware::from_fn_with_state(state.clone(), render_errors))
+        .layer(middleware::from_fn_with_state(state.clone(), slide_session))
+        .layer(middleware::from_fn_with_state(
+            content_security_policy(state.challenge.origins()),
+            security_headers,
+        ))
+        .layer(middleware::from_fn(track_in_flight))
+        .layer(
+            ServiceBuilder::new()
+                .layer(SetRequestIdLayer::x_request_id(MakeShortRequestId))
+                .layer(
+                    TraceLayer::new_for_http()
+                        .make_span_with(request_span)
+                        .on_response(DefaultOnResponse::new().level(tracing::Level::INFO)),
+                )
+                .layer(PropagateRequestIdLayer::x_request_id())
+                .layer(CompressionLayer::new()),
+        )
+        .with_state(state)
+}
+
+// MP Report Handlers
+async fn constituencies_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Response, AppError> {
+    let constituencies = auth::get_all_constituencies(&state.db_pool)
+        .await
+        .map_err(AppError::internal)?;
+    
+    let json_constituencies: Vec<serde_json::Value> = constituencies
+        .iter()
+        .map(|c| serde_json::json!({
+            "id": c.id,
+            "name": c.name,
+            "state": c.state
+        }))
+        .collect();
+    
+    Ok((
+        StatusCode::OK,
+        [(axum::http::header::CONTENT_TYPE, "application/json")],
+        serde_json::to_string(&json_constituencies).unwrap(),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+struct ReportPeriodQuery {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+// The report period from ?from=YYYY-MM-DD&to=YYYY-MM-DD; blank values leave that end open
+fn report_period(from: Option<&str>, to: Option<&str>) -> Result<models::ReportPeriod, AppError> {
+    let day = |value: Option<&str>| match value.map(str::trim).filter(|v| !v.is_empty()) {
+        None => Ok(None),
+        Some(v) => chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| AppError::bad_request("error.invalid_date")),
+    };
+    Ok(models::ReportPeriod {
+        from: day(from)?,
+        to: day(to)?,
+    })
+}
+
+async fn constituency_dashboard_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Path(constituency_id): Path<i32>,
+) -> impl IntoResponse {
+    let constituency = match auth::get_constituency_by_id(&state.db_pool, constituency_id).await {
+        Ok(Some(c)) => c,
+        Ok(None) => {
+            return AppError::not_found("error.constituency_not_found").into_response();
+        }
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load constituency")).into_response();
+        }
+    };
+
+    let user = get_current_user(&jar, &state.db_pool).await;
+    let current_user = user.map(|u| CurrentUser {
+        id: u.id.to_string(),
+        username: u.username,
+        is_admin: u.is_admin(),
+        can_moderate: u.can_moderate(),
+    });
+
+    let sentiments = db::get_constituency_sentiment(&state.db_pool, constituency_id, None, None)
+        .await
+        .unwrap_or_default();
+
+    let participants = db::count_constituency_participants(&state.db_pool, constituency_id)
+        .await
+        .unwrap_or(0);
+
+    let percent = |count: i64, total: i64| format!("{:.1}", count as f64 * 100.0 / total as f64);
+
+    let bills = sentiments
+        .into_iter()
+        .map(|s| {
+            let total = s.support_count + s.oppose_count + s.critique_count;
+            BillSentimentBar {
+                bill_id: s.bill_id.to_string(),
+                bill_number: s.bill_number,
+                bill_title: s.bill_title,
+                support: s.support_count,
+                oppose: s.oppose_count,
+                critique: s.critique_count,
+                total,
+                support_pct: percent(s.support_count, total),
+                oppose_pct: percent(s.oppose_count, total),
+                critique_pct: percent(s.critique_count, total),
+            }
+        })
+        .collect();
+
+    let constituencies = auth::get_all_constituencies(&state.db_pool)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| ConstituencyOption {
+            id: c.id,
+            name: c.name,
+            state: c.state,
+        })
+        .collect();
+
+    HtmlTemplate(ConstituencyTemplate {
+        constituency: ConstituencyOption {
+            id: constituency.id,
+            name: constituency.name,
+            state: constituency.state,
+        },
+        constituencies,
+        bills,
+        participants,
+        user: current_user,
+        lang,
+    })
+    .into_response()
+}
+
+// The leaderboard window from ?window=30|90|all (default 30), with the day count to filter by
+fn leaderboard_window(window: Option<&str>) -> (&'static str, Option<i32>) {
+    match window {
+        Some("90") => ("90", Some(90)),
+        Some("all") => ("all", None),
+        _ => ("30", Some(30)),
+    }
+}
+
+async fn constituencies_leaderboard_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    Query(params): Query<LeaderboardQuery>,
+) -> impl IntoResponse {
+    let (window, window_days) = leaderboard_window(params.window.as_deref());
+
+    let activity = match db::get_constituency_leaderboard(&state.db_pool, window_days).await {
+        Ok(activity) => activity,
+        Err(e) => {
+            return AppError::internal(e.context("Failed to load constituency leaderboard")).into_response();
+        }
+    };
+
+    let user = get_current_user(&jar, &state.db_pool).await;
+    let current_user = user.map(|u| CurrentUser {
+        id: u.id.to_string(),
+        username: u.username,
+        is_admin: u.is_admin(),
+        can_moderate: u.can_moderate(),
+    });
+
+    let rows = activity
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| ConstituencyRank {
+            rank: (c.post_count > 0).then_some(i + 1),
+            id: c.id,
+            name: c.name,
+            state: c.state,
+            posts: c.post_count,
+            participants: c.participant_count,
+        })
+        .collect();
+
+    HtmlTemplate(ConstituenciesTemplate {
+        rows,
+        window,
+        user: current_user,
+        lang,
+    })
+    .into_response()
+}
+
+async fn constituency_report_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(constituency_id): Path<i32>,
+    Query(params): Query<ReportPeriodQuery>,
+) -> Result<Response, AppError> {
+    if get_current_user(&jar, &state.db_pool).await.is_none() {
+        return Ok(Redirect::to("/login").into_response());
+    }
+
+    let period = report_period(params.from.as_deref(), params.to.as_deref())?;
+    constituency_report_response(&state, constituency_id, &period).await
+}
+
+#[derive(Deserialize)]
+struct ReportCsvQuery {
+    from: Option<String>,
+    to: Option<String>,
+    // "sentiment" (the default) for stance counts per bill, "posts" for the posts themselves
+    table: Option<String>,
+}
+
+async fn constituency_report_csv_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(constituency_id): Path<i32>,
+    Query(params): Query<ReportCsvQuery>,
+) -> Result<Response, AppError> {
+    if get_current_user(&jar, &state.db_pool).await.is_none() {
+        return Ok(Redirect::to("/login").into_response());
+    }
+
+    let posts_table = match params.table.as_deref().map(str::trim).unwrap_or("") {
+        "" | "sentiment" => false,
+        "posts" => true,
+        _ => return Err(AppError::bad_request("error.invalid_report_table")),
+    };
+    let period = report_period(params.from.as_deref(), params.to.as_deref())?;
+    let (stem, csv) = constituency_report_csv(&state.db_pool, constituency_id, &period)
+        .await
+        .map_err(AppError::internal)?
+        .ok_or_else(|| AppError::not_found("error.constituency_not_found"))?;
+
+    let (filename, body) = if posts_table {
+        (format!("{}_posts.csv", stem), csv.posts)
+    } else {
+        (format!("{}_sentiment.csv", stem), csv.sentiment)
+    };
+    Ok((
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                &format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Build the sentiment PDF for a constituency covering `period`, with a filename for it. None if the
+/// constituency doesn't exist.
+pub async fn constituency_report(
+    pool: &PgPool,
+    llm: &OllamaClient,
+    constituency_id: i32,
+    period: &models::ReportPeriod,
+) -> anyhow::Result<Option<(String, Vec<u8>)>> {
+    let Some((constituency, sentiments, posts)) = constituency_report_data(pool, constituency_id, period).await? else {
+        return Ok(None);
+    };
+    let ai_summary =
+        summary::constituency_report_summary(pool, llm, constituency_id, &constituency.name, period, &posts).await;
+
+    let pdf_bytes = crate::pdf_generator::generate_constituency_report(
+        &constituency.name,
+        &constituency.state,
+        period,
+        ai_summary.as_deref(),
+        sentiments,
+        posts,
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to generate PDF report: {}", e))?;
+
+    let filename = format!("{}.pdf", report_file_stem(&constituency, period));
+    Ok(Some((filename, pdf_bytes)))
+}
+
+/// The same report as two CSVs, with the filename stem they share. None if the constituency
+/// doesn't exist.
+pub async fn constituency_report_csv(
+    pool: &PgPool,
+    constituency_id: i32,
+    period: &models::ReportPeriod,
+) -> anyhow::Result<Option<(String, crate::pdf_generator::ConstituencyCsv)>> {
+    let Some((constituency, sentiments, posts)) = constituency_report_data(pool, constituency_id, period).await? else {
+        return Ok(None);
+    };
+
+    let csv = crate::pdf_generator::generate_constituency_csv(&sentiments, &posts)
+        .map_err(|e| anyhow::anyhow!("Failed to generate CSV report: {}", e))?;
+
+    Ok(Some((report_file_stem(&constituency, period), csv)))
+}
+
+// The constituency with its per-bill stance counts and posts within `period`
+async fn constituency_report_data(
+    pool: &PgPool,
+    constituency_id: i32,
+    period: &models::ReportPeriod,
+) -> anyhow::Result<Option<(models::Constituency, Vec<models::BillSentiment>, Vec<models::ConstituencyPost>)>> {
+    let Some(constituency) = auth::get_constituency_by_id(pool, constituency_id).await? else {
+        return Ok(None);
+    };
+    let (from, until) = period.bounds();
+
+    let sentiments = db::get_constituency_sentiment(pool, constituency_id, from, until).await?;
+    let posts = db::get_constituency_posts(pool, constituency_id, from, until).await?;
+    Ok(Some((constituency, sentiments, posts)))
+}
+
+// Report filenames without the extension, named for the period when there is one, otherwise for
+// the day it was made
+fn report_file_stem(constituency: &models::Constituency, period: &models::ReportPeriod) -> String {
+    let covered = match (period.from, period.to) {
+        (None, None) => chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        (from, to) => format!(
+            "{}_to_{}",
+            from.map(|d| d.to_string()).unwrap_or_else(|| "start".to_string()),
+            to.map(|d| d.to_string()).unwrap_or_else(|| "now".to_string())
+        ),
+    };
+    format!(
+        "{}_{}_report_{}",
+        constituency.name.replace(' ', "_"),
+        constituency.state.replace(' ', "_"),
+        covered
+    )
+}
+
+/// Build the national PDF covering every bill with posts, with a filename for it
+pub async fn national_report(pool: &PgPool) -> anyhow::Result<(String, Vec<u8>)> {
+    let totals = db::get_national_totals(pool).await?;
+    let bills = db::get_national_sentiment(pool).await?;
+    let states = db::get_sentiment_by_state(pool).await?;
+    let constituencies = db::get_sentiment_by_bill_and_constituency(pool).await?;
+
+    let pdf_bytes = crate::pdf_generator::generate_national_report(&totals, bills, states, constituencies)
+        .map_err(|e| anyhow::anyhow!("Failed to generate PDF report: {}", e))?;
+
+    let filename = format!("national_report_{}.pdf", chrono::Utc::now().format("%Y-%m-%d"));
+    Ok((filename, pdf_bytes))
+}
+
+async fn national_report_handler(
+    State(state): State<Arc<AppState>>,
+    RequireRole(admin, ..): auth::RequireAdmin,
+) -> Result<Response, AppError> {
+    let (filename, pdf_bytes) = national_report(&state.db_pool).await.map_err(AppError::internal)?;
+    tracing::info!("National report downloaded by {}", admin.username);
+
+    Ok((
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, "application/pdf"),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                &format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        pdf_bytes,
+    )
+        .into_response())
+}
+
+// Queue the constituency's PDF to be made in the background and go to the job's page. A job
+// already running for the constituency is reused.
+async fn start_report_job_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(constituency_id): Path<i32>,
+    Form(params): Form<ReportPeriodQuery>,
+) -> Result<Response, AppError> {
+    let Some(user) = get_current_user(&jar, &state.db_pool).await else {
+        return Ok(Redirect::to("/login").into_response());
+    };
+
+    let period = report_period(params.from.as_deref(), params.to.as_deref())?;
+    auth::get_constituency_by_id(&state.db_pool, constituency_id)
+        .await
+        .map_err(AppError::internal)?
+        .ok_or_else(|| AppError::not_found("error.constituency_not_found"))?;
+
+    if let Some(throttled) = action_throttled(&state, &user, rate_limit::ActionType::ReportJob).await {
+        return Ok((
+            [(header::RETRY_AFTER, throttled.retry_after.to_string())],
+            AppError::new(StatusCode::TOO_MANY_REQUESTS, "error.report_job_rate_limited"),
+        )
+            .into_response());
+    }
+
+    let job_id = match report_jobs::spawn(
+        state.report_jobs.clone(),
+        state.db_pool.clone(),
+        state.llm.clone(),
+        constituency_id,
+        period,
+    ) {
+        Ok(id) => {
+            tracing::info!("Report job {} for constituency {} started by {}", id, constituency_id, user.username);
+            id
+        }
+        Err(running) => running,
+    };
+    Ok(Redirect::to(&format!("/reports/{}", job_id)).into_response())
+}
+
+// The job's status page, polled through htmx while it runs; once the report is ready a plain
+// request downloads it
+async fn report_job_handler(
+    State(state): State<Arc<AppState>>,
+    lang: Locale,
+    jar: CookieJar,
+    headers: HeaderMap,
+    Path(job_id): Path<String>,
+) -> Result<Response, AppError> {
+    let Some(user) = get_current_user(&jar, &state.db_pool).await else {
+        return Ok(Redirect::to("/login").into_response());
+    };
+
+    let job_id = Uuid::parse_str(&job_id).map_err(|_| AppError::not_found("error.report_job_not_found"))?;
+    let job = state
+        .report_jobs
+        .get(job_id)
+        .ok_or_else(|| AppError::not_found("error.report_job_not_found"))?;
+
+    if is_htmx_request(&headers) {
+        return Ok(HtmlTemplate(ReportJobStatusTemplate { job: ReportJobView::new(job, lang), lang }).into_response());
+    }
+
+    if let (ingest::JobStatus::Succeeded, Some(filename)) = (job.status, job.filename.as_deref()) {
+        let pdf_bytes = tokio::fs::read(state.report_jobs.path(job_id))
+            .await
+            .map_err(|_| AppError::not_found("error.report_job_not_found"))?;
+        return Ok((
+            StatusCode::OK,
+            [
+                (axum::http::header::CONTENT_TYPE, "application/pdf"),
+                (
+                    axum::http::header::CONTENT_DISPOSITION,
+                    &format!("attachment; filename=\"{}\"", filename),
+                ),
+            ],
+            pdf_bytes,
+        )
+            .into_response());
+    }
+
+    Ok(HtmlTemplate(ReportJobTemplate {
+        job: ReportJobView::new(job, lang),
+        user: Some(CurrentUser {
+            id: user.id.to_string(),
+            username: user.username.clone(),
+            is_admin: user.is_admin(),
+            can_moderate: user.can_moderate(),
+        }),
+        lang,
+    })
+    .into_response())
+}
+
+// Most upvoted posts of each stance shown in a bill report
+const BILL_REPORT_POSTS_PER_STANCE: i64 = 5;
+
+/// Build the per-constituency PDF for a bill, with a filename for it. None if the bill doesn't exist.
+pub async fn bill_report(pool: &PgPool, bill_id: Uuid) -> anyhow::Result<Option<(String, Vec<u8>)>> {
+    let Some(bill) = db::get_bill_by_id(pool, bill_id).await? else {
+        return Ok(None);
+    };
+    let rows = db::get_bill_sentiment_by_constituency(pool, bill_id).await?;
+    let posts = db::get_bill_top_posts(pool, bill_id, BILL_REPORT_POSTS_PER_STANCE).await?;
+
+    let pdf_bytes = crate::pdf_generator::generate_bill_report(&bill, rows, posts)
+        .map_err(|e| anyhow::anyhow!("Failed to generate PDF report: {}", e))?;
+
+    let filename = format!(
+        "bill_{}_report_{}.pdf",
+        bill.bill_number.replace([' ', '/'], "_"),
+        chrono::Utc::now().format("%Y-%m-%d")
+    );
+    Ok(Some((filename, pdf_bytes)))
+}
+
+async fn bill_report_handler(
+    State(state): State<Arc<AppState>>,
+    jar: CookieJar,
+    Path(bill_id): Path<String>,
+) -> Result<Response, AppError> {
+    if get_current_user(&jar, &state.db_pool).await.is_none() {
+        return Ok(Redirect::to("/login").into_response());
+    }
+
+    let bill_uuid = Uuid::parse_str(&bill_id).map_err(|_| AppError::bad_request("error.invalid_bill_id"))?;
+    let (filename, pdf_bytes) = bill_report(&state.db_pool, bill_uuid)
+        .await
+        .map_err(AppError::internal)?
+        .ok_or_else(|| AppError::not_found("error.bill_not_found"))?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, "application/pdf"),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                &format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        pdf_bytes,
+    )
+        .into_response())
+}
+
+// The sentiment PDF as a download, 404 if the constituency doesn't exist
+async fn constituency_report_response(
+    state: &AppState,
+    constituency_id: i32,
+    period: &models::ReportPeriod,
+) -> Result<Response, AppError> {
+    let (filename, pdf_bytes) = constituency_report(&state.db_pool, &state.llm, constituency_id, period)
+        .await
+        .map_err(AppError::internal)?
+        .ok_or_else(|| AppError::not_found("error.constituency_not_found"))?;
+    
+    // Return PDF
+    Ok((
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, "application/pdf"),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                &format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        pdf_bytes,
+    )
+        .into_response())
+}
+
+// JSON API (v1)
+const API_MAX_SEARCH_LIMIT: usize = 20;
+
+/// RFC 7807 problem document returned by the JSON API on errors
+#[derive(Serialize, ToSchema)]
+struct ProblemDocument {
+    #[serde(rename = "type")]
+    problem_type: &'static str,
+    title: String,
+    status: u16,
+    detail: String,
+}
+
+struct ApiError {
+    status: StatusCode,
+    detail: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let problem = ProblemDocument {
+            problem_type: "about:blank",
+            title: self
+                .status
+                .canonical_reason()
+                .unwrap_or("Error")
+                .to_string(),
+            status: self.status.as_u16(),
+            detail: self.detail,
+        };
+
+        (
+            self.status,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            Json(problem),
+        )
+            .into_response()
+    }
+}
+
+/// `Query` for the JSON API: a query string that doesn't parse gets a problem document, not
+/// axum's plain-text rejection
+struct ApiQuery<T>(T);
+
+#[axum::async_trait]
+impl<T, S> axum::extract::FromRequestParts<S> for ApiQuery<T>
+where
+    T: serde::de::DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut axum::http::request::Parts, state: &S) -> Result<Self, Self::Rejection> {
+        <Query<T> as axum::extract::FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(|Query(value)| ApiQuery(value))
+            .map_err(|rejection| ApiError::new(rejection.status(), rejection.body_text()))
+    }
+}
+
+/// `Json` for the JSON API: a body that isn't the expected JSON gets a problem document, not
+/// axum's plain-text rejection
+struct ApiJson<T>(T);
+
+#[axum::async_trait]
+impl<T, S> axum::extract::FromRequest<S> for ApiJson<T>
+where
+    T: serde::de::DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        <Json<T> as axum::extract::FromRequest<S>>::from_request(request, state)
+            .await
+            .map(|Json(value)| ApiJson(value))
+            .map_err(|rejection| ApiError::new(rejection.status(), rejection.body_text()))
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiSearchResponse {
+    query: String,
+    results: Vec<SearchHit>,
+    /// True when semantic search was unavailable and results come from keyword matching
+    keyword_fallback: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiBillsResponse {
+    bills: Vec<RecentBill>,
+    page: i64,
+    per_page: i64,
+    total: i64,
+    total_pages: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiTrendingResponse {
+    bills: Vec<TrendingBill>,
+    window_days: i32,
+}
 
-    Router::new()
-        // Main pages
-        .route("/", get(index))
-        .route("/login", get(login_page).post(login_handler))
-        .route("/register", get(register_page).post(register_handler))
-        .route("/logout", get(logout_handler))
-        .route("/u/:username", get(profile_handler).post(update_profile_handler))
-        // Forum pages
-        .route("/f/:bill_id", get(forum_page_handler))
-        // API endpoints
-        .route("/api/search", get(search_handler))
-        .route("/api/bills", get(bills_list_handler))
-        .route("/api/bill/:id/forum", get(bill_forum_handler))
-        .route("/api/bill/:id/review", post(submit_review_handler))
-        .route("/api/review/:id/upvote", post(upvote_handler))
-        .route("/api/review/:id/downvote", post(downvote_handler))
-        .route("/api/constituencies", get(constituencies_handler))
-        .route("/api/mp/report", get(mp_report_handler))
-        // Static files
-        .nest_service("/static", ServeDir::new("static"))
-        .with_state(state)
+#[derive(Deserialize, ToSchema)]
+struct AskRequest {
+    question: String,
+    limit: Option<usize>,
 }
 
-// MP Report Handlers
-async fn constituencies_handler(
+/// Answer a question from bill text, citing the sources used
+#[utoipa::path(
+    post,
+    path = "/api/ask",
+    tag = "ask",
+    request_body = AskRequest,
+    responses(
+        (status = 200, description = "Answer with sources; `answer` is null when the LLM is unavailable", body = rag::Answer),
+        (status = 400, description = "Empty question or malformed body", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 429, description = "Too many questions; see Retry-After", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 503, description = "Search backend unavailable", body = ProblemDocument, content_type = "application/problem+json")
+    )
+)]
+async fn api_ask_handler(
     State(state): State<Arc<AppState>>,
-) -> Result<Response, (StatusCode, String)> {
-    let constituencies = auth::get_all_constituencies(&state.db_pool)
+    ApiJson(request): ApiJson<AskRequest>,
+) -> Result<Json<rag::Answer>, ApiError> {
+    let question = request.question.trim();
+
+    if question.is_empty() {
+        return Err(ApiError::new(StatusCode::BAD_REQUEST, "Question must not be empty"));
+    }
+
+    let limit = request.limit.unwrap_or(5).clamp(1, API_MAX_SEARCH_LIMIT);
+
+    let answer = rag::answer_question(state.embedder.as_ref(), state.vector_store.as_ref(), &state.llm, question, limit)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    
-    let json_constituencies: Vec<serde_json::Value> = constituencies
-        .iter()
-        .map(|c| serde_json::json!({
-            "id": c.id,
-            "name": c.name,
-            "state": c.state
-        }))
-        .collect();
-    
-    Ok((
-        StatusCode::OK,
-        [(axum::http::header::CONTENT_TYPE, "application/json")],
-        serde_json::to_string(&json_constituencies).unwrap(),
-    )
-        .into_response())
-}
+        .map_err(|e| {
+            tracing::error!("Ask failed: {}", e);
+            ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "Search is currently unavailable")
+        })?;
 
-#[derive(Deserialize)]
-struct MPReportQuery {
-    constituency_id: i32,
+    Ok(Json(answer))
 }
 
-async fn mp_report_handler(
+/// Semantic search over bill text
+#[utoipa::path(
+    get,
+    path = "/api/v1/search",
+    tag = "search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Matching bill sections", body = ApiSearchResponse),
+        (status = 400, description = "Missing, empty or malformed query", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 429, description = "Too many searches; see Retry-After", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 503, description = "Search backend unavailable", body = ProblemDocument, content_type = "application/problem+json")
+    )
+)]
+async fn api_search_handler(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<MPReportQuery>,
-) -> Result<Response, (StatusCode, String)> {
-    // Get constituency info
-    let constituency = auth::get_constituency_by_id(&state.db_pool, params.constituency_id)
+    ApiQuery(params): ApiQuery<SearchQuery>,
+) -> Result<Json<ApiSearchResponse>, ApiError> {
+    let query = params.query.trim();
+
+    if query.is_empty() {
+        return Err(ApiError::new(StatusCode::BAD_REQUEST, "Query must not be empty"));
+    }
+
+    let limit = params.limit.unwrap_or(3).clamp(1, API_MAX_SEARCH_LIMIT);
+
+    let outcome = perform_search(&state, query, limit, &params.filter(), params.rerank())
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-        .ok_or((StatusCode::NOT_FOUND, "Constituency not found".to_string()))?;
-    
-    // Get sentiment data
-    let sentiments = crate::pdf_generator::get_constituency_sentiment(&state.db_pool, params.constituency_id)
+        .map_err(|e| {
+            tracing::error!("Search failed: {}", e);
+            ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "Search is currently unavailable")
+        })?;
+
+    Ok(Json(ApiSearchResponse {
+        query: query.to_string(),
+        results: outcome.results,
+        keyword_fallback: outcome.keyword_fallback,
+    }))
+}
+
+/// Paginated list of bills, newest first, optionally filtered by year, status and title
+#[utoipa::path(
+    get,
+    path = "/api/v1/bills",
+    tag = "bills",
+    params(BillsQuery),
+    responses(
+        (status = 200, description = "A page of bills", body = ApiBillsResponse),
+        (status = 400, description = "Malformed query", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = ProblemDocument, content_type = "application/problem+json")
+    )
+)]
+async fn api_bills_handler(
+    State(state): State<Arc<AppState>>,
+    ApiQuery(params): ApiQuery<BillsQuery>,
+) -> Result<Json<ApiBillsResponse>, ApiError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = 5;
+
+    let (filter, _) = bill_filters(&state.db_pool, &params).await;
+    let (bills, total) = db::get_bills_paginated(&state.db_pool, page, per_page, &filter)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    
-    // Get all posts
-    let posts = crate::pdf_generator::get_constituency_posts(&state.db_pool, params.constituency_id)
+        .map_err(|e| {
+            tracing::error!("Failed to fetch bills: {}", e);
+            ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch bills")
+        })?;
+
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    let bills = recent_bills(&state.db_pool, bills, None).await;
+
+    Ok(Json(ApiBillsResponse {
+        bills,
+        page,
+        per_page,
+        total,
+        total_pages,
+    }))
+}
+
+/// Bills ranked by recent forum activity, most discussed first
+#[utoipa::path(
+    get,
+    path = "/api/v1/bills/trending",
+    tag = "bills",
+    params(TrendingQuery),
+    responses(
+        (status = 200, description = "Trending bills", body = ApiTrendingResponse),
+        (status = 500, description = "Database error", body = ProblemDocument, content_type = "application/problem+json")
+    )
+)]
+async fn api_trending_bills_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<TrendingQuery>,
+) -> Result<Json<ApiTrendingResponse>, ApiError> {
+    let window_days = params
+        .window_days
+        .unwrap_or(TRENDING_WINDOW_DAYS)
+        .clamp(1, API_MAX_TRENDING_WINDOW_DAYS);
+    let limit = params.limit.unwrap_or(TRENDING_LIMIT).clamp(1, API_MAX_SEARCH_LIMIT as i64);
+
+    let bills = db::get_trending_bills(&state.db_pool, window_days, limit)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    
-    // Generate PDF
-    let pdf_bytes = crate::pdf_generator::generate_constituency_report(
-        &constituency.name,
-        &constituency.state,
-        sentiments,
-        posts,
+        .map_err(|e| {
+            tracing::error!("Failed to fetch trending bills: {}", e);
+            ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch trending bills")
+        })?;
+
+    Ok(Json(ApiTrendingResponse {
+        bills: bills.into_iter().map(TrendingBill::from).collect(),
+        window_days,
+    }))
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiBookmark {
+    id: String,
+    title: String,
+    number: String,
+    year: i32,
+    status: Option<String>,
+    /// RFC 3339 timestamp
+    bookmarked_at: String,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ApiBookmarksResponse {
+    bookmarks: Vec<ApiBookmark>,
+}
+
+/// The caller's bookmarked bills, most recently bookmarked first
+#[utoipa::path(
+    get,
+    path = "/api/v1/bookmarks",
+    tag = "bookmarks",
+    security(("bearer_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 200, description = "Bookmarked bills", body = ApiBookmarksResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 500, description = "Database error", body = ProblemDocument, content_type = "application/problem+json")
     )
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    
-    // Return PDF
-    Ok((
-        StatusCode::OK,
-        [
-            (axum::http::header::CONTENT_TYPE, "application/pdf"),
-            (
-                axum::http::header::CONTENT_DISPOSITION,
-                &format!("attachment; filename=\"{}_report.pdf\"", constituency.name.replace(" ", "_")),
-            ),
-        ],
-        pdf_bytes,
+)]
+async fn api_bookmarks_handler(
+    State(state): State<Arc<AppState>>,
+    auth::ApiUser(user, ..): auth::ApiReader,
+) -> Result<Json<ApiBookmarksResponse>, ApiError> {
+    let bookmarks = db::get_bookmarks(&state.db_pool, user.id).await.map_err(|e| {
+        tracing::error!("Failed to fetch bookmarks: {}", e);
+        ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch bookmarks")
+    })?;
+
+    Ok(Json(ApiBookmarksResponse {
+        bookmarks: bookmarks
+            .into_iter()
+            .map(|b| ApiBookmark {
+                id: b.id.to_string(),
+                title: b.title,
+                number: b.bill_number,
+                year: b.year,
+                status: b.status,
+                bookmarked_at: b.bookmarked_at.to_rfc3339(),
+            })
+            .collect(),
+    }))
+}
+
+/// Bookmark a bill; bookmarking an already bookmarked bill is not an error
+#[utoipa::path(
+    put,
+    path = "/api/v1/bookmarks/{bill_id}",
+    tag = "bookmarks",
+    params(("bill_id" = String, Path, description = "Bill ID")),
+    security(("bearer_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Bookmarked"),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 403, description = "Token is read-only", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 404, description = "No such bill", body = ProblemDocument, content_type = "application/problem+json")
     )
-        .into_response())
+)]
+async fn api_add_bookmark_handler(
+    State(state): State<Arc<AppState>>,
+    auth::ApiUser(user, ..): auth::ApiWriter,
+    Path(bill_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    api_set_bookmark(&state, &user, &bill_id, true).await
+}
+
+/// Remove a bookmark; removing one that doesn't exist is not an error
+#[utoipa::path(
+    delete,
+    path = "/api/v1/bookmarks/{bill_id}",
+    tag = "bookmarks",
+    params(("bill_id" = String, Path, description = "Bill ID")),
+    security(("bearer_token" = []), ("session_cookie" = [])),
+    responses(
+        (status = 204, description = "Bookmark removed"),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 403, description = "Token is read-only", body = ProblemDocument, content_type = "application/problem+json"),
+        (status = 404, description = "No such bill", body = ProblemDocument, content_type = "application/problem+json")
+    )
+)]
+async fn api_remove_bookmark_handler(
+    State(state): State<Arc<AppState>>,
+    auth::ApiUser(user, ..): auth::ApiWriter,
+    Path(bill_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    api_set_bookmark(&state, &user, &bill_id, false).await
+}
+
+async fn api_set_bookmark(
+    state: &AppState,
+    user: &models::User,
+    bill_id: &str,
+    bookmarked: bool,
+) -> Result<StatusCode, ApiError> {
+    let not_found = || ApiError::new(StatusCode::NOT_FOUND, "Bill not found");
+    let bill_uuid = Uuid::parse_str(bill_id).map_err(|_| not_found())?;
+
+    match db::get_bill_by_id(&state.db_pool, bill_uuid).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(not_found()),
+        Err(e) => {
+            tracing::error!("Failed to fetch bill {}: {}", bill_uuid, e);
+            return Err(ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Failed to update bookmark"));
+        }
+    }
+
+    db::set_bookmark(&state.db_pool, user.id, bill_uuid, bookmarked)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to update bookmark: {}", e);
+            ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "Failed to update bookmark")
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// OpenAPI document, generated from the handler annotations above
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        api_search_handler,
+        api_bills_handler,
+        api_trending_bills_handler,
+        api_ask_handler,
+        api_bookmarks_handler,
+        api_add_bookmark_handler,
+        api_remove_bookmark_handler
+    ),
+    components(schemas(
+        ApiSearchResponse,
+        ApiBillsResponse,
+        ApiTrendingResponse,
+        ApiBookmarksResponse,
+        ApiBookmark,
+        AskRequest,
+        SearchHit,
+        RecentBill,
+        TrendingBill,
+        ProblemDocument,
+        rag::Answer,
+        models::SearchResult
+    )),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "search", description = "Semantic search over bill text"),
+        (name = "bills", description = "Bill listings"),
+        (name = "ask", description = "Grounded question answering"),
+        (name = "bookmarks", description = "The caller's bookmarks; needs an API token or a session")
+    )
+)]
+struct ApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "session_cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(SESSION_COOKIE_NAME))),
+        );
+        // Personal API tokens, created from the profile page
+        components.add_security_scheme(
+            "bearer_token",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_store::InMemoryStore;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    struct FixedEmbedder;
+
+    #[async_trait::async_trait]
+    impl Embedder for FixedEmbedder {
+        async fn embed_query(&self, _query: &str) -> anyhow::Result<Vec<f32>> {
+            Ok(vec![1.0, 0.0])
+        }
+
+        async fn embed_texts(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts.iter().map(|_| vec![1.0, 0.0]).collect())
+        }
+
+        async fn warm_up(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn is_loaded(&self) -> bool {
+            true
+        }
+    }
+
+    fn test_state(store: InMemoryStore) -> Arc<AppState> {
+        let db_pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        Arc::new(AppState {
+            keywords: Arc::new(moderation::KeywordCache::new(db_pool.clone())),
+            db_pool,
+            vector_store: Arc::new(store),
+            embedder: Arc::new(FixedEmbedder),
+            // Nothing listens here, so answers fall back to sources only
+            llm: OllamaClient::new(reqwest::Client::new()).with_base_url("http://127.0.0.1:9"),
+            reranker: Arc::new(reranker::OllamaReranker::new(
+                OllamaClient::new(reqwest::Client::new()).with_base_url("http://127.0.0.1:9"),
+            )),
+            // Built-in keyword lists only
+            moderation: Vec::new(),
+            mailer: Arc::new(mailer::LogMailer),
+            challenge: Arc::new(challenge::NoChallenge),
+            google_oauth: None,
+            similar_bills: Arc::new(vector_store::SimilarBillsCache::default()),
+            ingest_jobs: Arc::new(IngestJobs::default()),
+            report_jobs: Arc::new(report_jobs::ReportJobs::new(std::env::temp_dir().join("unused-reports"))),
+            review_events: broadcast::channel(REVIEW_EVENTS_CAPACITY).0,
+            moderation_queue: None,
+            rate_limits: rate_limit::RateLimitConfig::default(),
+            rate_limit_cache: Arc::new(rate_limit::RateLimitCache::default()),
+            shutdown: crate::shutdown::channel().1,
+        })
+    }
+
+    fn chunk(identifier: &str, index: usize) -> models::SearchResult {
+        models::SearchResult {
+            bill_title: "The Digital Personal Data Protection Bill, 2023".to_string(),
+            bill_number: "DPDP/2023".to_string(),
+            chunk_index: index,
+            chunk_identifier: identifier.to_string(),
+            content: format!("{} text", identifier),
+            score: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ask_uses_sources_from_vector_store() {
+        let store = InMemoryStore {
+            points: vec![
+                (vec![0.0, 1.0], chunk("Clause 3", 3)),
+                (vec![1.0, 0.0], chunk("Clause 7", 7)),
+            ],
+        };
+        let app = Router::new()
+            .route("/api/ask", post(api_ask_handler))
+            .with_state(test_state(store));
+
+        let response = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/ask")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(r#"{"question": "Can I correct my data?", "limit": 1}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let answer: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(answer["answer"].is_null());
+        assert!(answer["message"].is_string());
+        assert_eq!(answer["sources"].as_array().unwrap().len(), 1);
+        assert_eq!(answer["sources"][0]["chunk_identifier"], "Clause 7");
+    }
+
+    #[tokio::test]
+    async fn test_ask_rejects_bad_bodies_with_problem_documents() {
+        let app = Router::new()
+            .route("/api/ask", post(api_ask_handler))
+            .with_state(test_state(InMemoryStore { points: vec![] }));
+
+        for (content_type, body) in [
+            ("application/json", r#"{"question": "#),
+            ("application/json", r#"{"limit": 3}"#),
+            ("text/plain", r#"{"question": "Can I correct my data?"}"#),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    axum::http::Request::builder()
+                        .method("POST")
+                        .uri("/api/ask")
+                        .header(header::CONTENT_TYPE, content_type)
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert!(response.status().is_client_error(), "{}", body);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "application/problem+json");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bookmarks_api_requires_credentials() {
+        let state = test_state(InMemoryStore { points: vec![] });
+        let app = Router::new()
+            .route("/api/v1/bookmarks", get(api_bookmarks_handler))
+            .route("/api/v1/bookmarks/:bill_id", put(api_add_bookmark_handler))
+            .layer(middleware::from_fn_with_state(state.clone(), render_errors))
+            .with_state(state);
+
+        for method in ["GET", "PUT"] {
+            let uri = if method == "GET" {
+                "/api/v1/bookmarks".to_string()
+            } else {
+                format!("/api/v1/bookmarks/{}", Uuid::new_v4())
+            };
+            let response = app
+                .clone()
+                .oneshot(axum::http::Request::builder().method(method).uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "application/problem+json");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_rejects_bad_query_strings_with_problem_documents() {
+        let app = Router::new()
+            .route("/api/v1/search", get(api_search_handler))
+            .route("/api/v1/bills", get(api_bills_handler))
+            .with_state(test_state(InMemoryStore { points: vec![] }));
+
+        for uri in ["/api/v1/search", "/api/v1/search?query=data&limit=many", "/api/v1/bills?page=two"] {
+            let response = app
+                .clone()
+                .oneshot(axum::http::Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", uri);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "application/problem+json");
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(problem["status"], 400);
+            assert!(problem["detail"].as_str().is_some_and(|detail| !detail.is_empty()));
+        }
+    }
+
+    #[test]
+    fn test_snippet_escapes_and_marks_terms() {
+        let content = "Definitions. <script>alert(1)</script> The data fiduciary shall protect personal data.";
+        let snippet = highlight_snippet(content, "data protect");
+
+        assert!(!snippet.contains("<script>"));
+        assert!(snippet.contains("&lt;script&gt;"));
+        assert!(snippet.contains("The <mark>data</mark> fiduciary shall <mark>protect</mark> personal <mark>data</mark>."));
+    }
+
+    #[test]
+    fn test_snippet_centres_on_matches() {
+        let content = format!("{} privacy clause {}", "a ".repeat(300), "b ".repeat(300));
+        let snippet = highlight_snippet(&content, "privacy");
+
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("<mark>privacy</mark> clause"));
+        assert_eq!(snippet.trim_matches('…').chars().count(), SNIPPET_CHARS + "<mark></mark>".len());
+
+        let plain = highlight_snippet(&content, "taxation");
+        assert!(!plain.contains("<mark>"));
+        assert!(!plain.starts_with('…'));
+        assert_eq!(plain.trim_end_matches('…').chars().count(), SNIPPET_CHARS);
+    }
+
+    #[test]
+    fn test_audit_details_lists_set_fields() {
+        let metadata = serde_json::json!({ "reason": null, "role": "moderator", "username": "asha" });
+        assert_eq!(audit_details(&metadata), "role: moderator, username: asha");
+        assert_eq!(audit_details(&serde_json::json!({})), "");
+        assert_eq!(audit_details(&serde_json::json!({ "attempts": 3 })), "attempts: 3");
+    }
+
+    #[test]
+    fn test_search_queries_are_normalized() {
+        assert_eq!(normalize_search_query("  Data   PROTECTION\tbill "), "data protection bill");
+        assert_eq!(normalize_search_query(&"x".repeat(500)).len(), SEARCH_LOG_MAX_CHARS);
+
+        let user = Uuid::new_v4();
+        assert_eq!(search_user_hash(user), search_user_hash(user));
+        assert_ne!(search_user_hash(user), search_user_hash(Uuid::new_v4()));
+        assert!(!search_user_hash(user).contains(&user.to_string()));
+    }
+
+    #[test]
+    fn test_mentions_skip_emails_and_code() {
+        let content = "Agree with @asha and @ravi_k. Mail me at meera@example.org, \
+                       see `@not_this` or\n```\n@nor_this\n```\n@asha again, cc @.";
+        assert_eq!(mentioned_usernames(content), vec!["asha", "ravi_k"]);
+        assert!(mentioned_usernames("``unclosed @dev").contains(&"dev".to_string()));
+    }
+
+    #[test]
+    fn test_render_mentions_links_known_users_only() {
+        let known: HashSet<String> = ["asha".to_string()].into_iter().collect();
+        let html = render_mentions("<b>@asha</b> & @ghost: `@asha`", &known);
+
+        assert_eq!(
+            html,
+            "&lt;b&gt;<a href=\"/u/asha\" class=\"mention\">@asha</a>&lt;/b&gt; &amp; @ghost: `@asha`"
+        );
+    }
+
+    #[test]
+    fn test_invalid_bill_filters_are_ignored() {
+        let years = [2023, 2024];
+        let statuses = vec!["Passed".to_string(), "Pending".to_string()];
+        let query = |year: &str, status: &str, q: &str| BillsQuery {
+            page: None,
+            year: Some(year.to_string()),
+            status: Some(status.to_string()),
+            q: Some(q.to_string()),
+        };
+
+        let filter = query("2024", "passed", "  data protection ").filter(&years, &statuses);
+        assert_eq!(filter.year, Some(2024));
+        assert_eq!(filter.status.as_deref(), Some("Passed"));
+        assert_eq!(filter.query.as_deref(), Some("data protection"));
+
+        assert_eq!(query("20x4", "Lapsed", "   ").filter(&years, &statuses), models::BillFilter::default());
+        assert_eq!(query("1999", "", "").filter(&years, &statuses), models::BillFilter::default());
+
+        let view = BillFiltersView::new(&filter, &years, statuses);
+        assert_eq!(view.query_string, "&year=2024&status=Passed&q=data%20protection");
+        assert!(view.is_active());
+    }
+
+    #[test]
+    fn test_leaderboard_window_defaults_to_30_days() {
+        assert_eq!(leaderboard_window(None), ("30", Some(30)));
+        assert_eq!(leaderboard_window(Some("90")), ("90", Some(90)));
+        assert_eq!(leaderboard_window(Some("all")), ("all", None));
+        assert_eq!(leaderboard_window(Some("7")), ("30", Some(30)));
+    }
+
+    #[test]
+    fn test_report_period_covers_whole_days() {
+        assert_eq!(report_period(None, Some(" ")).ok(), Some(models::ReportPeriod::default()));
+        assert!(report_period(Some("31/03/2026"), None).is_err());
+
+        let Ok(period) = report_period(Some("2026-01-01"), Some("2026-03-31")) else {
+            panic!("valid dates were refused");
+        };
+        let (from, until) = period.bounds();
+        assert_eq!(from.unwrap().to_rfc3339(), "2026-01-01T00:00:00+00:00");
+        // The last day is included in full
+        assert_eq!(until.unwrap().to_rfc3339(), "2026-04-01T00:00:00+00:00");
+        assert_eq!(period.describe(), "Posts from 01 January 2026 to 31 March 2026");
+    }
+
+    #[tokio::test]
+    async fn test_review_stream_unsubscribes_when_dropped() {
+        let state = test_state(InMemoryStore { points: vec![] });
+        assert_eq!(state.review_events.receiver_count(), 0);
+
+        let stream = review_stream(state.clone(), Uuid::new_v4(), None, Locale::En);
+        let other = review_stream(state.clone(), Uuid::new_v4(), None, Locale::En);
+        assert_eq!(state.review_events.receiver_count(), 2);
+
+        drop(stream);
+        assert_eq!(state.review_events.receiver_count(), 1);
+        drop(other);
+        assert_eq!(state.review_events.receiver_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_security_headers_are_set() {
+        let app: Router = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(content_security_policy(&[]), security_headers));
+
+        let response = app
+            .oneshot(axum::http::Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let headers = response.headers();
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+        assert_eq!(headers[header::REFERRER_POLICY], "strict-origin-when-cross-origin");
+        assert!(headers[header::CONTENT_SECURITY_POLICY]
+            .to_str()
+            .unwrap()
+            .contains("frame-ancestors 'none'"));
+    }
+
+    #[test]
+    fn test_csp_allows_the_captcha_provider() {
+        let turnstile = content_security_policy(&["https://challenges.cloudflare.com"]);
+        let policy = turnstile.to_str().unwrap();
+        for directive in ["script-src", "frame-src", "connect-src"] {
+            let sources = policy
+                .split(';')
+                .map(str::trim)
+                .find(|d| d.starts_with(directive))
+                .unwrap();
+            assert!(sources.contains("https://challenges.cloudflare.com"), "{}", sources);
+        }
+
+        // Without a CAPTCHA nothing third-party is framed or contacted
+        let default = content_security_policy(&[]);
+        assert!(default.to_str().unwrap().contains("frame-src 'self';"));
+        assert!(default.to_str().unwrap().contains("connect-src 'self';"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_routes_render_error_page_or_problem() {
+        let state = test_state(InMemoryStore { points: vec![] });
+        let app: Router = Router::new()
+            .fallback(not_found_handler)
+            .layer(middleware::from_fn_with_state(state.clone(), render_errors))
+            .with_state(state);
+
+        let page = app
+            .clone()
+            .oneshot(axum::http::Request::builder().uri("/no-such-page").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(page.status(), StatusCode::NOT_FOUND);
+        assert!(page.headers()[header::CONTENT_TYPE].to_str().unwrap().starts_with("text/html"));
+        let body = axum::body::to_bytes(page.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("Page not found"));
+
+        let api = app
+            .oneshot(axum::http::Request::builder().uri("/api/v1/nothing").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(api.status(), StatusCode::NOT_FOUND);
+        assert_eq!(api.headers()[header::CONTENT_TYPE], "application/problem+json");
+    }
+
+    #[tokio::test]
+    async fn test_error_page_shows_request_id() {
+        let state = test_state(InMemoryStore { points: vec![] });
+        let app: Router = Router::new()
+            .route("/boom", get(|| async { AppError::internal(anyhow::anyhow!("database down")) }))
+            .layer(middleware::from_fn_with_state(state.clone(), render_errors))
+            .layer(
+                ServiceBuilder::new()
+                    .layer(SetRequestIdLayer::x_request_id(MakeShortRequestId))
+                    .layer(PropagateRequestIdLayer::x_request_id()),
+            )
+            .with_state(state);
+
+        let response = app
+            .oneshot(axum::http::Request::builder().uri("/boom").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let id = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert_eq!(id.len(), 12);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains(&format!("Reference: {}", id)));
+        assert!(!body.contains("database down"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_error_keeps_retry_after() {
+        let state = test_state(InMemoryStore { points: vec![] });
+        let app: Router = Router::new()
+            .route(
+                "/login",
+                post(|| async {
+                    (
+                        [(header::RETRY_AFTER, "90")],
+                        AppError::new(StatusCode::TOO_MANY_REQUESTS, "error.ip_rate_limited"),
+                    )
+                }),
+            )
+            .layer(middleware::from_fn_with_state(state.clone(), render_errors))
+            .with_state(state);
+
+        let response = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/login")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "90");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("Too many attempts from your network"));
+    }
+
+    #[test]
+    fn test_etag_matches_if_none_match() {
+        let fresh = html_with_etag(&HeaderMap::new(), "<p>bill</p>".to_string());
+        assert_eq!(fresh.status(), StatusCode::OK);
+        let etag = fresh.headers()[header::ETAG].clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag.clone());
+        let cached = html_with_etag(&headers, "<p>bill</p>".to_string());
+        assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(cached.headers()[header::ETAG], etag);
+
+        let changed = html_with_etag(&headers, "<p>bill, amended</p>".to_string());
+        assert_eq!(changed.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_openapi_document_deserializes() {
+        let json = ApiDoc::openapi().to_json().unwrap();
+        let spec: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        for path in [
+            "/api/v1/search",
+            "/api/v1/bills",
+            "/api/v1/bills/trending",
+            "/api/ask",
+            "/api/v1/bookmarks",
+            "/api/v1/bookmarks/{bill_id}",
+        ] {
+            assert!(spec["paths"][path].is_object(), "missing {}", path);
+        }
+        assert!(spec["components"]["securitySchemes"]["session_cookie"].is_object());
+        assert_eq!(spec["components"]["securitySchemes"]["bearer_token"]["scheme"], "bearer");
+        assert!(spec["components"]["schemas"]["ProblemDocument"].is_object());
+    }
+
+    #[test]
+    fn test_oauth_state_cookie_round_trips() {
+        let pending = PendingOAuth {
+            link: true,
+            csrf_state: "c5rf-_St4te".to_string(),
+            pkce_verifier: "v3rifier.with.dots".to_string(),
+        };
+        let parsed = PendingOAuth::parse(&pending.to_cookie_value()).unwrap();
+        assert!(parsed.link);
+        assert_eq!(parsed.csrf_state, "c5rf-_St4te");
+        assert_eq!(parsed.pkce_verifier, "v3rifier.with.dots");
+
+        assert!(!PendingOAuth::parse("login.a.b").unwrap().link);
+        assert!(PendingOAuth::parse("admin.a.b").is_none());
+        assert!(PendingOAuth::parse("login.only-state").is_none());
+
+        let cookie = oauth_state_cookie("value".to_string(), OAUTH_STATE_MAX_AGE_SECS);
+        assert_eq!(cookie.path(), Some("/auth/google"));
+        assert_eq!(cookie.http_only(), Some(true));
+    }
+
+    #[test]
+    fn test_session_cookie_attributes() {
+        let cookie = session_cookie("token".to_string(), chrono::Utc::now() + chrono::Duration::days(7));
+        assert_eq!(cookie.http_only(), Some(true));
+        let max_age = cookie.max_age().unwrap();
+        assert!(max_age > time::Duration::days(7) - time::Duration::minutes(1) && max_age <= time::Duration::days(7));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        assert_eq!(cookie.path(), Some("/"));
+    }
+
+    #[test]
+    fn test_feed_entries_are_escaped() {
+        let feed = FeedTemplate {
+            base_url: "https://example.org".to_string(),
+            updated: "2024-08-01T00:00:00+00:00".to_string(),
+            entries: vec![FeedEntry {
+                id: "6f1c2a9e-0000-0000-0000-000000000000".to_string(),
+                title: "The Waqf (Amendment) Bill & Rules <2024>".to_string(),
+                published: "2024-08-01T00:00:00+00:00".to_string(),
+                updated: "2024-08-01T00:00:00+00:00".to_string(),
+                summary: "Bill No. 109/2024".to_string(),
+            }],
+        };
+
+        let xml = feed.render().unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<id>urn:uuid:6f1c2a9e-0000-0000-0000-000000000000</id>"));
+        assert!(xml.contains("Bill &amp; Rules &lt;2024&gt;"));
+    }
+
+    #[test]
+    fn test_sitemap_profile_paths_are_encoded() {
+        assert_eq!(encode_path_segment("asha_k-1.2"), "asha_k-1.2");
+        assert_eq!(encode_path_segment("a b&c"), "a%20b%26c");
+        assert_eq!(encode_path_segment("राम"), "%E0%A4%B0%E0%A4%BE%E0%A4%AE");
+    }
+
+    #[test]
+    fn test_retry_after_message_rounds_up_to_minutes() {
+        assert_eq!(retry_after_message(Locale::En, 0), "Try again in a moment.");
+        assert_eq!(retry_after_message(Locale::En, 30), "Try again in 1 minute.");
+        assert_eq!(retry_after_message(Locale::En, 22 * 60 + 1), "Try again in 23 minutes.");
+        assert_eq!(retry_after_message(Locale::Hi, 22 * 60 + 1), "23 मिनट में फिर प्रयास करें।");
+        assert_eq!(retry_after_message(Locale::En, 90 * 60), "Try again in 90 minutes.");
+        assert_eq!(retry_after_message(Locale::En, 9 * 3600 + 60), "Try again in 10 hours.");
+    }
+
+    #[test]
+    fn test_rate_limited_form_counts_down() {
+        let form = |retry_after_secs: i64| {
+            ReviewFormTemplate {
+                bill: BillInfo {
+                    id: "bill-1".to_string(),
+                    title: "Test Bill".to_string(),
+                    number: "1".to_string(),
+                    year: 2024,
+                },
+                rate_limit_remaining: Some(0),
+                retry_message: Some(retry_after_message(Locale::En, retry_after_secs)),
+                retry_after_secs,
+                daily_limit_reached: false,
+                form_notice: None,
+                form_error: None,
+                lang: Locale::En,
+            }
+            .render()
+            .unwrap()
+        };
+
+        let html = form(14 * 60);
+        assert!(html.contains("disabled>Try again in 14 minutes.</button>"));
+        assert!(html.contains(r#"hx-get="/api/bill/bill-1/review-form""#));
+        assert!(html.contains("load delay:60s"));
+
+        // The last minute re-fetches the form just as the wait ends
+        assert!(form(25).contains("load delay:25s"));
+    }
+
+    #[test]
+    fn test_language_switch_returns_to_local_path() {
+        let referer = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::REFERER, HeaderValue::from_str(value).unwrap());
+            referer_path(&headers)
+        };
+
+        assert_eq!(referer("https://example.org/f/abc?sort=top"), "/f/abc?sort=top");
+        assert_eq!(referer("https://example.org"), "/");
+        assert_eq!(referer("https://example.org//evil.example/"), "/");
+        assert_eq!(referer_path(&HeaderMap::new()), "/");
+    }
 }